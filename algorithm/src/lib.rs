@@ -2,11 +2,18 @@
 //
 // SPDX-License-Identifier: GPL-2.0-or-later
 
-use common::position::Position;
+use common::position::{GnssPosition, Position};
 use common::track::Track;
 use core::f64;
 use tracing::debug;
 
+/// Returns the detection radius of a track in meters.
+///
+/// The [`Track::detection_radius_m`] of the track overrides the given global radius.
+fn detection_radius_of(track: &Track, detection_radius: u16) -> f64 {
+    track.detection_radius_m.unwrap_or(detection_radius as f64)
+}
+
 /// Returns a list of references to tracks whose start line is within a specified detection radius of a given position.
 ///
 /// Iterates through the provided collection of tracks, calculates the distance from each track’s start line to the specified position,
@@ -15,7 +22,8 @@ use tracing::debug;
 /// # Parameters
 /// - `tracks`: A reference to a vector of `Track` instances to check.
 /// - `pos`: The `Position` from which distances are measured.
-/// - `detection_radius`: The maximum distance (in meters) between `pos` and a track’s start line to consider it detected.
+/// - `detection_radius`: The maximum distance (in meters) between `pos` and a track’s start line to consider it detected,
+///   unless the track overrides it with [`Track::detection_radius_m`].
 ///
 /// # Returns
 /// A vector containing references to tracks whose start line is within the specified detection radius.
@@ -27,7 +35,7 @@ pub fn is_on_track<'a>(
     let mut detected_tracks = Vec::<&Track>::new();
     for track in tracks {
         let distance = calculate_distance(&track.startline, pos);
-        if distance <= detection_radius as f64 {
+        if distance <= detection_radius_of(track, detection_radius) {
             detected_tracks.push(track);
         }
         debug!("Track: {}, Distance: {}", track.name, distance);
@@ -35,6 +43,271 @@ pub fn is_on_track<'a>(
     detected_tracks
 }
 
+/// Maximum difference in meters between the start line distances of two tracks that are
+/// considered a tie by [`detect`].
+pub const TIE_TOLERANCE: f64 = 1.0;
+
+/// Defines how [`detect`] resolves tracks whose start lines are equally close.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum TieBreak {
+    /// The track with the lexicographically smallest name is selected.
+    #[default]
+    ByName,
+
+    /// All tied tracks are returned as [`DetectionResult::Ambiguous`], e.g. to let the
+    /// user select the track.
+    Ambiguous,
+}
+
+/// The result of a track detection with [`detect`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum DetectionResult {
+    /// Exactly one track was detected.
+    Single(Track),
+
+    /// Several tracks tie for the closest start line, sorted by name.
+    Ambiguous(Vec<Track>),
+
+    /// No start line is within the detection radius.
+    None,
+}
+
+/// Detects the track whose start line is closest to a given position.
+///
+/// Only tracks whose start line is within the detection radius are considered. Tracks whose
+/// start line distance differs by at most [`TIE_TOLERANCE`] from the closest one tie and are
+/// resolved with `tie_break`, so the result doesn't depend on the order of `tracks`.
+///
+/// # Parameters
+/// - `tracks`: The tracks to check.
+/// - `pos`: The `Position` from which distances are measured.
+/// - `detection_radius`: The maximum distance (in meters) between `pos` and a track’s start line to consider it detected,
+///   unless the track overrides it with [`Track::detection_radius_m`].
+/// - `tie_break`: How tracks with equally close start lines are resolved.
+pub fn detect(
+    tracks: &[Track],
+    pos: &Position,
+    detection_radius: u16,
+    tie_break: TieBreak,
+) -> DetectionResult {
+    detect_with_model(
+        tracks,
+        pos,
+        detection_radius,
+        tie_break,
+        DistanceModel::default(),
+    )
+}
+
+/// Detects the track whose start line is closest to a given position like [`detect`], but
+/// measures the start line distances with the given [`DistanceModel`].
+pub fn detect_with_model(
+    tracks: &[Track],
+    pos: &Position,
+    detection_radius: u16,
+    tie_break: TieBreak,
+    model: DistanceModel,
+) -> DetectionResult {
+    let candidates: Vec<(f64, &Track)> = tracks
+        .iter()
+        .map(|track| (calculate_distance_with(model, &track.startline, pos), track))
+        .filter(|(distance, track)| *distance <= detection_radius_of(track, detection_radius))
+        .collect();
+    let Some(closest) = candidates
+        .iter()
+        .map(|(distance, _)| *distance)
+        .min_by(f64::total_cmp)
+    else {
+        return DetectionResult::None;
+    };
+    let mut tied: Vec<&Track> = candidates
+        .into_iter()
+        .filter(|(distance, _)| *distance - closest <= TIE_TOLERANCE)
+        .map(|(_, track)| track)
+        .collect();
+    tied.sort_by(|a, b| a.name.cmp(&b.name));
+    if tied.len() > 1 {
+        debug!(
+            "Tracks {:?} tie in detection distance",
+            tied.iter().map(|track| &track.name).collect::<Vec<_>>()
+        );
+    }
+    match (tie_break, tied.as_slice()) {
+        (TieBreak::Ambiguous, [_, _, ..]) => {
+            DetectionResult::Ambiguous(tied.into_iter().cloned().collect())
+        }
+        (_, [track, ..]) => DetectionResult::Single((*track).clone()),
+        (_, []) => DetectionResult::None,
+    }
+}
+
+/// Defines which track is assigned when several tracks are detected, see [`select_track`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum TrackSelectionPolicy {
+    /// The first of the detected tracks is assigned.
+    #[default]
+    First,
+
+    /// The track whose start line is closest to the current position is assigned.
+    Nearest,
+
+    /// No track is assigned until the track is selected explicitly, e.g. by the user.
+    PromptAndWait,
+}
+
+/// Selects the track to assign from the detected tracks.
+///
+/// A single detected track is always selected, several tracks are resolved with `policy`.
+/// [`TrackSelectionPolicy::Nearest`] falls back to the first track without a position.
+///
+/// # Parameters
+/// - `tracks`: The detected tracks.
+/// - `pos`: The current position, if known.
+/// - `policy`: How several detected tracks are resolved.
+///
+/// # Returns
+/// The selected track, or `None` if no track was detected or the selection is left to the user.
+pub fn select_track<'a>(
+    tracks: &'a [Track],
+    pos: Option<&Position>,
+    policy: TrackSelectionPolicy,
+) -> Option<&'a Track> {
+    match (policy, tracks, pos) {
+        (_, [track], _) => Some(track),
+        (TrackSelectionPolicy::PromptAndWait, _, _) => None,
+        (TrackSelectionPolicy::Nearest, _, Some(pos)) => tracks.iter().min_by(|a, b| {
+            calculate_distance(&a.startline, pos).total_cmp(&calculate_distance(&b.startline, pos))
+        }),
+        _ => tracks.first(),
+    }
+}
+
+/// Places `n` sector markers at equal distances along a path.
+///
+/// The path is split by its cumulative distance into `n + 1` parts of equal length and the
+/// markers are placed between them. Markers between two points of the path are linearly
+/// interpolated, so `n` may exceed the number of points.
+///
+/// # Parameters
+/// - `points`: The points of the path in driving order.
+/// - `n`: The number of sector markers.
+///
+/// # Returns
+/// The markers in driving order, or an empty list if the path has no length.
+pub fn auto_sectors(points: &[Position], n: usize) -> Vec<Position> {
+    let total: f64 = points
+        .windows(2)
+        .map(|segment| calculate_distance(&segment[0], &segment[1]))
+        .sum();
+    if n == 0 || total <= 0.0 {
+        return vec![];
+    }
+    let mut markers = Vec::with_capacity(n);
+    let mut segments = points.windows(2);
+    let mut segment = segments.next();
+    // Cumulative distance of the start of the current segment.
+    let mut walked = 0.0;
+    for index in 1..=n {
+        let target = total * index as f64 / (n + 1) as f64;
+        while let Some([from, to]) = segment {
+            let length = calculate_distance(from, to);
+            if length > 0.0 && walked + length >= target {
+                let fraction = (target - walked) / length;
+                markers.push(Position {
+                    latitude: from.latitude + (to.latitude - from.latitude) * fraction,
+                    longitude: from.longitude + (to.longitude - from.longitude) * fraction,
+                });
+                break;
+            }
+            walked += length;
+            segment = segments.next();
+        }
+    }
+    markers
+}
+
+/// Returns the cumulative distance in meters of every point from the first point of a path.
+pub fn cumulative_distances(points: &[GnssPosition]) -> Vec<f64> {
+    let mut walked = 0.0;
+    let mut distances = Vec::with_capacity(points.len());
+    for (index, point) in points.iter().enumerate() {
+        if index > 0 {
+            walked += calculate_distance(&points[index - 1].to_position(), &point.to_position());
+        }
+        distances.push(walked);
+    }
+    distances
+}
+
+/// A sample of a reference lap aligned with the sample of a compared lap at the nearest distance.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AlignedSample {
+    /// Index of the sample in the reference lap.
+    pub index: usize,
+
+    /// Distance of the sample from the start of the reference lap in meters.
+    pub distance: f64,
+
+    /// Index of the sample of the compared lap with the nearest distance from its start,
+    /// `None` if the reference lap is longer than the compared lap at this sample.
+    pub other_index: Option<usize>,
+
+    /// Time since the start of the compared lap minus the time since the start of the
+    /// reference lap in seconds, positive if the compared lap is slower up to this point.
+    pub time_delta: Option<f64>,
+}
+
+/// Aligns the samples of two laps by their distance from the start of the lap.
+///
+/// For every sample of the reference lap the sample of the compared lap with the nearest
+/// distance from its start is selected, so laps with a different number of samples or
+/// sample rates can be overlaid. Samples of the reference lap beyond the length of the
+/// compared lap have no counterpart.
+///
+/// # Parameters
+/// - `reference`: The log points of the reference lap.
+/// - `other`: The log points of the compared lap.
+///
+/// # Returns
+/// One aligned sample for every log point of the reference lap.
+pub fn align_laps(reference: &[GnssPosition], other: &[GnssPosition]) -> Vec<AlignedSample> {
+    let other_distances = cumulative_distances(other);
+    let other_length = other_distances.last().copied();
+    cumulative_distances(reference)
+        .into_iter()
+        .enumerate()
+        .map(|(index, distance)| {
+            let other_index = other_length
+                .filter(|length| distance <= *length)
+                .map(|_| nearest_index(&other_distances, distance));
+            let time_delta = other_index.map(|other_index| {
+                let other_time = other[other_index].timestamp() - other[0].timestamp();
+                let reference_time = reference[index].timestamp() - reference[0].timestamp();
+                (other_time - reference_time).num_milliseconds() as f64 / 1000.0
+            });
+            AlignedSample {
+                index,
+                distance,
+                other_index,
+                time_delta,
+            }
+        })
+        .collect()
+}
+
+/// Returns the index of the ascending, non-empty `distances` closest to `distance`.
+fn nearest_index(distances: &[f64], distance: f64) -> usize {
+    let next = distances.partition_point(|d| *d < distance);
+    if next == 0 {
+        return 0;
+    }
+    if next == distances.len() || distance - distances[next - 1] <= distances[next] - distance {
+        next - 1
+    } else {
+        next
+    }
+}
+
 /// Calculates the approximate distance in meters between two geographic positions.
 ///
 /// This function uses a simplified equirectangular approximation to determine  
@@ -56,8 +329,179 @@ pub fn is_on_track<'a>(
 /// - This method is more efficient than more precise formulas (e.g., Haversine)  
 ///   but trades some accuracy for performance.
 pub fn calculate_distance(pos1: &Position, pos2: &Position) -> f64 {
-    let lat = (pos1.latitude + pos2.latitude) / 2.0 * 0.01745;
-    let dx = 111300.0 * lat.cos() * (pos1.longitude - pos2.longitude);
-    let dy = 111300.0 * (pos1.latitude - pos2.latitude);
-    (dx * dx + dy * dy).sqrt()
+    pos1.distance_to(pos2)
+}
+
+/// Mean radius of the earth in meters used by [`calculate_distance_haversine`].
+pub const EARTH_RADIUS_M: f64 = 6_371_000.0;
+
+/// Calculates the great-circle distance in meters between two geographic positions.
+///
+/// This function uses the Haversine formula on a spherical earth with
+/// [`EARTH_RADIUS_M`], so it stays accurate over long distances and near the poles,
+/// but is slower than [`calculate_distance`].
+///
+/// # Parameters
+/// - `pos1`: Reference to the first geographic position.
+/// - `pos2`: Reference to the second geographic position.
+///
+/// # Returns
+/// The calculated distance between `pos1` and `pos2` in meters as a `f64`.
+pub fn calculate_distance_haversine(pos1: &Position, pos2: &Position) -> f64 {
+    let lat1 = pos1.latitude.to_radians();
+    let lat2 = pos2.latitude.to_radians();
+    let d_lat = lat2 - lat1;
+    let d_lon = (pos2.longitude - pos1.longitude).to_radians();
+    let a = (d_lat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (d_lon / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_M * a.sqrt().min(1.0).asin()
+}
+
+/// The formula used to calculate the distance between two positions, see
+/// [`calculate_distance_with`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum DistanceModel {
+    /// The fast equirectangular approximation of [`calculate_distance`].
+    #[default]
+    Equirectangular,
+
+    /// The accurate great-circle distance of [`calculate_distance_haversine`].
+    Haversine,
+}
+
+/// Calculates the distance in meters between two geographic positions with the given model.
+pub fn calculate_distance_with(model: DistanceModel, pos1: &Position, pos2: &Position) -> f64 {
+    match model {
+        DistanceModel::Equirectangular => calculate_distance(pos1, pos2),
+        DistanceModel::Haversine => calculate_distance_haversine(pos1, pos2),
+    }
+}
+
+/// Semi-major axis of the WGS84 ellipsoid in meters.
+const WGS84_A: f64 = 6_378_137.0;
+
+/// Flattening of the WGS84 ellipsoid.
+const WGS84_F: f64 = 1.0 / 298.257_223_563;
+
+/// Squared first eccentricity of the WGS84 ellipsoid.
+const WGS84_E2: f64 = WGS84_F * (2.0 - WGS84_F);
+
+/// Converts a position on the WGS84 ellipsoid into earth-centered, earth-fixed coordinates.
+fn to_ecef(latitude: f64, longitude: f64) -> [f64; 3] {
+    let (sin_lat, cos_lat) = latitude.sin_cos();
+    let (sin_lon, cos_lon) = longitude.sin_cos();
+    let n = WGS84_A / (1.0 - WGS84_E2 * sin_lat * sin_lat).sqrt();
+    [
+        n * cos_lat * cos_lon,
+        n * cos_lat * sin_lon,
+        n * (1.0 - WGS84_E2) * sin_lat,
+    ]
+}
+
+/// Converts earth-centered, earth-fixed coordinates into the latitude and longitude in radians
+/// of the point on the WGS84 ellipsoid below them.
+fn from_ecef([x, y, z]: [f64; 3]) -> (f64, f64) {
+    let p = x.hypot(y);
+    let mut latitude = z.atan2(p * (1.0 - WGS84_E2));
+    for _ in 0..5 {
+        let sin_lat = latitude.sin();
+        let n = WGS84_A / (1.0 - WGS84_E2 * sin_lat * sin_lat).sqrt();
+        let height = p / latitude.cos() - n;
+        latitude = z.atan2(p * (1.0 - WGS84_E2 * n / (n + height)));
+    }
+    (latitude, y.atan2(x))
+}
+
+/// A local east/north/up (ENU) frame tangent to the WGS84 ellipsoid at an origin, e.g. the
+/// start line of a track.
+///
+/// Positions are projected into the flat east/north plane of the frame, so the geometry of
+/// marker crossings can be calculated in meters. The projection is accurate to a few
+/// millimeters within some kilometers of the origin.
+///
+/// # Example
+///
+/// ```rust
+/// use algorithm::LocalFrame;
+/// use common::position::Position;
+///
+/// let origin = Position { latitude: 52.0, longitude: 11.0 };
+/// let frame = LocalFrame::new(&origin);
+/// let (east, north) = frame.to_local(&Position { latitude: 52.001, longitude: 11.0 });
+/// assert!(east.abs() < 0.01);
+/// assert!((north - 111.25).abs() < 0.1);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LocalFrame {
+    origin: [f64; 3],
+    sin_lat: f64,
+    cos_lat: f64,
+    sin_lon: f64,
+    cos_lon: f64,
+}
+
+impl LocalFrame {
+    /// Creates the local frame with its origin at the given position.
+    pub fn new(origin: &Position) -> LocalFrame {
+        let latitude = origin.latitude.to_radians();
+        let longitude = origin.longitude.to_radians();
+        let (sin_lat, cos_lat) = latitude.sin_cos();
+        let (sin_lon, cos_lon) = longitude.sin_cos();
+        LocalFrame {
+            origin: to_ecef(latitude, longitude),
+            sin_lat,
+            cos_lat,
+            sin_lon,
+            cos_lon,
+        }
+    }
+
+    /// Returns the east, north and up offsets of the position from the origin in meters.
+    fn enu_of(&self, pos: &Position) -> [f64; 3] {
+        let [x, y, z] = to_ecef(pos.latitude.to_radians(), pos.longitude.to_radians());
+        let (dx, dy, dz) = (x - self.origin[0], y - self.origin[1], z - self.origin[2]);
+        [
+            -self.sin_lon * dx + self.cos_lon * dy,
+            -self.sin_lat * self.cos_lon * dx - self.sin_lat * self.sin_lon * dy
+                + self.cos_lat * dz,
+            self.cos_lat * self.cos_lon * dx + self.cos_lat * self.sin_lon * dy + self.sin_lat * dz,
+        ]
+    }
+
+    /// Returns the position of the given east, north and up offsets from the origin.
+    fn position_of(&self, [east, north, up]: [f64; 3]) -> Position {
+        let x = -self.sin_lon * east - self.sin_lat * self.cos_lon * north
+            + self.cos_lat * self.cos_lon * up;
+        let y = self.cos_lon * east - self.sin_lat * self.sin_lon * north
+            + self.cos_lat * self.sin_lon * up;
+        let z = self.cos_lat * north + self.sin_lat * up;
+        let (latitude, longitude) =
+            from_ecef([self.origin[0] + x, self.origin[1] + y, self.origin[2] + z]);
+        Position {
+            latitude: latitude.to_degrees(),
+            longitude: longitude.to_degrees(),
+        }
+    }
+
+    /// Projects the position into the local frame.
+    ///
+    /// # Returns
+    /// The offsets `(east, north)` of the position from the origin in meters.
+    pub fn to_local(&self, pos: &Position) -> (f64, f64) {
+        let [east, north, _] = self.enu_of(pos);
+        (east, north)
+    }
+
+    /// Returns the position on the ellipsoid with the given offsets from the origin in meters,
+    /// the inverse of [`to_local`](Self::to_local).
+    pub fn from_local(&self, east: f64, north: f64) -> Position {
+        // The plane of the frame rises above the curved surface with the distance from the
+        // origin, follow the surface down so the position projects back to the same offsets.
+        let mut up = 0.0;
+        let mut pos = self.position_of([east, north, up]);
+        for _ in 0..2 {
+            up = self.enu_of(&pos)[2];
+            pos = self.position_of([east, north, up]);
+        }
+        pos
+    }
 }
@@ -0,0 +1,73 @@
+// SPDX-FileCopyrightText: 2026 All contributors
+//
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use algorithm::{auto_sectors, calculate_distance};
+use common::position::Position;
+
+/// Returns a position the given meters north and east of latitude 52.0 and longitude 11.0.
+fn offset(north: f64, east: f64) -> Position {
+    let latitude = 52.0 + north / 111300.0;
+    let longitude = 11.0 + east / (111300.0 * (latitude * 0.01745).cos());
+    Position::new(&latitude, &longitude)
+}
+
+/// Returns the cumulative distance of a position that lies on the path.
+fn distance_along(points: &[Position], pos: &Position) -> f64 {
+    let mut walked = 0.0;
+    for segment in points.windows(2) {
+        let length = calculate_distance(&segment[0], &segment[1]);
+        let to_pos = calculate_distance(&segment[0], pos);
+        if (to_pos + calculate_distance(pos, &segment[1]) - length).abs() < 0.1 {
+            return walked + to_pos;
+        }
+        walked += length;
+    }
+    panic!("Position {pos:?} is not on the path");
+}
+
+fn assert_evenly_spaced(points: &[Position], n: usize) {
+    let total: f64 = points
+        .windows(2)
+        .map(|segment| calculate_distance(&segment[0], &segment[1]))
+        .sum();
+    let markers = auto_sectors(points, n);
+    assert_eq!(markers.len(), n);
+    for (index, marker) in markers.iter().enumerate() {
+        let expected = total * (index + 1) as f64 / (n + 1) as f64;
+        let distance = distance_along(points, marker);
+        assert!(
+            (distance - expected).abs() < 1.0,
+            "Marker {index} at {distance} m, expected {expected} m"
+        );
+    }
+}
+
+#[test]
+fn auto_sectors_evenly_spaced_along_path() {
+    // An L-shaped path of 3000 m with unevenly distributed points.
+    let points = vec![
+        offset(0.0, 0.0),
+        offset(300.0, 0.0),
+        offset(1000.0, 0.0),
+        offset(1000.0, 200.0),
+        offset(1000.0, 2000.0),
+    ];
+    for n in [1, 2, 3, 5] {
+        assert_evenly_spaced(&points, n);
+    }
+}
+
+#[test]
+fn auto_sectors_more_than_points() {
+    let points = vec![offset(0.0, 0.0), offset(1000.0, 0.0)];
+    assert_evenly_spaced(&points, 9);
+}
+
+#[test]
+fn auto_sectors_without_path_length() {
+    assert!(auto_sectors(&[], 3).is_empty());
+    assert!(auto_sectors(&[offset(0.0, 0.0)], 3).is_empty());
+    assert!(auto_sectors(&[offset(0.0, 0.0), offset(0.0, 0.0)], 3).is_empty());
+    assert!(auto_sectors(&[offset(0.0, 0.0), offset(100.0, 0.0)], 0).is_empty());
+}
@@ -0,0 +1,66 @@
+// SPDX-FileCopyrightText: 2026 All contributors
+//
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use algorithm::{
+    DistanceModel, calculate_distance, calculate_distance_haversine, calculate_distance_with,
+};
+use common::position::Position;
+use common::test_helper::track::get_track;
+
+fn position(latitude: f64, longitude: f64) -> Position {
+    Position {
+        latitude,
+        longitude,
+    }
+}
+
+#[test]
+fn haversine_distance_of_oschersleben_markers() {
+    let track = get_track();
+    let expected = [519.664, 385.573];
+    for (sector, expected) in track.sectors.iter().zip(expected) {
+        let haversine = calculate_distance_haversine(&track.startline, sector);
+        assert!(
+            (haversine - expected).abs() < 0.01,
+            "haversine distance {haversine}, expected {expected}"
+        );
+        // Both models agree on the short distances between the markers of a track.
+        let equirectangular = calculate_distance(&track.startline, sector);
+        assert!(
+            (equirectangular - haversine).abs() < 1.0,
+            "equirectangular distance {equirectangular}, haversine distance {haversine}"
+        );
+    }
+}
+
+#[test]
+fn haversine_distance_of_long_and_polar_distances() {
+    // One degree along a meridian.
+    let from = position(52.0, 11.0);
+    let to = position(53.0, 11.0);
+    assert!((calculate_distance_haversine(&from, &to) - 111_194.93).abs() < 0.01);
+    assert!((calculate_distance(&from, &to) - 111_300.0).abs() < 0.01);
+
+    // Across the north pole, where the equirectangular approximation breaks down.
+    let from = position(89.9, 0.0);
+    let to = position(89.9, 180.0);
+    assert!((calculate_distance_haversine(&from, &to) - 22_238.99).abs() < 0.01);
+    assert!(calculate_distance(&from, &to) > 40_000.0);
+}
+
+#[test]
+fn calculate_distance_with_selected_model() {
+    let track = get_track();
+    let (from, to) = (&track.startline, &track.sectors[0]);
+    assert_eq!(DistanceModel::default(), DistanceModel::Equirectangular);
+    assert_eq!(
+        calculate_distance_with(DistanceModel::Equirectangular, from, to),
+        calculate_distance(from, to)
+    );
+    assert_eq!(
+        calculate_distance_with(DistanceModel::Haversine, from, to),
+        calculate_distance_haversine(from, to)
+    );
+    assert_eq!(calculate_distance_haversine(from, from), 0.0);
+}
@@ -0,0 +1,81 @@
+// SPDX-FileCopyrightText: 2026 All contributors
+//
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use algorithm::{LocalFrame, calculate_distance_haversine};
+use common::position::Position;
+use common::test_helper::track::get_track;
+
+/// Maximum error of a round trip through the local frame in meters.
+const TOLERANCE_M: f64 = 0.01;
+
+#[test]
+fn origin_is_zero_in_local_frame() {
+    let track = get_track();
+    let frame = LocalFrame::new(&track.startline);
+
+    let (east, north) = frame.to_local(&track.startline);
+
+    assert!(east.abs() < TOLERANCE_M && north.abs() < TOLERANCE_M);
+}
+
+#[test]
+fn local_offsets_match_distance_and_direction() {
+    let track = get_track();
+    let frame = LocalFrame::new(&track.startline);
+
+    for sector in &track.sectors {
+        let (east, north) = frame.to_local(sector);
+        let distance = calculate_distance_haversine(&track.startline, sector);
+        // The spherical haversine distance deviates from the ellipsoid by a few per mille.
+        assert!(
+            (east.hypot(north) - distance).abs() < distance * 0.005,
+            "local distance {}, haversine distance {distance}",
+            east.hypot(north)
+        );
+        assert_eq!(east > 0.0, sector.longitude > track.startline.longitude);
+        assert_eq!(north > 0.0, sector.latitude > track.startline.latitude);
+    }
+}
+
+#[test]
+fn positions_round_trip_through_local_frame() {
+    let track = get_track();
+    let frame = LocalFrame::new(&track.startline);
+    let positions = [
+        track.startline,
+        track.sectors[0],
+        track.sectors[1],
+        Position {
+            latitude: track.startline.latitude + 0.01,
+            longitude: track.startline.longitude - 0.01,
+        },
+    ];
+
+    for pos in positions {
+        let (east, north) = frame.to_local(&pos);
+        let round_trip = frame.from_local(east, north);
+        let error = calculate_distance_haversine(&pos, &round_trip);
+        assert!(
+            error < TOLERANCE_M,
+            "round trip error of {error}m for {pos:?}"
+        );
+    }
+}
+
+#[test]
+fn offsets_round_trip_through_local_frame() {
+    let frame = LocalFrame::new(&Position {
+        latitude: 52.0,
+        longitude: 11.0,
+    });
+
+    for (east, north) in [(0.0, 0.0), (12.5, -3.0), (-800.0, 450.0), (1500.0, 1500.0)] {
+        let (round_trip_east, round_trip_north) = frame.to_local(&frame.from_local(east, north));
+        assert!(
+            (round_trip_east - east).abs() < TOLERANCE_M
+                && (round_trip_north - north).abs() < TOLERANCE_M,
+            "({east}, {north}) round tripped to ({round_trip_east}, {round_trip_north})"
+        );
+    }
+}
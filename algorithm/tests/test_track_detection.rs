@@ -2,9 +2,13 @@
 //
 // SPDX-License-Identifier: GPL-2.0-or-later
 
-use algorithm::is_on_track;
+use algorithm::{
+    DetectionResult, DistanceModel, TieBreak, TrackSelectionPolicy, detect, detect_with_model,
+    is_on_track, select_track,
+};
 use common::position::Position;
 use common::test_helper::track::get_track;
+use common::track::Track;
 
 #[test]
 fn position_is_in_radius() {
@@ -30,3 +34,158 @@ fn position_is_not_in_radius() {
     let detected_tracks = is_on_track(&tracks, &test_pos, detection_radius);
     assert_eq!(0, detected_tracks.len());
 }
+
+/// Returns a copy of the test track with the given name and start line.
+fn track_at(name: &str, latitude: f64, longitude: f64) -> Track {
+    Track {
+        name: name.to_string(),
+        startline: Position {
+            latitude,
+            longitude,
+        },
+        ..get_track()
+    }
+}
+
+#[test]
+fn detect_single_closest_track() {
+    let tracks = vec![
+        track_at("Far", 52.0030, 11.0),
+        track_at("Near", 52.0010, 11.0),
+    ];
+    let pos = Position {
+        latitude: 52.0,
+        longitude: 11.0,
+    };
+    for tie_break in [TieBreak::ByName, TieBreak::Ambiguous] {
+        assert_eq!(
+            detect(&tracks, &pos, 500, tie_break),
+            DetectionResult::Single(tracks[1].clone())
+        );
+    }
+}
+
+#[test]
+fn detect_tied_tracks() {
+    // Both start lines are about 111m away in opposite directions.
+    let tracks = vec![
+        track_at("Zandvoort", 52.001, 11.0),
+        track_at("Assen", 51.999, 11.0),
+    ];
+    let pos = Position {
+        latitude: 52.0,
+        longitude: 11.0,
+    };
+    assert_eq!(
+        detect(&tracks, &pos, 500, TieBreak::ByName),
+        DetectionResult::Single(tracks[1].clone())
+    );
+    assert_eq!(
+        detect(&tracks, &pos, 500, TieBreak::Ambiguous),
+        DetectionResult::Ambiguous(vec![tracks[1].clone(), tracks[0].clone()])
+    );
+}
+
+#[test]
+fn detect_no_track_outside_of_radius() {
+    let tracks = vec![track_at("Far", 52.01, 11.0)];
+    let pos = Position {
+        latitude: 52.0,
+        longitude: 11.0,
+    };
+    assert_eq!(
+        detect(&tracks, &pos, 500, TieBreak::Ambiguous),
+        DetectionResult::None
+    );
+    assert_eq!(
+        detect(&[], &pos, 500, TieBreak::ByName),
+        DetectionResult::None
+    );
+}
+
+#[test]
+fn track_detection_radius_overrides_global_radius() {
+    let pos = Position {
+        latitude: 52.0,
+        longitude: 11.0,
+    };
+    // Start lines roughly 1.1 km north of the position, outside of the global radius.
+    let circuit = Track {
+        detection_radius_m: Some(1500.0),
+        ..track_at("Circuit", 52.01, 11.0)
+    };
+    let kart = track_at("Kart", 52.01, 11.0);
+    let tracks = vec![circuit.clone(), kart];
+
+    let detected_tracks = is_on_track(&tracks, &pos, 500);
+    assert_eq!(detected_tracks, vec![&circuit]);
+    assert_eq!(
+        detect(&tracks, &pos, 500, TieBreak::ByName),
+        DetectionResult::Single(circuit)
+    );
+}
+
+#[test]
+fn select_nearest_of_several_tracks() {
+    let tracks = vec![
+        track_at("Far", 52.0030, 11.0),
+        track_at("Near", 52.0010, 11.0),
+    ];
+    let pos = Position {
+        latitude: 52.0,
+        longitude: 11.0,
+    };
+    assert_eq!(
+        select_track(&tracks, Some(&pos), TrackSelectionPolicy::Nearest),
+        Some(&tracks[1])
+    );
+    assert_eq!(
+        select_track(&tracks, None, TrackSelectionPolicy::Nearest),
+        Some(&tracks[0])
+    );
+    assert_eq!(
+        select_track(&tracks, Some(&pos), TrackSelectionPolicy::First),
+        Some(&tracks[0])
+    );
+    assert_eq!(
+        select_track(&tracks, Some(&pos), TrackSelectionPolicy::PromptAndWait),
+        None
+    );
+    assert_eq!(
+        select_track(
+            &tracks[..1],
+            Some(&pos),
+            TrackSelectionPolicy::PromptAndWait
+        ),
+        Some(&tracks[0])
+    );
+}
+
+#[test]
+fn detect_with_haversine_distance_model() {
+    let tracks = vec![track_at("Polar", 89.9, 180.0)];
+    let pos = Position {
+        latitude: 89.9,
+        longitude: 0.0,
+    };
+    assert_eq!(
+        detect_with_model(
+            &tracks,
+            &pos,
+            30_000,
+            TieBreak::ByName,
+            DistanceModel::Equirectangular
+        ),
+        DetectionResult::None
+    );
+    assert_eq!(
+        detect_with_model(
+            &tracks,
+            &pos,
+            30_000,
+            TieBreak::ByName,
+            DistanceModel::Haversine
+        ),
+        DetectionResult::Single(tracks[0].clone())
+    );
+}
@@ -0,0 +1,26 @@
+// SPDX-FileCopyrightText: 2026 All contributors
+//
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use chrono::{DateTime, Utc};
+
+/// A trait for reading the current wall clock time.
+///
+/// Implementations of this trait provide the date and time, e.g. of a new session. In
+/// contrast to an [`ElapsedTimeSource`](crate::elapsed_time_source::ElapsedTimeSource) the
+/// time isn't monotonic and follows adjustments of the system clock.
+pub trait Clock {
+    /// Returns the current date and time in UTC.
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// A [`Clock`] implementation that reads the system clock.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    /// Returns the current date and time of the system clock.
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
@@ -0,0 +1,84 @@
+// SPDX-FileCopyrightText: 2026 All contributors
+//
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+//! Formatting of lap, sector and running times.
+//!
+//! All times are formatted as `HH:MM:SS.mmm`, the format used by the REST API, the
+//! WebSocket messages and the stored sessions.
+
+use std::time::Duration;
+
+/// Formats a duration as `HH:MM:SS.mmm`.
+///
+/// Sub-millisecond parts are truncated. Durations of 100 hours and more get more hour digits
+/// instead of wrapping around.
+///
+/// # Example
+/// ```
+/// use common::format::format_lap_time;
+/// use std::time::Duration;
+///
+/// assert_eq!(format_lap_time(&Duration::from_millis(83_456)), "00:01:23.456");
+/// ```
+pub fn format_lap_time(duration: &Duration) -> String {
+    let secs = duration.as_secs();
+    format!(
+        "{:02}:{:02}:{:02}.{:03}",
+        secs / 3600,
+        (secs % 3600) / 60,
+        secs % 60,
+        duration.subsec_millis()
+    )
+}
+
+/// Parses a duration in the format `HH:MM:SS.mmm` or `HH:MM:SS`.
+///
+/// The fraction of a second may have up to nine digits. Returns `None` if the string is not
+/// in one of these formats or minutes or seconds are out of range.
+///
+/// # Example
+/// ```
+/// use common::format::parse_lap_time;
+/// use std::time::Duration;
+///
+/// assert_eq!(parse_lap_time("00:01:23.456"), Some(Duration::from_millis(83_456)));
+/// ```
+pub fn parse_lap_time(s: &str) -> Option<Duration> {
+    let mut parts = s.split(':');
+    let (hours, minutes, seconds) = (parts.next()?, parts.next()?, parts.next()?);
+    if parts.next().is_some() {
+        return None;
+    }
+    let (seconds, fraction) = match seconds.split_once('.') {
+        Some((seconds, fraction)) => (seconds, Some(fraction)),
+        None => (seconds, None),
+    };
+    let hours = parse_digits(hours, 2, usize::MAX)?;
+    let minutes = parse_digits(minutes, 2, 2)?;
+    let seconds = parse_digits(seconds, 2, 2)?;
+    if minutes >= 60 || seconds >= 60 {
+        return None;
+    }
+    let nanos = match fraction {
+        Some(fraction) => {
+            let digits = parse_digits(fraction, 1, 9)?;
+            digits * 10u64.pow(9 - fraction.len() as u32)
+        }
+        None => 0,
+    };
+    Some(Duration::new(
+        hours
+            .checked_mul(3600)?
+            .checked_add(minutes * 60 + seconds)?,
+        nanos as u32,
+    ))
+}
+
+/// Parses an unsigned number consisting of `min` to `max` ASCII digits.
+fn parse_digits(s: &str, min: usize, max: usize) -> Option<u64> {
+    if s.len() < min || s.len() > max || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    s.parse().ok()
+}
@@ -0,0 +1,74 @@
+// SPDX-FileCopyrightText: 2026 All contributors
+//
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+//! Typed identifiers of the stored sessions and tracks.
+//!
+//! Both identifiers are strings, the newtypes prevent passing the id of a track where the id
+//! of a session is expected and vice versa. They serialize as plain strings.
+//!
+//! The identifiers don't convert into each other:
+//!
+//! ```compile_fail
+//! use common::id::{SessionId, TrackId};
+//!
+//! let track_id = TrackId::from("oschersleben");
+//! let session_id: SessionId = track_id.into();
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+macro_rules! string_id {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        #[derive(Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+        #[serde(transparent)]
+        pub struct $name(String);
+
+        impl $name {
+            /// Creates a new identifier from the given string.
+            pub fn new(id: impl Into<String>) -> Self {
+                $name(id.into())
+            }
+
+            /// Returns the identifier as string slice, e.g. to build a file name.
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+
+            /// Consumes the identifier and returns the inner string.
+            pub fn into_string(self) -> String {
+                self.0
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+
+        impl From<&str> for $name {
+            fn from(id: &str) -> Self {
+                $name(id.to_owned())
+            }
+        }
+
+        impl From<String> for $name {
+            fn from(id: String) -> Self {
+                $name(id)
+            }
+        }
+    };
+}
+
+string_id!(
+    /// Identifier of a stored session, e.g. `oschersleben_01_01_1970_13_00_00_000`.
+    SessionId
+);
+
+string_id!(
+    /// Identifier of a stored track, e.g. `oschersleben`.
+    TrackId
+);
@@ -2,7 +2,10 @@
 //
 // SPDX-License-Identifier: GPL-2.0-or-later
 
-use crate::{position::GnssPosition, serde::duration_list};
+use crate::{
+    position::{GnssPosition, Position},
+    serde::duration_list,
+};
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
@@ -78,6 +81,63 @@ impl Lap {
         laptime
     }
 
+    /// Returns the highest velocity of the log points and the position it was reached at.
+    ///
+    /// If several log points share the highest velocity, the first of them is returned.
+    /// Returns `None` for a lap without log points.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use chrono::{NaiveDate, NaiveTime};
+    /// use common::{lap::Lap, position::{GnssPosition, Position}};
+    ///
+    /// let date = NaiveDate::from_ymd_opt(1970, 1, 1).unwrap();
+    /// let time = NaiveTime::from_hms_opt(13, 0, 0).unwrap();
+    /// let lap = Lap {
+    ///     sectors: vec![],
+    ///     log_points: vec![
+    ///         GnssPosition::new(52.0, 11.0, 30.0, &time, &date),
+    ///         GnssPosition::new(52.1, 11.1, 45.0, &time, &date),
+    ///     ],
+    /// };
+    ///
+    /// assert_eq!(lap.max_speed(), Some((45.0, Position::new(&52.1, &11.1))));
+    /// ```
+    pub fn max_speed(&self) -> Option<(f64, Position)> {
+        self.log_points
+            .iter()
+            .reduce(|max, point| {
+                if point.velocity() > max.velocity() {
+                    point
+                } else {
+                    max
+                }
+            })
+            .map(|point| (point.velocity(), point.to_position()))
+    }
+
+    /// Returns the time of the sector with the given zero based index, or `None` if the lap
+    /// has no such sector.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use common::lap::Lap;
+    ///
+    /// let lap = Lap {
+    ///     sectors: vec![Duration::from_secs(30), Duration::from_secs(32)],
+    ///     log_points: vec![],
+    /// };
+    ///
+    /// assert_eq!(lap.sector(1), Some(Duration::from_secs(32)));
+    /// assert_eq!(lap.sector(2), None);
+    /// ```
+    pub fn sector(&self, index: usize) -> Option<Duration> {
+        self.sectors.get(index).copied()
+    }
+
     pub fn from_json(json: &str) -> serde_json::Result<Self> {
         serde_json::from_str(json)
     }
@@ -6,7 +6,10 @@
 //!
 //! Provides the common data types that are used across every modul.
 
+pub mod clock;
 pub mod elapsed_time_source;
+pub mod format;
+pub mod id;
 pub mod lap;
 pub mod position;
 pub mod serde;
@@ -3,7 +3,7 @@
 // SPDX-License-Identifier: GPL-2.0-or-later
 
 use crate::serde::{date, time};
-use chrono::{NaiveDate, NaiveTime};
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
 use serde::{Deserialize, Serialize};
 
 /// Represents a geographical coordinate with latitude and longitude.
@@ -69,12 +69,32 @@ impl Position {
     pub fn from_json(json: &str) -> serde_json::Result<Self> {
         serde_json::from_str(json)
     }
+
+    /// Calculates the approximate distance in meters to another position.
+    ///
+    /// Uses a simplified equirectangular approximation, which assumes that the Earth's
+    /// surface is locally flat. It's suitable for the short distances on a race track
+    /// but loses accuracy over long distances or near the poles.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The position to measure the distance to.
+    ///
+    /// # Returns
+    ///
+    /// The distance between both positions in meters.
+    pub fn distance_to(&self, other: &Position) -> f64 {
+        let lat = (self.latitude + other.latitude) / 2.0 * 0.01745;
+        let dx = 111300.0 * lat.cos() * (self.longitude - other.longitude);
+        let dy = 111300.0 * (self.latitude - other.latitude);
+        (dx * dx + dy * dy).sqrt()
+    }
 }
 
 /// Represents a GNSS (Global Navigation Satellite System) position reading.
 ///
 /// This structure stores the latitude, longitude, velocity, and timestamp
-/// of a GNSS fix using UTC time.
+/// of a GNSS fix using UTC time, and the altitude if the source reports it.
 #[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub struct GnssPosition {
     latitude: f64,
@@ -84,6 +104,8 @@ pub struct GnssPosition {
     time: NaiveTime,
     #[serde(with = "date")]
     date: NaiveDate,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    altitude: Option<f64>,
 }
 
 impl GnssPosition {
@@ -122,9 +144,27 @@ impl GnssPosition {
             velocity,
             time: *time,
             date: *date,
+            altitude: None,
         }
     }
 
+    /// Returns the position with the given altitude in meters above mean sea level.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use common::position::GnssPosition;
+    ///
+    /// let time = chrono::Utc::now();
+    /// let pos = GnssPosition::new(52.0, 13.0, 15.5, &time.time(), &time.date_naive())
+    ///     .with_altitude(87.5);
+    /// assert_eq!(pos.altitude(), Some(87.5));
+    /// ```
+    pub fn with_altitude(mut self, altitude: f64) -> GnssPosition {
+        self.altitude = Some(altitude);
+        self
+    }
+
     pub fn from_json(json: &str) -> serde_json::Result<Self> {
         serde_json::from_str(json)
     }
@@ -162,6 +202,59 @@ impl GnssPosition {
     pub fn velocity(&self) -> f64 {
         self.velocity
     }
+
+    /// Returns the altitude of the GNSS fix.
+    ///
+    /// # Returns
+    ///
+    /// `Option<f64>` – The altitude in meters above mean sea level, `None` if not reported.
+    pub fn altitude(&self) -> Option<f64> {
+        self.altitude
+    }
+
+    /// Returns the UTC time of the GNSS fix.
+    ///
+    /// # Returns
+    ///
+    /// `NaiveTime` – The time of the fix.
+    pub fn time(&self) -> NaiveTime {
+        self.time
+    }
+
+    /// Returns the UTC date of the GNSS fix.
+    ///
+    /// # Returns
+    ///
+    /// `NaiveDate` – The date of the fix.
+    pub fn date(&self) -> NaiveDate {
+        self.date
+    }
+
+    /// Returns the combined UTC date and time of the GNSS fix.
+    ///
+    /// # Returns
+    ///
+    /// `NaiveDateTime` – The timestamp of the fix.
+    pub fn timestamp(&self) -> NaiveDateTime {
+        self.date.and_time(self.time)
+    }
+
+    /// Compares two GNSS positions with a tolerance, ignoring the time of the fix.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` – The position to compare with.
+    /// * `lat_lon_eps` – Maximum allowed difference of latitude and longitude in decimal degrees.
+    /// * `vel_eps` – Maximum allowed difference of the velocity.
+    ///
+    /// # Returns
+    ///
+    /// `true` if latitude, longitude and velocity are within the given tolerances.
+    pub fn approx_eq(&self, other: &GnssPosition, lat_lon_eps: f64, vel_eps: f64) -> bool {
+        (self.latitude - other.latitude).abs() <= lat_lon_eps
+            && (self.longitude - other.longitude).abs() <= lat_lon_eps
+            && (self.velocity - other.velocity).abs() <= vel_eps
+    }
 }
 
 // The GNSS status from a GNSS source
@@ -173,7 +266,7 @@ pub enum GnssStatus {
     NoFix,
     // The GNSS system is in the 2d fix mode only latitude and longitude are valid
     Fix2d,
-    // The GNSS system is in the 3d Fix mode latitue, longitude and alitude(currently not reported) are valid
+    // The GNSS system is in the 3d Fix mode latitue, longitude and altitude are valid
     Fix3d,
 }
 
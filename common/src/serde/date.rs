@@ -7,6 +7,9 @@ use serde::{self, Deserialize, Deserializer, Serializer};
 
 const FORMAT: &str = "%d.%m.%Y"; // Custom format: DD.MM.YYYY
 
+/// Formats accepted on deserialization, e.g. `24.12.2025` and `2025-12-24`.
+const ACCEPTED_FORMATS: [&str; 2] = [FORMAT, "%Y-%m-%d"];
+
 pub fn serialize<S>(date: &NaiveDate, serializer: S) -> Result<S::Ok, S::Error>
 where
     S: Serializer,
@@ -15,10 +18,18 @@ where
     serializer.serialize_str(&s)
 }
 
+/// Deserializes a date in the format `DD.MM.YYYY` or `YYYY-MM-DD`.
 pub fn deserialize<'de, D>(deserializer: D) -> Result<NaiveDate, D::Error>
 where
     D: Deserializer<'de>,
 {
     let s = String::deserialize(deserializer)?;
-    NaiveDate::parse_from_str(&s, FORMAT).map_err(serde::de::Error::custom)
+    ACCEPTED_FORMATS
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(&s, format).ok())
+        .ok_or_else(|| {
+            serde::de::Error::custom(format!(
+                "invalid date \"{s}\", expected DD.MM.YYYY or YYYY-MM-DD"
+            ))
+        })
 }
@@ -7,6 +7,16 @@ use serde::{self, Deserialize, Deserializer, Serializer};
 
 const FORMAT: &str = "%H:%M:%S%.3f"; // Custom format with milliseconds
 
+/// Formats accepted on deserialization, e.g. `13:45:07.123` and `13:45:07`.
+const ACCEPTED_FORMATS: [&str; 2] = [FORMAT, "%H:%M:%S"];
+
+/// Parses a time in one of the accepted formats.
+pub(crate) fn parse(s: &str) -> Option<NaiveTime> {
+    ACCEPTED_FORMATS
+        .iter()
+        .find_map(|format| NaiveTime::parse_from_str(s, format).ok())
+}
+
 pub fn serialize<S>(time: &NaiveTime, serializer: S) -> Result<S::Ok, S::Error>
 where
     S: Serializer,
@@ -15,10 +25,15 @@ where
     serializer.serialize_str(&s)
 }
 
+/// Deserializes a time in the format `HH:MM:SS.mmm` or `HH:MM:SS`.
 pub fn deserialize<'de, D>(deserializer: D) -> Result<NaiveTime, D::Error>
 where
     D: Deserializer<'de>,
 {
     let s = String::deserialize(deserializer)?;
-    NaiveTime::parse_from_str(&s, FORMAT).map_err(serde::de::Error::custom)
+    parse(&s).ok_or_else(|| {
+        serde::de::Error::custom(format!(
+            "invalid time \"{s}\", expected HH:MM:SS.mmm or HH:MM:SS"
+        ))
+    })
 }
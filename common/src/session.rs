@@ -2,9 +2,10 @@
 //
 // SPDX-License-Identifier: GPL-2.0-or-later
 
-use crate::{lap::Lap, serde::date, serde::time, track::Track};
+use crate::{id::SessionId, lap::Lap, serde::date, serde::time, track::Track};
 use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
 /// `SessionInfo` contains only high-level metadata useful for listing or indexing
 /// sessions without loading full lap data.
@@ -19,7 +20,7 @@ use serde::{Deserialize, Serialize};
 /// See also: [`Session`].
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SessionInfo {
-    pub id: String,
+    pub id: SessionId,
     pub date: NaiveDateTime,
     pub track_name: String,
     pub laps: usize,
@@ -34,9 +35,14 @@ impl SessionInfo {
     /// * `date` – Calendar date of the session (date-only, no time zone).
     /// * `track_name` – Track on which the session took place.
     /// * `laps` – Total number of completed laps in the session.
-    pub fn new(id: String, date: NaiveDateTime, track_name: String, laps: usize) -> Self {
+    pub fn new(
+        id: impl Into<SessionId>,
+        date: NaiveDateTime,
+        track_name: String,
+        laps: usize,
+    ) -> Self {
         SessionInfo {
-            id,
+            id: id.into(),
             date,
             track_name,
             laps,
@@ -77,6 +83,10 @@ impl SessionInfo {
 /// - `time` – The time of day when the session started.
 /// - `track` – The track configuration (`Track`) used during the session.
 /// - `laps` – A list of completed laps (`Lap`) with sector times and telemetry.
+/// - `session_group` – Optional id shared by sessions that were split from one
+///   continuous run (e.g. because of a maximum lap count per session).
+/// - `finalized` – Whether the recording of the session is completed. Sessions that are
+///   stored while they are still recorded are open, i.e. not finalized.
 ///
 /// # Example
 ///
@@ -96,8 +106,11 @@ impl SessionInfo {
 ///             Position { latitude: 52.01, longitude: 13.01 },
 ///             Position { latitude: 52.02, longitude: 13.02 },
 ///         ],
+///         detection_radius_m: None,
 ///     },
 ///     laps: vec![], // Add laps here
+///     session_group: None,
+///     finalized: true,
 /// };
 /// ```
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
@@ -109,6 +122,19 @@ pub struct Session {
     pub time: NaiveTime,
     pub track: Track,
     pub laps: Vec<Lap>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_group: Option<String>,
+    #[serde(default = "finalized_default", skip_serializing_if = "is_finalized")]
+    pub finalized: bool,
+}
+
+/// Sessions stored without the `finalized` flag were recorded completely.
+fn finalized_default() -> bool {
+    true
+}
+
+fn is_finalized(finalized: &bool) -> bool {
+    *finalized
 }
 
 impl Session {
@@ -118,7 +144,9 @@ impl Session {
     /// - an `id` of `0`, which can later be replaced with a unique identifier,
     /// - the provided [`NaiveDate`] and [`NaiveTime`] values,
     /// - the provided [`Track`],
-    /// - and an empty list of laps.
+    /// - an empty list of laps,
+    /// - no session group,
+    /// - and the finalized state.
     ///
     /// This function provides a convenient way to construct a fresh session
     /// before laps or a persisted identifier are added.
@@ -129,9 +157,65 @@ impl Session {
             time,
             track,
             laps: vec![],
+            session_group: None,
+            finalized: true,
         }
     }
 
+    /// Returns the best time of every sector over all laps of the session.
+    ///
+    /// Returns `None` if the session has no laps or the laps have different numbers of
+    /// sectors, e.g. because the track was changed during the session.
+    pub fn best_sectors(&self) -> Option<Vec<Duration>> {
+        let sector_count = self.laps.first()?.sectors.len();
+        if self
+            .laps
+            .iter()
+            .any(|lap| lap.sectors.len() != sector_count)
+        {
+            return None;
+        }
+        (0..sector_count)
+            .map(|index| self.laps.iter().filter_map(|lap| lap.sector(index)).min())
+            .collect()
+    }
+
+    /// Returns the theoretical best lap time of the session, the sum of the
+    /// [`best_sectors`](Self::best_sectors).
+    ///
+    /// Returns `None` under the same conditions as [`Session::best_sectors`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use chrono::{NaiveDate, NaiveTime};
+    /// use common::{lap::Lap, position::Position, session::Session, track::Track};
+    ///
+    /// let track = Track {
+    ///     name: "Oschersleben".to_string(),
+    ///     startline: Position::new(&52.0258, &11.2792),
+    ///     finishline: None,
+    ///     sectors: vec![],
+    ///     detection_radius_m: None,
+    /// };
+    /// let lap = |sectors: [u64; 2]| Lap {
+    ///     sectors: sectors.into_iter().map(Duration::from_secs).collect(),
+    ///     log_points: vec![],
+    /// };
+    /// let mut session = Session::new(
+    ///     NaiveDate::from_ymd_opt(1970, 1, 1).unwrap(),
+    ///     NaiveTime::from_hms_opt(13, 0, 0).unwrap(),
+    ///     track,
+    /// );
+    /// session.laps = vec![lap([30, 35]), lap([32, 33])];
+    ///
+    /// assert_eq!(session.theoretical_best_lap(), Some(Duration::from_secs(63)));
+    /// ```
+    pub fn theoretical_best_lap(&self) -> Option<Duration> {
+        self.best_sectors().map(|sectors| sectors.iter().sum())
+    }
+
     /// Deserializes a [`Session`] instance from a JSON string.
     ///
     /// This method parses the provided JSON string and attempts to construct a [`Session`]
@@ -166,4 +250,11 @@ impl Session {
     pub fn to_json(session: &Session) -> serde_json::Result<String> {
         serde_json::to_string(session)
     }
+
+    /// Serializes a [`Session`] into an indented, human readable JSON `String`.
+    ///
+    /// Same as [`Session::to_json`] but uses [`serde_json::to_string_pretty`].
+    pub fn to_json_pretty(session: &Session) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(session)
+    }
 }
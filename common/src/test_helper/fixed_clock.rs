@@ -0,0 +1,27 @@
+// SPDX-FileCopyrightText: 2026 All contributors
+//
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use crate::clock::Clock;
+use chrono::{DateTime, Utc};
+
+/// A test implementation of [`Clock`] that always returns the same date and time,
+/// so the wall clock time is deterministic in tests.
+#[derive(Clone, Copy, Debug)]
+pub struct FixedClock {
+    now: DateTime<Utc>,
+}
+
+impl FixedClock {
+    /// Creates a clock that always returns `now`.
+    pub fn new(now: DateTime<Utc>) -> Self {
+        FixedClock { now }
+    }
+}
+
+impl Clock for FixedClock {
+    /// Returns the fixed date and time.
+    fn now(&self) -> DateTime<Utc> {
+        self.now
+    }
+}
@@ -3,5 +3,6 @@
 // SPDX-License-Identifier: GPL-2.0-or-later
 
 pub mod elapsed_test_time_source;
+pub mod fixed_clock;
 pub mod session;
 pub mod track;
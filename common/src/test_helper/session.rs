@@ -102,10 +102,13 @@ pub fn get_session() -> Session {
                     longitude: 11.279166,
                 },
             ],
+            detection_radius_m: None,
         },
         laps: vec![Lap {
             sectors: vec![time, time, time, time],
             log_points: vec![log_point, log_point],
         }],
+        session_group: None,
+        finalized: true,
     }
 }
@@ -29,5 +29,6 @@ pub fn get_track() -> Track {
                 longitude: 11.2772076,
             },
         ],
+        detection_radius_m: None,
     }
 }
@@ -4,6 +4,41 @@
 
 use crate::position::Position;
 use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Minimum distance in meters between a sector and its neighbouring markers.
+pub const MIN_SECTOR_SPACING: f64 = 10.0;
+
+/// Reasons why the sectors of a track are rejected.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SectorValidationError {
+    /// The track has no sectors.
+    Empty,
+
+    /// The sector with the given index has coordinates outside of the valid range.
+    InvalidCoordinate(usize),
+
+    /// The sector with the given index is closer than [`MIN_SECTOR_SPACING`] to its preceding
+    /// marker or, for the last sector, to the finish line.
+    TooClose(usize),
+}
+
+impl fmt::Display for SectorValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SectorValidationError::Empty => write!(f, "The track has no sectors"),
+            SectorValidationError::InvalidCoordinate(index) => {
+                write!(f, "Sector {index} has invalid coordinates")
+            }
+            SectorValidationError::TooClose(index) => write!(
+                f,
+                "Sector {index} is closer than {MIN_SECTOR_SPACING}m to its neighbouring marker"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SectorValidationError {}
 
 /// Represents a race track with optional finish line and defined sectors.
 ///
@@ -31,6 +66,7 @@ use serde::{Deserialize, Serialize};
 ///         Position { latitude: 52.01, longitude: 13.01 },
 ///         Position { latitude: 52.02, longitude: 13.02 },
 ///     ],
+///     detection_radius_m: None,
 /// };
 /// ```
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -39,6 +75,10 @@ pub struct Track {
     pub startline: Position,
     pub finishline: Option<Position>,
     pub sectors: Vec<Position>,
+    /// Detection radius in meters around the start line that overrides the global radius
+    /// of the track detection, e.g. for large circuits. `None` uses the global radius.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detection_radius_m: Option<f64>,
 }
 
 impl Track {
@@ -59,4 +99,157 @@ impl Track {
     pub fn from_json(json: &str) -> serde_json::Result<Self> {
         serde_json::from_str(json)
     }
+
+    /// Serializes a [`Track`] into a JSON `String`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` – A JSON-formatted string representing the track.
+    /// * `Err(serde_json::Error)` – If serialization fails.
+    pub fn to_json(track: &Track) -> serde_json::Result<String> {
+        serde_json::to_string(track)
+    }
+
+    /// Validates the sectors of the track.
+    ///
+    /// The track needs at least one sector, every sector needs a latitude within ±90° and a
+    /// longitude within ±180°, and every sector needs a distance of at least
+    /// [`MIN_SECTOR_SPACING`] to its preceding marker. The last sector needs the same distance
+    /// to the finish line, which is the start line for circuits.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` – If the sectors are valid.
+    /// * `Err(SectorValidationError)` – The first violation found.
+    pub fn validate_sectors(&self) -> Result<(), SectorValidationError> {
+        let Some(last) = self.sectors.last() else {
+            return Err(SectorValidationError::Empty);
+        };
+        let mut previous = &self.startline;
+        for (index, sector) in self.sectors.iter().enumerate() {
+            if !(-90.0..=90.0).contains(&sector.latitude)
+                || !(-180.0..=180.0).contains(&sector.longitude)
+            {
+                return Err(SectorValidationError::InvalidCoordinate(index));
+            }
+            if previous.distance_to(sector) < MIN_SECTOR_SPACING {
+                return Err(SectorValidationError::TooClose(index));
+            }
+            previous = sector;
+        }
+        let finishline = self.finishline.as_ref().unwrap_or(&self.startline);
+        if last.distance_to(finishline) < MIN_SECTOR_SPACING {
+            return Err(SectorValidationError::TooClose(self.sectors.len() - 1));
+        }
+        Ok(())
+    }
+
+    /// Returns the total length of the track in meters.
+    ///
+    /// The length is approximated by straight lines from the start line over all
+    /// sectors to the finish line. Without a finish line, or with a finish line at
+    /// the start line, the track is a circuit and the way back to the start line is
+    /// included. Otherwise the track is a point-to-point stage ending at the finish line.
+    ///
+    /// # Returns
+    ///
+    /// The track length in meters, `0.0` for a circuit without sectors.
+    pub fn length_meters(&self) -> f64 {
+        self.sector_distances()
+            .last()
+            .map_or(0.0, |sector| sector.cumulative_distance)
+    }
+
+    /// Returns the end markers of the sectors with their distances along the track.
+    ///
+    /// A track with `n` sector markers has `n + 1` sectors, the last one ends at the finish
+    /// line, which is the start line for circuits. The distances are approximated by straight
+    /// lines between the markers like [`Track::length_meters`], so the cumulative distance of
+    /// the last sector is the track length.
+    pub fn sector_distances(&self) -> Vec<SectorDistance> {
+        let finishline = self.finishline.unwrap_or(self.startline);
+        let mut previous = &self.startline;
+        let mut cumulative_distance = 0.0;
+        self.sectors
+            .iter()
+            .chain(std::iter::once(&finishline))
+            .map(|position| {
+                let segment_length = previous.distance_to(position);
+                cumulative_distance += segment_length;
+                previous = position;
+                SectorDistance {
+                    position: *position,
+                    segment_length,
+                    cumulative_distance,
+                }
+            })
+            .collect()
+    }
+}
+
+/// The end marker of a sector with its distance along the track.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SectorDistance {
+    /// Position of the marker that ends the sector, the finish line for the last sector.
+    pub position: Position,
+
+    /// Length of the sector from its start marker in meters.
+    pub segment_length: f64,
+
+    /// Distance of the end marker from the start line in meters.
+    pub cumulative_distance: f64,
+}
+
+/// Returns a copy of the track whose sectors are at least `min_spacing_m` meters apart.
+///
+/// Consecutive sectors closer than `min_spacing_m` to each other are merged into a single
+/// sector at their mean position, e.g. for auto-generated or imported tracks whose sectors
+/// can't be distinguished at speed. Sectors closer than `min_spacing_m` to the start line,
+/// or the last sector to the finish line, are removed. With [`MIN_SECTOR_SPACING`] the
+/// cleaned track passes the spacing checks of [`Track::validate_sectors`].
+///
+/// # Example
+///
+/// ```rust
+/// use common::{position::Position, track::{Track, clean_sectors}};
+///
+/// let track = Track {
+///     name: "Example Track".into(),
+///     startline: Position { latitude: 52.0, longitude: 13.0 },
+///     finishline: None,
+///     sectors: vec![
+///         Position { latitude: 52.01, longitude: 13.0 },
+///         Position { latitude: 52.01002, longitude: 13.0 },
+///     ],
+///     detection_radius_m: None,
+/// };
+/// assert_eq!(clean_sectors(&track, 10.0).sectors.len(), 1);
+/// ```
+pub fn clean_sectors(track: &Track, min_spacing_m: f64) -> Track {
+    // Merged sectors with the number of sectors they were merged from.
+    let mut merged: Vec<(Position, usize)> = Vec::with_capacity(track.sectors.len());
+    for sector in &track.sectors {
+        match merged.last_mut() {
+            Some((position, count)) if position.distance_to(sector) < min_spacing_m => {
+                let weight = *count as f64;
+                position.latitude = (position.latitude * weight + sector.latitude) / (weight + 1.0);
+                position.longitude =
+                    (position.longitude * weight + sector.longitude) / (weight + 1.0);
+                *count += 1;
+            }
+            _ if track.startline.distance_to(sector) < min_spacing_m => (),
+            _ => merged.push((*sector, 1)),
+        }
+    }
+    let finishline = track.finishline.as_ref().unwrap_or(&track.startline);
+    if merged
+        .last()
+        .is_some_and(|(position, _)| position.distance_to(finishline) < min_spacing_m)
+    {
+        merged.pop();
+    }
+    Track {
+        sectors: merged.into_iter().map(|(position, _)| position).collect(),
+        ..track.clone()
+    }
 }
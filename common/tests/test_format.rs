@@ -0,0 +1,70 @@
+// SPDX-FileCopyrightText: 2026 All contributors
+//
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use common::format::{format_lap_time, parse_lap_time};
+use std::time::Duration;
+
+#[test]
+fn format_sub_second_lap_time() {
+    assert_eq!(format_lap_time(&Duration::ZERO), "00:00:00.000");
+    assert_eq!(format_lap_time(&Duration::from_millis(7)), "00:00:00.007");
+    // Sub-millisecond parts are truncated, not rounded.
+    assert_eq!(
+        format_lap_time(&Duration::from_micros(999_999)),
+        "00:00:00.999"
+    );
+}
+
+#[test]
+fn format_minutes_lap_time() {
+    assert_eq!(
+        format_lap_time(&Duration::from_millis(83_456)),
+        "00:01:23.456"
+    );
+    assert_eq!(
+        format_lap_time(&Duration::from_millis(3_599_999)),
+        "00:59:59.999"
+    );
+}
+
+#[test]
+fn format_hour_spanning_lap_time() {
+    assert_eq!(format_lap_time(&Duration::from_secs(3600)), "01:00:00.000");
+    assert_eq!(
+        format_lap_time(&Duration::from_millis(37_230_010)),
+        "10:20:30.010"
+    );
+    // Durations of a day and more don't wrap around.
+    assert_eq!(
+        format_lap_time(&Duration::from_secs(25 * 3600)),
+        "25:00:00.000"
+    );
+}
+
+#[test]
+fn round_trip_lap_time() {
+    for millis in [0, 7, 83_456, 3_599_999, 37_230_010, 90_000_000] {
+        let duration = Duration::from_millis(millis);
+        assert_eq!(parse_lap_time(&format_lap_time(&duration)), Some(duration));
+    }
+}
+
+#[test]
+fn parse_lap_time_formats() {
+    assert_eq!(parse_lap_time("00:01:23"), Some(Duration::from_secs(83)));
+    assert_eq!(
+        parse_lap_time("00:01:23.4"),
+        Some(Duration::from_millis(83_400))
+    );
+    assert_eq!(
+        parse_lap_time("00:00:00.000000001"),
+        Some(Duration::from_nanos(1))
+    );
+    assert_eq!(parse_lap_time("83.456"), None);
+    assert_eq!(parse_lap_time("00:01"), None);
+    assert_eq!(parse_lap_time("00:60:00.000"), None);
+    assert_eq!(parse_lap_time("00:00:23."), None);
+    assert_eq!(parse_lap_time("00:0a:23.456"), None);
+    assert_eq!(parse_lap_time("00:00:00:00"), None);
+}
@@ -33,3 +33,30 @@ pub fn dserialize_gnss_position_from_json() {
         .unwrap_or_else(|e| panic!("Failed to deserialize the raw json. Reason: {e}"));
     assert_eq!(pos, get_gnss_position());
 }
+
+#[test]
+pub fn approx_eq_within_epsilon() {
+    let pos = get_gnss_position();
+    let other = GnssPosition::new(
+        52.025833 + 0.000_000_5,
+        11.279166 - 0.000_000_5,
+        10.05,
+        &NaiveTime::parse_from_str("12:00:00.000", "%H:%M:%S%.3f").unwrap(),
+        &NaiveDate::parse_from_str("02.01.1970", "%d.%m.%Y").unwrap(),
+    );
+    assert!(pos.approx_eq(&other, 0.000_001, 0.1));
+    assert!(other.approx_eq(&pos, 0.000_001, 0.1));
+}
+
+#[test]
+pub fn approx_eq_outside_epsilon() {
+    let pos = get_gnss_position();
+    let time = NaiveTime::parse_from_str("00:00:00.000", "%H:%M:%S%.3f").unwrap();
+    let date = NaiveDate::parse_from_str("01.01.1970", "%d.%m.%Y").unwrap();
+    let latitude_off = GnssPosition::new(52.025843, 11.279166, 10.0, &time, &date);
+    let longitude_off = GnssPosition::new(52.025833, 11.279156, 10.0, &time, &date);
+    let velocity_off = GnssPosition::new(52.025833, 11.279166, 10.5, &time, &date);
+    assert!(!pos.approx_eq(&latitude_off, 0.000_001, 0.1));
+    assert!(!pos.approx_eq(&longitude_off, 0.000_001, 0.1));
+    assert!(!pos.approx_eq(&velocity_off, 0.000_001, 0.1));
+}
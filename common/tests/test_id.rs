@@ -0,0 +1,56 @@
+// SPDX-FileCopyrightText: 2026 All contributors
+//
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use common::id::{SessionId, TrackId};
+
+#[test]
+fn create_ids_from_strings() {
+    let session_id = SessionId::from("oschersleben_01_01_1970_13_00_00_000");
+    assert_eq!(
+        session_id,
+        SessionId::new("oschersleben_01_01_1970_13_00_00_000")
+    );
+    assert_eq!(
+        session_id,
+        SessionId::from("oschersleben_01_01_1970_13_00_00_000".to_owned())
+    );
+    assert_eq!(session_id.as_str(), "oschersleben_01_01_1970_13_00_00_000");
+    assert_eq!(
+        session_id.to_string(),
+        "oschersleben_01_01_1970_13_00_00_000"
+    );
+
+    let track_id = TrackId::from("Oschersleben");
+    assert_eq!(track_id.as_str(), "Oschersleben");
+    assert_eq!(format!("{track_id}"), "Oschersleben");
+    assert_eq!(track_id.into_string(), "Oschersleben");
+}
+
+#[test]
+fn serialize_ids_as_plain_strings() {
+    let session_id = SessionId::from("session");
+    let json = serde_json::to_string(&session_id).unwrap();
+    assert_eq!(json, r#""session""#);
+    assert_eq!(
+        serde_json::from_str::<SessionId>(&json).unwrap(),
+        session_id
+    );
+
+    let track_ids = vec![TrackId::from("Most"), TrackId::from("Oschersleben")];
+    let json = serde_json::to_string(&track_ids).unwrap();
+    assert_eq!(json, r#"["Most","Oschersleben"]"#);
+    assert_eq!(
+        serde_json::from_str::<Vec<TrackId>>(&json).unwrap(),
+        track_ids
+    );
+}
+
+#[test]
+fn convert_ids_only_explicitly_via_string() {
+    // A track id can only become a session id by going through its string, see the
+    // compile_fail example of the `id` module for the implicit conversion.
+    let track_id = TrackId::from("Oschersleben");
+    let session_id = SessionId::from(track_id.as_str());
+    assert_eq!(session_id.as_str(), track_id.as_str());
+}
@@ -3,7 +3,10 @@
 // SPDX-License-Identifier: GPL-2.0-or-later
 
 use chrono::{NaiveDate, NaiveTime};
-use common::{lap::Lap, position::GnssPosition};
+use common::{
+    lap::Lap,
+    position::{GnssPosition, Position},
+};
 use std::str::FromStr;
 use std::time::Duration;
 
@@ -83,3 +86,33 @@ fn derserialize_lap_from_json() {
         .unwrap_or_else(|e| panic!("Failed to deserialize the raw json. Reason: {e}"));
     assert_eq!(lap, get_lap());
 }
+
+#[test]
+fn max_speed_of_lap_with_varying_speeds() {
+    let time = NaiveTime::from_str("13:00:00.000").unwrap();
+    let date = NaiveDate::from_ymd_opt(1970, 1, 1).unwrap();
+    let point =
+        |latitude: f64, velocity: f64| GnssPosition::new(latitude, 11.0, velocity, &time, &date);
+    let lap = Lap {
+        sectors: vec![],
+        log_points: vec![
+            point(52.0, 20.5),
+            point(52.1, 48.0),
+            point(52.2, 61.25),
+            point(52.3, 61.25),
+            point(52.4, 33.0),
+        ],
+    };
+
+    assert_eq!(lap.max_speed(), Some((61.25, Position::new(&52.2, &11.0))));
+}
+
+#[test]
+fn max_speed_of_lap_without_log_points() {
+    let lap = Lap {
+        sectors: vec![Duration::from_secs(60)],
+        log_points: vec![],
+    };
+
+    assert_eq!(lap.max_speed(), None);
+}
@@ -0,0 +1,72 @@
+// SPDX-FileCopyrightText: 2026 All contributors
+//
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use chrono::{NaiveDate, NaiveTime};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Timestamps {
+    #[serde(with = "common::serde::date")]
+    date: NaiveDate,
+    #[serde(with = "common::serde::time")]
+    time: NaiveTime,
+    #[serde(with = "common::serde::duration")]
+    duration: Duration,
+    #[serde(with = "common::serde::duration_list")]
+    durations: Vec<Duration>,
+}
+
+fn from_json(date: &str, time: &str, duration: &str) -> Result<Timestamps, serde_json::Error> {
+    serde_json::from_str(&format!(
+        r#"{{"date":"{date}","time":"{time}","duration":"{duration}","durations":["{duration}"]}}"#
+    ))
+}
+
+#[test]
+fn round_trip_timestamps() {
+    let timestamps = Timestamps {
+        date: NaiveDate::from_ymd_opt(2025, 12, 24).unwrap(),
+        time: NaiveTime::from_hms_milli_opt(13, 45, 7, 123).unwrap(),
+        duration: Duration::from_millis(83_456),
+        durations: vec![Duration::from_millis(25_144), Duration::from_millis(30_002)],
+    };
+
+    let json = serde_json::to_string(&timestamps).unwrap();
+
+    assert_eq!(
+        json,
+        r#"{"date":"24.12.2025","time":"13:45:07.123","duration":"00:01:23.456","durations":["00:00:25.144","00:00:30.002"]}"#
+    );
+    assert_eq!(
+        serde_json::from_str::<Timestamps>(&json).unwrap(),
+        timestamps
+    );
+}
+
+#[test]
+fn deserialize_iso_date() {
+    let timestamps = from_json("2025-12-24", "13:45:07.123", "00:01:23.456").unwrap();
+
+    assert_eq!(
+        timestamps.date,
+        NaiveDate::from_ymd_opt(2025, 12, 24).unwrap()
+    );
+}
+
+#[test]
+fn deserialize_time_without_milliseconds() {
+    let timestamps = from_json("24.12.2025", "13:45:07", "00:01:23").unwrap();
+
+    assert_eq!(timestamps.time, NaiveTime::from_hms_opt(13, 45, 7).unwrap());
+    assert_eq!(timestamps.duration, Duration::from_secs(83));
+    assert_eq!(timestamps.durations, vec![Duration::from_secs(83)]);
+}
+
+#[test]
+fn reject_unknown_formats() {
+    assert!(from_json("12/24/2025", "13:45:07.123", "00:01:23.456").is_err());
+    assert!(from_json("24.12.2025", "13:45", "00:01:23.456").is_err());
+    assert!(from_json("24.12.2025", "13:45:07.123", "83.456").is_err());
+}
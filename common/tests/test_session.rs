@@ -3,9 +3,11 @@
 // SPDX-License-Identifier: GPL-2.0-or-later
 
 use common::{
+    lap::Lap,
     session::Session,
     test_helper::session::{get_session, get_session_as_json},
 };
+use std::time::Duration;
 
 #[test]
 pub fn deserialize_session_from_json() {
@@ -23,3 +25,53 @@ pub fn serialize_session_to_json() {
         serde_json::from_str(get_session_as_json()).unwrap()
     );
 }
+
+/// Returns a lap with the given sector times in milliseconds.
+fn lap_with_sectors(sectors: &[u64]) -> Lap {
+    Lap {
+        sectors: sectors.iter().copied().map(Duration::from_millis).collect(),
+        log_points: vec![],
+    }
+}
+
+#[test]
+pub fn theoretical_best_lap_of_session() {
+    let mut session = get_session();
+    session.laps = vec![
+        lap_with_sectors(&[30_100, 41_000, 25_500]),
+        lap_with_sectors(&[29_800, 41_700, 25_900]),
+        lap_with_sectors(&[30_400, 40_900, 26_200]),
+    ];
+
+    assert_eq!(
+        session.laps[1].sector(0),
+        Some(Duration::from_millis(29_800))
+    );
+    assert_eq!(session.laps[1].sector(3), None);
+    assert_eq!(
+        session.best_sectors(),
+        Some(vec![
+            Duration::from_millis(29_800),
+            Duration::from_millis(40_900),
+            Duration::from_millis(25_500),
+        ])
+    );
+    assert_eq!(
+        session.theoretical_best_lap(),
+        Some(Duration::from_millis(96_200))
+    );
+}
+
+#[test]
+pub fn no_theoretical_best_lap_for_inconsistent_sectors() {
+    let mut session = get_session();
+    session.laps = vec![
+        lap_with_sectors(&[30_100, 41_000, 25_500]),
+        lap_with_sectors(&[29_800, 41_700]),
+    ];
+    assert_eq!(session.best_sectors(), None);
+    assert_eq!(session.theoretical_best_lap(), None);
+
+    session.laps.clear();
+    assert_eq!(session.theoretical_best_lap(), None);
+}
@@ -2,7 +2,17 @@
 //
 // SPDX-License-Identifier: GPL-2.0-or-later
 
-use common::{test_helper::track::get_track, test_helper::track::get_track_as_json, track::Track};
+use common::{
+    position::Position,
+    test_helper::track::get_track,
+    test_helper::track::get_track_as_json,
+    track::{SectorValidationError, Track, clean_sectors},
+};
+
+/// Returns a position the given meters north of latitude 52.0 and longitude 11.0.
+fn north(meters: f64) -> Position {
+    Position::new(&(52.0 + meters / 111300.0), &11.0)
+}
 
 #[test]
 pub fn deserialize_track_from_json() {
@@ -10,3 +20,133 @@ pub fn deserialize_track_from_json() {
         .unwrap_or_else(|e| panic!("Failed to deserialize the raw json. Reason: {e}"));
     assert_eq!(track, get_track());
 }
+
+#[test]
+pub fn length_of_looped_track() {
+    let track = Track {
+        name: "Loop".to_string(),
+        startline: north(0.0),
+        finishline: Some(north(0.0)),
+        sectors: vec![north(1000.0), north(2500.0)],
+        detection_radius_m: None,
+    };
+    assert!((track.length_meters() - 5000.0).abs() < 1e-6);
+
+    let track = Track {
+        finishline: None,
+        ..track
+    };
+    assert!((track.length_meters() - 5000.0).abs() < 1e-6);
+}
+
+#[test]
+pub fn sector_distances_of_looped_track() {
+    let track = Track {
+        name: "Loop".to_string(),
+        startline: north(0.0),
+        finishline: None,
+        sectors: vec![north(1000.0), north(2500.0)],
+        detection_radius_m: None,
+    };
+    let sectors = track.sector_distances();
+    let distances: Vec<(f64, f64)> = sectors
+        .iter()
+        .map(|sector| (sector.segment_length, sector.cumulative_distance))
+        .collect();
+    let expected = [(1000.0, 1000.0), (1500.0, 2500.0), (2500.0, 5000.0)];
+    assert_eq!(distances.len(), expected.len());
+    for (distance, expected) in distances.iter().zip(expected) {
+        assert!((distance.0 - expected.0).abs() < 1e-6, "{distance:?}");
+        assert!((distance.1 - expected.1).abs() < 1e-6, "{distance:?}");
+    }
+    assert_eq!(sectors[2].position, track.startline);
+}
+
+#[test]
+pub fn length_of_point_to_point_stage() {
+    let track = Track {
+        name: "Hillclimb".to_string(),
+        startline: north(0.0),
+        finishline: Some(north(3000.0)),
+        sectors: vec![north(1000.0)],
+        detection_radius_m: None,
+    };
+    assert!((track.length_meters() - 3000.0).abs() < 1e-6);
+}
+
+#[test]
+pub fn length_of_track_without_sectors() {
+    let stage = Track {
+        name: "Sprint".to_string(),
+        startline: north(0.0),
+        finishline: Some(north(400.0)),
+        sectors: vec![],
+        detection_radius_m: None,
+    };
+    assert!((stage.length_meters() - 400.0).abs() < 1e-6);
+
+    let circuit = Track {
+        finishline: None,
+        ..stage
+    };
+    assert_eq!(circuit.length_meters(), 0.0);
+}
+
+#[test]
+pub fn validate_sectors() {
+    let track = |sectors: Vec<Position>| Track {
+        name: "Loop".to_string(),
+        startline: north(0.0),
+        finishline: None,
+        sectors,
+        detection_radius_m: None,
+    };
+    assert_eq!(
+        track(vec![north(500.0), north(1000.0)]).validate_sectors(),
+        Ok(())
+    );
+    assert_eq!(
+        track(vec![]).validate_sectors(),
+        Err(SectorValidationError::Empty)
+    );
+    assert_eq!(
+        track(vec![north(500.0), Position::new(&91.0, &11.0)]).validate_sectors(),
+        Err(SectorValidationError::InvalidCoordinate(1))
+    );
+    assert_eq!(
+        track(vec![north(500.0), north(505.0)]).validate_sectors(),
+        Err(SectorValidationError::TooClose(1))
+    );
+    assert_eq!(
+        track(vec![north(500.0), north(5.0)]).validate_sectors(),
+        Err(SectorValidationError::TooClose(1))
+    );
+}
+
+#[test]
+pub fn clean_sectors_merges_close_sectors() {
+    let track = Track {
+        name: "Loop".to_string(),
+        startline: north(0.0),
+        finishline: None,
+        sectors: vec![north(500.0), north(504.0), north(1000.0)],
+        detection_radius_m: None,
+    };
+    let cleaned = clean_sectors(&track, 10.0);
+    assert_eq!(cleaned.sectors.len(), 2);
+    assert!(cleaned.sectors[0].distance_to(&north(502.0)) < 0.01);
+    assert_eq!(cleaned.sectors[1], north(1000.0));
+    assert_eq!(cleaned.name, track.name);
+    assert_eq!(cleaned.validate_sectors(), Ok(()));
+
+    // Sectors at the start or finish line are removed.
+    let track = Track {
+        sectors: vec![north(5.0), north(500.0), north(1000.0)],
+        finishline: Some(north(1003.0)),
+        ..track
+    };
+    assert_eq!(clean_sectors(&track, 10.0).sectors, vec![north(500.0)]);
+
+    // Well spaced sectors are kept.
+    assert_eq!(clean_sectors(&get_track(), 10.0), get_track());
+}
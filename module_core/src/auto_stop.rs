@@ -0,0 +1,54 @@
+// SPDX-FileCopyrightText: 2026 All contributors
+//
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+//! Provides a module that shuts down all modules after a number of laps.
+
+use crate::{EventKind, Module, ModuleCtx};
+use tracing::{error, info};
+
+/// Publishes a [`EventKind::QuitEvent`] after a configured number of finished laps.
+///
+/// Intended for demo and test runs, e.g. deterministic end-to-end tests that drive a
+/// fixed number of laps and expect all modules to shut down afterwards.
+pub struct AutoStopModule {
+    ctx: ModuleCtx,
+    max_laps: usize,
+    finished_laps: usize,
+}
+
+impl AutoStopModule {
+    /// Creates a new auto stop module that quits after `max_laps` finished laps.
+    pub fn new(ctx: ModuleCtx, max_laps: usize) -> Self {
+        AutoStopModule {
+            ctx,
+            max_laps,
+            finished_laps: 0,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Module for AutoStopModule {
+    async fn run(&mut self) -> Result<(), ()> {
+        loop {
+            match self.ctx.recv().await {
+                Ok(event) => match event.kind {
+                    EventKind::QuitEvent => break,
+                    EventKind::LapFinishedEvent(_) => {
+                        self.finished_laps += 1;
+                        if self.finished_laps >= self.max_laps {
+                            info!("{} laps finished, stopping all modules", self.finished_laps);
+                            let _ = self.ctx.publish_event(EventKind::QuitEvent);
+                        }
+                    }
+                    _ => (),
+                },
+                Err(e) => {
+                    error!("Failed to receive event in module AutoStop. Error: {e}");
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -3,19 +3,29 @@
 // SPDX-License-Identifier: GPL-2.0-or-later
 
 use common::{
+    id::{SessionId, TrackId},
     session::{Session, SessionInfo},
     track::Track,
 };
+use futures::FutureExt;
 use std::{
+    any::Any,
+    collections::{HashMap, HashSet},
     io::ErrorKind,
+    panic::AssertUnwindSafe,
+    path::PathBuf,
     sync::{
-        Arc, RwLock,
+        Arc, Mutex, RwLock,
         atomic::{self, AtomicUsize},
     },
 };
-use strum_macros::EnumDiscriminants;
-use tokio::time::timeout;
-use tracing::info;
+use strum::IntoEnumIterator;
+use strum_macros::{EnumDiscriminants, EnumIter, IntoStaticStr};
+use tokio::{
+    sync::{broadcast, mpsc},
+    time::timeout,
+};
+use tracing::{error, info, warn};
 
 /// Represents a high-level event in the system.
 ///
@@ -51,17 +61,29 @@ impl Event {
             | EventKind::LoadAllStoredTracksRequestEvent(req)
             | EventKind::DetectTrackRequestEvent(req) => Some(req.id),
             EventKind::SaveSessionRequestEvent(req) => Some(req.id),
+            EventKind::ImportSessionRequestEvent(req) => Some(req.id),
             EventKind::LoadSessionRequestEvent(req) => Some(req.id),
             EventKind::DeleteSessionRequestEvent(req) => Some(req.id),
+            EventKind::FinalizeSessionRequestEvent(req) => Some(req.id),
             EventKind::CurrentSessionRequestEvent(req) => Some(req.id),
+            EventKind::SessionFilePathRequestEvent(req) => Some(req.id),
+            EventKind::LaptimerStateRequestEvent(req) => Some(req.id),
+            EventKind::LoadTrackRequestEvent(req) => Some(req.id),
+            EventKind::SaveTrackRequestEvent(req) => Some(req.id),
             EventKind::LoadStoredSessionIdsResponseEvent(res) => Some(res.id),
             EventKind::SaveSessionResponseEvent(res) => Some(res.id),
+            EventKind::ImportSessionResponseEvent(res) => Some(res.id),
             EventKind::LoadSessionResponseEvent(res) => Some(res.id),
             EventKind::DeleteSessionResponseEvent(res) => Some(res.id),
+            EventKind::FinalizeSessionResponseEvent(res) => Some(res.id),
             EventKind::LoadStoredTrackIdsResponseEvent(res) => Some(res.id),
             EventKind::LoadAllStoredTracksResponseEvent(res) => Some(res.id),
             EventKind::DetectTrackResponseEvent(res) => Some(res.id),
             EventKind::CurrentSessionResponseEvent(res) => Some(res.id),
+            EventKind::SessionFilePathResponseEvent(res) => Some(res.id),
+            EventKind::LaptimerStateResponseEvent(res) => Some(res.id),
+            EventKind::LoadTrackResponseEvent(res) => Some(res.id),
+            EventKind::SaveTrackResponseEvent(res) => Some(res.id),
             _ => None,
         }
     }
@@ -75,20 +97,32 @@ impl Event {
         match &self.kind {
             EventKind::LoadStoredSessionIdsRequestEvent(req) => Some(req.sender_addr),
             EventKind::SaveSessionRequestEvent(req) => Some(req.sender_addr),
+            EventKind::ImportSessionRequestEvent(req) => Some(req.sender_addr),
             EventKind::LoadSessionRequestEvent(req) => Some(req.sender_addr),
             EventKind::DeleteSessionRequestEvent(req) => Some(req.sender_addr),
+            EventKind::FinalizeSessionRequestEvent(req) => Some(req.sender_addr),
             EventKind::LoadStoredTrackIdsRequest(req)
             | EventKind::LoadAllStoredTracksRequestEvent(req)
             | EventKind::DetectTrackRequestEvent(req) => Some(req.sender_addr),
             EventKind::CurrentSessionRequestEvent(req) => Some(req.sender_addr),
+            EventKind::SessionFilePathRequestEvent(req) => Some(req.sender_addr),
+            EventKind::LaptimerStateRequestEvent(req) => Some(req.sender_addr),
+            EventKind::LoadTrackRequestEvent(req) => Some(req.sender_addr),
+            EventKind::SaveTrackRequestEvent(req) => Some(req.sender_addr),
             EventKind::LoadStoredSessionIdsResponseEvent(res) => Some(res.receiver_addr),
             EventKind::SaveSessionResponseEvent(res) => Some(res.receiver_addr),
+            EventKind::ImportSessionResponseEvent(res) => Some(res.receiver_addr),
             EventKind::LoadSessionResponseEvent(res) => Some(res.receiver_addr),
             EventKind::DeleteSessionResponseEvent(res) => Some(res.receiver_addr),
+            EventKind::FinalizeSessionResponseEvent(res) => Some(res.receiver_addr),
             EventKind::LoadStoredTrackIdsResponseEvent(res) => Some(res.receiver_addr),
             EventKind::LoadAllStoredTracksResponseEvent(res) => Some(res.receiver_addr),
             EventKind::DetectTrackResponseEvent(res) => Some(res.receiver_addr),
             EventKind::CurrentSessionResponseEvent(res) => Some(res.receiver_addr),
+            EventKind::SessionFilePathResponseEvent(res) => Some(res.receiver_addr),
+            EventKind::LaptimerStateResponseEvent(res) => Some(res.receiver_addr),
+            EventKind::LoadTrackResponseEvent(res) => Some(res.receiver_addr),
+            EventKind::SaveTrackResponseEvent(res) => Some(res.receiver_addr),
             _ => None,
         }
     }
@@ -190,6 +224,9 @@ impl<T> Response<T> {
 /// of the same GNSS position data without copying it.
 pub type GnssPositionPtr = Arc<common::position::GnssPosition>;
 
+/// A thread-safe, reference-counted pointer to a batch of [`GnssPosition`]s.
+pub type GnssPositionBatchPtr = Arc<Vec<common::position::GnssPosition>>;
+
 /// A thread-safe shared reference-counted pointer to a [`GnssInformation`].
 ///
 /// This type alias wraps a [`GnssInformation`] instance in an [`Arc`],
@@ -210,32 +247,143 @@ pub type StoredSessionIdsResponsePtr = Arc<Response<Arc<Vec<SessionInfo>>>>;
 pub type SaveSessionRequestPtr = Arc<Request<Arc<RwLock<Session>>>>;
 
 /// A thread-safe, shared pointer to a save session response.
-pub type SaveSessionResponsePtr = Arc<Response<Result<String, ErrorKind>>>;
+pub type SaveSessionResponsePtr = Arc<Response<Result<SessionId, ErrorKind>>>;
 
 /// A thread-safe, shared pointer to a load session request.
-pub type LoadSessionRequestPtr = Arc<Request<String>>;
+pub type LoadSessionRequestPtr = Arc<Request<SessionId>>;
 
 /// A thread-safe, shared pointer to a load session response.
 pub type LoadSessionResponsePtr = Arc<Response<Result<Arc<RwLock<Session>>, ErrorKind>>>;
 
 /// A thread-safe, shared pointer to a delete session request.
-pub type DeleteSessionRequestPtr = Arc<Request<String>>;
+pub type DeleteSessionRequestPtr = Arc<Request<SessionId>>;
 
 /// A thread-safe, shared pointer to a delete session response.
 pub type DeleteSessionResponsePtr = Arc<Response<Result<(), ErrorKind>>>;
 
+/// A thread-safe, shared pointer to a finalize session request.
+pub type FinalizeSessionRequestPtr = Arc<Request<SessionId>>;
+
+/// A thread-safe, shared pointer to a finalize session response.
+pub type FinalizeSessionResponsePtr = Arc<Response<Result<(), ErrorKind>>>;
+
 /// A thread-safe, shared pointer to a load stored track ids request.
-pub type LoadStoredTrackIdsResponsePtr = Arc<Response<Vec<String>>>;
+pub type LoadStoredTrackIdsResponsePtr = Arc<Response<Vec<TrackId>>>;
 
 /// A thread-safe shared pointer to a load all stored tracks request.
 pub type LoadStoredTracksReponsePtr = Arc<Response<Vec<Track>>>;
 
+/// A thread-safe shared pointer to a load track request.
+pub type LoadTrackRequestPtr = Arc<Request<TrackId>>;
+
+/// A thread-safe shared pointer to a load track response.
+pub type LoadTrackResponsePtr = Arc<Response<Result<Track, ErrorKind>>>;
+
+/// A thread-safe shared pointer to a save track request.
+pub type SaveTrackRequestPtr = Arc<Request<(TrackId, Track)>>;
+
+/// A thread-safe shared pointer to a save track response.
+pub type SaveTrackResponsePtr = Arc<Response<Result<(), ErrorKind>>>;
+
 /// A thread-safe shared pointer to a track detection request.
 pub type TrackDetectionResponsePtr = Arc<Response<Vec<Track>>>;
 
+/// A thread-safe shared pointer to a track.
+pub type TrackPtr = Arc<Track>;
+
 /// A thread-safe shared pointer to a current session response.
 pub type CurrentSessionResponsePtr = Arc<Response<Option<Arc<RwLock<Session>>>>>;
 
+/// A thread-safe shared pointer to a session.
+pub type SessionPtr = Arc<RwLock<Session>>;
+
+/// A thread-safe shared pointer to a session file path request.
+pub type SessionFilePathRequestPtr = Arc<Request<SessionId>>;
+
+/// A thread-safe shared pointer to a session file path response.
+pub type SessionFilePathResponsePtr = Arc<Response<Result<PathBuf, ErrorKind>>>;
+
+/// Describes a session that couldn't be persisted.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SaveFailure {
+    /// The ID under which the session should have been stored.
+    pub session_id: SessionId,
+
+    /// The error that caused the save to fail.
+    pub error: ErrorKind,
+}
+
+/// A thread-safe shared pointer to a save failure.
+pub type SaveFailedPtr = Arc<SaveFailure>;
+
+/// Snapshot of the state of the lap timer.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LaptimerStateInfo {
+    /// Name of the configured track, `None` if no track is configured yet.
+    pub track_name: Option<String>,
+
+    /// Whether no track was configured within the grace period after the start of the lap timer.
+    pub no_track_configured: bool,
+
+    /// Whether a lap is currently running.
+    pub lap_running: bool,
+}
+
+/// A thread-safe shared pointer to a lap timer state response.
+pub type LaptimerStateResponsePtr = Arc<Response<LaptimerStateInfo>>;
+
+/// A finished lap.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LapFinished {
+    /// Time of the finished lap.
+    pub lap_time: std::time::Duration,
+
+    /// UTC timestamp of the GNSS fix at the finish line crossing, only set if enabled in the
+    /// lap timer.
+    pub finish_time: Option<chrono::NaiveDateTime>,
+}
+
+impl From<std::time::Duration> for LapFinished {
+    fn from(lap_time: std::time::Duration) -> Self {
+        LapFinished {
+            lap_time,
+            finish_time: None,
+        }
+    }
+}
+
+/// A thread-safe shared pointer to a finished lap.
+pub type LapFinishedPtr = Arc<LapFinished>;
+
+/// A new best lap of the session.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BestLap {
+    /// Time of the new best lap.
+    pub lap_time: std::time::Duration,
+
+    /// Improvement over the previous best lap, `None` for the first completed lap.
+    pub delta: Option<std::time::Duration>,
+}
+
+/// A thread-safe shared pointer to a new best lap.
+pub type BestLapPtr = Arc<BestLap>;
+
+/// Interval in which the modules publish their [`EventKind::ModuleHeartbeatEvent`].
+pub const HEARTBEAT_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Liveness signal of a module.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ModuleHeartbeat {
+    /// Name of the module, e.g. `storage`.
+    pub name: String,
+
+    /// Point in time when the module published the heartbeat.
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+}
+
+/// A thread-safe shared pointer to a module heartbeat.
+pub type ModuleHeartbeatPtr = Arc<ModuleHeartbeat>;
+
 /// Generic helper macro to extract enum payloads
 #[macro_export]
 macro_rules! payload_ref {
@@ -251,7 +399,7 @@ macro_rules! payload_ref {
 /// Enumerates the different kinds of events that can be emitted
 /// and transmitted via the [`EventBus`].
 #[derive(Clone, Debug, EnumDiscriminants)]
-#[strum_discriminants(derive(Hash))]
+#[strum_discriminants(derive(Hash, EnumIter, IntoStaticStr))]
 #[strum_discriminants(name(EventKindType))]
 pub enum EventKind {
     /// Indicates that a module shall terminate.
@@ -263,6 +411,29 @@ pub enum EventKind {
     /// with the current geolocation and related information.
     GnssPositionEvent(GnssPositionPtr),
 
+    /// A batch of GNSS position updates in chronological order, e.g. of a bulk replay.
+    ///
+    /// Consumers process the positions as if they were received as single
+    /// [`EventKind::GnssPositionEvent`]s, but the events caused by the positions, e.g. a
+    /// started lap, are only published after the whole batch was processed.
+    GnssPositionBatchEvent(GnssPositionBatchPtr),
+
+    /// A smoothed GNSS position update published by the GNSS smoothing module.
+    ///
+    /// This event carries the filtered [`common::position::GnssPosition`] of the latest
+    /// [`EventKind::GnssPositionEvent`], including its timestamp.
+    SmoothedGnssPositionEvent(GnssPositionPtr),
+
+    /// A rate limited GNSS position update published by the GNSS rate limiter module.
+    ///
+    /// This event carries a [`common::position::GnssPosition`] at most at the configured rate.
+    ThrottledGnssPositionEvent(GnssPositionPtr),
+
+    /// The longitudinal acceleration derived from consecutive GNSS positions by the
+    /// acceleration module.
+    /// This event carries the acceleration in m/s², negative values indicate braking.
+    AccelerationEvent(f64),
+
     /// A GNSS (Global Navigation Satellite System) position update.
     ///
     /// This event carries a [`common::position::GnssInformation`] structure
@@ -272,14 +443,36 @@ pub enum EventKind {
     /// Indicates that a new lap has started.
     LapStartedEvent,
 
+    /// Indicates that the lap started right before was started retroactively, because the
+    /// start line crossing was missed but the first sector was crossed.
+    /// This event carries the estimated time since the start line crossing as a
+    /// [`std:time::Duration`] structure.
+    LapStartEstimatedEvent(DurationPtr),
+
     /// Indicates that a lap has finished.
-    /// This event carries a [`std:time::Duration`] structure
-    LapFinishedEvent(DurationPtr),
+    /// This event carries a [`LapFinishedPtr`] with the lap time and the optional finish time.
+    LapFinishedEvent(LapFinishedPtr),
+
+    /// Indicates that the finished lap is the fastest lap of the session so far.
+    /// Emitted right after the [`EventKind::LapFinishedEvent`], always for the first lap.
+    /// This event carries a [`BestLapPtr`] with the lap time and the improvement.
+    NewBestLapEvent(BestLapPtr),
+
+    /// Indicates that the running lap was abandoned because it exceeded the maximum lap
+    /// duration, e.g. when the finish line crossing was never detected.
+    /// The lap timer waits for the next start line crossing afterwards.
+    LapAbandonedEvent,
 
     /// Indicates that a sector has been completed.
     /// This event carries a [`std:time::Duration`] structure
     SectorFinishedEvent(DurationPtr),
 
+    /// Indicates that the vehicle is about to cross the finish line.
+    /// Emitted at most once per lap, after the last sector, when the vehicle gets closer
+    /// to the finish line than the configured approach distance.
+    /// This event carries the current distance to the finish line in meters.
+    ApproachingFinishEvent(f64),
+
     /// Represents the current laptime (may be used for reporting purposes).
     /// This event carries a [`std:time::Duration`] structure.
     CurrentLaptimeEvent(DurationPtr),
@@ -297,13 +490,24 @@ pub enum EventKind {
     SaveSessionRequestEvent(SaveSessionRequestPtr),
 
     /// Response to store a session request in the persistent storage.
-    /// This event variant carries a [`SaveSessionResponsePtr`] with payload (`Result<String, std::io::ErrorKind>`).
-    /// The string is the ID under which the session was stored.
+    /// This event variant carries a [`SaveSessionResponsePtr`] with payload (`Result<SessionId, std::io::ErrorKind>`).
+    /// The payload is the ID under which the session was stored.
     SaveSessionResponseEvent(SaveSessionResponsePtr),
 
+    /// Request to store a session that isn't stored yet, e.g. an imported one.
+    /// This event variant carries a [`SaveSessionRequestPtr`] with payload (`Arc<RwLock<Session>`).
+    /// Unlike a save, an already stored session with the same ID isn't overwritten.
+    ImportSessionRequestEvent(SaveSessionRequestPtr),
+
+    /// Response to an import session request.
+    /// This event variant carries a [`SaveSessionResponsePtr`] with payload (`Result<SessionId, std::io::ErrorKind>`).
+    /// The payload is the ID under which the session was stored, or
+    /// [`std::io::ErrorKind::AlreadyExists`] if a session with the same ID is already stored.
+    ImportSessionResponseEvent(SaveSessionResponsePtr),
+
     /// Request to store a session in the persistent storage.
-    /// This event variant carries a [`LoadSessionRequestPtr`] with payload (`String`).
-    /// The string is the ID of the session that shall be loaded.
+    /// This event variant carries a [`LoadSessionRequestPtr`] with payload (`SessionId`).
+    /// The payload is the ID of the session that shall be loaded.
     LoadSessionRequestEvent(LoadSessionRequestPtr),
 
     /// Response to store a session request in the persistent storage.
@@ -311,20 +515,29 @@ pub enum EventKind {
     LoadSessionResponseEvent(LoadSessionResponsePtr),
 
     /// Request to store a session in the persistent storage.
-    /// This event variant carries a [`DeleteSessionRequestPtr`] with payload (`String`).
-    /// The string is the ID of the session that shall be deleted.
+    /// This event variant carries a [`DeleteSessionRequestPtr`] with payload (`SessionId`).
+    /// The payload is the ID of the session that shall be deleted.
     DeleteSessionRequestEvent(DeleteSessionRequestPtr),
 
     /// Response to store a session request in the persistent storage.
     /// This event variant carries a [`SaveSessionResponsePtr`] with payload (`Result<(), std::io::ErrorKind>`).
     DeleteSessionResponseEvent(DeleteSessionResponsePtr),
 
+    /// Request to finalize a stored session, e.g. after the recording stopped.
+    /// This event variant carries a [`FinalizeSessionRequestPtr`] with payload (`SessionId`).
+    /// The storage marks the session as finalized and compresses it.
+    FinalizeSessionRequestEvent(FinalizeSessionRequestPtr),
+
+    /// Response to a finalize session request.
+    /// This event variant carries a [`FinalizeSessionResponsePtr`] with payload (`Result<(), std::io::ErrorKind>`).
+    FinalizeSessionResponseEvent(FinalizeSessionResponsePtr),
+
     /// Request to load all stored track ids in the persistent storage.
     /// This event variant carries a [`EmptyRequestPtr`].
     LoadStoredTrackIdsRequest(EmptyRequestPtr),
 
     /// Reponse to load all stored track ids in the persistent storage.
-    /// This event variant carries a [`Vec<String>`].
+    /// This event variant carries a [`Vec<TrackId>`].
     /// The vector contains all track ids found in the persistent storage.
     LoadStoredTrackIdsResponseEvent(LoadStoredTrackIdsResponsePtr),
 
@@ -337,6 +550,24 @@ pub enum EventKind {
     /// The vector contains all tracks found in the persistent storage.
     LoadAllStoredTracksResponseEvent(LoadStoredTracksReponsePtr),
 
+    /// Request to load a single track from the persistent storage.
+    /// This event variant carries a [`LoadTrackRequestPtr`] with payload (`TrackId`).
+    /// The payload is the ID of the track that shall be loaded.
+    LoadTrackRequestEvent(LoadTrackRequestPtr),
+
+    /// Response to a load track request.
+    /// This event variant carries a [`LoadTrackResponsePtr`] with payload (`Result<Track, std::io::ErrorKind>`).
+    LoadTrackResponseEvent(LoadTrackResponsePtr),
+
+    /// Request to store a track in the persistent storage.
+    /// This event variant carries a [`SaveTrackRequestPtr`] with payload (`(TrackId, Track)`).
+    /// The ID is the ID under which the track is stored, an existing track is replaced.
+    SaveTrackRequestEvent(SaveTrackRequestPtr),
+
+    /// Response to a save track request.
+    /// This event variant carries a [`SaveTrackResponsePtr`] with payload (`Result<(), std::io::ErrorKind>`).
+    SaveTrackResponseEvent(SaveTrackResponsePtr),
+
     /// Event carrying a request to start a track detection operation.
     /// Uses `EmptyRequestPtr` as a signal-only payload (no parameters).
     DetectTrackRequestEvent(EmptyRequestPtr),
@@ -345,12 +576,163 @@ pub enum EventKind {
     /// Contains the `TrackDetectionResponsePtr` with detection results.
     DetectTrackResponseEvent(TrackDetectionResponsePtr),
 
+    /// Selects the track of the lap timer and the active session, e.g. by the user after
+    /// several tracks were detected with the track selection policy `PromptAndWait`.
+    /// Carries the [`TrackPtr`] of the selected track, it's ignored once a track is assigned.
+    TrackSelectedEvent(TrackPtr),
+
     /// Event carrying a request to get the current session.
     CurrentSessionRequestEvent(EmptyRequestPtr),
 
     /// Event emitted in response to a current session request.
     /// Contains the `CurrentSessionResponsePtr` with the session data.
     CurrentSessionResponseEvent(CurrentSessionResponsePtr),
+
+    /// Indicates that the active session was replaced as a whole (e.g. a new session started).
+    /// Carries the [`SessionPtr`] of the new active session so consumers can resynchronize.
+    SessionChangedEvent(SessionPtr),
+
+    /// Request for the path of the raw file of a stored session.
+    /// This event variant carries a [`SessionFilePathRequestPtr`] with payload (`SessionId`).
+    /// The payload is the ID of the session whose file path is requested.
+    SessionFilePathRequestEvent(SessionFilePathRequestPtr),
+
+    /// Response to a session file path request.
+    /// This event variant carries a [`SessionFilePathResponsePtr`] with payload (`Result<PathBuf, std::io::ErrorKind>`).
+    SessionFilePathResponseEvent(SessionFilePathResponsePtr),
+
+    /// Indicates that a session couldn't be persisted by a storage running in strict mode.
+    /// This event variant carries a [`SaveFailedPtr`] with the session ID and the error.
+    /// It is emitted in addition to the [`EventKind::SaveSessionResponseEvent`], so supervising
+    /// modules notice the failure without being the requester of the save, e.g. the REST
+    /// module reports the last one in `/v1/diag/modules`.
+    SaveFailedEvent(SaveFailedPtr),
+
+    /// Indicates that the lap timer got no track within the grace period after its start,
+    /// e.g. because no stored track is near the current position.
+    /// Emitted at most once, so user interfaces can prompt the user to configure a track.
+    NoTrackConfiguredEvent,
+
+    /// Indicates that a finite replay of GNSS positions published its last position.
+    ReplayFinishedEvent,
+
+    /// Request for the current state of the lap timer.
+    /// This event variant carries a [`EmptyRequestPtr`].
+    LaptimerStateRequestEvent(EmptyRequestPtr),
+
+    /// Response to a lap timer state request.
+    /// This event variant carries a [`LaptimerStateResponsePtr`] with payload ([`LaptimerStateInfo`]).
+    LaptimerStateResponseEvent(LaptimerStateResponsePtr),
+
+    /// Periodic liveness signal of a module, published every [`HEARTBEAT_INTERVAL`].
+    /// This event variant carries a [`ModuleHeartbeatPtr`] with the module name and the time of the heartbeat.
+    ModuleHeartbeatEvent(ModuleHeartbeatPtr),
+}
+
+/// Kinds of the events that are delivered over the priority lane by default, see
+/// [`EventBusConfig::critical_events`].
+pub const DEFAULT_CRITICAL_EVENTS: &[EventKindType] = &[
+    EventKindType::QuitEvent,
+    EventKindType::SaveSessionRequestEvent,
+    EventKindType::DeleteSessionRequestEvent,
+    EventKindType::FinalizeSessionRequestEvent,
+];
+
+/// Configuration of an [`EventBus`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct EventBusConfig {
+    /// Number of events buffered for every subscriber before the oldest ones are dropped.
+    pub capacity: usize,
+
+    /// Kinds of the events that are never dropped.
+    ///
+    /// Besides the broadcast channel, these events are sent over an unbounded priority lane
+    /// to every [`ModuleCtx`], which [`ModuleCtx::recv`] polls first. So a flood of high-rate
+    /// events, e.g. GNSS positions, can't push them out of the buffer. Intended for rare
+    /// events whose loss would lose data, e.g. a save request.
+    ///
+    /// The order between the lane and the broadcast channel isn't kept, a critical event
+    /// overtakes the non-critical events that were published before it but aren't received
+    /// yet. E.g. a [`EventKind::QuitEvent`] may be received before a pending response.
+    pub critical_events: Vec<EventKindType>,
+}
+
+impl Default for EventBusConfig {
+    fn default() -> Self {
+        EventBusConfig {
+            capacity: 100,
+            critical_events: DEFAULT_CRITICAL_EVENTS.to_vec(),
+        }
+    }
+}
+
+/// Delivers the critical events to every [`ModuleCtx`] without dropping them.
+#[derive(Clone, Debug)]
+struct PriorityLane {
+    critical_events: Arc<HashSet<EventKindType>>,
+    subscribers: Arc<Mutex<Vec<mpsc::UnboundedSender<Event>>>>,
+}
+
+impl PriorityLane {
+    fn new(critical_events: &[EventKindType]) -> Self {
+        PriorityLane {
+            critical_events: Arc::new(critical_events.iter().copied().collect()),
+            subscribers: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    fn is_critical(&self, event: &Event) -> bool {
+        self.critical_events.contains(&event.event_type())
+    }
+
+    fn subscribe(&self) -> mpsc::UnboundedReceiver<Event> {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        if let Ok(mut subscribers) = self.subscribers.lock() {
+            subscribers.push(sender);
+        }
+        receiver
+    }
+
+    /// Sends the event to every subscriber if it is critical, dropped subscribers are removed.
+    fn send(&self, event: &Event) {
+        if !self.is_critical(event) {
+            return;
+        }
+        if let Ok(mut subscribers) = self.subscribers.lock() {
+            subscribers.retain(|subscriber| subscriber.send(event.clone()).is_ok());
+        }
+    }
+}
+
+/// Publishes events on an [`EventBus`].
+///
+/// Events are broadcast to all subscribers, critical events are additionally sent over the
+/// priority lane, see [`EventBusConfig::critical_events`].
+#[derive(Clone, Debug)]
+pub struct EventSender {
+    sender: broadcast::Sender<Event>,
+    lane: PriorityLane,
+}
+
+impl EventSender {
+    /// Publishes the event and returns the number of broadcast receivers it was sent to.
+    ///
+    /// Fails if there are no broadcast receivers, the event may still be delivered over the
+    /// priority lane.
+    pub fn send(&self, event: Event) -> Result<usize, broadcast::error::SendError<Event>> {
+        self.lane.send(&event);
+        self.sender.send(event)
+    }
+
+    /// Returns the number of active broadcast receivers.
+    pub fn receiver_count(&self) -> usize {
+        self.sender.receiver_count()
+    }
+
+    /// Creates a new broadcast receiver for the events published after this call.
+    pub fn subscribe(&self) -> broadcast::Receiver<Event> {
+        self.sender.subscribe()
+    }
 }
 
 /// A simple asynchronous event bus for publishing and subscribing to [`Event`]s.
@@ -360,10 +742,12 @@ pub enum EventKind {
 ///
 /// Each published event is cloned and distributed to all active subscribers.
 /// If no subscribers exist at the time of publication, the event is discarded silently.
+/// Critical events are additionally delivered over a priority lane that never drops them,
+/// see [`EventBusConfig::critical_events`].
 pub struct EventBus {
     id: usize,
-    /// The broadcast sender used internally to distribute events.
-    sender: tokio::sync::broadcast::Sender<Event>,
+    /// The sender used internally to distribute events.
+    sender: EventSender,
 }
 
 /// Global counter used to assign unique, monotonically increasing IDs to bus instances.
@@ -371,16 +755,27 @@ pub struct EventBus {
 static BUS_ID: AtomicUsize = AtomicUsize::new(0);
 
 impl EventBus {
-    /// Creates a new [`EventBus`] with a fixed buffer capacity of 100 messages.
+    /// Creates a new [`EventBus`] with the default [`EventBusConfig`], i.e. a buffer
+    /// capacity of 100 messages.
     ///
     /// When the buffer is full, the oldest messages are dropped automatically
     /// as new ones are published.
     pub fn new() -> Self {
-        let (sender, _) = tokio::sync::broadcast::channel(100);
+        EventBus::new_with_config(EventBusConfig::default())
+    }
+
+    /// Creates a new [`EventBus`] with a custom configuration.
+    pub fn new_with_config(config: EventBusConfig) -> Self {
+        let (sender, _) = broadcast::channel(config.capacity);
         let id = BUS_ID.fetch_add(1, atomic::Ordering::Relaxed);
         info!("Creating EventBus with id {}", id);
-        BUS_ID.store(id, atomic::Ordering::SeqCst);
-        EventBus { id, sender }
+        EventBus {
+            id,
+            sender: EventSender {
+                sender,
+                lane: PriorityLane::new(&config.critical_events),
+            },
+        }
     }
 
     /// Subscribes to the event bus and returns a [`tokio::sync::broadcast::Receiver`].
@@ -424,6 +819,71 @@ impl Default for EventBus {
     }
 }
 
+/// Counters describing the traffic observed on an [`EventBus`].
+///
+/// Keeps the number of published events per [`EventKindType`] and the number of
+/// subscribers seen at the last update. The metrics are filled by a subscriber that
+/// observes every event on the bus, e.g. the REST module.
+#[derive(Clone, Debug, Default)]
+pub struct EventBusMetrics {
+    published: HashMap<EventKindType, u64>,
+    subscribers: usize,
+}
+
+impl EventBusMetrics {
+    /// Counts one published event of the kind of `event`.
+    pub fn record(&mut self, event: &EventKind) {
+        *self
+            .published
+            .entry(EventKindType::from(event))
+            .or_insert(0) += 1;
+    }
+
+    /// Returns the number of published events of the given kind.
+    pub fn published(&self, kind: EventKindType) -> u64 {
+        self.published.get(&kind).copied().unwrap_or(0)
+    }
+
+    /// Returns the publish counters for every event kind, including kinds that were never published.
+    pub fn published_per_kind(&self) -> Vec<(EventKindType, u64)> {
+        EventKindType::iter()
+            .map(|kind| (kind, self.published(kind)))
+            .collect()
+    }
+
+    /// Updates the number of active subscribers on the bus.
+    pub fn set_subscribers(&mut self, subscribers: usize) {
+        self.subscribers = subscribers;
+    }
+
+    /// Returns the number of active subscribers on the bus.
+    pub fn subscribers(&self) -> usize {
+        self.subscribers
+    }
+}
+
+/// The kinds of the events a module consumes and produces, see [`Module::event_interface`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ModuleInterface {
+    /// Kinds of the events the module reacts to.
+    pub consumes: Vec<EventKindType>,
+
+    /// Kinds of the events the module publishes.
+    pub produces: Vec<EventKindType>,
+}
+
+impl ModuleInterface {
+    /// Returns whether the module reacts to events of the given kind.
+    pub fn consumes(&self, kind: EventKindType) -> bool {
+        self.consumes.contains(&kind)
+    }
+
+    /// Returns whether the module publishes events of the given kind.
+    pub fn produces(&self, kind: EventKindType) -> bool {
+        self.produces.contains(&kind)
+    }
+}
+
 /// Defines the common interface for an asynchronous module
 /// that can be executed and communicate via the [`EventBus`].
 #[async_trait::async_trait]
@@ -433,6 +893,60 @@ pub trait Module {
     /// This function typically contains the module's main event loop,
     /// reacting to messages received through the [`ModuleCtx`].
     async fn run(&mut self) -> Result<(), ()>;
+
+    /// Returns the kinds of the events the module consumes and produces, e.g. to document
+    /// the event wiring of the system.
+    ///
+    /// Modules that don't declare their interface return an empty [`ModuleInterface`].
+    fn event_interface(&self) -> ModuleInterface {
+        ModuleInterface::default()
+    }
+}
+
+/// Runs a module and restarts it when its run loop panics.
+///
+/// A panic of the run loop is caught, logged and the module is started again by calling
+/// [`Module::run`] on the same instance, at most `max_restarts` times. Events published
+/// while the module was restarting stay buffered in its [`ModuleCtx`] receiver.
+///
+/// # Arguments
+/// * `name` - Name of the module used in the log messages.
+/// * `module` - The module to run.
+/// * `max_restarts` - Number of restarts after a panic before giving up.
+///
+/// # Returns
+/// The result of the last run, or `Err(())` if the module panicked more than `max_restarts` times.
+pub async fn supervise<M: Module + ?Sized>(
+    name: &str,
+    module: &mut M,
+    max_restarts: usize,
+) -> Result<(), ()> {
+    let mut restarts = 0;
+    loop {
+        match AssertUnwindSafe(module.run()).catch_unwind().await {
+            Ok(result) => return result,
+            Err(panic) => {
+                let reason = panic_reason(panic.as_ref());
+                if restarts >= max_restarts {
+                    error!("Module {name} panicked: {reason}. Giving up after {restarts} restarts");
+                    return Err(());
+                }
+                restarts += 1;
+                warn!("Module {name} panicked: {reason}. Restarting ({restarts}/{max_restarts})");
+            }
+        }
+    }
+}
+
+/// Returns the message of a caught panic payload.
+fn panic_reason(panic: &(dyn Any + Send)) -> &str {
+    if let Some(reason) = panic.downcast_ref::<&str>() {
+        reason
+    } else if let Some(reason) = panic.downcast_ref::<String>() {
+        reason
+    } else {
+        "unknown reason"
+    }
 }
 
 /// Provides a module-scoped context for interacting with the [`EventBus`].
@@ -444,11 +958,17 @@ pub struct ModuleCtx {
     /// Unique identifier of the event bus that this context belongs to.
     id: usize,
 
-    /// The broadcast sender used to publish events.
-    pub sender: tokio::sync::broadcast::Sender<Event>,
+    /// The sender used to publish events.
+    pub sender: EventSender,
 
     /// The broadcast receiver used to listen for events.
-    pub receiver: tokio::sync::broadcast::Receiver<Event>,
+    ///
+    /// Critical events may be dropped from it under load, prefer [`ModuleCtx::recv`].
+    pub receiver: broadcast::Receiver<Event>,
+
+    /// The priority lane of the critical events, `None` if detached, see
+    /// [`ModuleCtx::without_priority_lane`].
+    priority_receiver: Option<mpsc::UnboundedReceiver<Event>>,
 }
 
 #[derive(Debug)]
@@ -466,6 +986,39 @@ impl ModuleCtx {
             .map_err(|e| ModuleCtxError::PublishError(format!("Failed to publish event: {}", e)))
     }
 
+    /// Publishes a [`EventKind::ModuleHeartbeatEvent`] for the module with the given name.
+    pub fn publish_heartbeat(&self, name: &str) -> Result<(), ModuleCtxError> {
+        self.publish_event(EventKind::ModuleHeartbeatEvent(Arc::new(ModuleHeartbeat {
+            name: name.to_string(),
+            timestamp: chrono::Utc::now(),
+        })))
+    }
+
+    /// Receives the next event.
+    ///
+    /// Critical events of the priority lane are received first and never dropped, see
+    /// [`EventBusConfig::critical_events`]. Their copies on the broadcast channel are skipped.
+    /// The lane is polled first, so a critical event overtakes earlier non-critical events
+    /// that are still buffered in the broadcast channel.
+    /// Other events are received from the broadcast channel, which fails with
+    /// [`broadcast::error::RecvError::Lagged`] if events were dropped. A context detached from
+    /// the priority lane receives all events from the broadcast channel.
+    pub async fn recv(&mut self) -> Result<Event, broadcast::error::RecvError> {
+        let Some(priority_receiver) = &mut self.priority_receiver else {
+            return self.receiver.recv().await;
+        };
+        loop {
+            tokio::select! {
+                biased;
+                Some(event) = priority_receiver.recv() => return Ok(event),
+                event = self.receiver.recv() => match event {
+                    Ok(event) if self.sender.lane.is_critical(&event) => continue,
+                    event => return event,
+                },
+            }
+        }
+    }
+
     pub async fn wait_for_event(
         &mut self,
         id: u64,
@@ -482,6 +1035,10 @@ impl Clone for ModuleCtx {
             id: self.id,
             sender: self.sender.clone(),
             receiver: self.receiver.resubscribe(),
+            priority_receiver: self
+                .priority_receiver
+                .as_ref()
+                .map(|_| self.sender.lane.subscribe()),
         }
     }
 }
@@ -496,6 +1053,7 @@ impl ModuleCtx {
             id: event_bus.id(),
             sender: event_bus.sender.clone(),
             receiver: event_bus.subscribe(),
+            priority_receiver: Some(event_bus.sender.lane.subscribe()),
         }
     }
 
@@ -511,6 +1069,19 @@ impl ModuleCtx {
         self.receiver.resubscribe()
     }
 
+    /// Detaches the context from the priority lane of the critical events.
+    ///
+    /// The priority lane buffers every critical event until it is received. A context that
+    /// only receives events while it waits for the response of a request, e.g. the context
+    /// of the REST request handlers, would buffer them without bound in between. Without the
+    /// lane, [`ModuleCtx::recv`] receives the critical events from the broadcast channel,
+    /// where they may be dropped under load. Clones of the context don't subscribe the lane
+    /// either.
+    pub fn without_priority_lane(mut self) -> Self {
+        self.priority_receiver = None;
+        self
+    }
+
     /// Returns the unique identifier of the event bus that this module context belongs to.
     /// The ID is stable for the lifetime of the context and can be used for logging.
     pub fn bus_id(&self) -> usize {
@@ -526,7 +1097,7 @@ async fn wait_for_event(
 ) -> Result<Event, ModuleCtxError> {
     let func = async move {
         loop {
-            match ctx.receiver.recv().await {
+            match ctx.recv().await {
                 Ok(event) => {
                     if EventKindType::from(&event.kind) == *response_type
                         && event.id() == Some(id)
@@ -558,4 +1129,5 @@ async fn wait_for_event(
         .map_err(|_| ModuleCtxError::ReceiveTimeout)?
 }
 
+pub mod auto_stop;
 pub mod test_helper;
@@ -216,7 +216,7 @@ fn run(mut rt: ResponseHandlerRuntime) -> tokio::task::JoinHandle<()> {
     tokio::spawn(async move {
         loop {
             tokio::select! {
-                event = rt.ctx.receiver.recv() =>
+                event = rt.ctx.recv() =>
                 match event {
                     Ok(event) => {
                         debug!("ResponseHandler received event {:?}", event);
@@ -0,0 +1,30 @@
+// SPDX-FileCopyrightText: 2026 All contributors
+//
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use module_core::{Event, EventBus, EventKind, LapFinished, Module, auto_stop::AutoStopModule};
+use std::time::Duration;
+
+#[tokio::test]
+#[test_log::test]
+pub async fn quit_after_configured_laps() {
+    let event_bus = EventBus::new();
+    let mut rx = event_bus.subscribe();
+    let mut module = AutoStopModule::new(event_bus.context(), 2);
+    let handle = tokio::spawn(async move { module.run().await });
+
+    for _ in 0..2 {
+        event_bus.publish(&Event {
+            kind: EventKind::LapFinishedEvent(LapFinished::from(Duration::from_secs(30)).into()),
+        });
+    }
+
+    let result = tokio::time::timeout(Duration::from_secs(1), handle)
+        .await
+        .expect("Auto stop module not stopped after two laps");
+    assert_eq!(result.unwrap(), Ok(()));
+    let quit_events = std::iter::from_fn(|| rx.try_recv().ok())
+        .filter(|event| matches!(event.kind, EventKind::QuitEvent))
+        .count();
+    assert_eq!(quit_events, 1);
+}
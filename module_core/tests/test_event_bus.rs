@@ -36,7 +36,7 @@ pub async fn test_wait_for_event() {
                 0,
                 0xFA,
                 Arc::new(vec![SessionInfo {
-                    id: "session1".to_string(),
+                    id: "session1".into(),
                     date: NaiveDateTime::default(),
                     track_name: "Test Track".to_string(),
                     laps: 0_usize,
@@ -64,5 +64,90 @@ pub async fn test_wait_for_event() {
     let response = payload_ref!(event.kind, EventKind::LoadStoredSessionIdsResponseEvent).unwrap();
     assert_eq!(response.id, 0);
     assert_eq!(response.receiver_addr, 0xFA);
-    assert_eq!(*response.data[0].id, "session1".to_string());
+    assert_eq!(response.data[0].id.as_str(), "session1");
+}
+
+#[tokio::test]
+#[test_log::test]
+pub async fn critical_event_survives_flood_of_positions() {
+    let event_bus = EventBus::new_with_config(EventBusConfig {
+        capacity: 16,
+        ..Default::default()
+    });
+    let mut ctx = event_bus.context();
+    let mut receiver = event_bus.subscribe();
+    let position = Arc::new(common::position::GnssPosition::new(
+        52.0,
+        11.0,
+        30.0,
+        &chrono::NaiveTime::default(),
+        &chrono::NaiveDate::default(),
+    ));
+    let publish_positions = |count: usize| {
+        for _ in 0..count {
+            event_bus.publish(&Event {
+                kind: EventKind::GnssPositionEvent(position.clone()),
+            });
+        }
+    };
+
+    publish_positions(100);
+    event_bus.publish(&Event {
+        kind: EventKind::SaveSessionRequestEvent(Request::new(
+            7,
+            0xFA,
+            Arc::new(std::sync::RwLock::new(
+                common::test_helper::session::get_session(),
+            )),
+        )),
+    });
+    publish_positions(100);
+
+    // The broadcast channel dropped the save request.
+    let mut broadcast_types = vec![];
+    loop {
+        match receiver.try_recv() {
+            Ok(event) => broadcast_types.push(event.event_type()),
+            Err(tokio::sync::broadcast::error::TryRecvError::Lagged(_)) => continue,
+            Err(_) => break,
+        }
+    }
+    assert!(!broadcast_types.contains(&EventKindType::SaveSessionRequestEvent));
+
+    // The context receives it first over the priority lane and exactly once.
+    let event = ctx.recv().await.unwrap();
+    assert_eq!(event.event_type(), EventKindType::SaveSessionRequestEvent);
+    assert_eq!(event.id(), Some(7));
+    let mut save_requests = 0;
+    loop {
+        match tokio::time::timeout(std::time::Duration::from_millis(50), ctx.recv()).await {
+            Ok(Ok(event)) => {
+                if event.event_type() == EventKindType::SaveSessionRequestEvent {
+                    save_requests += 1;
+                }
+            }
+            Ok(Err(tokio::sync::broadcast::error::RecvError::Lagged(_))) => continue,
+            _ => break,
+        }
+    }
+    assert_eq!(save_requests, 0);
+}
+
+#[tokio::test]
+#[test_log::test]
+pub async fn detached_context_receives_critical_events_from_broadcast() {
+    let event_bus = EventBus::default();
+    let mut ctx = event_bus.context().without_priority_lane();
+    let mut clone = ctx.clone();
+
+    event_bus.publish(&Event {
+        kind: EventKind::QuitEvent,
+    });
+
+    for ctx in [&mut ctx, &mut clone] {
+        let event = ctx.recv().await.unwrap();
+        assert_eq!(event.event_type(), EventKindType::QuitEvent);
+        let next = tokio::time::timeout(std::time::Duration::from_millis(50), ctx.recv()).await;
+        assert!(next.is_err(), "Critical event received twice");
+    }
 }
@@ -0,0 +1,66 @@
+// SPDX-FileCopyrightText: 2026 All contributors
+//
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use module_core::{EventBus, EventKind, Module, ModuleCtx, supervise};
+
+/// Module that panics in the first `panics` runs and afterwards runs until a quit event.
+struct PanickingModule {
+    ctx: ModuleCtx,
+    panics: usize,
+    runs: usize,
+}
+
+#[async_trait::async_trait]
+impl Module for PanickingModule {
+    async fn run(&mut self) -> Result<(), ()> {
+        self.runs += 1;
+        if self.runs <= self.panics {
+            panic!("run {} failed", self.runs);
+        }
+        let mut receiver = self.ctx.receiver();
+        while let Ok(event) = receiver.recv().await {
+            if let EventKind::QuitEvent = event.kind {
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[tokio::test]
+#[test_log::test]
+pub async fn restart_module_after_panic() {
+    let event_bus = EventBus::new();
+    let mut module = PanickingModule {
+        ctx: event_bus.context(),
+        panics: 1,
+        runs: 0,
+    };
+    let ctx = event_bus.context();
+    let quit = async move {
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        let _ = ctx.publish_event(EventKind::QuitEvent);
+    };
+
+    let (result, _) = tokio::join!(supervise("panicking", &mut module, 3), quit);
+
+    assert_eq!(result, Ok(()));
+    assert_eq!(module.runs, 2);
+}
+
+#[tokio::test]
+#[test_log::test]
+pub async fn give_up_after_max_restarts() {
+    let event_bus = EventBus::new();
+    let mut module = PanickingModule {
+        ctx: event_bus.context(),
+        panics: usize::MAX,
+        runs: 0,
+    };
+
+    let result = supervise("panicking", &mut module, 2).await;
+
+    assert_eq!(result, Err(()));
+    assert_eq!(module.runs, 3);
+}
@@ -2,55 +2,181 @@
 //
 // SPDX-License-Identifier: GPL-2.0-or-later
 
+use algorithm::{TrackSelectionPolicy, calculate_distance, select_track};
 use async_trait::async_trait;
-use chrono::Utc;
-use common::{lap::Lap, position::GnssPosition, session::Session};
+use common::{
+    clock::{Clock, SystemClock},
+    id::SessionId,
+    lap::Lap,
+    position::{GnssPosition, Position},
+    session::Session,
+    track::Track,
+};
 use module_core::{
-    DurationPtr, EventKind, Module, ModuleCtx, Request, Response, SaveSessionRequestPtr,
+    DurationPtr, EventKind, EventKindType, FinalizeSessionRequestPtr, LapFinishedPtr, Module,
+    ModuleCtx, ModuleInterface, Request, Response, SaveSessionRequestPtr, SaveSessionResponsePtr,
     TrackDetectionResponsePtr,
 };
 use std::sync::{Arc, RwLock};
 use tracing::{debug, error, info};
 
-pub struct ActiveSession {
+/// Configuration of the [`ActiveSession`] module.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ActiveSessionConfig {
+    /// Maximum number of laps per session.
+    ///
+    /// When the limit is reached, the session is finalized and a new session is started
+    /// on the same track. All sessions of such a run share the same `session_group` id.
+    /// `None` means the session is never split.
+    pub max_laps: Option<usize>,
+
+    /// Minimum distance in meters between two logged positions.
+    ///
+    /// A position closer to the last logged position is not logged, which reduces the size
+    /// of the sessions when the vehicle is (nearly) stationary while preserving its motion.
+    /// `None` logs every position.
+    pub min_log_distance: Option<f64>,
+
+    /// Minimum velocity in meters per second to start logging the out-lap.
+    ///
+    /// Positions before the first start of a lap are buffered from the first position faster
+    /// than this velocity on, and become the first log points of the first lap. So the out-lap
+    /// is preserved without buffering positions while the vehicle is parked.
+    /// `None` doesn't log positions before the first start of a lap.
+    pub pre_start_min_velocity: Option<f64>,
+
+    /// Defines which track the session is started on when several tracks are detected.
+    ///
+    /// With [`TrackSelectionPolicy::PromptAndWait`] no session is started until an
+    /// [`EventKind::TrackSelectedEvent`] arrives.
+    pub track_selection: TrackSelectionPolicy,
+
+    /// Records only the timing of the laps without log points.
+    ///
+    /// The saved sessions contain the sector times of the laps but no telemetry, which
+    /// shrinks them considerably. [`ActiveSessionConfig::pre_start_min_velocity`] and
+    /// [`ActiveSessionConfig::min_log_distance`] have no effect.
+    pub lightweight: bool,
+}
+
+/// Records the active session from the lap timer events.
+///
+/// # Type Parameters
+/// - `C`: The clock (e.g., [`SystemClock`]) that provides the date and time of new sessions.
+///   Defaults to [`SystemClock`].
+pub struct ActiveSession<C: Clock = SystemClock> {
     ctx: ModuleCtx,
+    clock: C,
     session: Option<Arc<RwLock<Session>>>,
     active_lap: Option<Lap>,
+    pre_start_log: Vec<GnssPosition>,
+    first_lap_started: bool,
+    last_position: Option<Position>,
+    /// Request id of the saves of the active session, changes with every new session.
+    save_request_id: u64,
+    /// Id under which the storage stored the active session.
+    stored_session_id: Option<SessionId>,
+    /// Request id of the final save of a replaced session that is finalized once stored.
+    finalize_on_save: Option<u64>,
+    config: ActiveSessionConfig,
 }
 
-impl ActiveSession {
+impl ActiveSession<SystemClock> {
     pub fn new(ctx: ModuleCtx) -> Self {
+        ActiveSession::new_with_config(ctx, ActiveSessionConfig::default())
+    }
+
+    /// Creates a new active session module with a custom configuration.
+    pub fn new_with_config(ctx: ModuleCtx, config: ActiveSessionConfig) -> Self {
+        ActiveSession::new_with_clock(ctx, config, SystemClock)
+    }
+}
+
+impl<C: Clock> ActiveSession<C> {
+    /// Creates a new active session module with a custom configuration and clock.
+    pub fn new_with_clock(ctx: ModuleCtx, config: ActiveSessionConfig, clock: C) -> Self {
         ActiveSession {
             ctx,
+            clock,
             session: None,
             active_lap: None,
+            pre_start_log: Vec::new(),
+            first_lap_started: false,
+            last_position: None,
+            save_request_id: 30,
+            stored_session_id: None,
+            finalize_on_save: None,
+            config,
         }
     }
 
+    /// Creates a new open session on the given track, makes it the active session and
+    /// announces the change.
+    fn start_session(&mut self, track: Track, session_group: Option<String>) {
+        let utc_date = self.clock.now();
+        let mut session = Session::new(utc_date.date_naive(), utc_date.time(), track);
+        session.finalized = false;
+        session.session_group =
+            session_group.or_else(|| self.config.max_laps.map(|_| session_group_id(&session)));
+        let session = Arc::new(RwLock::new(session));
+        info!(
+            "Active Session started on Track {}",
+            session.read().unwrap().track.name
+        );
+        self.session = Some(session.clone());
+        let _ = self
+            .ctx
+            .publish_event(EventKind::SessionChangedEvent(session));
+    }
+
     fn on_track_detected(&mut self, track_request: TrackDetectionResponsePtr) {
         if track_request.id != 10 || track_request.receiver_addr != 100 {
             return;
         }
-        let track = match track_request.data.first() {
-            Some(t) => t.clone(),
-            None => return, // TODO! send here a new request.
-        };
-
-        let utc_date = Utc::now();
-        let session = Arc::new(RwLock::new(Session::new(
-            utc_date.date_naive(),
-            utc_date.time(),
-            track,
-        )));
-        info!(
-            "Active Session started on Track {}",
-            session.read().unwrap().track.name
+        if track_request.data.is_empty() {
+            return; // TODO! send here a new request.
+        }
+        if self.session.is_some() {
+            return; // The track was already selected.
+        }
+        let track = select_track(
+            &track_request.data,
+            self.last_position.as_ref(),
+            self.config.track_selection,
         );
-        self.session = Some(session);
+        match track {
+            Some(track) => self.start_session(track.clone(), None),
+            None => info!(
+                "{} tracks detected, waiting for the track selection",
+                track_request.data.len()
+            ),
+        }
+    }
+
+    fn on_track_selected(&mut self, track: &Track) {
+        if self.session.is_some() {
+            debug!(
+                "Ignoring selection of track {}, session already started",
+                track.name
+            );
+            return;
+        }
+        self.start_session(track.clone(), None);
     }
 
     fn on_lap_started(&mut self) {
-        self.active_lap = Some(Lap::default());
+        let mut lap = Lap::default();
+        if !self.first_lap_started {
+            self.first_lap_started = true;
+            lap.log_points = std::mem::take(&mut self.pre_start_log);
+        }
+        self.active_lap = Some(lap);
+    }
+
+    fn on_lap_abandoned(&mut self) {
+        if self.active_lap.take().is_some() {
+            info!("Discarded abandoned lap");
+        }
     }
 
     fn on_sector_finished(&mut self, duration: DurationPtr) {
@@ -64,7 +190,8 @@ impl ActiveSession {
         }
     }
 
-    fn on_lap_finished(&mut self, duration: DurationPtr) {
+    fn on_lap_finished(&mut self, lap: LapFinishedPtr) {
+        let mut next_session = None;
         if let Some(session_ptr) = &self.session {
             let mut session = session_ptr
                 .write()
@@ -74,64 +201,238 @@ impl ActiveSession {
                 info!(
                     "Lap {} finished with duration {:?}",
                     session.laps.len(),
-                    duration
+                    lap.lap_time
                 );
             }
-            let request = SaveSessionRequestPtr::new(Request {
-                id: 30,
-                sender_addr: 40,
-                data: session_ptr.clone(),
-            });
-            let _ = self
-                .ctx
-                .publish_event(EventKind::SaveSessionRequestEvent(request));
+            if self
+                .config
+                .max_laps
+                .is_some_and(|max_laps| session.laps.len() >= max_laps)
+            {
+                info!(
+                    "Session reached the maximum of {} laps, starting a new session",
+                    session.laps.len()
+                );
+                session.finalized = true;
+                next_session = Some((session.track.clone(), session.session_group.clone()));
+            }
+            drop(session);
+            self.save_session(session_ptr);
+        }
+        if let Some((track, session_group)) = next_session {
+            // The storage compresses the finalized session once it stored the final save.
+            self.finalize_on_save = Some(self.save_request_id);
+            self.save_request_id += 1;
+            self.stored_session_id = None;
+            self.start_session(track, session_group);
+        }
+    }
+
+    /// Tracks under which id the storage stored the sessions.
+    ///
+    /// A replaced session is finalized as soon as its final save is stored.
+    fn on_session_saved(&mut self, response: &SaveSessionResponsePtr) {
+        if response.receiver_addr != 40 {
+            return;
+        }
+        let Ok(id) = &response.data else {
+            return;
+        };
+        if response.id == self.save_request_id {
+            self.stored_session_id = Some(id.clone());
+        }
+        if self.finalize_on_save == Some(response.id) {
+            self.finalize_on_save = None;
+            self.finalize_session(id.clone());
+        }
+    }
+
+    /// Finalizes the active session when the module stops.
+    ///
+    /// A session with finished laps is marked as finalized and saved a last time, so the
+    /// storage doesn't take it for an abandoned recording on the next start, and then
+    /// compressed. A session that wasn't stored yet is compressed by the storage on the next
+    /// start. A session without laps was never saved and is dropped.
+    fn on_quit(&mut self) {
+        let Some(session_ptr) = &self.session else {
+            return;
+        };
+        {
+            let mut session = session_ptr
+                .write()
+                .unwrap_or_else(|session| session.into_inner());
+            if session.laps.is_empty() {
+                return;
+            }
+            session.finalized = true;
         }
+        info!("Finalizing active session");
+        self.save_session(session_ptr);
+        if let Some(id) = self.stored_session_id.clone() {
+            self.finalize_session(id);
+        }
+    }
+
+    /// Requests the storage to finalize and compress the stored session with the given id.
+    fn finalize_session(&self, id: SessionId) {
+        let request = FinalizeSessionRequestPtr::new(Request {
+            id: self.save_request_id,
+            sender_addr: 40,
+            data: id,
+        });
+        let _ = self
+            .ctx
+            .publish_event(EventKind::FinalizeSessionRequestEvent(request));
+    }
+
+    /// Requests the storage to save the given session.
+    fn save_session(&self, session_ptr: &Arc<RwLock<Session>>) {
+        let request = SaveSessionRequestPtr::new(Request {
+            id: self.save_request_id,
+            sender_addr: 40,
+            data: session_ptr.clone(),
+        });
+        let _ = self
+            .ctx
+            .publish_event(EventKind::SaveSessionRequestEvent(request));
     }
 
     /// Handles a new GNSS position update.
     ///
     /// If a lap is currently active, the position is appended to its log for tracking.
+    /// Before the first start of a lap the position is buffered for the out-lap once the
+    /// configured pre-start velocity is exceeded. In lightweight mode nothing is logged.
     fn on_gnss_position(&mut self, gnss_pos: GnssPosition) {
+        self.last_position = Some(gnss_pos.to_position());
+        if self.config.lightweight {
+            return;
+        }
+        let min_log_distance = self.config.min_log_distance;
         if let Some(active_lap) = &mut self.active_lap {
-            active_lap.log_points.push(gnss_pos);
+            append_log_point(&mut active_lap.log_points, gnss_pos, min_log_distance);
+        } else if !self.first_lap_started
+            && let Some(min_velocity) = self.config.pre_start_min_velocity
+            && (!self.pre_start_log.is_empty() || gnss_pos.velocity() > min_velocity)
+        {
+            append_log_point(&mut self.pre_start_log, gnss_pos, min_log_distance);
         }
     }
 }
 
+/// Appends a GNSS position to a log.
+///
+/// Positions whose timestamp is not strictly after the last logged point (e.g. duplicates
+/// or out-of-order positions after a reconnect) are dropped to keep the log monotonic.
+/// Positions closer to the last logged point than the minimum log distance are dropped as well.
+fn append_log_point(
+    log_points: &mut Vec<GnssPosition>,
+    gnss_pos: GnssPosition,
+    min_log_distance: Option<f64>,
+) {
+    if let Some(last) = log_points.last() {
+        if gnss_pos.timestamp() <= last.timestamp() {
+            debug!(
+                "Dropping GNSS position at {}, not after last log point at {}",
+                gnss_pos.timestamp(),
+                last.timestamp()
+            );
+            return;
+        }
+        if let Some(min_log_distance) = min_log_distance
+            && calculate_distance(&last.to_position(), &gnss_pos.to_position()) < min_log_distance
+        {
+            return;
+        }
+    }
+    log_points.push(gnss_pos);
+}
+
+/// Returns the session group id for a run starting with the given session.
+fn session_group_id(session: &Session) -> String {
+    format!(
+        "{}_{}_{}",
+        session.track.name.to_lowercase(),
+        session.date.format("%Y%m%d"),
+        session.time.format("%H%M%S_%3f")
+    )
+}
+
 #[async_trait]
-impl Module for ActiveSession {
+impl<C: Clock + Send> Module for ActiveSession<C> {
+    fn event_interface(&self) -> ModuleInterface {
+        ModuleInterface {
+            consumes: vec![
+                EventKindType::QuitEvent,
+                EventKindType::DetectTrackResponseEvent,
+                EventKindType::TrackSelectedEvent,
+                EventKindType::LapStartedEvent,
+                EventKindType::LapAbandonedEvent,
+                EventKindType::SectorFinishedEvent,
+                EventKindType::LapFinishedEvent,
+                EventKindType::GnssPositionEvent,
+                EventKindType::GnssPositionBatchEvent,
+                EventKindType::CurrentSessionRequestEvent,
+                EventKindType::SaveSessionResponseEvent,
+            ],
+            produces: vec![
+                EventKindType::DetectTrackRequestEvent,
+                EventKindType::SessionChangedEvent,
+                EventKindType::SaveSessionRequestEvent,
+                EventKindType::FinalizeSessionRequestEvent,
+                EventKindType::CurrentSessionResponseEvent,
+            ],
+        }
+    }
+
     async fn run(&mut self) -> std::result::Result<(), ()> {
         let request = Request::empty_request(10, 100);
         let _ = self
             .ctx
             .publish_event(EventKind::DetectTrackRequestEvent(request));
         let mut run = true;
-        let mut receiver = self.ctx.receiver();
         while run {
             tokio::select! {
-                event = receiver.recv() => {
+                event = self.ctx.recv() => {
                     match event {
                         Ok(event) => {
                             match event.kind {
-                                EventKind::QuitEvent => run = false,
+                                EventKind::QuitEvent => {
+                                    self.on_quit();
+                                    run = false;
+                                },
                                 EventKind::DetectTrackResponseEvent(response) => {
                                     self.on_track_detected(response);
                                 },
+                                EventKind::TrackSelectedEvent(track) => {
+                                    self.on_track_selected(&track);
+                                },
                                 EventKind::LapStartedEvent => {
                                     debug!("Lap Started Event received in ActiveSession module");
                                     self.on_lap_started();
                                 },
+                                EventKind::LapAbandonedEvent => {
+                                    debug!("Lap Abandoned Event received in ActiveSession module");
+                                    self.on_lap_abandoned();
+                                },
                                 EventKind::SectorFinishedEvent(duration) => {
                                     debug!("Sector Finished Event received in ActiveSession module");
                                     self.on_sector_finished(duration);
                                 },
-                                EventKind::LapFinishedEvent(duration) => {
+                                EventKind::LapFinishedEvent(lap) => {
                                     debug!("Lap Finished Event received in ActiveSession module");
-                                    self.on_lap_finished(duration);
+                                    self.on_lap_finished(lap);
                                 }
                                 EventKind::GnssPositionEvent(gnss_pos) => {
                                     self.on_gnss_position(*gnss_pos);
                                 }
+                                EventKind::GnssPositionBatchEvent(positions) => {
+                                    for gnss_pos in positions.iter() {
+                                        self.on_gnss_position(*gnss_pos);
+                                    }
+                                }
+                                EventKind::SaveSessionResponseEvent(response) => {
+                                    self.on_session_saved(&response);
+                                }
                                 EventKind::CurrentSessionRequestEvent(request) => {
                                     let resp = Response {
                                         id: request.id,
@@ -2,16 +2,32 @@
 //
 // SPDX-License-Identifier: GPL-2.0-or-later
 
-use active_session::ActiveSession;
-use common::{lap::Lap, position::GnssPosition, test_helper::track::get_track};
+use active_session::{ActiveSession, ActiveSessionConfig};
+use algorithm::TrackSelectionPolicy;
+use chrono::NaiveDate;
+use common::{
+    id::SessionId,
+    lap::Lap,
+    position::{GnssPosition, Position},
+    test_helper::{fixed_clock::FixedClock, track::get_track},
+};
 use module_core::{
-    Event, EventBus, EventKind, EventKindType, Module, Request, Response, payload_ref,
+    Event, EventBus, EventKind, EventKindType, LapFinished, Module, Request, Response, payload_ref,
     test_helper::{register_response_event, stop_module, wait_for_event},
 };
-use std::time::Duration;
+use std::{path::PathBuf, sync::Arc, time::Duration};
+use storage::{FilesSystemStorage, IdScheme, read_session_file};
+use tokio::task::JoinHandle;
 use tracing::debug;
 
 fn create_module(eb: &EventBus) -> tokio::task::JoinHandle<Result<(), ()>> {
+    create_module_with_config(eb, ActiveSessionConfig::default())
+}
+
+fn create_module_with_config(
+    eb: &EventBus,
+    config: ActiveSessionConfig,
+) -> tokio::task::JoinHandle<Result<(), ()>> {
     if register_response_event(
         EventKindType::DetectTrackRequestEvent,
         Event {
@@ -31,7 +47,7 @@ fn create_module(eb: &EventBus) -> tokio::task::JoinHandle<Result<(), ()>> {
         panic!("Failed to register DetectTrackResponseEvent");
     }
 
-    let session = ActiveSession::new(eb.context());
+    let session = ActiveSession::new_with_config(eb.context(), config);
     tokio::spawn(async move {
         let mut session = session;
         session.run().await
@@ -65,7 +81,9 @@ async fn test_store_session_when_lap_finished() {
         kind: EventKind::SectorFinishedEvent(std::time::Duration::from_secs_f32(10.250).into()),
     });
     eb.publish(&Event {
-        kind: EventKind::LapFinishedEvent(std::time::Duration::from_secs_f32(30.750).into()),
+        kind: EventKind::LapFinishedEvent(
+            LapFinished::from(std::time::Duration::from_secs_f32(30.750)).into(),
+        ),
     });
 
     debug!("Waiting for SaveSessionRequestEvent...");
@@ -123,14 +141,23 @@ async fn test_store_log_points() {
         &chrono::NaiveTime::from_hms_milli_opt(0, 0, 0, 0).unwrap(),
         &chrono::NaiveDate::from_ymd_opt(1970, 1, 1).unwrap(),
     );
+    let next_gnss_position = GnssPosition::new(
+        52.0,
+        11.0,
+        100.0,
+        &chrono::NaiveTime::from_hms_milli_opt(0, 0, 0, 100).unwrap(),
+        &chrono::NaiveDate::from_ymd_opt(1970, 1, 1).unwrap(),
+    );
     eb.publish(&Event {
         kind: EventKind::GnssPositionEvent(gnss_position.into()),
     });
     eb.publish(&Event {
-        kind: EventKind::GnssPositionEvent(gnss_position.into()),
+        kind: EventKind::GnssPositionEvent(next_gnss_position.into()),
     });
     eb.publish(&Event {
-        kind: EventKind::LapFinishedEvent(std::time::Duration::from_secs_f32(30.750).into()),
+        kind: EventKind::LapFinishedEvent(
+            LapFinished::from(std::time::Duration::from_secs_f32(30.750)).into(),
+        ),
     });
 
     debug!("Waiting for SaveSessionRequestEvent...");
@@ -155,7 +182,7 @@ async fn test_store_log_points() {
         assert_eq!(session.laps[0].log_points.len(), 2);
         let lap = Lap {
             sectors: vec![],
-            log_points: vec![gnss_position, gnss_position],
+            log_points: vec![gnss_position, next_gnss_position],
         };
         assert_eq!(session.laps[0], lap);
         assert_eq!(session.track, get_track());
@@ -164,6 +191,71 @@ async fn test_store_log_points() {
     stop_module(&eb, &mut active_session).await;
 }
 
+fn gnss_position_at(millis: u32) -> GnssPosition {
+    GnssPosition::new(
+        52.0,
+        11.0,
+        100.0,
+        &chrono::NaiveTime::from_hms_milli_opt(0, 0, 0, millis).unwrap(),
+        &chrono::NaiveDate::from_ymd_opt(1970, 1, 1).unwrap(),
+    )
+}
+
+#[tokio::test]
+#[test_log::test]
+async fn test_drop_out_of_order_log_points() {
+    let eb = EventBus::default();
+    let mut active_session = create_module(&eb);
+
+    // Before emitting the lap start wait for the track detected event.
+    let _track_event = wait_for_event(
+        &mut eb.subscribe(),
+        Duration::from_millis(100),
+        EventKindType::DetectTrackResponseEvent,
+    )
+    .await;
+
+    eb.publish(&Event {
+        kind: EventKind::LapStartedEvent,
+    });
+    for millis in [100, 200, 150, 200, 300] {
+        eb.publish(&Event {
+            kind: EventKind::GnssPositionEvent(gnss_position_at(millis).into()),
+        });
+    }
+    eb.publish(&Event {
+        kind: EventKind::LapFinishedEvent(
+            LapFinished::from(std::time::Duration::from_secs_f32(30.750)).into(),
+        ),
+    });
+
+    let store_event = wait_for_event(
+        &mut eb.subscribe(),
+        Duration::from_millis(100),
+        EventKindType::SaveSessionRequestEvent,
+    )
+    .await;
+
+    //scope is needed to clear the rwlock at the end.
+    {
+        let session = payload_ref!(store_event.kind, EventKind::SaveSessionRequestEvent)
+            .expect("Received session doesn't have a payload")
+            .data
+            .read()
+            .unwrap_or_else(|session| session.into_inner());
+        assert_eq!(
+            session.laps[0].log_points,
+            vec![
+                gnss_position_at(100),
+                gnss_position_at(200),
+                gnss_position_at(300)
+            ]
+        );
+    }
+
+    stop_module(&eb, &mut active_session).await;
+}
+
 #[tokio::test]
 #[test_log::test]
 async fn test_current_session_request_response() {
@@ -181,7 +273,9 @@ async fn test_current_session_request_response() {
         kind: EventKind::LapStartedEvent,
     });
     eb.publish(&Event {
-        kind: EventKind::LapFinishedEvent(std::time::Duration::from_secs_f32(30.750).into()),
+        kind: EventKind::LapFinishedEvent(
+            LapFinished::from(std::time::Duration::from_secs_f32(30.750)).into(),
+        ),
     });
     debug!("Waiting for CurrentSessionRequestEvent...");
     eb.publish(&Event {
@@ -225,3 +319,634 @@ async fn test_current_session_request_response() {
 
     stop_module(&eb, &mut active_session).await;
 }
+
+#[tokio::test]
+#[test_log::test]
+async fn test_start_new_session_after_max_laps() {
+    let eb = EventBus::default();
+    let mut rx = eb.subscribe();
+    let mut active_session = create_module_with_config(
+        &eb,
+        ActiveSessionConfig {
+            max_laps: Some(2),
+            ..Default::default()
+        },
+    );
+    let first_session = wait_for_event(
+        &mut rx,
+        Duration::from_millis(100),
+        EventKindType::SessionChangedEvent,
+    )
+    .await;
+    let first_session = payload_ref!(first_session.kind, EventKind::SessionChangedEvent)
+        .unwrap()
+        .clone();
+
+    let mut saved_sessions = vec![];
+    for _ in 0..3 {
+        eb.publish(&Event {
+            kind: EventKind::LapStartedEvent,
+        });
+        eb.publish(&Event {
+            kind: EventKind::LapFinishedEvent(
+                LapFinished::from(std::time::Duration::from_secs_f32(30.750)).into(),
+            ),
+        });
+        let store_event = wait_for_event(
+            &mut rx,
+            Duration::from_millis(100),
+            EventKindType::SaveSessionRequestEvent,
+        )
+        .await;
+        let request = payload_ref!(store_event.kind, EventKind::SaveSessionRequestEvent).unwrap();
+        saved_sessions.push(request.data.clone());
+    }
+
+    assert!(Arc::ptr_eq(&saved_sessions[0], &first_session));
+    assert!(Arc::ptr_eq(&saved_sessions[1], &first_session));
+    assert!(!Arc::ptr_eq(&saved_sessions[2], &first_session));
+    //scope is needed to clear the rwlock at the end.
+    {
+        let first = first_session.read().unwrap();
+        let second = saved_sessions[2].read().unwrap();
+        assert_eq!(first.laps.len(), 2);
+        assert_eq!(second.laps.len(), 1);
+        assert_eq!(second.track, get_track());
+        assert!(first.session_group.is_some());
+        assert_eq!(first.session_group, second.session_group);
+    }
+
+    stop_module(&eb, &mut active_session).await;
+}
+
+#[tokio::test]
+#[test_log::test]
+async fn test_skip_log_points_within_min_log_distance() {
+    let eb = EventBus::default();
+    let mut active_session = create_module_with_config(
+        &eb,
+        ActiveSessionConfig {
+            min_log_distance: Some(2.0),
+            ..Default::default()
+        },
+    );
+
+    // Before emitting the lap start wait for the track detected event.
+    let _track_event = wait_for_event(
+        &mut eb.subscribe(),
+        Duration::from_millis(100),
+        EventKindType::DetectTrackResponseEvent,
+    )
+    .await;
+
+    // Positions north of the first position, the offset in meters and the time in ms.
+    let position = |meters: f64, millis: u32| {
+        GnssPosition::new(
+            52.0 + meters / 111300.0,
+            11.0,
+            100.0,
+            &chrono::NaiveTime::from_hms_milli_opt(0, 0, 0, millis).unwrap(),
+            &chrono::NaiveDate::from_ymd_opt(1970, 1, 1).unwrap(),
+        )
+    };
+    eb.publish(&Event {
+        kind: EventKind::LapStartedEvent,
+    });
+    for (index, meters) in [0.0, 0.5, 1.0, 2.5, 2.7, 6.0].iter().enumerate() {
+        eb.publish(&Event {
+            kind: EventKind::GnssPositionEvent(position(*meters, index as u32 * 100).into()),
+        });
+    }
+    eb.publish(&Event {
+        kind: EventKind::LapFinishedEvent(
+            LapFinished::from(std::time::Duration::from_secs_f32(30.750)).into(),
+        ),
+    });
+
+    let store_event = wait_for_event(
+        &mut eb.subscribe(),
+        Duration::from_millis(100),
+        EventKindType::SaveSessionRequestEvent,
+    )
+    .await;
+
+    //scope is needed to clear the rwlock at the end.
+    {
+        let session = payload_ref!(store_event.kind, EventKind::SaveSessionRequestEvent)
+            .expect("Received session doesn't have a payload")
+            .data
+            .read()
+            .unwrap_or_else(|session| session.into_inner());
+        assert_eq!(
+            session.laps[0].log_points,
+            vec![position(0.0, 0), position(2.5, 300), position(6.0, 500)]
+        );
+    }
+
+    stop_module(&eb, &mut active_session).await;
+}
+
+#[tokio::test]
+#[test_log::test]
+async fn test_discard_abandoned_lap() {
+    let eb = EventBus::default();
+    let mut active_session = create_module(&eb);
+
+    // Before emitting the lap start wait for the track detected event.
+    let _track_event = wait_for_event(
+        &mut eb.subscribe(),
+        Duration::from_millis(100),
+        EventKindType::DetectTrackResponseEvent,
+    )
+    .await;
+
+    let mut rx = eb.subscribe();
+    eb.publish(&Event {
+        kind: EventKind::LapStartedEvent,
+    });
+    eb.publish(&Event {
+        kind: EventKind::SectorFinishedEvent(std::time::Duration::from_secs_f32(10.250).into()),
+    });
+    eb.publish(&Event {
+        kind: EventKind::LapAbandonedEvent,
+    });
+    eb.publish(&Event {
+        kind: EventKind::LapFinishedEvent(
+            LapFinished::from(std::time::Duration::from_secs_f32(30.750)).into(),
+        ),
+    });
+
+    let store_event = wait_for_event(
+        &mut rx,
+        Duration::from_millis(100),
+        EventKindType::SaveSessionRequestEvent,
+    )
+    .await;
+
+    //scope is needed to clear the rwlock at the end.
+    {
+        let session = payload_ref!(store_event.kind, EventKind::SaveSessionRequestEvent)
+            .unwrap()
+            .data
+            .read()
+            .unwrap_or_else(|session| session.into_inner());
+        assert!(session.laps.is_empty());
+    }
+
+    stop_module(&eb, &mut active_session).await;
+}
+
+#[tokio::test]
+#[test_log::test]
+async fn test_buffer_out_lap_once_moving() {
+    let eb = EventBus::default();
+    let mut active_session = create_module_with_config(
+        &eb,
+        ActiveSessionConfig {
+            pre_start_min_velocity: Some(2.0),
+            ..Default::default()
+        },
+    );
+
+    // Before emitting the positions wait for the track detected event.
+    let _track_event = wait_for_event(
+        &mut eb.subscribe(),
+        Duration::from_millis(100),
+        EventKindType::DetectTrackResponseEvent,
+    )
+    .await;
+
+    let position = |velocity: f64, millis: u32| {
+        GnssPosition::new(
+            52.0,
+            11.0,
+            velocity,
+            &chrono::NaiveTime::from_hms_milli_opt(0, 0, 0, millis).unwrap(),
+            &chrono::NaiveDate::from_ymd_opt(1970, 1, 1).unwrap(),
+        )
+    };
+    // Parked, nothing must be buffered.
+    for millis in [100, 200, 300] {
+        eb.publish(&Event {
+            kind: EventKind::GnssPositionEvent(position(0.0, millis).into()),
+        });
+    }
+    // Moving, the positions are buffered even if slowing down again.
+    for (velocity, millis) in [(5.0, 400), (10.0, 500), (1.0, 600)] {
+        eb.publish(&Event {
+            kind: EventKind::GnssPositionEvent(position(velocity, millis).into()),
+        });
+    }
+    eb.publish(&Event {
+        kind: EventKind::LapStartedEvent,
+    });
+    eb.publish(&Event {
+        kind: EventKind::GnssPositionEvent(position(20.0, 700).into()),
+    });
+    eb.publish(&Event {
+        kind: EventKind::LapFinishedEvent(
+            LapFinished::from(std::time::Duration::from_secs_f32(30.750)).into(),
+        ),
+    });
+
+    let store_event = wait_for_event(
+        &mut eb.subscribe(),
+        Duration::from_millis(100),
+        EventKindType::SaveSessionRequestEvent,
+    )
+    .await;
+
+    //scope is needed to clear the rwlock at the end.
+    {
+        let session = payload_ref!(store_event.kind, EventKind::SaveSessionRequestEvent)
+            .expect("Received session doesn't have a payload")
+            .data
+            .read()
+            .unwrap_or_else(|session| session.into_inner());
+        assert_eq!(
+            session.laps[0].log_points,
+            vec![
+                position(5.0, 400),
+                position(10.0, 500),
+                position(1.0, 600),
+                position(20.0, 700)
+            ]
+        );
+    }
+
+    stop_module(&eb, &mut active_session).await;
+}
+
+#[tokio::test]
+#[test_log::test]
+async fn test_session_date_and_time_from_clock() {
+    let eb = EventBus::default();
+    let now = NaiveDate::from_ymd_opt(2026, 5, 17)
+        .unwrap()
+        .and_hms_milli_opt(14, 30, 15, 250)
+        .unwrap()
+        .and_utc();
+    if register_response_event(
+        EventKindType::DetectTrackRequestEvent,
+        Event {
+            kind: EventKind::DetectTrackResponseEvent(Response::new(10, 100, vec![get_track()])),
+        },
+        eb.context(),
+    )
+    .is_err()
+    {
+        panic!("Failed to register DetectTrackResponseEvent");
+    }
+    let mut rx = eb.subscribe();
+    let session = ActiveSession::new_with_clock(
+        eb.context(),
+        ActiveSessionConfig::default(),
+        FixedClock::new(now),
+    );
+    let mut active_session = tokio::spawn(async move {
+        let mut session = session;
+        session.run().await
+    });
+
+    let event = wait_for_event(
+        &mut rx,
+        Duration::from_millis(100),
+        EventKindType::SessionChangedEvent,
+    )
+    .await;
+    {
+        let session = payload_ref!(event.kind, EventKind::SessionChangedEvent)
+            .unwrap()
+            .read()
+            .unwrap_or_else(|session| session.into_inner());
+        assert_eq!(session.date, now.date_naive());
+        assert_eq!(session.time, now.time());
+    }
+
+    stop_module(&eb, &mut active_session).await;
+}
+
+/// Starts an active session without answering its track detection request.
+fn create_module_without_detection(
+    eb: &EventBus,
+    config: ActiveSessionConfig,
+) -> tokio::task::JoinHandle<Result<(), ()>> {
+    let session = ActiveSession::new_with_config(eb.context(), config);
+    tokio::spawn(async move {
+        let mut session = session;
+        session.run().await
+    })
+}
+
+fn track_at(name: &str, latitude: f64, longitude: f64) -> common::track::Track {
+    common::track::Track {
+        name: name.to_string(),
+        startline: Position {
+            latitude,
+            longitude,
+        },
+        ..get_track()
+    }
+}
+
+#[tokio::test]
+#[test_log::test]
+async fn start_session_on_nearest_of_several_detected_tracks() {
+    let eb = EventBus::default();
+    let mut rx = eb.subscribe();
+    let mut active_session = create_module_without_detection(
+        &eb,
+        ActiveSessionConfig {
+            track_selection: TrackSelectionPolicy::Nearest,
+            ..Default::default()
+        },
+    );
+    wait_for_event(
+        &mut rx,
+        Duration::from_millis(100),
+        EventKindType::DetectTrackRequestEvent,
+    )
+    .await;
+
+    eb.publish(&Event {
+        kind: EventKind::GnssPositionEvent(
+            GnssPosition::new(
+                52.0,
+                11.0,
+                10.0,
+                &chrono::NaiveTime::from_hms_milli_opt(13, 0, 0, 0).unwrap(),
+                &NaiveDate::from_ymd_opt(1970, 1, 1).unwrap(),
+            )
+            .into(),
+        ),
+    });
+    eb.publish(&Event {
+        kind: EventKind::DetectTrackResponseEvent(Response::new(
+            10,
+            100,
+            vec![
+                track_at("Far", 52.0030, 11.0),
+                track_at("Near", 52.0010, 11.0),
+            ],
+        )),
+    });
+
+    let event = wait_for_event(
+        &mut rx,
+        Duration::from_millis(100),
+        EventKindType::SessionChangedEvent,
+    )
+    .await;
+    let session = payload_ref!(event.kind, EventKind::SessionChangedEvent)
+        .unwrap()
+        .clone();
+    assert_eq!(session.read().unwrap().track.name, "Near");
+
+    stop_module(&eb, &mut active_session).await;
+}
+
+#[tokio::test]
+#[test_log::test]
+async fn wait_for_track_selection_of_several_detected_tracks() {
+    let eb = EventBus::default();
+    let mut rx = eb.subscribe();
+    let mut active_session = create_module_without_detection(
+        &eb,
+        ActiveSessionConfig {
+            track_selection: TrackSelectionPolicy::PromptAndWait,
+            ..Default::default()
+        },
+    );
+    wait_for_event(
+        &mut rx,
+        Duration::from_millis(100),
+        EventKindType::DetectTrackRequestEvent,
+    )
+    .await;
+
+    let tracks = vec![
+        track_at("Far", 52.0030, 11.0),
+        track_at("Near", 52.0010, 11.0),
+    ];
+    eb.publish(&Event {
+        kind: EventKind::DetectTrackResponseEvent(Response::new(10, 100, tracks.clone())),
+    });
+    eb.publish(&Event {
+        kind: EventKind::TrackSelectedEvent(tracks[1].clone().into()),
+    });
+
+    let event = wait_for_event(
+        &mut rx,
+        Duration::from_millis(100),
+        EventKindType::SessionChangedEvent,
+    )
+    .await;
+    let session = payload_ref!(event.kind, EventKind::SessionChangedEvent)
+        .unwrap()
+        .clone();
+    assert_eq!(session.read().unwrap().track.name, "Near");
+
+    stop_module(&eb, &mut active_session).await;
+}
+
+#[tokio::test]
+#[test_log::test]
+async fn lightweight_mode_records_sectors_without_log_points() {
+    let eb = EventBus::default();
+    let mut rx = eb.subscribe();
+    let mut active_session = create_module_with_config(
+        &eb,
+        ActiveSessionConfig {
+            lightweight: true,
+            pre_start_min_velocity: Some(0.0),
+            ..Default::default()
+        },
+    );
+    wait_for_event(
+        &mut rx,
+        Duration::from_millis(100),
+        EventKindType::SessionChangedEvent,
+    )
+    .await;
+
+    eb.publish(&Event {
+        kind: EventKind::GnssPositionEvent(gnss_position_at(0).into()),
+    });
+    eb.publish(&Event {
+        kind: EventKind::LapStartedEvent,
+    });
+    for millis in [100, 200] {
+        eb.publish(&Event {
+            kind: EventKind::GnssPositionEvent(gnss_position_at(millis).into()),
+        });
+    }
+    eb.publish(&Event {
+        kind: EventKind::SectorFinishedEvent(Duration::from_secs(20).into()),
+    });
+    eb.publish(&Event {
+        kind: EventKind::GnssPositionBatchEvent(
+            vec![gnss_position_at(300), gnss_position_at(400)].into(),
+        ),
+    });
+    eb.publish(&Event {
+        kind: EventKind::SectorFinishedEvent(Duration::from_secs(25).into()),
+    });
+    eb.publish(&Event {
+        kind: EventKind::LapFinishedEvent(LapFinished::from(Duration::from_secs(45)).into()),
+    });
+
+    let store_event = wait_for_event(
+        &mut rx,
+        Duration::from_millis(100),
+        EventKindType::SaveSessionRequestEvent,
+    )
+    .await;
+    let session = payload_ref!(store_event.kind, EventKind::SaveSessionRequestEvent)
+        .unwrap()
+        .data
+        .clone();
+    assert_eq!(
+        session.read().unwrap().laps,
+        vec![Lap {
+            sectors: vec![Duration::from_secs(20), Duration::from_secs(25)],
+            log_points: vec![],
+        }]
+    );
+
+    stop_module(&eb, &mut active_session).await;
+}
+
+/// Runs a storage module in an empty folder and returns it with its session folder.
+fn create_storage_module(
+    eb: &EventBus,
+    folder_name: &str,
+) -> (JoinHandle<Result<(), ()>>, PathBuf) {
+    let folder = PathBuf::from(format!("/tmp/rapid-rusty/{folder_name}"));
+    let _ = std::fs::remove_dir_all(&folder);
+    std::fs::create_dir_all(&folder).unwrap();
+    let mut storage = FilesSystemStorage::new(&folder, eb.context()).unwrap();
+    (
+        tokio::spawn(async move { storage.run().await }),
+        folder.join("session"),
+    )
+}
+
+/// Records sessions with the given configuration until `laps` laps are finished and stops
+/// the active session and the storage.
+///
+/// Returns the id of the first recorded session.
+async fn record_and_stop(
+    eb: &EventBus,
+    storage: &mut JoinHandle<Result<(), ()>>,
+    config: ActiveSessionConfig,
+    laps: usize,
+) -> SessionId {
+    let mut rx = eb.subscribe();
+    let mut active_session = create_module_with_config(eb, config);
+    let event = wait_for_event(
+        &mut rx,
+        Duration::from_millis(100),
+        EventKindType::SessionChangedEvent,
+    )
+    .await;
+    let session = payload_ref!(event.kind, EventKind::SessionChangedEvent)
+        .unwrap()
+        .clone();
+
+    for _ in 0..laps {
+        eb.publish(&Event {
+            kind: EventKind::LapStartedEvent,
+        });
+        eb.publish(&Event {
+            kind: EventKind::LapFinishedEvent(LapFinished::from(Duration::from_secs(45)).into()),
+        });
+        wait_for_event(
+            &mut rx,
+            Duration::from_secs(1),
+            EventKindType::SaveSessionResponseEvent,
+        )
+        .await;
+    }
+    // The QuitEvent overtakes the save response on the priority lane, so make sure the
+    // active session handled the response by waiting for the answer of a later request.
+    eb.publish(&Event {
+        kind: EventKind::CurrentSessionRequestEvent(
+            Request {
+                id: 20,
+                sender_addr: 200,
+                data: {},
+            }
+            .into(),
+        ),
+    });
+    wait_for_event(
+        &mut rx,
+        Duration::from_millis(100),
+        EventKindType::CurrentSessionResponseEvent,
+    )
+    .await;
+
+    stop_module(eb, &mut active_session).await;
+    // The storage handles the final save and the finalization within its shutdown grace.
+    wait_for_event(
+        &mut rx,
+        Duration::from_secs(1),
+        EventKindType::FinalizeSessionResponseEvent,
+    )
+    .await;
+    stop_module(eb, storage).await;
+    IdScheme::default().session_id(&session.read().unwrap())
+}
+
+#[tokio::test]
+#[test_log::test]
+async fn stopped_session_is_stored_finalized() {
+    let eb = EventBus::default();
+    let (mut storage, session_folder) =
+        create_storage_module(&eb, "stopped_session_is_stored_finalized");
+
+    let id = record_and_stop(&eb, &mut storage, ActiveSessionConfig::default(), 1).await;
+
+    let session = read_session_file(session_folder.join(format!("{id}.session")))
+        .await
+        .unwrap();
+    assert!(session.finalized);
+    assert_eq!(session.laps.len(), 1);
+}
+
+#[tokio::test]
+#[test_log::test]
+async fn stopped_session_is_compressed() {
+    let eb = EventBus::default();
+    let (mut storage, session_folder) = create_storage_module(&eb, "stopped_session_is_compressed");
+
+    let id = record_and_stop(&eb, &mut storage, ActiveSessionConfig::default(), 2).await;
+
+    assert!(session_folder.join(format!("{id}.session.gz")).exists());
+    assert!(!session_folder.join(format!("{id}.session")).exists());
+}
+
+#[tokio::test]
+#[test_log::test]
+async fn session_replaced_after_max_laps_is_compressed() {
+    let eb = EventBus::default();
+    let (mut storage, session_folder) =
+        create_storage_module(&eb, "session_replaced_after_max_laps_is_compressed");
+    let mut rx = eb.subscribe();
+    let config = ActiveSessionConfig {
+        max_laps: Some(1),
+        ..Default::default()
+    };
+
+    let id = record_and_stop(&eb, &mut storage, config, 1).await;
+
+    let event = wait_for_event(
+        &mut rx,
+        Duration::from_millis(100),
+        EventKindType::FinalizeSessionRequestEvent,
+    )
+    .await;
+    let request = payload_ref!(event.kind, EventKind::FinalizeSessionRequestEvent).unwrap();
+    assert_eq!(request.data, id);
+    assert!(session_folder.join(format!("{id}.session.gz")).exists());
+}
@@ -0,0 +1,116 @@
+// SPDX-FileCopyrightText: 2026 All contributors
+//
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use crate::GnssPosition;
+use chrono::{NaiveDateTime, TimeDelta};
+use module_core::{EventKind, Module, ModuleCtx};
+use tracing::error;
+
+/// Configuration of an [`AccelerationModule`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AccelerationConfig {
+    /// Maximum time between two positions to derive an acceleration from them.
+    ///
+    /// Larger gaps, e.g. after a loss of the GNSS fix, would average the acceleration over
+    /// the whole gap, so no acceleration is published for them.
+    pub max_interval: TimeDelta,
+}
+
+impl Default for AccelerationConfig {
+    fn default() -> Self {
+        AccelerationConfig {
+            max_interval: TimeDelta::seconds(1),
+        }
+    }
+}
+
+/// Derives the longitudinal acceleration from the velocity of consecutive GNSS positions.
+///
+/// The acceleration is the velocity change divided by the time between the timestamps of
+/// the positions, so irregular position rates are handled.
+#[derive(Clone, Debug)]
+pub struct AccelerationCalculator {
+    config: AccelerationConfig,
+    last: Option<(NaiveDateTime, f64)>,
+}
+
+impl AccelerationCalculator {
+    /// Creates a new calculator with the given configuration.
+    pub fn new(config: AccelerationConfig) -> Self {
+        AccelerationCalculator { config, last: None }
+    }
+
+    /// Adds a position and returns the acceleration in m/s² since the previous position.
+    ///
+    /// Returns `None` for the first position, for positions with the same timestamp as the
+    /// previous one and after gaps longer than [`AccelerationConfig::max_interval`].
+    pub fn update(&mut self, pos: &GnssPosition) -> Option<f64> {
+        let timestamp = pos.timestamp();
+        let velocity = pos.velocity();
+        let (last_timestamp, last_velocity) = match self.last {
+            Some(last) => last,
+            None => {
+                self.last = Some((timestamp, velocity));
+                return None;
+            }
+        };
+        let interval = timestamp - last_timestamp;
+        if interval == TimeDelta::zero() {
+            // A duplicate fix carries no new information, keep the previous one as reference.
+            return None;
+        }
+        self.last = Some((timestamp, velocity));
+        if interval < TimeDelta::zero() || interval > self.config.max_interval {
+            return None;
+        }
+        let seconds = interval.num_nanoseconds()? as f64 / 1e9;
+        Some((velocity - last_velocity) / seconds)
+    }
+}
+
+/// Publishes an [`EventKind::AccelerationEvent`] for every [`EventKind::GnssPositionEvent`]
+/// an acceleration can be derived for.
+pub struct AccelerationModule {
+    ctx: ModuleCtx,
+    calculator: AccelerationCalculator,
+}
+
+impl AccelerationModule {
+    /// Creates a new acceleration module with the default configuration.
+    pub fn new(ctx: ModuleCtx) -> Self {
+        AccelerationModule::new_with_config(ctx, AccelerationConfig::default())
+    }
+
+    /// Creates a new acceleration module with a custom configuration.
+    pub fn new_with_config(ctx: ModuleCtx, config: AccelerationConfig) -> Self {
+        AccelerationModule {
+            ctx,
+            calculator: AccelerationCalculator::new(config),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Module for AccelerationModule {
+    async fn run(&mut self) -> Result<(), ()> {
+        let mut run = true;
+        while run {
+            match self.ctx.recv().await {
+                Ok(event) => match event.kind {
+                    EventKind::QuitEvent => run = false,
+                    EventKind::GnssPositionEvent(pos) => {
+                        if let Some(acceleration) = self.calculator.update(&pos) {
+                            let _ = self
+                                .ctx
+                                .publish_event(EventKind::AccelerationEvent(acceleration));
+                        }
+                    }
+                    _ => (),
+                },
+                Err(e) => error!("Failed to receive event in acceleration module. Error: {e}"),
+            }
+        }
+        Ok(())
+    }
+}
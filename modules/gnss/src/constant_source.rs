@@ -5,12 +5,13 @@
 use crate::GnssPosition;
 use chrono::Utc;
 use common::position::{GnssInformation, GnssStatus, Position};
-use module_core::{Event, EventKind, Module, ModuleCtx};
+use module_core::{Event, EventKind, EventSender, HEARTBEAT_INTERVAL, Module, ModuleCtx};
 use std::{
     io::{Error, ErrorKind},
     sync::Arc,
     time,
 };
+use tracing::info;
 use utm::{self, lat_lon_to_zone_number, lat_to_zone_letter, to_utm_wgs84, wsg84_utm_to_lat_lon};
 
 /// A GNSS source that reports GNSS positions in a constant frequency
@@ -19,7 +20,8 @@ struct ConstantGnssPositionSourceRuntime {
     next_position: usize,
     current_position: UtmPoint,
     velocity: f64,
-    sender: tokio::sync::broadcast::Sender<Event>,
+    looping: bool,
+    sender: EventSender,
 }
 
 #[derive(Debug, Clone, Copy, Default)]
@@ -31,8 +33,12 @@ struct UtmPoint {
 }
 
 impl ConstantGnssPositionSourceRuntime {
-    async fn handle_tick(&mut self) {
-        if self.next_position > 0 && self.next_position <= self.points.len() {
+    /// Moves the position towards the next point and publishes it.
+    ///
+    /// Returns `true` if the last point is reached and the replay is not looping.
+    async fn handle_tick(&mut self) -> bool {
+        let mut finished = false;
+        if self.next_position > 0 && self.next_position < self.points.len() {
             let p0 = &self.points[self.next_position];
             let mut direction = UtmPoint {
                 x: p0.x - self.current_position.x,
@@ -61,13 +67,19 @@ impl ConstantGnssPositionSourceRuntime {
             direction.y = p0.y - self.current_position.y;
             let new_length = (direction.x * direction.x + direction.y * direction.y).sqrt();
             if new_length > length {
-                self.next_position += 2;
+                self.next_position += 1;
                 if self.next_position >= self.points.len() {
-                    self.next_position = 0;
+                    if self.looping {
+                        self.next_position = 0;
+                    } else {
+                        self.current_position = self.points[self.points.len() - 1];
+                        finished = true;
+                    }
                 }
             }
         } else if self.next_position == 0 {
             self.next_position += 1;
+            finished = !self.looping && self.points.len() == 1;
         } else {
             self.next_position = 0;
         }
@@ -78,7 +90,7 @@ impl ConstantGnssPositionSourceRuntime {
             self.current_position.zone,
             self.current_position.zone_letter,
         ) else {
-            return;
+            return finished;
         };
 
         let gnss_pos = Arc::new(GnssPosition::new(
@@ -91,6 +103,7 @@ impl ConstantGnssPositionSourceRuntime {
         let _ = self.sender.send(Event {
             kind: EventKind::GnssPositionEvent(gnss_pos.clone()),
         });
+        finished
     }
 
     const POSITION_INTERVAL_MS: u8 = 100;
@@ -126,6 +139,7 @@ struct ConstantGnssModuleConfig {
     positions: Vec<UtmPoint>,
     velocity: f64,
     information_interval: std::time::Duration,
+    looping: bool,
 }
 
 pub struct ConstantGnssModule {
@@ -153,10 +167,20 @@ impl ConstantGnssModule {
                 positions: utm_points,
                 velocity,
                 information_interval,
+                looping: true,
             }),
         };
         Ok(module)
     }
+
+    /// Sets whether the replay starts over after the last position.
+    ///
+    /// Defaults to `true`. Without looping the replay stops at the last position and
+    /// publishes an [`EventKind::ReplayFinishedEvent`], e.g. for finite-length replay tests.
+    pub fn with_looping(mut self, looping: bool) -> Self {
+        Arc::make_mut(&mut self.config).looping = looping;
+        self
+    }
 }
 
 #[async_trait::async_trait]
@@ -171,10 +195,14 @@ impl Module for ConstantGnssModule {
         let sender = self.ctx.sender.clone();
         let gnss_info_task_handle =
             tokio::spawn(async move { constant_gnss_infomation_task(sender, config).await });
+        let mut heartbeat = tokio::time::interval(HEARTBEAT_INTERVAL);
         let mut run = true;
         while run {
             tokio::select! {
-                event = self.ctx.receiver.recv() => {
+                _ = heartbeat.tick() => {
+                    let _ = self.ctx.publish_heartbeat("gnss");
+                }
+                event = self.ctx.recv() => {
                 match event {
                     Ok(event) => {
                         if let EventKind::QuitEvent = event.kind {
@@ -192,10 +220,7 @@ impl Module for ConstantGnssModule {
     }
 }
 
-async fn constant_gnss_position_task(
-    sender: tokio::sync::broadcast::Sender<Event>,
-    config: Arc<ConstantGnssModuleConfig>,
-) {
+async fn constant_gnss_position_task(sender: EventSender, config: Arc<ConstantGnssModuleConfig>) {
     let mut timer = tokio::time::interval(time::Duration::from_millis(
         ConstantGnssPositionSourceRuntime::POSITION_INTERVAL_MS.into(),
     ));
@@ -204,18 +229,22 @@ async fn constant_gnss_position_task(
         next_position: 0,
         current_position: config.positions[0],
         velocity: config.velocity,
+        looping: config.looping,
         sender,
     };
     loop {
         timer.tick().await;
-        runtime.handle_tick().await;
+        if runtime.handle_tick().await {
+            info!("Replay of the constant GNSS source finished");
+            let _ = runtime.sender.send(Event {
+                kind: EventKind::ReplayFinishedEvent,
+            });
+            return;
+        }
     }
 }
 
-async fn constant_gnss_infomation_task(
-    sender: tokio::sync::broadcast::Sender<Event>,
-    config: Arc<ConstantGnssModuleConfig>,
-) {
+async fn constant_gnss_infomation_task(sender: EventSender, config: Arc<ConstantGnssModuleConfig>) {
     let mut timer = tokio::time::interval(config.information_interval);
     let info = Arc::new(GnssInformation::new(&GnssStatus::Fix3d, 8));
     loop {
@@ -3,41 +3,123 @@
 // SPDX-License-Identifier: GPL-2.0-or-later
 
 use crate::GnssPosition;
+use chrono::{DateTime, NaiveDateTime, Utc};
 use common::position::{GnssInformation, GnssStatus};
 use futures::StreamExt;
 use gpsd_proto::{self, Mode, Satellite, Sky, Tpv};
 use module_core::Event;
-use module_core::{EventKind, Module, ModuleCtx};
+use module_core::{EventKind, EventSender, HEARTBEAT_INTERVAL, Module, ModuleCtx};
 use std::{
     io::{self, Error, ErrorKind},
     net::SocketAddr,
     str::FromStr,
     sync::Arc,
+    time::Duration,
 };
 use tokio::sync::Notify;
 use tokio::{io::AsyncWriteExt, net::TcpStream};
 use tokio_util::codec::{Framed, LinesCodec};
+use tracing::warn;
+
+/// Defines how strict the time of a gpsd TPV message is parsed.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum TimeParsing {
+    /// Only accepts times that `chrono` parses as RFC 3339 without any tolerance.
+    Strict,
+
+    /// Additionally tolerates surrounding whitespace and a missing timezone designator
+    /// (interpreted as UTC), also with a space instead of the `T` separator, as sent by
+    /// some receivers.
+    #[default]
+    Lenient,
+}
+
+/// Configuration of a [`GpsdModule`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GpsdConfig {
+    /// Defines how the time of TPV messages is parsed.
+    pub time_parsing: TimeParsing,
+
+    /// Minimum change of the used satellites before a SKY message emits a
+    /// [`EventKind::GnssInformationEvent`].
+    ///
+    /// The count must differ by more than the threshold from the last reported one, so
+    /// satellites flickering in and out don't flood the event bus. `0` reports
+    /// every SKY message.
+    pub satellite_change_threshold: usize,
+
+    /// Delay before the first attempt to reconnect to gpsd after the connection was lost,
+    /// e.g. because gpsd restarted.
+    ///
+    /// The delay doubles with every failed attempt up to
+    /// [`max_reconnect_delay`](Self::max_reconnect_delay).
+    pub reconnect_delay: Duration,
+
+    /// Maximum delay between two attempts to reconnect to gpsd.
+    pub max_reconnect_delay: Duration,
+}
+
+impl Default for GpsdConfig {
+    fn default() -> Self {
+        GpsdConfig {
+            time_parsing: TimeParsing::default(),
+            satellite_change_threshold: 0,
+            reconnect_delay: Duration::from_millis(500),
+            max_reconnect_delay: Duration::from_secs(5),
+        }
+    }
+}
+
+/// Formats without timezone designator that are accepted in [`TimeParsing::Lenient`] mode.
+const LENIENT_TIME_FORMATS: [&str; 2] = ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f"];
+
+/// Parses the time of a gpsd TPV message.
+///
+/// Returns `None` if the time can't be parsed with the given [`TimeParsing`] mode.
+pub fn parse_time(time: &str, time_parsing: TimeParsing) -> Option<DateTime<Utc>> {
+    if let Ok(datetime) = DateTime::<Utc>::from_str(time) {
+        return Some(datetime);
+    }
+    if time_parsing == TimeParsing::Strict {
+        return None;
+    }
+    let time = time.trim();
+    if let Ok(datetime) = DateTime::<Utc>::from_str(time) {
+        return Some(datetime);
+    }
+    LENIENT_TIME_FORMATS.iter().find_map(|format| {
+        NaiveDateTime::parse_from_str(time, format)
+            .ok()
+            .map(|datetime| datetime.and_utc())
+    })
+}
 
 /// GPSD daemon based GNSS source
+///
+/// The runtime isn't bound to a single gpsd connection, the last known `mode` and `sats`
+/// are kept until a new connection delivers new data.
 struct GpsdPositionInformationRuntime {
-    /// The status of GNSS system
+    /// The last known status of GNSS system
     mode: GnssStatus,
-    /// The amount of satellites used for the GNSS position
+    /// The last known amount of satellites used for the GNSS position
     sats: usize,
     /// The start signal for the GPSD task to start execution
     notify: Arc<Notify>,
     /// The sender of the event_bus to emit the events
-    sender: tokio::sync::broadcast::Sender<Event>,
+    sender: EventSender,
+    /// The configuration of the GPSD source
+    config: GpsdConfig,
 }
 
 impl GpsdPositionInformationRuntime {
     /// Creates a new instance of the GPSD runtime.
-    pub fn new(sender: tokio::sync::broadcast::Sender<Event>) -> Self {
+    pub fn new(sender: EventSender, config: GpsdConfig) -> Self {
         GpsdPositionInformationRuntime {
             mode: GnssStatus::Unknown,
             sats: 0,
             notify: Arc::new(Notify::new()),
             sender,
+            config,
         }
     }
 
@@ -46,16 +128,22 @@ impl GpsdPositionInformationRuntime {
         let Some(lon) = tpv.lon else { return };
         let Some(speed) = tpv.speed else { return };
         let Some(ref time) = tpv.time else { return };
-        let Ok(datetime) = chrono::DateTime::<chrono::Utc>::from_str(time) else {
+        let Some(datetime) = parse_time(time, self.config.time_parsing) else {
+            warn!("Dropping gpsd TPV message with unparseable time \"{time}\"");
             return;
         };
-        let position = Arc::new(GnssPosition::new(
+        let mut position = GnssPosition::new(
             lat,
             lon,
             speed.into(),
             &datetime.time(),
             &datetime.date_naive(),
-        ));
+        );
+        // gpsd 3.20 and newer report the MSL altitude in altMSL and deprecated alt.
+        if let Some(altitude) = tpv.alt_msl.or(tpv.alt) {
+            position = position.with_altitude(altitude.into());
+        }
+        let position = Arc::new(position);
         let _ = self.sender.send(Event {
             kind: EventKind::GnssPositionEvent(position.clone()),
         });
@@ -70,7 +158,13 @@ impl GpsdPositionInformationRuntime {
         let Some(ref sat) = sky.satellites else {
             return;
         };
-        self.sats = used_satellites(sat);
+        let sats = used_satellites(sat);
+        if self.config.satellite_change_threshold > 0
+            && sats.abs_diff(self.sats) <= self.config.satellite_change_threshold
+        {
+            return;
+        }
+        self.sats = sats;
         let info = Arc::new(GnssInformation::new(&self.mode, self.sats));
         let _ = self.sender.send(Event {
             kind: EventKind::GnssInformationEvent(info.clone()),
@@ -90,12 +184,15 @@ fn used_satellites(sattelites: &[Satellite]) -> usize {
     sattelites.iter().filter(|s| s.used).count()
 }
 
-async fn gpsd_reader(mut stream: TcpStream, mut runtime: GpsdPositionInformationRuntime) {
-    runtime.notify.notified().await;
-    stream
+/// Reads the messages of a single gpsd connection until the stream ends.
+async fn gpsd_reader(mut stream: TcpStream, runtime: &mut GpsdPositionInformationRuntime) {
+    if let Err(e) = stream
         .write_all(gpsd_proto::ENABLE_WATCH_CMD.as_bytes())
         .await
-        .unwrap();
+    {
+        warn!("Failed to enable the gpsd notifications. Error: {e}");
+        return;
+    }
     let mut framed = Framed::new(stream, LinesCodec::new());
     while let Some(result) = framed.next().await {
         match result {
@@ -114,6 +211,35 @@ async fn gpsd_reader(mut stream: TcpStream, mut runtime: GpsdPositionInformation
     }
 }
 
+/// Reads the messages of gpsd and reconnects whenever the connection is lost.
+///
+/// The reconnection attempts back off from [`GpsdConfig::reconnect_delay`] up to
+/// [`GpsdConfig::max_reconnect_delay`].
+async fn gpsd_connection(
+    stream: TcpStream,
+    address: SocketAddr,
+    runtime: &mut GpsdPositionInformationRuntime,
+) {
+    let mut stream = Some(stream);
+    loop {
+        if let Some(stream) = stream.take() {
+            gpsd_reader(stream, runtime).await;
+            warn!("Connection to gpsd at {address} lost, reconnecting");
+        }
+        let mut delay = runtime.config.reconnect_delay;
+        while stream.is_none() {
+            tokio::time::sleep(delay).await;
+            match TcpStream::connect(address).await {
+                Ok(connected) => stream = Some(connected),
+                Err(e) => {
+                    warn!("Failed to reconnect to gpsd at {address}. Error: {e}");
+                    delay = (delay * 2).min(runtime.config.max_reconnect_delay);
+                }
+            }
+        }
+    }
+}
+
 pub struct GpsdModule {
     ctx: ModuleCtx,
     gpsd_handle: tokio::task::JoinHandle<()>,
@@ -122,14 +248,29 @@ pub struct GpsdModule {
 
 impl GpsdModule {
     pub async fn new(ctx: ModuleCtx, address: &str) -> Result<Self, Error> {
+        GpsdModule::new_with_config(ctx, address, GpsdConfig::default()).await
+    }
+
+    /// Creates a new gpsd source with a custom configuration.
+    ///
+    /// The initial connection to gpsd must succeed, later connection losses are recovered
+    /// with the reconnection parameters of the configuration.
+    pub async fn new_with_config(
+        ctx: ModuleCtx,
+        address: &str,
+        config: GpsdConfig,
+    ) -> Result<Self, Error> {
         let address: SocketAddr = match address.parse() {
             Ok(addr) => addr,
             Err(e) => return Err(io::Error::new(ErrorKind::InvalidInput, e)),
         };
         let socket = TcpStream::connect(address).await?;
-        let rt = GpsdPositionInformationRuntime::new(ctx.sender.clone());
+        let mut rt = GpsdPositionInformationRuntime::new(ctx.sender.clone(), config);
         let notify = rt.notify.clone();
-        let gpsd_reader_task_handle = tokio::spawn(async move { gpsd_reader(socket, rt).await });
+        let gpsd_reader_task_handle = tokio::spawn(async move {
+            rt.notify.notified().await;
+            gpsd_connection(socket, address, &mut rt).await
+        });
         Ok(GpsdModule {
             ctx,
             gpsd_handle: gpsd_reader_task_handle,
@@ -142,10 +283,14 @@ impl GpsdModule {
 impl Module for GpsdModule {
     async fn run(&mut self) -> Result<(), ()> {
         self.task_notify.notify_one();
+        let mut heartbeat = tokio::time::interval(HEARTBEAT_INTERVAL);
         let mut run = true;
         while run {
             tokio::select! {
-                event = self.ctx.receiver.recv() => {
+                _ = heartbeat.tick() => {
+                    let _ = self.ctx.publish_heartbeat("gnss");
+                }
+                event = self.ctx.recv() => {
                     match event {
                         Ok(event) => {
                             if let EventKind::QuitEvent = event.kind {
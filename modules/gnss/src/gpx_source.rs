@@ -0,0 +1,170 @@
+// SPDX-FileCopyrightText: 2026 All contributors
+//
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+//! A GNSS source that replays the track points of a GPX file with their recorded timing.
+
+use crate::{
+    GnssPosition,
+    gpsd_source::{TimeParsing, parse_time},
+    replay::{ReplayConfig, run_replay},
+};
+use chrono::NaiveDateTime;
+use common::position::Position;
+use module_core::{Module, ModuleCtx};
+use std::{
+    io::{Error, ErrorKind},
+    path::Path,
+    sync::Arc,
+};
+
+/// Returns the value of the attribute `name` of an XML start tag.
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let pattern = format!("{name}=");
+    let mut search = 0;
+    while let Some(found) = tag[search..].find(&pattern) {
+        let start = search + found;
+        search = start + pattern.len();
+        if !tag[..start].ends_with(char::is_whitespace) {
+            continue;
+        }
+        let quote = tag[search..].chars().next()?;
+        if quote != '"' && quote != '\'' {
+            return None;
+        }
+        let value = &tag[search + 1..];
+        return value.find(quote).map(|end| &value[..end]);
+    }
+    None
+}
+
+/// Returns the text of the child element `name` of an XML element body.
+fn element_text<'a>(body: &'a str, name: &str) -> Option<&'a str> {
+    let start = body.find(&format!("<{name}>"))? + name.len() + 2;
+    let end = body[start..].find(&format!("</{name}>"))?;
+    Some(body[start..start + end].trim())
+}
+
+/// Parses the track points of a GPX document.
+///
+/// Every `<trkpt>` needs the `lat` and `lon` attributes and a `<time>` child, the optional
+/// `<ele>` child becomes the altitude. The velocity of a point is derived from the distance to
+/// the previous point, the first point gets the velocity of the second one.
+pub fn parse_gpx(gpx: &str) -> Result<Vec<GnssPosition>, Error> {
+    let invalid = |msg: String| Error::new(ErrorKind::InvalidData, msg);
+    let mut points: Vec<(Position, NaiveDateTime, Option<f64>)> = Vec::new();
+    let mut rest = gpx;
+    while let Some(start) = rest.find("<trkpt") {
+        rest = &rest[start..];
+        let tag_end = rest
+            .find('>')
+            .ok_or_else(|| invalid("Unterminated trkpt element".to_string()))?;
+        let tag = &rest[..tag_end];
+        let body = if tag.ends_with('/') {
+            ""
+        } else {
+            let end = rest
+                .find("</trkpt>")
+                .ok_or_else(|| invalid("Unterminated trkpt element".to_string()))?;
+            &rest[tag_end + 1..end]
+        };
+        let coordinate = |name: &str| {
+            attribute(tag, name)
+                .and_then(|value| value.trim().parse::<f64>().ok())
+                .ok_or_else(|| invalid(format!("trkpt without valid {name} attribute")))
+        };
+        let position = Position {
+            latitude: coordinate("lat")?,
+            longitude: coordinate("lon")?,
+        };
+        let time =
+            element_text(body, "time").ok_or_else(|| invalid("trkpt without time".to_string()))?;
+        let time = parse_time(time, TimeParsing::Lenient)
+            .ok_or_else(|| invalid(format!("trkpt with invalid time \"{time}\"")))?;
+        let altitude = element_text(body, "ele").and_then(|ele| ele.parse::<f64>().ok());
+        points.push((position, time.naive_utc(), altitude));
+        rest = &rest[tag_end..];
+    }
+
+    let velocities: Vec<f64> = points
+        .windows(2)
+        .map(|pair| {
+            let seconds = (pair[1].1 - pair[0].1).num_milliseconds() as f64 / 1000.0;
+            if seconds > 0.0 {
+                pair[0].0.distance_to(&pair[1].0) / seconds
+            } else {
+                0.0
+            }
+        })
+        .collect();
+    Ok(points
+        .iter()
+        .enumerate()
+        .map(|(index, (position, timestamp, altitude))| {
+            let velocity = match index {
+                0 => velocities.first().copied().unwrap_or_default(),
+                _ => velocities[index - 1],
+            };
+            let pos = GnssPosition::new(
+                position.latitude,
+                position.longitude,
+                velocity,
+                &timestamp.time(),
+                &timestamp.date(),
+            );
+            match altitude {
+                Some(altitude) => pos.with_altitude(*altitude),
+                None => pos,
+            }
+        })
+        .collect())
+}
+
+/// A GNSS source that replays the track points of a GPX file, e.g. to replay a recorded drive.
+///
+/// The positions are published with the recorded time between the track points divided by
+/// the playback rate and carry the recorded timestamps.
+pub struct GpxReplayModule {
+    ctx: ModuleCtx,
+    config: Arc<ReplayConfig>,
+}
+
+impl GpxReplayModule {
+    /// Creates the replay of the GPX file at `path`.
+    ///
+    /// A `playback_rate` of `2.0` replays the track twice as fast as it was recorded.
+    ///
+    /// Errors if the file can't be read, has no track points, a track point can't be parsed
+    /// or the playback rate isn't positive.
+    pub fn new(ctx: ModuleCtx, path: &Path, playback_rate: f64) -> Result<Self, Error> {
+        let gpx = std::fs::read_to_string(path)?;
+        GpxReplayModule::from_gpx(ctx, &gpx, playback_rate)
+    }
+
+    /// Creates the replay of a GPX document, see [`GpxReplayModule::new`].
+    pub fn from_gpx(ctx: ModuleCtx, gpx: &str, playback_rate: f64) -> Result<Self, Error> {
+        let config = ReplayConfig::new(parse_gpx(gpx)?, playback_rate, "GPX track")?;
+        Ok(GpxReplayModule {
+            ctx,
+            config: Arc::new(config),
+        })
+    }
+
+    /// Sets whether the replay starts over after the last track point.
+    ///
+    /// Defaults to `false`, the replay stops at the last track point and publishes an
+    /// [`module_core::EventKind::ReplayFinishedEvent`]. A looping replay shifts the timestamps of
+    /// every round, so they keep increasing.
+    pub fn with_looping(mut self, looping: bool) -> Self {
+        Arc::make_mut(&mut self.config).looping = looping;
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl Module for GpxReplayModule {
+    async fn run(&mut self) -> Result<(), ()> {
+        run_replay(&mut self.ctx, self.config.clone()).await;
+        Ok(())
+    }
+}
@@ -4,5 +4,11 @@
 
 use common::position::GnssPosition;
 
+pub mod acceleration;
 pub mod constant_source;
 pub mod gpsd_source;
+pub mod gpx_source;
+pub mod rate_limiter;
+mod replay;
+pub mod session_replay;
+pub mod smoothing;
@@ -0,0 +1,147 @@
+// SPDX-FileCopyrightText: 2026 All contributors
+//
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use crate::GnssPosition;
+use chrono::{NaiveDateTime, TimeDelta};
+use module_core::{EventKind, Module, ModuleCtx};
+use std::sync::Arc;
+use tracing::error;
+
+/// Defines how the [`GnssRateLimiterModule`] handles the positions between two published ones.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum RateLimitMode {
+    /// Intermediate positions are dropped.
+    #[default]
+    Drop,
+
+    /// Latitude and longitude of the published position are the average of all positions
+    /// received since the last published position.
+    Average,
+}
+
+/// Configuration of a [`GnssRateLimiterModule`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GnssRateLimiterConfig {
+    /// Maximum rate in Hz at which positions are published.
+    pub rate_hz: f64,
+
+    /// Defines how the positions between two published ones are handled.
+    pub mode: RateLimitMode,
+}
+
+impl Default for GnssRateLimiterConfig {
+    fn default() -> Self {
+        GnssRateLimiterConfig {
+            rate_hz: 10.0,
+            mode: RateLimitMode::Drop,
+        }
+    }
+}
+
+/// Limits the rate of a stream of GNSS positions.
+///
+/// The rate is measured with the timestamps of the positions, so the output doesn't depend
+/// on the delivery latency of the positions.
+#[derive(Clone, Debug)]
+pub struct PositionRateLimiter {
+    config: GnssRateLimiterConfig,
+    interval: TimeDelta,
+    last_published: Option<NaiveDateTime>,
+    pending: Vec<(f64, f64)>,
+}
+
+impl PositionRateLimiter {
+    /// Creates a new rate limiter with the given configuration.
+    pub fn new(config: GnssRateLimiterConfig) -> Self {
+        let interval = if config.rate_hz > 0.0 {
+            TimeDelta::nanoseconds((1e9 / config.rate_hz) as i64)
+        } else {
+            TimeDelta::zero()
+        };
+        PositionRateLimiter {
+            config,
+            interval,
+            last_published: None,
+            pending: vec![],
+        }
+    }
+
+    /// Adds a position and returns the position to publish, if the configured rate allows it.
+    pub fn limit(&mut self, pos: &GnssPosition) -> Option<GnssPosition> {
+        if self.config.mode == RateLimitMode::Average {
+            self.pending.push((pos.latitude(), pos.longitude()));
+        }
+        let timestamp = pos.timestamp();
+        if self
+            .last_published
+            .is_some_and(|last| timestamp < last + self.interval)
+        {
+            return None;
+        }
+        self.last_published = Some(timestamp);
+        if self.pending.is_empty() {
+            return Some(*pos);
+        }
+        let len = self.pending.len() as f64;
+        let (latitude, longitude) = self
+            .pending
+            .drain(..)
+            .fold((0.0, 0.0), |(lat, lon), (la, lo)| (lat + la, lon + lo));
+        Some(GnssPosition::new(
+            latitude / len,
+            longitude / len,
+            pos.velocity(),
+            &pos.time(),
+            &pos.date(),
+        ))
+    }
+}
+
+/// Republishes [`EventKind::GnssPositionEvent`]s at most at the configured rate as
+/// [`EventKind::ThrottledGnssPositionEvent`].
+pub struct GnssRateLimiterModule {
+    ctx: ModuleCtx,
+    limiter: PositionRateLimiter,
+}
+
+impl GnssRateLimiterModule {
+    /// Creates a new rate limiter module with the default configuration.
+    pub fn new(ctx: ModuleCtx) -> Self {
+        GnssRateLimiterModule::new_with_config(ctx, GnssRateLimiterConfig::default())
+    }
+
+    /// Creates a new rate limiter module with a custom configuration.
+    pub fn new_with_config(ctx: ModuleCtx, config: GnssRateLimiterConfig) -> Self {
+        GnssRateLimiterModule {
+            ctx,
+            limiter: PositionRateLimiter::new(config),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Module for GnssRateLimiterModule {
+    async fn run(&mut self) -> Result<(), ()> {
+        let mut run = true;
+        while run {
+            match self.ctx.recv().await {
+                Ok(event) => match event.kind {
+                    EventKind::QuitEvent => run = false,
+                    EventKind::GnssPositionEvent(pos) => {
+                        if let Some(pos) = self.limiter.limit(&pos) {
+                            let _ = self
+                                .ctx
+                                .publish_event(EventKind::ThrottledGnssPositionEvent(Arc::new(
+                                    pos,
+                                )));
+                        }
+                    }
+                    _ => (),
+                },
+                Err(e) => error!("Failed to receive event in GNSS rate limiter module. Error: {e}"),
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,138 @@
+// SPDX-FileCopyrightText: 2026 All contributors
+//
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+//! The timed replay of recorded positions shared by the replaying GNSS sources.
+
+use crate::GnssPosition;
+use chrono::TimeDelta;
+use module_core::{Event, EventKind, EventSender, HEARTBEAT_INTERVAL, ModuleCtx};
+use std::{
+    io::{Error, ErrorKind},
+    sync::Arc,
+    time::Duration,
+};
+use tracing::{error, info};
+
+/// The recorded positions of a replay and how they are replayed.
+#[derive(Clone)]
+pub(crate) struct ReplayConfig {
+    positions: Vec<GnssPosition>,
+    playback_rate: f64,
+    /// Name of the replayed source for the log.
+    source: &'static str,
+    pub(crate) looping: bool,
+}
+
+impl ReplayConfig {
+    /// Creates the replay of the positions that doesn't loop.
+    ///
+    /// Errors if there are no positions or the playback rate isn't positive.
+    pub(crate) fn new(
+        positions: Vec<GnssPosition>,
+        playback_rate: f64,
+        source: &'static str,
+    ) -> Result<Self, Error> {
+        if !playback_rate.is_finite() || playback_rate <= 0.0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("playback rate {playback_rate} is not positive"),
+            ));
+        }
+        if positions.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("{source} has no positions"),
+            ));
+        }
+        Ok(ReplayConfig {
+            positions,
+            playback_rate,
+            source,
+            looping: false,
+        })
+    }
+}
+
+/// Returns the position with its timestamp shifted by `offset`.
+fn shifted(pos: &GnssPosition, offset: TimeDelta) -> GnssPosition {
+    let timestamp = pos.timestamp() + offset;
+    let shifted = GnssPosition::new(
+        pos.latitude(),
+        pos.longitude(),
+        pos.velocity(),
+        &timestamp.time(),
+        &timestamp.date(),
+    );
+    match pos.altitude() {
+        Some(altitude) => shifted.with_altitude(altitude),
+        None => shifted,
+    }
+}
+
+/// Runs the replay until a [`EventKind::QuitEvent`] arrives.
+pub(crate) async fn run_replay(ctx: &mut ModuleCtx, config: Arc<ReplayConfig>) {
+    let sender = ctx.sender.clone();
+    let replay_task_handle = tokio::spawn(async move {
+        replay_task(sender, config).await;
+    });
+    let mut heartbeat = tokio::time::interval(HEARTBEAT_INTERVAL);
+    let mut run = true;
+    while run {
+        tokio::select! {
+            _ = heartbeat.tick() => {
+                let _ = ctx.publish_heartbeat("gnss");
+            }
+            event = ctx.recv() => {
+                match event {
+                    Ok(event) => {
+                        if let EventKind::QuitEvent = event.kind {
+                            replay_task_handle.abort();
+                            run = false;
+                        }
+                    }
+                    Err(e) => error!("Failed to receive event in GNSS replay module. Error: {e}"),
+                }
+            }
+        }
+    }
+}
+
+/// Publishes the positions with the recorded time between them divided by the playback rate.
+///
+/// Positions with a timestamp before the previous one are published right away.
+async fn replay_task(sender: EventSender, config: Arc<ReplayConfig>) {
+    let positions = &config.positions;
+    let first = positions[0].timestamp();
+    let last = positions[positions.len() - 1].timestamp();
+    // A looping replay restarts after the average time between two positions.
+    let restart_gap = match positions.len() {
+        1 => TimeDelta::seconds(1),
+        len => (last - first) / (len as i32 - 1),
+    };
+    let playback_delay = |delta: TimeDelta| {
+        Duration::from_secs_f64(
+            delta.to_std().unwrap_or_default().as_secs_f64() / config.playback_rate,
+        )
+    };
+    let mut offset = TimeDelta::zero();
+    loop {
+        let mut previous = first;
+        for pos in positions {
+            tokio::time::sleep(playback_delay(pos.timestamp() - previous)).await;
+            previous = pos.timestamp();
+            let _ = sender.send(Event {
+                kind: EventKind::GnssPositionEvent(Arc::new(shifted(pos, offset))),
+            });
+        }
+        if !config.looping {
+            info!("Replay of the {} finished", config.source);
+            let _ = sender.send(Event {
+                kind: EventKind::ReplayFinishedEvent,
+            });
+            return;
+        }
+        tokio::time::sleep(playback_delay(restart_gap)).await;
+        offset += last - first + restart_gap;
+    }
+}
@@ -0,0 +1,68 @@
+// SPDX-FileCopyrightText: 2026 All contributors
+//
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+//! A GNSS source that replays the log points of a stored session.
+
+use crate::replay::{ReplayConfig, run_replay};
+use common::session::Session;
+use module_core::{Module, ModuleCtx};
+use std::{
+    io::Error,
+    sync::{Arc, RwLock},
+};
+
+/// A GNSS source that replays the log points of a session, e.g. to debug the lap timer with
+/// the data of a recorded session.
+///
+/// The log points of all laps are published in order with the recorded time between them
+/// divided by the playback rate and carry the recorded timestamps.
+pub struct SessionReplayModule {
+    ctx: ModuleCtx,
+    config: Arc<ReplayConfig>,
+}
+
+impl SessionReplayModule {
+    /// Creates the replay of the log points of the session.
+    ///
+    /// A `playback_rate` of `2.0` replays the session twice as fast as it was recorded.
+    ///
+    /// Errors if the session has no log points, its lock is poisoned or the playback rate
+    /// isn't positive.
+    pub fn new(
+        ctx: ModuleCtx,
+        session: Arc<RwLock<Session>>,
+        playback_rate: f64,
+    ) -> Result<Self, Error> {
+        let positions = session
+            .read()
+            .map_err(|e| Error::other(format!("session is poisoned: {e}")))?
+            .laps
+            .iter()
+            .flat_map(|lap| lap.log_points.iter().copied())
+            .collect();
+        let config = ReplayConfig::new(positions, playback_rate, "session")?;
+        Ok(SessionReplayModule {
+            ctx,
+            config: Arc::new(config),
+        })
+    }
+
+    /// Sets whether the replay starts over after the last log point.
+    ///
+    /// Defaults to `false`, the replay stops at the last log point and publishes an
+    /// [`module_core::EventKind::ReplayFinishedEvent`]. A looping replay shifts the timestamps
+    /// of every round, so they keep increasing.
+    pub fn with_looping(mut self, looping: bool) -> Self {
+        Arc::make_mut(&mut self.config).looping = looping;
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl Module for SessionReplayModule {
+    async fn run(&mut self) -> Result<(), ()> {
+        run_replay(&mut self.ctx, self.config.clone()).await;
+        Ok(())
+    }
+}
@@ -0,0 +1,168 @@
+// SPDX-FileCopyrightText: 2026 All contributors
+//
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use crate::GnssPosition;
+use module_core::{EventKind, Module, ModuleCtx};
+use std::{collections::VecDeque, sync::Arc};
+use tracing::error;
+
+/// The filter applied by the [`GnssSmoothingModule`] to latitude and longitude.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SmoothingFilter {
+    /// Averages the last `window` positions. A window of 0 or 1 disables the smoothing.
+    MovingAverage { window: usize },
+
+    /// Applies a one-dimensional Kalman filter with a constant position model to each
+    /// coordinate.
+    ///
+    /// The ratio of `process_noise` to `measurement_noise` defines the filter gain. A
+    /// smaller ratio smooths stronger but follows changes of the position slower.
+    Kalman {
+        process_noise: f64,
+        measurement_noise: f64,
+    },
+}
+
+/// Configuration of a [`GnssSmoothingModule`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GnssSmoothingConfig {
+    /// The filter that is applied to the positions.
+    pub filter: SmoothingFilter,
+}
+
+impl Default for GnssSmoothingConfig {
+    fn default() -> Self {
+        GnssSmoothingConfig {
+            filter: SmoothingFilter::MovingAverage { window: 5 },
+        }
+    }
+}
+
+/// State of a one-dimensional Kalman filter.
+#[derive(Clone, Copy, Debug)]
+struct KalmanState {
+    estimate: f64,
+    variance: f64,
+}
+
+impl KalmanState {
+    fn update(&mut self, measurement: f64, process_noise: f64, measurement_noise: f64) -> f64 {
+        self.variance += process_noise;
+        let gain = self.variance / (self.variance + measurement_noise);
+        self.estimate += gain * (measurement - self.estimate);
+        self.variance *= 1.0 - gain;
+        self.estimate
+    }
+}
+
+/// Smooths a stream of GNSS positions with the configured [`SmoothingFilter`].
+///
+/// Only latitude and longitude are filtered, the velocity and the timestamp of a smoothed
+/// position are the ones of the latest raw position.
+#[derive(Clone, Debug)]
+pub struct PositionSmoother {
+    filter: SmoothingFilter,
+    window: VecDeque<(f64, f64)>,
+    kalman: Option<(KalmanState, KalmanState)>,
+}
+
+impl PositionSmoother {
+    /// Creates a new smoother applying the given filter.
+    pub fn new(filter: SmoothingFilter) -> Self {
+        PositionSmoother {
+            filter,
+            window: VecDeque::new(),
+            kalman: None,
+        }
+    }
+
+    /// Adds a raw position to the filter and returns the smoothed position.
+    pub fn smooth(&mut self, pos: &GnssPosition) -> GnssPosition {
+        let (latitude, longitude) = match self.filter {
+            SmoothingFilter::MovingAverage { window } => {
+                if self.window.len() >= window.max(1) {
+                    self.window.pop_front();
+                }
+                self.window.push_back((pos.latitude(), pos.longitude()));
+                let len = self.window.len() as f64;
+                let (latitude, longitude) = self
+                    .window
+                    .iter()
+                    .fold((0.0, 0.0), |(lat, lon), (la, lo)| (lat + la, lon + lo));
+                (latitude / len, longitude / len)
+            }
+            SmoothingFilter::Kalman {
+                process_noise,
+                measurement_noise,
+            } => match &mut self.kalman {
+                Some((latitude, longitude)) => (
+                    latitude.update(pos.latitude(), process_noise, measurement_noise),
+                    longitude.update(pos.longitude(), process_noise, measurement_noise),
+                ),
+                None => {
+                    let state = |estimate| KalmanState {
+                        estimate,
+                        variance: measurement_noise,
+                    };
+                    self.kalman = Some((state(pos.latitude()), state(pos.longitude())));
+                    (pos.latitude(), pos.longitude())
+                }
+            },
+        };
+        GnssPosition::new(
+            latitude,
+            longitude,
+            pos.velocity(),
+            &pos.time(),
+            &pos.date(),
+        )
+    }
+}
+
+/// Republishes every [`EventKind::GnssPositionEvent`] smoothed as
+/// [`EventKind::SmoothedGnssPositionEvent`].
+pub struct GnssSmoothingModule {
+    ctx: ModuleCtx,
+    smoother: PositionSmoother,
+}
+
+impl GnssSmoothingModule {
+    /// Creates a new smoothing module with the default configuration.
+    pub fn new(ctx: ModuleCtx) -> Self {
+        GnssSmoothingModule::new_with_config(ctx, GnssSmoothingConfig::default())
+    }
+
+    /// Creates a new smoothing module with a custom configuration.
+    pub fn new_with_config(ctx: ModuleCtx, config: GnssSmoothingConfig) -> Self {
+        GnssSmoothingModule {
+            ctx,
+            smoother: PositionSmoother::new(config.filter),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Module for GnssSmoothingModule {
+    async fn run(&mut self) -> Result<(), ()> {
+        let mut run = true;
+        while run {
+            match self.ctx.recv().await {
+                Ok(event) => {
+                    match event.kind {
+                        EventKind::QuitEvent => run = false,
+                        EventKind::GnssPositionEvent(pos) => {
+                            let smoothed = self.smoother.smooth(&pos);
+                            let _ = self.ctx.publish_event(EventKind::SmoothedGnssPositionEvent(
+                                Arc::new(smoothed),
+                            ));
+                        }
+                        _ => (),
+                    }
+                }
+                Err(e) => error!("Failed to receive event in GNSS smoothing module. Error: {e}"),
+            }
+        }
+        Ok(())
+    }
+}
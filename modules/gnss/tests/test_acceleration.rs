@@ -0,0 +1,71 @@
+// SPDX-FileCopyrightText: 2026 All contributors
+//
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use chrono::{NaiveDate, NaiveTime, TimeDelta};
+use common::position::GnssPosition;
+use gnss::acceleration::{AccelerationCalculator, AccelerationConfig, AccelerationModule};
+use module_core::{
+    Event, EventBus, EventKind, EventKindType, Module,
+    test_helper::{stop_module, wait_for_event},
+};
+use std::{sync::Arc, time::Duration};
+
+/// Returns a position with the given velocity at the given milliseconds after midnight.
+fn position_at(millis: i64, velocity: f64) -> GnssPosition {
+    GnssPosition::new(
+        52.0,
+        11.0,
+        velocity,
+        &(NaiveTime::MIN + TimeDelta::milliseconds(millis)),
+        &NaiveDate::from_ymd_opt(2026, 1, 1).unwrap(),
+    )
+}
+
+#[tokio::test]
+async fn publish_acceleration_of_velocity_change() {
+    let event_bus = EventBus::default();
+    let mut rx = event_bus.subscribe();
+    let ctx = event_bus.context();
+    let mut module_handle = tokio::spawn(async move {
+        let mut acceleration = AccelerationModule::new(ctx);
+        acceleration.run().await
+    });
+
+    // 10 m/s to 15 m/s within 250 ms
+    for pos in [position_at(0, 10.0), position_at(250, 15.0)] {
+        event_bus.publish(&Event {
+            kind: EventKind::GnssPositionEvent(Arc::new(pos)),
+        });
+    }
+    let event = wait_for_event(
+        &mut rx,
+        Duration::from_millis(100),
+        EventKindType::AccelerationEvent,
+    )
+    .await;
+    stop_module(&event_bus, &mut module_handle).await;
+
+    match event.kind {
+        EventKind::AccelerationEvent(acceleration) => {
+            assert!((acceleration - 20.0).abs() < 1e-9, "{acceleration}")
+        }
+        kind => panic!("Unexpected event {kind:?}"),
+    }
+}
+
+#[test]
+fn skip_zero_duration_and_long_gaps() {
+    let mut calculator = AccelerationCalculator::new(AccelerationConfig::default());
+
+    assert_eq!(calculator.update(&position_at(0, 20.0)), None);
+    // Duplicate fix with the same timestamp
+    assert_eq!(calculator.update(&position_at(0, 25.0)), None);
+    // Irregular interval of 400 ms, braking from 20 m/s to 16 m/s
+    let acceleration = calculator.update(&position_at(400, 16.0)).unwrap();
+    assert!((acceleration + 10.0).abs() < 1e-9, "{acceleration}");
+    // Gap longer than the maximum interval
+    assert_eq!(calculator.update(&position_at(5000, 30.0)), None);
+    let acceleration = calculator.update(&position_at(5100, 31.0)).unwrap();
+    assert!((acceleration - 10.0).abs() < 1e-9, "{acceleration}");
+}
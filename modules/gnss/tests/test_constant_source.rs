@@ -12,16 +12,7 @@ use module_core::{
 
 const TIMEOUT_MS: u16 = 100;
 const VELOCITY: f64 = 2.77778;
-
-fn gnss_pos_validator(lhs: &GnssPosition, rhs: &GnssPosition) -> bool {
-    if lhs.velocity() == rhs.velocity()
-        && lhs.longitude() == rhs.longitude()
-        && lhs.latitude() == rhs.latitude()
-    {
-        return true;
-    }
-    false
-}
+const EPSILON: f64 = 1e-9;
 
 fn start_module(ctx: ModuleCtx) -> tokio::task::JoinHandle<Result<(), ()>> {
     let positions = vec![
@@ -65,16 +56,21 @@ async fn interpolate_between_two_points() {
     )
     .await;
 
-    assert!(gnss_pos_validator(
-        payload_ref!(pos_event.kind, EventKind::GnssPositionEvent).unwrap(),
-        &GnssPosition::new(
-            52.026648994186836,
-            11.282535438555783,
-            VELOCITY,
-            &DateTime::<Utc>::default().time(),
-            &DateTime::<Utc>::default().date_naive(),
-        )
-    ));
+    assert!(
+        payload_ref!(pos_event.kind, EventKind::GnssPositionEvent)
+            .unwrap()
+            .approx_eq(
+                &GnssPosition::new(
+                    52.026648994186836,
+                    11.282535438555783,
+                    VELOCITY,
+                    &DateTime::<Utc>::default().time(),
+                    &DateTime::<Utc>::default().date_naive(),
+                ),
+                EPSILON,
+                EPSILON,
+            )
+    );
 
     stop_module(&event_bus, &mut module_handle).await;
 }
@@ -97,3 +93,62 @@ async fn notify_gnss_information() {
 
     stop_module(&event_bus, &mut module_handle).await;
 }
+
+#[tokio::test]
+async fn non_looping_replay_finishes_after_last_point() {
+    let event_bus = EventBus::default();
+    let mut receiver = event_bus.subscribe();
+    let ctx = event_bus.context();
+    let mut module_handle = tokio::spawn(async move {
+        let mut constant_source = ConstantGnssModule::new(
+            ctx,
+            &[
+                Position::new(&52.026649, &11.282535),
+                Position::new(&52.026751, &11.282047),
+                Position::new(&52.026807, &11.281746),
+            ],
+            100.0,
+            std::time::Duration::from_secs(10),
+        )
+        .unwrap()
+        .with_looping(false);
+        constant_source.run().await
+    });
+
+    let mut last_position = None;
+    tokio::time::timeout(std::time::Duration::from_secs(5), async {
+        loop {
+            let event = receiver.recv().await.unwrap();
+            match event.kind {
+                EventKind::GnssPositionEvent(pos) => last_position = Some(*pos),
+                EventKind::ReplayFinishedEvent => break,
+                _ => (),
+            }
+        }
+    })
+    .await
+    .expect("ReplayFinishedEvent not received");
+
+    let last_position = last_position.expect("No GNSS position received");
+    assert!(last_position.approx_eq(
+        &GnssPosition::new(
+            52.026807,
+            11.281746,
+            100.0,
+            &last_position.time(),
+            &last_position.date(),
+        ),
+        1e-6,
+        EPSILON,
+    ));
+
+    tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+    while let Ok(event) = receiver.try_recv() {
+        assert!(
+            !matches!(event.kind, EventKind::GnssPositionEvent(_)),
+            "GNSS position published after the replay finished"
+        );
+    }
+
+    stop_module(&event_bus, &mut module_handle).await;
+}
@@ -5,7 +5,7 @@
 use chrono::DateTime;
 use common::position::{GnssInformation, GnssPosition, GnssStatus};
 use core::panic;
-use gnss::gpsd_source::GpsdModule;
+use gnss::gpsd_source::{GpsdConfig, GpsdModule, TimeParsing, parse_time};
 use module_core::{
     EventBus, EventKind, EventKindType, Module, ModuleCtx, payload_ref,
     test_helper::{stop_module, wait_for_event},
@@ -58,11 +58,19 @@ const TIMEOUT_MS: u8 = 100;
 async fn test_setup(
     addr: &str,
     ctx: ModuleCtx,
+) -> (tokio::task::JoinHandle<Result<(), ()>>, GpsdServer) {
+    test_setup_with_config(addr, ctx, GpsdConfig::default()).await
+}
+
+async fn test_setup_with_config(
+    addr: &str,
+    ctx: ModuleCtx,
+    config: GpsdConfig,
 ) -> (tokio::task::JoinHandle<Result<(), ()>>, GpsdServer) {
     let mut server = GpsdServer::new(addr).await;
     let owned_addr = addr.to_owned();
     let gpsd_module_handle = tokio::spawn(async move {
-        let gpsd_source = GpsdModule::new(ctx, &owned_addr).await;
+        let gpsd_source = GpsdModule::new_with_config(ctx, &owned_addr, config).await;
         match gpsd_source {
             Ok(mut gpsd_source) => gpsd_source.run().await,
             Err(_) => Err(()),
@@ -129,6 +137,39 @@ async fn notify_gnss_position() {
     stop_module(&event_bus, &mut source).await;
 }
 
+const TPV_MSG_WITH_ALTITUDE: &str = " \
+{ \
+    \"class\": \"TPV\", \
+    \"time\": \"2005-06-08T10:34:48.283Z\", \
+    \"lat\": 1.0, \
+    \"lon\": 1.0, \
+    \"alt\": 87.5, \
+    \"speed\": 22.0, \
+    \"mode\": 3 \
+}\n\r";
+
+#[tokio::test]
+async fn notify_gnss_position_with_altitude() {
+    let event_bus = EventBus::default();
+    let mut rx = event_bus.subscribe();
+    let (mut source, mut server) = test_setup("127.0.0.1:35506", event_bus.context()).await;
+    server
+        .send(TPV_MSG_WITH_ALTITUDE.as_bytes())
+        .await
+        .expect("Failed to send TPV msg");
+
+    let event = wait_for_event(
+        &mut rx,
+        Duration::from_millis(TIMEOUT_MS.into()),
+        EventKindType::GnssPositionEvent,
+    )
+    .await;
+    let position = payload_ref!(event.kind, EventKind::GnssPositionEvent).unwrap();
+    assert_eq!(position.altitude(), Some(87.5));
+
+    stop_module(&event_bus, &mut source).await;
+}
+
 #[tokio::test]
 async fn notify_gnss_information_on_fix_change() {
     let event_bus = EventBus::default();
@@ -193,3 +234,296 @@ async fn notify_gnss_information_on_sky_change() {
 
     stop_module(&event_bus, &mut source).await;
 }
+
+fn sky_msg_with_used_satellites(used: usize) -> String {
+    let satellites = (0..used)
+        .map(|prn| {
+            format!(
+                "{{\"PRN\":{},\"el\":45,\"az\":90,\"ss\":40,\"used\":true}}",
+                prn + 1
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+    format!("{{\"class\":\"SKY\",\"satellites\":[{satellites}]}}\n")
+}
+
+#[tokio::test]
+async fn notify_gnss_information_only_above_satellite_change_threshold() {
+    let event_bus = EventBus::default();
+    let mut rx = event_bus.subscribe();
+    let config = GpsdConfig {
+        satellite_change_threshold: 2,
+        ..Default::default()
+    };
+    let (mut source, mut server) =
+        test_setup_with_config("127.0.0.1:35505", event_bus.context(), config).await;
+    for used in [8, 9, 8, 12] {
+        server
+            .send(sky_msg_with_used_satellites(used).as_bytes())
+            .await
+            .expect("Failed to send SKY msg");
+    }
+
+    let mut reported = Vec::new();
+    while reported.len() < 2 {
+        let event = wait_for_event(
+            &mut rx,
+            Duration::from_millis(TIMEOUT_MS.into()),
+            EventKindType::GnssInformationEvent,
+        )
+        .await;
+        let info = payload_ref!(event.kind, EventKind::GnssInformationEvent).unwrap();
+        reported.push((**info).clone());
+    }
+    tokio::time::sleep(Duration::from_millis(TIMEOUT_MS.into())).await;
+    while let Ok(event) = rx.try_recv() {
+        assert_ne!(
+            EventKindType::from(&event.kind),
+            EventKindType::GnssInformationEvent
+        );
+    }
+    // The first count is a change from no satellites, the flicker to 9 and back is dropped.
+    assert_eq!(
+        reported,
+        vec![
+            GnssInformation::new(&GnssStatus::Unknown, 8),
+            GnssInformation::new(&GnssStatus::Unknown, 12)
+        ]
+    );
+
+    stop_module(&event_bus, &mut source).await;
+}
+
+fn expected_time() -> DateTime<chrono::Utc> {
+    DateTime::<chrono::Utc>::from_str("2005-06-08T10:34:48.283Z").unwrap()
+}
+
+#[test]
+fn parse_rfc3339_time() {
+    for time in ["2005-06-08T10:34:48.283Z", "2005-06-08T12:34:48.283+02:00"] {
+        assert_eq!(parse_time(time, TimeParsing::Strict), Some(expected_time()));
+        assert_eq!(
+            parse_time(time, TimeParsing::Lenient),
+            Some(expected_time())
+        );
+    }
+}
+
+#[test]
+fn parse_rfc3339_time_without_fractional_seconds() {
+    let expected = DateTime::<chrono::Utc>::from_str("2005-06-08T10:34:48Z").unwrap();
+    assert_eq!(
+        parse_time("2005-06-08T10:34:48Z", TimeParsing::Lenient),
+        Some(expected)
+    );
+}
+
+#[test]
+fn parse_time_with_surrounding_whitespace() {
+    let time = " 2005-06-08T10:34:48.283Z \t";
+    assert_eq!(
+        parse_time(time, TimeParsing::Lenient),
+        Some(expected_time())
+    );
+}
+
+#[test]
+fn parse_time_without_timezone_as_utc() {
+    for time in ["2005-06-08T10:34:48.283", "2005-06-08 10:34:48.283"] {
+        assert_eq!(parse_time(time, TimeParsing::Strict), None);
+        assert_eq!(
+            parse_time(time, TimeParsing::Lenient),
+            Some(expected_time())
+        );
+    }
+}
+
+const TPV_MSG_INVALID_TIME: &str = " \
+{ \
+    \"class\": \"TPV\", \
+    \"time\": \"08.06.2005 10:34\", \
+    \"lat\": 1.0, \
+    \"lon\": 1.0, \
+    \"speed\": 22.0, \
+    \"mode\": 3 \
+}\n\r";
+
+#[tokio::test]
+async fn drop_gnss_position_with_unparseable_time() {
+    let event_bus = EventBus::default();
+    let mut rx = event_bus.subscribe();
+    let (mut source, mut server) = test_setup("127.0.0.1:35504", event_bus.context()).await;
+    assert_eq!(parse_time("08.06.2005 10:34", TimeParsing::Lenient), None);
+    server
+        .send(TPV_MSG_INVALID_TIME.as_bytes())
+        .await
+        .expect("Failed to send TPV msg");
+    server
+        .send(TPV_MSG.as_bytes())
+        .await
+        .expect("Failed to send TPV msg");
+
+    let event = wait_for_event(
+        &mut rx,
+        Duration::from_millis(TIMEOUT_MS.into()),
+        EventKindType::GnssPositionEvent,
+    )
+    .await;
+    assert_eq!(
+        **payload_ref!(event.kind, EventKind::GnssPositionEvent).unwrap(),
+        GnssPosition::new(
+            1.0,
+            1.0,
+            22.0,
+            &expected_time().time(),
+            &expected_time().date_naive()
+        )
+    );
+    while let Ok(event) = rx.try_recv() {
+        assert_ne!(
+            EventKindType::from(&event.kind),
+            EventKindType::GnssPositionEvent
+        );
+    }
+
+    stop_module(&event_bus, &mut source).await;
+}
+
+#[tokio::test]
+async fn reconnect_after_connection_loss() {
+    let event_bus = EventBus::default();
+    let mut rx = event_bus.subscribe();
+    let config = GpsdConfig {
+        reconnect_delay: Duration::from_millis(10),
+        max_reconnect_delay: Duration::from_millis(40),
+        ..Default::default()
+    };
+    let (mut source, mut server) =
+        test_setup_with_config("127.0.0.1:35507", event_bus.context(), config).await;
+    server
+        .send(TPV_MSG.as_bytes())
+        .await
+        .expect("Failed to send TPV msg");
+    wait_for_event(
+        &mut rx,
+        Duration::from_millis(TIMEOUT_MS.into()),
+        EventKindType::GnssPositionEvent,
+    )
+    .await;
+
+    // Restart gpsd, the reconnection attempts back off while no listener is bound.
+    drop(server);
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    while rx.try_recv().is_ok() {}
+    let mut server = GpsdServer::new("127.0.0.1:35507").await;
+    timeout(Duration::from_secs(1), server.accept_client())
+        .await
+        .expect("gpsd source didn't reconnect");
+    let enable_cmd: &str = r#"?WATCH={"enable":true,"json":true}"#;
+    let mut buf: Vec<u8> = vec![0; enable_cmd.len()];
+    timeout(
+        Duration::from_millis(TIMEOUT_MS.into()),
+        server.receive(&mut buf),
+    )
+    .await
+    .expect("Enable command not received after reconnect")
+    .expect("Failed to receive enable command");
+    assert_eq!(std::str::from_utf8(&buf).unwrap(), enable_cmd);
+
+    server
+        .send(SKY_MSG.as_bytes())
+        .await
+        .expect("Failed to send SKY msg");
+    server
+        .send(TPV_MSG.as_bytes())
+        .await
+        .expect("Failed to send TPV msg");
+    // The fix mode of the previous connection is kept until the next TPV message.
+    let event = wait_for_event(
+        &mut rx,
+        Duration::from_millis(TIMEOUT_MS.into()),
+        EventKindType::GnssInformationEvent,
+    )
+    .await;
+    assert_eq!(
+        **payload_ref!(event.kind, EventKind::GnssInformationEvent).unwrap(),
+        GnssInformation::new(&GnssStatus::Fix3d, 5)
+    );
+    let event = wait_for_event(
+        &mut rx,
+        Duration::from_millis(TIMEOUT_MS.into()),
+        EventKindType::GnssPositionEvent,
+    )
+    .await;
+    assert_eq!(
+        **payload_ref!(event.kind, EventKind::GnssPositionEvent).unwrap(),
+        GnssPosition::new(
+            1.0,
+            1.0,
+            22.0,
+            &expected_time().time(),
+            &expected_time().date_naive()
+        )
+    );
+
+    stop_module(&event_bus, &mut source).await;
+}
+
+#[tokio::test]
+async fn keep_gnss_information_across_reconnect() {
+    let event_bus = EventBus::default();
+    let mut rx = event_bus.subscribe();
+    let config = GpsdConfig {
+        reconnect_delay: Duration::from_millis(10),
+        max_reconnect_delay: Duration::from_millis(40),
+        ..Default::default()
+    };
+    let (mut source, mut server) =
+        test_setup_with_config("127.0.0.1:35508", event_bus.context(), config).await;
+    server
+        .send(TPV_MSG.as_bytes())
+        .await
+        .expect("Failed to send TPV msg");
+    server
+        .send(SKY_MSG.as_bytes())
+        .await
+        .expect("Failed to send SKY msg");
+    let mut info = GnssInformation::new(&GnssStatus::Unknown, 0);
+    while info != GnssInformation::new(&GnssStatus::Fix3d, 5) {
+        let event = wait_for_event(
+            &mut rx,
+            Duration::from_millis(TIMEOUT_MS.into()),
+            EventKindType::GnssInformationEvent,
+        )
+        .await;
+        info = (**payload_ref!(event.kind, EventKind::GnssInformationEvent).unwrap()).clone();
+    }
+
+    drop(server);
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    while rx.try_recv().is_ok() {}
+    let mut server = GpsdServer::new("127.0.0.1:35508").await;
+    timeout(Duration::from_secs(1), server.accept_client())
+        .await
+        .expect("gpsd source didn't reconnect");
+
+    // The SKY message reports the same satellites, so the fix mode can only be the one
+    // received before the connection was lost.
+    server
+        .send(SKY_MSG.as_bytes())
+        .await
+        .expect("Failed to send SKY msg");
+    let event = wait_for_event(
+        &mut rx,
+        Duration::from_millis(TIMEOUT_MS.into()),
+        EventKindType::GnssInformationEvent,
+    )
+    .await;
+    assert_eq!(
+        **payload_ref!(event.kind, EventKind::GnssInformationEvent).unwrap(),
+        GnssInformation::new(&GnssStatus::Fix3d, 5)
+    );
+
+    stop_module(&event_bus, &mut source).await;
+}
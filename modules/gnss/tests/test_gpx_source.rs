@@ -0,0 +1,109 @@
+// SPDX-FileCopyrightText: 2026 All contributors
+//
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use chrono::{NaiveDate, NaiveTime};
+use common::position::GnssPosition;
+use gnss::gpx_source::GpxReplayModule;
+use module_core::{
+    EventBus, EventKind, EventKindType, Module, payload_ref,
+    test_helper::{stop_module, wait_for_event},
+};
+use std::time::{Duration, Instant};
+
+/// Three track points one and two seconds apart.
+const GPX: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<gpx version="1.1" creator="rapid" xmlns="http://www.topografix.com/GPX/1/1">
+  <trk>
+    <trkseg>
+      <trkpt lat="52.0" lon="11.0"><ele>87.5</ele><time>2026-01-01T13:00:00.000Z</time></trkpt>
+      <trkpt lat="52.0001" lon="11.0"><time>2026-01-01T13:00:01.000Z</time></trkpt>
+      <trkpt lat='52.0003' lon='11.0'>
+        <time>2026-01-01T13:00:03.000Z</time>
+      </trkpt>
+    </trkseg>
+  </trk>
+</gpx>
+"#;
+
+fn position_at(latitude: f64, second: u32) -> GnssPosition {
+    GnssPosition::new(
+        latitude,
+        11.0,
+        0.0,
+        &NaiveTime::from_hms_opt(13, 0, second).unwrap(),
+        &NaiveDate::from_ymd_opt(2026, 1, 1).unwrap(),
+    )
+}
+
+#[test]
+fn report_creation_error_for_invalid_gpx() {
+    let event_bus = EventBus::default();
+    for (gpx, playback_rate) in [
+        (GPX, 0.0),
+        ("<gpx></gpx>", 1.0),
+        (r#"<trkpt lat="52.0" lon="11.0"></trkpt>"#, 1.0),
+        (
+            r#"<trkpt lat="52.0"><time>2026-01-01T13:00:00Z</time></trkpt>"#,
+            1.0,
+        ),
+    ] {
+        assert!(GpxReplayModule::from_gpx(event_bus.context(), gpx, playback_rate).is_err());
+    }
+}
+
+#[tokio::test]
+async fn replay_track_points_with_recorded_spacing() {
+    let path = std::env::temp_dir().join("rapid_test_replay_track_points.gpx");
+    std::fs::write(&path, GPX).unwrap();
+    let event_bus = EventBus::default();
+    let mut rx = event_bus.subscribe();
+    let mut gpx_source = GpxReplayModule::new(event_bus.context(), &path, 10.0).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    let mut module_handle = tokio::spawn(async move { gpx_source.run().await });
+
+    let mut positions = Vec::new();
+    let mut arrivals = Vec::new();
+    while positions.len() < 3 {
+        let event = wait_for_event(
+            &mut rx,
+            Duration::from_millis(500),
+            EventKindType::GnssPositionEvent,
+        )
+        .await;
+        arrivals.push(Instant::now());
+        positions.push(**payload_ref!(event.kind, EventKind::GnssPositionEvent).unwrap());
+    }
+    wait_for_event(
+        &mut rx,
+        Duration::from_millis(100),
+        EventKindType::ReplayFinishedEvent,
+    )
+    .await;
+    stop_module(&event_bus, &mut module_handle).await;
+
+    // The points are 11.13m and 22.26m apart, so the velocity is the same for all of them.
+    let expected = [
+        position_at(52.0, 0).with_altitude(87.5),
+        position_at(52.0001, 1),
+        position_at(52.0003, 3),
+    ];
+    for (position, expected) in positions.iter().zip(expected) {
+        assert_eq!(position.latitude(), expected.latitude());
+        assert_eq!(position.longitude(), expected.longitude());
+        assert_eq!(position.timestamp(), expected.timestamp());
+        assert_eq!(position.altitude(), expected.altitude());
+        assert!((position.velocity() - 11.13).abs() < 1e-6);
+    }
+
+    // The recorded spacing of one and two seconds is replayed ten times as fast.
+    let spacing: Vec<Duration> = arrivals.windows(2).map(|w| w[1] - w[0]).collect();
+    for (spacing, expected) in spacing.iter().zip([100, 200]) {
+        let expected = Duration::from_millis(expected);
+        assert!(
+            *spacing >= expected - Duration::from_millis(10)
+                && *spacing < expected + Duration::from_millis(80),
+            "spacing {spacing:?}, expected {expected:?}"
+        );
+    }
+}
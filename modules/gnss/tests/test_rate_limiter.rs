@@ -0,0 +1,84 @@
+// SPDX-FileCopyrightText: 2026 All contributors
+//
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use chrono::{NaiveDate, NaiveTime};
+use common::position::GnssPosition;
+use gnss::rate_limiter::{
+    GnssRateLimiterConfig, GnssRateLimiterModule, PositionRateLimiter, RateLimitMode,
+};
+use module_core::{Event, EventBus, EventKind, Module, test_helper::stop_module};
+use std::{sync::Arc, time::Duration};
+
+/// Returns positions at 100 Hz moving north by 1e-6 degree per position.
+fn positions_100hz(count: i64) -> Vec<GnssPosition> {
+    (0..count)
+        .map(|i| {
+            GnssPosition::new(
+                52.0 + i as f64 * 1e-6,
+                11.0,
+                10.0,
+                &(NaiveTime::MIN + chrono::Duration::milliseconds(i * 10)),
+                &NaiveDate::from_ymd_opt(2026, 1, 1).unwrap(),
+            )
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn throttle_100hz_input_to_10hz() {
+    let event_bus = EventBus::default();
+    let mut rx = event_bus.subscribe();
+    let ctx = event_bus.context();
+    let mut module_handle = tokio::spawn(async move {
+        let mut limiter = GnssRateLimiterModule::new_with_config(
+            ctx,
+            GnssRateLimiterConfig {
+                rate_hz: 10.0,
+                mode: RateLimitMode::Drop,
+            },
+        );
+        limiter.run().await
+    });
+
+    // Half a second of positions, so the input and output fit into the event bus.
+    for pos in positions_100hz(50) {
+        event_bus.publish(&Event {
+            kind: EventKind::GnssPositionEvent(Arc::new(pos)),
+        });
+    }
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let mut throttled = vec![];
+    while let Ok(event) = rx.try_recv() {
+        if let EventKind::ThrottledGnssPositionEvent(pos) = event.kind {
+            throttled.push(*pos);
+        }
+    }
+    stop_module(&event_bus, &mut module_handle).await;
+
+    assert_eq!(throttled.len(), 5);
+    for pair in throttled.windows(2) {
+        assert_eq!(
+            pair[1].timestamp() - pair[0].timestamp(),
+            chrono::Duration::milliseconds(100)
+        );
+    }
+}
+
+#[test]
+fn average_positions_between_published_ones() {
+    let mut limiter = PositionRateLimiter::new(GnssRateLimiterConfig {
+        rate_hz: 10.0,
+        mode: RateLimitMode::Average,
+    });
+    let positions = positions_100hz(100);
+    let published: Vec<GnssPosition> = positions
+        .iter()
+        .filter_map(|pos| limiter.limit(pos))
+        .collect();
+
+    assert_eq!(published.len(), 10);
+    // The second published position averages the positions 1 to 10.
+    assert!((published[1].latitude() - (52.0 + 5.5e-6)).abs() < 1e-9);
+    assert_eq!(published[1].timestamp(), positions[10].timestamp());
+}
@@ -0,0 +1,83 @@
+// SPDX-FileCopyrightText: 2026 All contributors
+//
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use chrono::{NaiveDate, NaiveTime};
+use common::{lap::Lap, position::GnssPosition, test_helper::session::get_session};
+use gnss::session_replay::SessionReplayModule;
+use module_core::{
+    EventBus, EventKind, EventKindType, Module, payload_ref,
+    test_helper::{stop_module, wait_for_event},
+};
+use std::{
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+
+fn log_point(latitude: f64, millis: u32) -> GnssPosition {
+    GnssPosition::new(
+        latitude,
+        11.0,
+        30.0,
+        &NaiveTime::from_hms_milli_opt(13, 0, 0, millis).unwrap(),
+        &NaiveDate::from_ymd_opt(2026, 1, 1).unwrap(),
+    )
+}
+
+#[test]
+fn report_creation_error_for_session_without_log_points() {
+    let event_bus = EventBus::default();
+    let session = Arc::new(RwLock::new(get_session()));
+    session
+        .write()
+        .unwrap()
+        .laps
+        .iter_mut()
+        .for_each(|lap| lap.log_points.clear());
+    assert!(SessionReplayModule::new(event_bus.context(), session, 1.0).is_err());
+}
+
+#[tokio::test]
+async fn replay_log_points_of_all_laps_in_order() {
+    let event_bus = EventBus::default();
+    let mut rx = event_bus.subscribe();
+    let mut session = get_session();
+    session.laps = vec![
+        Lap {
+            sectors: vec![Duration::from_millis(100)],
+            log_points: vec![log_point(52.0, 0), log_point(52.0001, 50)],
+        },
+        Lap {
+            sectors: vec![Duration::from_millis(100)],
+            log_points: vec![log_point(52.0002, 100), log_point(52.0003, 150)],
+        },
+    ];
+    let expected: Vec<GnssPosition> = session
+        .laps
+        .iter()
+        .flat_map(|lap| lap.log_points.clone())
+        .collect();
+    let mut session_replay =
+        SessionReplayModule::new(event_bus.context(), Arc::new(RwLock::new(session)), 1.0).unwrap();
+    let mut module_handle = tokio::spawn(async move { session_replay.run().await });
+
+    let mut positions = Vec::new();
+    while positions.len() < expected.len() {
+        let event = wait_for_event(
+            &mut rx,
+            Duration::from_millis(200),
+            EventKindType::GnssPositionEvent,
+        )
+        .await;
+        positions.push(**payload_ref!(event.kind, EventKind::GnssPositionEvent).unwrap());
+    }
+    wait_for_event(
+        &mut rx,
+        Duration::from_millis(100),
+        EventKindType::ReplayFinishedEvent,
+    )
+    .await;
+    stop_module(&event_bus, &mut module_handle).await;
+
+    assert_eq!(positions, expected);
+}
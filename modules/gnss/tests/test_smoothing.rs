@@ -0,0 +1,136 @@
+// SPDX-FileCopyrightText: 2026 All contributors
+//
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use chrono::{NaiveDate, NaiveTime};
+use common::position::GnssPosition;
+use gnss::smoothing::{
+    GnssSmoothingConfig, GnssSmoothingModule, PositionSmoother, SmoothingFilter,
+};
+use module_core::{
+    Event, EventBus, EventKind, EventKindType, Module, payload_ref,
+    test_helper::{stop_module, wait_for_event},
+};
+use std::sync::Arc;
+
+const STEP: f64 = 5e-6;
+const NOISE: f64 = 5e-5;
+
+/// Returns the true latitude of the i-th position of a vehicle moving north.
+fn true_latitude(i: usize) -> f64 {
+    52.0 + i as f64 * STEP
+}
+
+/// Returns noisy positions along the true track, the noise is uniformly distributed.
+fn noisy_positions(count: usize) -> Vec<GnssPosition> {
+    let mut seed: u64 = 42;
+    let mut noise = || {
+        seed = seed
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        ((seed >> 11) as f64 / (1u64 << 53) as f64 * 2.0 - 1.0) * NOISE
+    };
+    (0..count)
+        .map(|i| {
+            GnssPosition::new(
+                true_latitude(i) + noise(),
+                11.0 + noise(),
+                10.0,
+                &(NaiveTime::MIN + chrono::Duration::milliseconds(i as i64 * 100)),
+                &NaiveDate::from_ymd_opt(2026, 1, 1).unwrap(),
+            )
+        })
+        .collect()
+}
+
+/// Returns the mean and the variance of the latitude errors from the true track, skipping
+/// the first positions until the filter settled.
+fn latitude_error_stats(positions: &[GnssPosition]) -> (f64, f64) {
+    let errors: Vec<f64> = positions
+        .iter()
+        .enumerate()
+        .skip(20)
+        .map(|(i, pos)| pos.latitude() - true_latitude(i))
+        .collect();
+    let mean = errors.iter().sum::<f64>() / errors.len() as f64;
+    let variance = errors.iter().map(|e| (e - mean).powi(2)).sum::<f64>() / errors.len() as f64;
+    (mean, variance)
+}
+
+fn assert_smoothing(filter: SmoothingFilter) {
+    let raw = noisy_positions(500);
+    let mut smoother = PositionSmoother::new(filter);
+    let smoothed: Vec<GnssPosition> = raw.iter().map(|pos| smoother.smooth(pos)).collect();
+
+    let (_, raw_variance) = latitude_error_stats(&raw);
+    let (smoothed_mean, smoothed_variance) = latitude_error_stats(&smoothed);
+    assert!(
+        smoothed_variance < raw_variance / 2.0,
+        "smoothed variance {smoothed_variance} not below raw variance {raw_variance}"
+    );
+    assert!(
+        smoothed_mean.abs() < 1e-4,
+        "smoothed positions lag {smoothed_mean} behind the trend"
+    );
+    for (raw, smoothed) in raw.iter().zip(smoothed.iter()) {
+        assert_eq!(raw.timestamp(), smoothed.timestamp());
+        assert_eq!(raw.velocity(), smoothed.velocity());
+    }
+}
+
+#[test]
+fn moving_average_reduces_variance() {
+    assert_smoothing(SmoothingFilter::MovingAverage { window: 5 });
+}
+
+#[test]
+fn kalman_reduces_variance() {
+    assert_smoothing(SmoothingFilter::Kalman {
+        process_noise: 1e-11,
+        measurement_noise: NOISE * NOISE / 3.0,
+    });
+}
+
+#[tokio::test]
+async fn republish_smoothed_positions() {
+    let event_bus = EventBus::default();
+    let mut rx = event_bus.subscribe();
+    let ctx = event_bus.context();
+    let mut module_handle = tokio::spawn(async move {
+        let mut smoothing = GnssSmoothingModule::new_with_config(
+            ctx,
+            GnssSmoothingConfig {
+                filter: SmoothingFilter::MovingAverage { window: 2 },
+            },
+        );
+        smoothing.run().await
+    });
+
+    let positions = noisy_positions(2);
+    for pos in &positions {
+        event_bus.publish(&Event {
+            kind: EventKind::GnssPositionEvent(Arc::new(*pos)),
+        });
+    }
+    let _ = wait_for_event(
+        &mut rx,
+        std::time::Duration::from_millis(100),
+        EventKindType::SmoothedGnssPositionEvent,
+    )
+    .await;
+    let event = wait_for_event(
+        &mut rx,
+        std::time::Duration::from_millis(100),
+        EventKindType::SmoothedGnssPositionEvent,
+    )
+    .await;
+
+    let smoothed = payload_ref!(event.kind, EventKind::SmoothedGnssPositionEvent).unwrap();
+    assert!(
+        (smoothed.latitude() - (positions[0].latitude() + positions[1].latitude()) / 2.0).abs()
+            < 1e-12
+    );
+    assert_eq!(smoothed.timestamp(), positions[1].timestamp());
+
+    stop_module(&event_bus, &mut module_handle).await;
+}
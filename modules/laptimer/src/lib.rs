@@ -2,11 +2,17 @@
 //
 // SPDX-License-Identifier: GPL-2.0-or-later
 
-use algorithm::calculate_distance;
+use algorithm::{
+    DistanceModel, LocalFrame, TrackSelectionPolicy, calculate_distance_with, select_track,
+};
+use chrono::{NaiveDateTime, TimeDelta};
 use common::elapsed_time_source::{ElapsedTimeSource, MonotonicTimeSource};
 use common::position::{GnssPosition, Position};
 use core::f64;
-use module_core::{Event, EventKind, Module, ModuleCtx, Request};
+use module_core::{
+    BestLap, EmptyRequestPtr, Event, EventKind, EventKindType, HEARTBEAT_INTERVAL, LapFinished,
+    LaptimerStateInfo, Module, ModuleCtx, ModuleInterface, Request, Response,
+};
 use std::collections::VecDeque;
 use std::sync::Arc;
 use std::time::Duration;
@@ -49,6 +55,326 @@ enum LaptimerState {
     WaitingForFinish,
 }
 
+/// Defines how the first lap of a [`SimpleLaptimer`] is started.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum StartMode {
+    /// The first lap starts when the vehicle crosses the start line.
+    #[default]
+    LineCross,
+
+    /// The first lap starts on the very first received position, e.g. for hillclimbs
+    /// where the vehicle is powered on at the start beacon. The lap still finishes on
+    /// the finish line crossing.
+    FirstFix,
+}
+
+/// Defines the clock the lap and sector times of a [`SimpleLaptimer`] are measured with.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum TimingSource {
+    /// Times are measured with the [`ElapsedTimeSource`] of the lap timer.
+    #[default]
+    ElapsedClock,
+
+    /// Times are the difference of the GNSS fix timestamps of the positions at which the
+    /// markers were passed, so they don't depend on the latency of the position processing.
+    GnssTimestamp,
+}
+
+/// Defines how a [`SimpleLaptimer`] detects that a marker (start line, sector, finish line)
+/// was passed, see [`LaptimerConfig::crossing_detection`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CrossingDetection {
+    /// The marker is passed when the last four positions are within the radius of the marker
+    /// and the distance to it first decreases and then increases again.
+    Proximity,
+
+    /// The marker is a line segment through the marker point, perpendicular to the heading
+    /// of the vehicle, with the given half width in meters on each side of the marker.
+    ///
+    /// The marker is passed when the segment lies between the two most recent positions, so
+    /// it is also detected when the vehicle jumps over the marker between two sparse GNSS
+    /// samples. Once passed, the marker is only reported again after the vehicle left the
+    /// [`exit_radius`](LaptimerConfig::exit_radius).
+    LineSegment {
+        /// Half width of the segment in meters.
+        half_width: f64,
+    },
+}
+
+impl Default for CrossingDetection {
+    /// A [`CrossingDetection::LineSegment`] as wide as the default enter radius.
+    fn default() -> Self {
+        CrossingDetection::LineSegment { half_width: 25.0 }
+    }
+}
+
+/// Configuration of the lap completion by distance, see [`LaptimerConfig::distance_fallback`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DistanceFallback {
+    /// Allowed relative shortfall of the distance traveled since lap start compared to the
+    /// track length, e.g. `0.1` completes the lap after 90% of the track length.
+    ///
+    /// The track length is the straight-line distance through all markers
+    /// ([`common::track::Track::length_meters`]), so the driven distance usually exceeds it.
+    pub tolerance: f64,
+
+    /// Radius in meters around the finish line in which the lap may be completed.
+    pub finish_radius: f64,
+}
+
+impl Default for DistanceFallback {
+    fn default() -> Self {
+        DistanceFallback {
+            tolerance: 0.1,
+            finish_radius: 50.0,
+        }
+    }
+}
+
+/// Defines when the finish line of a lap is armed, see [`LaptimerConfig::finish_arming`].
+///
+/// The finish line is never armed before all sectors of the track are passed.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum FinishArming {
+    /// The finish line is armed after the last sector. On tracks without sectors it is
+    /// armed right after the lap start.
+    #[default]
+    AfterSectors,
+
+    /// The finish line is armed after the vehicle traveled at least the given distance in
+    /// meters since the lap start, e.g. for circuits without sectors.
+    MinDistance(f64),
+
+    /// The finish line is armed after the vehicle traveled at least the given fraction of
+    /// the track length ([`common::track::Track::length_meters`]) since the lap start.
+    MinTrackFraction(f64),
+}
+
+/// Configuration of a [`SimpleLaptimer`].
+///
+/// The default configuration detects the markers with a [`CrossingDetection::LineSegment`]
+/// and discards the buffered positions after a gap in the position events, see
+/// [`max_position_gap`](LaptimerConfig::max_position_gap).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LaptimerConfig {
+    /// Radius in meters in which a marker (start line, sector, finish line) becomes engaged.
+    ///
+    /// With [`CrossingDetection::Proximity`] the last four positions must lie within the
+    /// radius around the marker, so the radius needs to cover about four GNSS updates at the
+    /// speed the marker is passed with, e.g. at 50 m/s and 10 Hz the vehicle moves 5m per
+    /// update. Larger radii tolerate low update rates and high speeds, smaller ones avoid
+    /// markers of close by track parts, e.g. on kart tracks. See
+    /// [`SimpleLaptimer::with_detection_range`].
+    pub enter_radius: f64,
+
+    /// Defines how the passing of a marker is detected.
+    ///
+    /// The enter radius only applies to [`CrossingDetection::Proximity`]. Defaults to a
+    /// [`CrossingDetection::LineSegment`] with a half width of 25m.
+    pub crossing_detection: CrossingDetection,
+
+    /// Radius in meters the vehicle must leave before an engaged marker is released again.
+    ///
+    /// A marker is reported as passed at most once per engagement. Choosing a value larger
+    /// than [`enter_radius`](Self::enter_radius) adds hysteresis, so position jitter around
+    /// the boundary doesn't count the same marker twice.
+    pub exit_radius: f64,
+
+    /// Defines how the first lap is started.
+    pub start_mode: StartMode,
+
+    /// Defines the clock the lap and sector times are measured with.
+    ///
+    /// The periodic [`EventKind::CurrentLaptimeEvent`] is always based on the elapsed clock.
+    pub timing_source: TimingSource,
+
+    /// Distance in meters to the finish line below which an
+    /// [`EventKind::ApproachingFinishEvent`] is emitted after the last sector.
+    pub approach_distance: f64,
+
+    /// Velocity below which positions are only buffered but not evaluated for marker crossings.
+    ///
+    /// Skipping the evaluation of positions while the vehicle is (nearly) stationary, e.g. in
+    /// the pits, saves processing and avoids false crossings caused by position noise. The unit
+    /// is the one of [`GnssPosition::velocity`]. `0.0` evaluates every position.
+    pub min_velocity: f64,
+
+    /// Completes a lap without a detected finish line crossing, e.g. on tracks with poor
+    /// GNSS reception around the finish line.
+    ///
+    /// After the last sector, the lap is completed when the vehicle traveled about the track
+    /// length since lap start and starts to move away from the finish line within the
+    /// configured radius. `None` disables the fallback.
+    pub distance_fallback: Option<DistanceFallback>,
+
+    /// Defines the minimum progress of a lap before a finish line crossing is accepted.
+    ///
+    /// Avoids that the finish line trips right after the lap start, e.g. on circuits where
+    /// the finish line is the start line and the vehicle jitters around it.
+    pub finish_arming: FinishArming,
+
+    /// Duration after which a running lap is abandoned with an [`EventKind::LapAbandonedEvent`]
+    /// and the lap timer waits for the next start line crossing again.
+    ///
+    /// Guards against laps that never finish, e.g. when the vehicle stopped on the track.
+    /// The duration is measured with the elapsed time source. `None` never abandons a lap.
+    pub max_lap_duration: Option<Duration>,
+
+    /// Time after the start of the lap timer without a configured track after which a
+    /// single [`EventKind::NoTrackConfiguredEvent`] is emitted.
+    pub no_track_grace_period: Duration,
+
+    /// Starts the first lap retroactively when the start line crossing was missed, e.g.
+    /// because of a GNSS dropout, but the first sector is crossed.
+    ///
+    /// The lap start is estimated from the straight-line distance between the start line
+    /// and the first sector at the current velocity. The lap start is followed by an
+    /// [`EventKind::LapStartEstimatedEvent`] and the finished first sector. Only applies to
+    /// [`StartMode::LineCross`] on tracks with sectors.
+    pub start_recovery: bool,
+
+    /// Defines which track is configured when several tracks are detected.
+    ///
+    /// With [`TrackSelectionPolicy::PromptAndWait`] no track is configured until an
+    /// [`EventKind::TrackSelectedEvent`] arrives.
+    pub track_selection: TrackSelectionPolicy,
+
+    /// The formula the distances to the markers and the lap distance are measured with.
+    pub distance_model: DistanceModel,
+
+    /// Ignores crossings of the markers against the driving direction of the track, e.g.
+    /// when reversing in the pit lane or after a spin.
+    ///
+    /// The heading of the vehicle over the last positions is compared with the direction of
+    /// the track at the marker, which is derived from the neighbouring markers. Crossings are
+    /// accepted when the direction is unknown, e.g. at the start line of circuits with less
+    /// than two sectors.
+    pub ignore_backward_crossings: bool,
+
+    /// Maximum angle in degrees between the heading of the vehicle and the direction of the
+    /// track at a marker for a crossing to count, see
+    /// [`ignore_backward_crossings`](Self::ignore_backward_crossings).
+    ///
+    /// `90.0` only rejects crossings against the driving direction, smaller values also
+    /// reject oblique crossings, e.g. of a pit lane exit close to the start line.
+    pub max_heading_deviation: f64,
+
+    /// Maximum time between the arrival of two position events before the buffered positions
+    /// are discarded, e.g. when the GNSS reception is lost in a tunnel.
+    ///
+    /// Without the discard a marker crossing could be computed from positions before and
+    /// after the gap, although they may lie far apart. The distance driven during the gap is
+    /// not counted to the lap distance. Defaults to 3 seconds, `None` never discards the
+    /// positions.
+    pub max_position_gap: Option<Duration>,
+
+    /// Calculates the geometry of [`CrossingDetection::LineSegment`] in a [`LocalFrame`]
+    /// around the marker instead of the equirectangular approximation, e.g. for precise
+    /// split lines far from the equator.
+    pub local_frame: bool,
+
+    /// Sets the GNSS fix time of the finish line crossing in every
+    /// [`EventKind::LapFinishedEvent`], e.g. to log laps with their absolute time to external
+    /// systems.
+    pub wall_clock_finish: bool,
+}
+
+impl Default for LaptimerConfig {
+    fn default() -> Self {
+        LaptimerConfig {
+            enter_radius: 25.0,
+            exit_radius: 25.0,
+            crossing_detection: CrossingDetection::default(),
+            start_mode: StartMode::LineCross,
+            timing_source: TimingSource::ElapsedClock,
+            approach_distance: 100.0,
+            min_velocity: 0.0,
+            distance_fallback: None,
+            finish_arming: FinishArming::AfterSectors,
+            max_lap_duration: None,
+            no_track_grace_period: Duration::from_secs(30),
+            start_recovery: false,
+            track_selection: TrackSelectionPolicy::First,
+            distance_model: DistanceModel::Equirectangular,
+            ignore_backward_crossings: false,
+            max_heading_deviation: 90.0,
+            max_position_gap: Some(Duration::from_secs(3)),
+            local_frame: false,
+            wall_clock_finish: false,
+        }
+    }
+}
+
+/// Direction of a marker crossing relative to the driving direction of the track.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum CrossingDirection {
+    Forward,
+    Backward,
+    Unknown,
+}
+
+/// Meters per degree of latitude, the same as the one of [`Position::distance_to`].
+const METERS_PER_DEGREE: f64 = 111_300.0;
+
+/// Returns the vector from `from` to `to` in a local east/north plane, scaled in degrees of
+/// latitude.
+fn direction_vector(from: &Position, to: &Position) -> (f64, f64) {
+    let latitude = ((from.latitude + to.latitude) / 2.0).to_radians();
+    (
+        (to.longitude - from.longitude) * latitude.cos(),
+        to.latitude - from.latitude,
+    )
+}
+
+/// Returns the driving direction of the track at its start or finish line.
+///
+/// On circuits the direction is the one from the last to the first sector, on point to point
+/// tracks from the start line to the next marker respectively from the previous marker to the
+/// finish line. Returns `None` if the direction can't be derived.
+fn line_direction(track: &common::track::Track, line: &Position) -> Option<(f64, f64)> {
+    let circuit = track
+        .finishline
+        .is_none_or(|finishline| finishline == track.startline);
+    let (from, to) = if circuit {
+        (track.sectors.last()?, track.sectors.first()?)
+    } else if *line == track.startline {
+        (
+            &track.startline,
+            track.sectors.first().or(track.finishline.as_ref())?,
+        )
+    } else {
+        (track.sectors.last().unwrap_or(&track.startline), line)
+    };
+    (from != to).then(|| direction_vector(from, to))
+}
+
+/// Returns the driving direction of the track at the sector with the given index.
+///
+/// The direction is the one from the previous marker to the next marker of the sector.
+/// Returns `None` if the direction can't be derived.
+fn sector_direction(track: &common::track::Track, index: usize) -> Option<(f64, f64)> {
+    let from = match index {
+        0 => &track.startline,
+        _ => track.sectors.get(index - 1)?,
+    };
+    let to = track
+        .sectors
+        .get(index + 1)
+        .or(track.finishline.as_ref())
+        .unwrap_or(&track.startline);
+    (from != to).then(|| direction_vector(from, to))
+}
+
+/// Engagement state of the marker that is currently checked by the lap timer.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct MarkerEngagement {
+    /// The marker position the vehicle is currently engaged with.
+    marker: Position,
+
+    /// Whether the marker was already reported as passed during this engagement.
+    passed: bool,
+}
+
 /// A simple lap timer that tracks lap and sector times based on GNSS position updates.
 ///
 /// # Type Parameters
@@ -66,6 +392,18 @@ pub struct SimpleLaptimer<T: ElapsedTimeSource = MonotonicTimeSource> {
     notify_laptime: Arc<Notify>,
     laptime_notifaction_active: bool,
     notification_timer_handle: Option<tokio::task::JoinHandle<()>>,
+    config: LaptimerConfig,
+    engagement: Option<MarkerEngagement>,
+    marker_distance: Option<f64>,
+    finish_approach_announced: bool,
+    lap_distance: f64,
+    no_track_announced: bool,
+    fix_timestamp: Option<NaiveDateTime>,
+    lap_start_timestamp: Option<NaiveDateTime>,
+    velocity: f64,
+    lap_start_offset: Duration,
+    best_lap: Option<Duration>,
+    last_position_arrival: Option<tokio::time::Instant>,
 }
 
 impl SimpleLaptimer<MonotonicTimeSource> {
@@ -78,6 +416,17 @@ impl SimpleLaptimer<MonotonicTimeSource> {
 impl<T: ElapsedTimeSource + Default> SimpleLaptimer<T> {
     /// Creates a new lap timer with a custom time source.
     pub fn new_with_source(elapsed_time_source: T, ctx: ModuleCtx) -> Self {
+        SimpleLaptimer::new_with_config(elapsed_time_source, LaptimerConfig::default(), ctx)
+    }
+
+    /// Creates a new lap timer with a custom time source and the given detection range in
+    /// meters, see [`SimpleLaptimer::with_detection_range`].
+    pub fn new_with_detection_range(elapsed_time_source: T, meters: f64, ctx: ModuleCtx) -> Self {
+        SimpleLaptimer::new_with_source(elapsed_time_source, ctx).with_detection_range(meters)
+    }
+
+    /// Creates a new lap timer with a custom time source and configuration.
+    pub fn new_with_config(elapsed_time_source: T, config: LaptimerConfig, ctx: ModuleCtx) -> Self {
         SimpleLaptimer {
             last_positions: VecDeque::with_capacity(4),
             track: None,
@@ -89,16 +438,43 @@ impl<T: ElapsedTimeSource + Default> SimpleLaptimer<T> {
             notify_laptime: Arc::new(Notify::new()),
             laptime_notifaction_active: false,
             notification_timer_handle: None,
+            config,
+            engagement: None,
+            marker_distance: None,
+            finish_approach_announced: false,
+            lap_distance: 0.0,
+            no_track_announced: false,
+            fix_timestamp: None,
+            lap_start_timestamp: None,
+            velocity: 0.0,
+            lap_start_offset: Duration::ZERO,
+            best_lap: None,
+            last_position_arrival: None,
         }
     }
 
+    /// Sets the [`enter_radius`](LaptimerConfig::enter_radius) and the
+    /// [`exit_radius`](LaptimerConfig::exit_radius) of the markers to the given range in meters,
+    /// as well as the half width of a [`CrossingDetection::LineSegment`].
+    ///
+    /// A range of 5m suits kart tracks with a high GNSS update rate, while low update rates
+    /// or high speeds need a larger range to have enough positions around a marker.
+    pub fn with_detection_range(mut self, meters: f64) -> Self {
+        self.config.enter_radius = meters;
+        self.config.exit_radius = meters;
+        if let CrossingDetection::LineSegment { half_width } = &mut self.config.crossing_detection {
+            *half_width = meters;
+        }
+        self
+    }
+
     /// Returns the current lap time.
     ///
     /// If the lap timer has not yet started (`WaitingForFirstStart`),
     /// this returns `Duration::zero()`.
     pub fn lap_time(&self) -> Duration {
         if self.state != LaptimerState::WaitingForFirstStart {
-            return self.elapsed_time_source.elapsed_time();
+            return self.clock_elapsed_time();
         }
         Duration::default()
     }
@@ -107,14 +483,29 @@ impl<T: ElapsedTimeSource + Default> SimpleLaptimer<T> {
     ///
     /// This method:
     /// - Adds the position to the position history.
-    /// - Ensures enough positions are stored to detect line crossing.
+    /// - Accumulates the distance traveled since lap start.
+    /// - Ensures enough positions are stored to detect line crossing. In [`StartMode::FirstFix`]
+    ///   the first position is enough to start the first lap.
+    /// - Skips the evaluation of positions slower than the configured minimum velocity.
     /// - Triggers FSM state transitions and event notifications if needed.
     pub fn update_position(&mut self, pos: &GnssPosition) {
+        self.fix_timestamp = Some(pos.timestamp());
+        self.velocity = pos.velocity();
         if self.last_positions.len() == self.last_positions.capacity() {
             self.last_positions.pop_back();
         }
         self.last_positions.push_front(pos.to_position());
-        if self.last_positions.len() < 4 {
+        if let (Some(current), Some(previous)) =
+            (self.last_positions.front(), self.last_positions.get(1))
+        {
+            self.lap_distance += self.distance(current, previous);
+        }
+        if pos.velocity() < self.config.min_velocity {
+            return;
+        }
+        if self.last_positions.len() < self.required_positions()
+            && self.config.start_mode == StartMode::LineCross
+        {
             return;
         }
         if self.track.is_some() {
@@ -135,86 +526,425 @@ impl<T: ElapsedTimeSource + Default> SimpleLaptimer<T> {
             }
         };
 
-        if self.state == LaptimerState::WaitingForFirstStart
-            && self.is_point_passed(&track.startline)
-        {
-            self.elapsed_time_source.start();
-            self.state = LaptimerState::IteratingTrackPoints;
+        if self.state == LaptimerState::WaitingForFirstStart && self.is_first_start(&track) {
+            self.start_lap_timing();
+            self.state = Self::lap_start_state(&track);
             self.sector_start = Duration::default();
+            self.lap_distance = 0.0;
+            self.finish_approach_announced = false;
             self.notify_consumer(Event {
                 kind: EventKind::LapStartedEvent,
             });
+        } else if self.state == LaptimerState::WaitingForFirstStart
+            && self.is_missed_start_recovered(&track)
+        {
+            self.start_estimated_lap(&track);
         } else if self.state == LaptimerState::IteratingTrackPoints
-            && self.is_point_passed(&track.sectors[self.sector])
+            && self.is_point_passed(
+                &track.sectors[self.sector],
+                sector_direction(&track, self.sector),
+            )
         {
             self.sector += 1;
             if self.sector >= track.sectors.len() {
                 self.state = LaptimerState::WaitingForFinish;
+                self.finish_approach_announced = false;
             }
             self.handle_sector_finsihed();
-        } else if self.state == LaptimerState::WaitingForFinish {
+        } else if self.state == LaptimerState::WaitingForFinish && self.is_finish_armed(&track) {
             let finish_point = track
                 .finishline
                 .map_or(track.startline, |finishline| finishline);
-            if self.is_point_passed(&finish_point) {
+            if self.is_point_passed(&finish_point, line_direction(&track, &finish_point))
+                || self.is_lap_completed_by_distance(&track, &finish_point)
+            {
                 self.handle_sector_finsihed();
+                let lap_time = self.lap_elapsed_time();
                 self.notify_consumer(Event {
                     kind: EventKind::LapFinishedEvent(
-                        self.elapsed_time_source.elapsed_time().into(),
+                        LapFinished {
+                            lap_time,
+                            finish_time: self
+                                .fix_timestamp
+                                .filter(|_| self.config.wall_clock_finish),
+                        }
+                        .into(),
                     ),
                 });
-                if !track.sectors.is_empty() {
+                self.record_lap_time(lap_time);
+                if !track.sectors.is_empty() || finish_point == track.startline {
                     // Start a new lap immediately
                     self.sector = 0;
                     self.sector_start = Duration::default();
-                    self.elapsed_time_source.start();
-                    self.state = LaptimerState::IteratingTrackPoints;
+                    self.lap_distance = 0.0;
+                    self.finish_approach_announced = false;
+                    self.start_lap_timing();
+                    self.state = Self::lap_start_state(&track);
                     self.notify_consumer(Event {
                         kind: EventKind::LapStartedEvent,
                     });
+                } else {
+                    // Point to point tracks without sectors wait for the next start.
+                    self.sector = 0;
+                    self.state = LaptimerState::WaitingForFirstStart;
                 }
+            } else {
+                self.announce_finish_approach();
             }
         }
     }
 
+    /// Returns the state after a lap start, tracks without sectors directly wait for the finish.
+    fn lap_start_state(track: &common::track::Track) -> LaptimerState {
+        if track.sectors.is_empty() {
+            LaptimerState::WaitingForFinish
+        } else {
+            LaptimerState::IteratingTrackPoints
+        }
+    }
+
+    /// Checks whether the lap progressed far enough to accept a finish line crossing
+    /// according to the configured [`FinishArming`].
+    fn is_finish_armed(&self, track: &common::track::Track) -> bool {
+        match self.config.finish_arming {
+            FinishArming::AfterSectors => true,
+            FinishArming::MinDistance(distance) => self.lap_distance >= distance,
+            FinishArming::MinTrackFraction(fraction) => {
+                self.lap_distance >= track.length_meters() * fraction
+            }
+        }
+    }
+
+    /// Emits an [`EventKind::ApproachingFinishEvent`] once per lap when the distance to the
+    /// finish line, as computed by the last marker check, drops below the approach distance.
+    fn announce_finish_approach(&mut self) {
+        if self.finish_approach_announced {
+            return;
+        }
+        if let Some(distance) = self.marker_distance
+            && distance < self.config.approach_distance
+        {
+            self.finish_approach_announced = true;
+            self.notify_consumer(Event {
+                kind: EventKind::ApproachingFinishEvent(distance),
+            });
+        }
+    }
+
+    /// Checks whether the lap is completed by the configured [`DistanceFallback`].
+    ///
+    /// Returns `true` if the vehicle traveled about the track length since lap start and
+    /// its newest position moves away from the finish line within the finish radius.
+    fn is_lap_completed_by_distance(
+        &self,
+        track: &common::track::Track,
+        finish_point: &Position,
+    ) -> bool {
+        let Some(fallback) = self.config.distance_fallback else {
+            return false;
+        };
+        let (Some(current), Some(previous)) =
+            (self.last_positions.front(), self.last_positions.get(1))
+        else {
+            return false;
+        };
+        let distance = self.distance(current, finish_point);
+        let completed = distance < fallback.finish_radius
+            && distance > self.distance(previous, finish_point)
+            && self.lap_distance >= track.length_meters() * (1.0 - fallback.tolerance);
+        if completed {
+            info!(
+                "Lap completed by distance fallback after {:.1}m, {:.1}m away from the finish line",
+                self.lap_distance, distance
+            );
+        }
+        completed
+    }
+
+    /// Checks whether the first lap shall be started according to the configured [`StartMode`].
+    fn is_first_start(&mut self, track: &common::track::Track) -> bool {
+        match self.config.start_mode {
+            StartMode::LineCross => {
+                self.is_point_passed(&track.startline, line_direction(track, &track.startline))
+            }
+            StartMode::FirstFix => !self.last_positions.is_empty(),
+        }
+    }
+
+    /// Checks whether the missed start line crossing shall be recovered because the first
+    /// sector was crossed, see [`LaptimerConfig::start_recovery`].
+    ///
+    /// The first sector isn't checked while the vehicle is engaged with the start line, the
+    /// check would release the engagement and the start line could be passed again within
+    /// its exit radius.
+    fn is_missed_start_recovered(&mut self, track: &common::track::Track) -> bool {
+        self.config.start_recovery
+            && self.config.start_mode == StartMode::LineCross
+            && !track.sectors.is_empty()
+            && !self.is_engaged_with(&track.startline)
+            && self.is_point_passed(&track.sectors[0], sector_direction(track, 0))
+    }
+
+    /// Returns whether the vehicle is engaged with the given marker.
+    fn is_engaged_with(&self, marker: &Position) -> bool {
+        self.engagement
+            .is_some_and(|engagement| engagement.marker == *marker)
+    }
+
+    /// Starts a lap retroactively at the first sector crossing.
+    ///
+    /// The time since the start line crossing is estimated from the straight-line distance
+    /// between the start line and the first sector at the current velocity, the first
+    /// sector is finished right away.
+    fn start_estimated_lap(&mut self, track: &common::track::Track) {
+        let distance = self.distance(&track.startline, &track.sectors[0]);
+        let offset = if self.velocity > 0.0 {
+            Duration::from_secs_f64(distance / self.velocity)
+        } else {
+            Duration::ZERO
+        };
+        info!(
+            "Start line crossing missed, lap started {:?} before the first sector",
+            offset
+        );
+        self.start_lap_timing();
+        self.lap_start_offset = offset;
+        self.lap_start_timestamp = self
+            .fix_timestamp
+            .map(|timestamp| timestamp - TimeDelta::from_std(offset).unwrap_or_default());
+        self.state = LaptimerState::IteratingTrackPoints;
+        self.sector = 0;
+        self.sector_start = Duration::default();
+        self.lap_distance = distance;
+        self.finish_approach_announced = false;
+        self.notify_consumer(Event {
+            kind: EventKind::LapStartedEvent,
+        });
+        self.notify_consumer(Event {
+            kind: EventKind::LapStartEstimatedEvent(offset.into()),
+        });
+        self.sector = 1;
+        if self.sector >= track.sectors.len() {
+            self.state = LaptimerState::WaitingForFinish;
+        }
+        self.handle_sector_finsihed();
+    }
+
     /// Handles sector completion:
     /// - Computes the sector time relative to the previous sector start.
     /// - Notifies consumers with [`LaptimerStatus::SectorFinshed`].
     /// - Updates the sector start timestamp.
     fn handle_sector_finsihed(&mut self) {
-        let duration = self.elapsed_time_source.elapsed_time() - self.sector_start;
+        let elapsed = self.lap_elapsed_time();
+        let duration = elapsed.saturating_sub(self.sector_start);
         self.notify_consumer(Event {
             kind: EventKind::SectorFinishedEvent(duration.into()),
         });
-        self.sector_start = self.elapsed_time_source.elapsed_time();
+        self.sector_start = elapsed;
+    }
+
+    /// Records the time of a finished lap and emits an [`EventKind::NewBestLapEvent`] if it
+    /// is the first lap or faster than the best lap so far.
+    fn record_lap_time(&mut self, lap_time: Duration) {
+        let delta = match self.best_lap {
+            Some(best) if lap_time >= best => return,
+            Some(best) => Some(best - lap_time),
+            None => None,
+        };
+        self.best_lap = Some(lap_time);
+        self.notify_consumer(Event {
+            kind: EventKind::NewBestLapEvent(Arc::new(BestLap { lap_time, delta })),
+        });
+    }
+
+    /// Starts the timing of a new lap at the current position.
+    fn start_lap_timing(&mut self) {
+        self.elapsed_time_source.start();
+        self.lap_start_timestamp = self.fix_timestamp;
+        self.lap_start_offset = Duration::ZERO;
+    }
+
+    /// Returns the time since the lap start measured with the elapsed time source,
+    /// including the estimated part of a retroactively started lap.
+    fn clock_elapsed_time(&self) -> Duration {
+        self.elapsed_time_source.elapsed_time() + self.lap_start_offset
+    }
+
+    /// Returns the time since the lap start according to the configured [`TimingSource`].
+    fn lap_elapsed_time(&self) -> Duration {
+        match self.config.timing_source {
+            TimingSource::ElapsedClock => self.clock_elapsed_time(),
+            TimingSource::GnssTimestamp => match (self.lap_start_timestamp, self.fix_timestamp) {
+                (Some(start), Some(current)) => (current - start).to_std().unwrap_or_default(),
+                _ => Duration::default(),
+            },
+        }
     }
 
-    /// Detects whether a position marker (start line, sector, or finish line) has been crossed.
+    /// Detects whether a position marker (start line, sector, or finish line) has been crossed
+    /// with the configured [`CrossingDetection`].
+    ///
+    /// The distance of the newest position to the marker is kept for further evaluation.
+    /// With [`LaptimerConfig::ignore_backward_crossings`], a crossing against the given
+    /// driving direction of the track at the marker is not reported as passed.
+    ///
+    /// Returns `true` if the point has been passed, `false` otherwise.
+    fn is_point_passed(&mut self, pos: &Position, direction: Option<(f64, f64)>) -> bool {
+        self.marker_distance = None;
+        if self.last_positions.len() < self.required_positions() {
+            return false;
+        }
+        let passed = match self.config.crossing_detection {
+            CrossingDetection::Proximity => self.is_marker_approached_and_left(pos),
+            CrossingDetection::LineSegment { half_width } => {
+                self.is_segment_crossed(pos, half_width)
+            }
+        };
+        if passed
+            && self.config.ignore_backward_crossings
+            && self.crossing_direction(direction) == CrossingDirection::Backward
+        {
+            info!("Ignored backward crossing of marker {:?}", pos);
+            return false;
+        }
+        passed
+    }
+
+    /// Returns the number of positions the configured crossing detection needs to detect a
+    /// crossing: the last 4 positions for the proximity detection and the last 2 for the
+    /// line segment.
+    fn required_positions(&self) -> usize {
+        match self.config.crossing_detection {
+            CrossingDetection::Proximity => 4,
+            CrossingDetection::LineSegment { .. } => 2,
+        }
+    }
+
+    /// Detects a marker crossing by the distances of the last positions to the marker.
     ///
     /// Uses the last 4 recorded positions to determine:
-    /// - Whether the vehicle is within the detection range.
+    /// - Whether the vehicle is engaged with the marker, i.e. it came closer than the
+    ///   configured enter radius and hasn't left the exit radius since.
     /// - Whether the crossing direction indicates a valid pass.
     ///
-    /// Returns `true` if the point has been passed, `false` otherwise.
-    fn is_point_passed(&self, pos: &Position) -> bool {
-        let detection_range = 25_u8;
-        let mut distances = Vec::<f64>::with_capacity(4);
-        let is_in_range = self.last_positions.iter().all(|pos1| {
-            let distance = calculate_distance(pos1, pos);
-            distances.push(distance);
-            distance < detection_range.into()
-        });
+    /// A marker is reported as passed at most once per engagement.
+    fn is_marker_approached_and_left(&mut self, pos: &Position) -> bool {
+        let distances: Vec<f64> = self
+            .last_positions
+            .iter()
+            .map(|pos1| self.distance(pos1, pos))
+            .collect();
+        self.marker_distance = Some(distances[0]);
 
-        if !is_in_range {
+        let mut engagement = match self.engagement {
+            Some(engagement) if engagement.marker == *pos => engagement,
+            _ => {
+                if distances[0] >= self.config.enter_radius {
+                    self.engagement = None;
+                    return false;
+                }
+                MarkerEngagement {
+                    marker: *pos,
+                    passed: false,
+                }
+            }
+        };
+        if distances[0] >= self.config.exit_radius {
+            self.engagement = None;
             return false;
         }
 
+        let is_in_range = distances.iter().all(|d| *d < self.config.exit_radius);
         let first_distance = distances[0] > distances[1];
         let last_distance = distances[2] < distances[3];
-        if first_distance && last_distance && distances[1] != distances[2] {
-            return true;
+        let passed = is_in_range
+            && !engagement.passed
+            && first_distance
+            && last_distance
+            && distances[1] != distances[2];
+        engagement.passed |= passed;
+        self.engagement = Some(engagement);
+        passed
+    }
+
+    /// Detects a marker crossing by intersecting the path between the two most recent
+    /// positions with a segment through the marker perpendicular to this path, see
+    /// [`CrossingDetection::LineSegment`].
+    fn is_segment_crossed(&mut self, pos: &Position, half_width: f64) -> bool {
+        let (Some(current), Some(previous)) =
+            (self.last_positions.front(), self.last_positions.get(1))
+        else {
+            return false;
+        };
+        let distance = self.distance(current, pos);
+        self.marker_distance = Some(distance);
+        if let Some(engagement) = self.engagement {
+            if engagement.marker == *pos && distance < self.config.exit_radius {
+                return false;
+            }
+            self.engagement = None;
         }
-        false
+
+        // Offsets of both positions from the marker in meters.
+        let ((x0, y0), (x1, y1)) = if self.config.local_frame {
+            let frame = LocalFrame::new(pos);
+            (frame.to_local(current), frame.to_local(previous))
+        } else {
+            let to_meters = |(x, y): (f64, f64)| (x * METERS_PER_DEGREE, y * METERS_PER_DEGREE);
+            (
+                to_meters(direction_vector(pos, current)),
+                to_meters(direction_vector(pos, previous)),
+            )
+        };
+        let (dx, dy) = (x0 - x1, y0 - y1);
+        let length = dx.hypot(dy);
+        if length == 0.0 {
+            return false;
+        }
+        let (hx, hy) = (dx / length, dy / length);
+        // Signed distances of the positions along the heading, the segment is at zero.
+        let behind = x1 * hx + y1 * hy;
+        let ahead = x0 * hx + y0 * hy;
+        let lateral = (hx * y1 - hy * x1).abs();
+        let crossed = behind < 0.0 && ahead >= 0.0 && lateral <= half_width;
+        if crossed {
+            self.engagement = Some(MarkerEngagement {
+                marker: *pos,
+                passed: true,
+            });
+        }
+        crossed
+    }
+
+    /// Classifies the crossing of the last positions against the driving direction.
+    ///
+    /// The crossing is backward if the heading deviates by more than the configured
+    /// [`max_heading_deviation`](LaptimerConfig::max_heading_deviation) from the direction.
+    fn crossing_direction(&self, direction: Option<(f64, f64)>) -> CrossingDirection {
+        let (Some(direction), Some(newest), Some(oldest)) = (
+            direction,
+            self.last_positions.front(),
+            self.last_positions.back(),
+        ) else {
+            return CrossingDirection::Unknown;
+        };
+        let heading = direction_vector(oldest, newest);
+        let norms = heading.0.hypot(heading.1) * direction.0.hypot(direction.1);
+        if norms == 0.0 {
+            return CrossingDirection::Unknown;
+        }
+        let cos = (heading.0 * direction.0 + heading.1 * direction.1) / norms;
+        if cos.clamp(-1.0, 1.0).acos().to_degrees() <= self.config.max_heading_deviation {
+            CrossingDirection::Forward
+        } else {
+            CrossingDirection::Backward
+        }
+    }
+
+    /// Returns the distance in meters between both positions with the configured
+    /// [`DistanceModel`].
+    fn distance(&self, pos1: &Position, pos2: &Position) -> f64 {
+        calculate_distance_with(self.config.distance_model, pos1, pos2)
     }
 
     /// Notifies all registered consumers of a new lap timer status update.
@@ -222,9 +952,100 @@ impl<T: ElapsedTimeSource + Default> SimpleLaptimer<T> {
         let _ = self.module_ctx.sender.send(event);
     }
 
+    /// Configures the track selected from the detected tracks, see
+    /// [`LaptimerConfig::track_selection`].
+    fn on_track_detected(&mut self, tracks: &[common::track::Track]) {
+        match select_track(
+            tracks,
+            self.last_positions.front(),
+            self.config.track_selection,
+        ) {
+            Some(track) => self.configure_track(track.clone()),
+            None => info!(
+                "{} tracks detected, waiting for the track selection",
+                tracks.len()
+            ),
+        }
+    }
+
+    fn configure_track(&mut self, track: common::track::Track) {
+        info!("Track configured for Track {}", track.name);
+        self.track = Some(track);
+        self.calculate_laptimer_state();
+    }
+
+    /// Announces once that no track got configured within the grace period.
+    fn announce_no_track(&mut self) {
+        self.no_track_announced = true;
+        info!(
+            "No track configured within {:?}",
+            self.config.no_track_grace_period
+        );
+        self.notify_consumer(Event {
+            kind: EventKind::NoTrackConfiguredEvent,
+        });
+    }
+
+    /// Replies to a lap timer state request with a snapshot of the current state.
+    fn handle_state_request(&self, request: &EmptyRequestPtr) {
+        let state = LaptimerStateInfo {
+            track_name: self.track.as_ref().map(|track| track.name.clone()),
+            no_track_configured: self.track.is_none() && self.no_track_announced,
+            lap_running: self.state != LaptimerState::WaitingForFirstStart,
+        };
+        self.notify_consumer(Event {
+            kind: EventKind::LaptimerStateResponseEvent(Response::new(
+                request.id,
+                request.sender_addr,
+                state,
+            )),
+        });
+    }
+
+    /// Abandons the running lap if it exceeded the configured maximum lap duration.
+    ///
+    /// Emits an [`EventKind::LapAbandonedEvent`] and resets the lap timer to wait for the
+    /// next start line crossing.
+    fn abandon_stuck_lap(&mut self) {
+        let Some(max_lap_duration) = self.config.max_lap_duration else {
+            return;
+        };
+        if self.state == LaptimerState::WaitingForFirstStart
+            || self.clock_elapsed_time() < max_lap_duration
+        {
+            return;
+        }
+        info!("Lap abandoned after exceeding {:?}", max_lap_duration);
+        self.state = LaptimerState::WaitingForFirstStart;
+        self.sector = 0;
+        self.sector_start = Duration::default();
+        self.lap_distance = 0.0;
+        self.engagement = None;
+        self.notify_consumer(Event {
+            kind: EventKind::LapAbandonedEvent,
+        });
+    }
+
+    /// Discards the buffered positions if the last position event arrived longer than the
+    /// configured [`max_position_gap`](LaptimerConfig::max_position_gap) ago.
+    fn discard_positions_after_gap(&mut self) {
+        let now = tokio::time::Instant::now();
+        if let (Some(max_gap), Some(last_arrival)) =
+            (self.config.max_position_gap, self.last_position_arrival)
+            && now.duration_since(last_arrival) > max_gap
+        {
+            info!(
+                "No position received for {:?}, discarding buffered positions",
+                now.duration_since(last_arrival)
+            );
+            self.last_positions.clear();
+        }
+        self.last_position_arrival = Some(now);
+    }
+
     fn announce_laptime(&self) {
         let _ = self.module_ctx.sender.send(Event {
-            kind: EventKind::CurrentLaptimeEvent(self.elapsed_time_source.elapsed_time().into()),
+            kind: EventKind::CurrentLaptimeEvent(self.clock_elapsed_time().into()),
         });
     }
 }
@@ -241,6 +1062,34 @@ fn announce_laptime_timer_task(notify: Arc<Notify>) -> tokio::task::JoinHandle<(
 
 #[async_trait::async_trait]
 impl<T: ElapsedTimeSource + Default + Send> Module for SimpleLaptimer<T> {
+    fn event_interface(&self) -> ModuleInterface {
+        ModuleInterface {
+            consumes: vec![
+                EventKindType::QuitEvent,
+                EventKindType::GnssPositionEvent,
+                EventKindType::GnssPositionBatchEvent,
+                EventKindType::LapStartedEvent,
+                EventKindType::TrackSelectedEvent,
+                EventKindType::DetectTrackResponseEvent,
+                EventKindType::LaptimerStateRequestEvent,
+            ],
+            produces: vec![
+                EventKindType::DetectTrackRequestEvent,
+                EventKindType::ModuleHeartbeatEvent,
+                EventKindType::LapStartedEvent,
+                EventKindType::LapStartEstimatedEvent,
+                EventKindType::SectorFinishedEvent,
+                EventKindType::LapFinishedEvent,
+                EventKindType::NewBestLapEvent,
+                EventKindType::LapAbandonedEvent,
+                EventKindType::CurrentLaptimeEvent,
+                EventKindType::ApproachingFinishEvent,
+                EventKindType::NoTrackConfiguredEvent,
+                EventKindType::LaptimerStateResponseEvent,
+            ],
+        }
+    }
+
     async fn run(&mut self) -> Result<(), ()> {
         let _ = self.module_ctx.sender.send(Event {
             kind: EventKind::DetectTrackRequestEvent(
@@ -253,35 +1102,53 @@ impl<T: ElapsedTimeSource + Default + Send> Module for SimpleLaptimer<T> {
             ),
         });
 
+        let grace_period = tokio::time::sleep(self.config.no_track_grace_period);
+        tokio::pin!(grace_period);
+        let mut heartbeat = tokio::time::interval(HEARTBEAT_INTERVAL);
         let mut run = true;
         while run {
             tokio::select! {
+                _ = heartbeat.tick() => {
+                    let _ = self.module_ctx.publish_heartbeat("laptimer");
+                },
                 _ = self.notify_laptime.notified() => {
+                    self.abandon_stuck_lap();
                     self.announce_laptime();
                 },
-                event = self.module_ctx.receiver.recv() => {
+                _ = &mut grace_period, if self.track.is_none() && !self.no_track_announced => {
+                    self.announce_no_track();
+                },
+                event = self.module_ctx.recv() => {
                     match event {
                         Ok(event) => {
                             match event.kind  {
                                EventKind::QuitEvent => {
                                    run = false
                                },
-                               EventKind::LapStartedEvent => {
-                                  if !self.laptime_notifaction_active {
-                                    let notify = self.notify_laptime.clone();
-                                    self.notification_timer_handle = Some(announce_laptime_timer_task(notify));
-                                    self.laptime_notifaction_active = true;
-                                  }
+                               EventKind::LapStartedEvent if !self.laptime_notifaction_active => {
+                                  let notify = self.notify_laptime.clone();
+                                  self.notification_timer_handle = Some(announce_laptime_timer_task(notify));
+                                  self.laptime_notifaction_active = true;
                                },
                                EventKind::GnssPositionEvent(pos) => {
+                                   self.discard_positions_after_gap();
                                    self.update_position(&pos);
                                },
-                               EventKind::DetectTrackResponseEvent(track) => {
-                                   if !track.data.is_empty() && track.id == 10  && track.receiver_addr == 22 {
-                                       self.track = Some(track.data[0].clone());
-                                       self.calculate_laptimer_state();
-                                       info!("Track configured for Track {}", self.track.as_ref().unwrap().name);
+                               EventKind::GnssPositionBatchEvent(positions) => {
+                                   self.discard_positions_after_gap();
+                                   for pos in positions.iter() {
+                                       self.update_position(pos);
                                    }
+                               },
+                               EventKind::LaptimerStateRequestEvent(request) => {
+                                   self.handle_state_request(&request);
+                               },
+                               EventKind::DetectTrackResponseEvent(track)
+                                   if !track.data.is_empty() && track.id == 10  && track.receiver_addr == 22 => {
+                                   self.on_track_detected(&track.data);
+                               }
+                               EventKind::TrackSelectedEvent(track) if self.track.is_none() => {
+                                   self.configure_track((*track).clone());
                                }
                                 _ => (),
                             }
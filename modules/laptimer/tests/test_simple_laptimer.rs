@@ -2,13 +2,18 @@
 //
 // SPDX-License-Identifier: GPL-2.0-or-later
 
+use chrono::NaiveTime;
 use common::elapsed_time_source::ElapsedTimeSource;
-use common::position::GnssPosition;
+use common::position::{GnssPosition, Position};
 use common::test_helper::elapsed_test_time_source::{ElapsedTestTimeSource, set_elapsed_time};
 use common::test_helper::track::get_track;
+use common::track::Track;
 use laptimer::*;
 use module_core::test_helper::{register_response_event, stop_module, wait_for_event};
-use module_core::{Event, EventBus, EventKind, EventKindType, Module, Response, payload_ref};
+use module_core::{
+    BestLap, Event, EventBus, EventKind, EventKindType, Module, Response,
+    auto_stop::AutoStopModule, payload_ref,
+};
 use std::sync::Arc;
 use std::time::Duration;
 mod util;
@@ -24,6 +29,39 @@ fn create_laptimer<T>(
     event_bus: &EventBus,
     elapsed_time_source: T,
 ) -> tokio::task::JoinHandle<Result<(), ()>>
+where
+    T: ElapsedTimeSource + Default + Send + 'static,
+{
+    create_laptimer_with_config(
+        event_bus,
+        elapsed_time_source,
+        get_track(),
+        LaptimerConfig::default(),
+    )
+}
+
+fn create_laptimer_with_config<T>(
+    event_bus: &EventBus,
+    elapsed_time_source: T,
+    track: Track,
+    config: LaptimerConfig,
+) -> tokio::task::JoinHandle<Result<(), ()>>
+where
+    T: ElapsedTimeSource + Default + Send + 'static,
+{
+    spawn_laptimer(
+        event_bus,
+        SimpleLaptimer::new_with_config(elapsed_time_source, config, event_bus.context()),
+        track,
+    )
+}
+
+/// Spawns the laptimer, the given track is detected for it.
+fn spawn_laptimer<T>(
+    event_bus: &EventBus,
+    laptimer: SimpleLaptimer<T>,
+    track: Track,
+) -> tokio::task::JoinHandle<Result<(), ()>>
 where
     T: ElapsedTimeSource + Default + Send + 'static,
 {
@@ -34,7 +72,7 @@ where
                 Response {
                     id: 10,
                     receiver_addr: 22,
-                    data: vec![get_track()],
+                    data: vec![track],
                 }
                 .into(),
             ),
@@ -46,9 +84,8 @@ where
         panic!("Failed to register DetectTrackResponseEvent");
     }
 
-    let lp = SimpleLaptimer::new_with_source(elapsed_time_source, event_bus.context());
     tokio::spawn(async move {
-        let mut laptimer = lp;
+        let mut laptimer = laptimer;
         laptimer.run().await
     })
 }
@@ -59,7 +96,14 @@ pub async fn drive_whole_map_with_sectors() {
     let event_bus = EventBus::default();
     let elapsed_time_source = ElapsedTestTimeSource::default();
     let elapsed_time_source_sender = elapsed_time_source.sender();
+    let mut rx = event_bus.subscribe();
     let mut laptimer_handle = create_laptimer(&event_bus, elapsed_time_source);
+    wait_for_event(
+        &mut rx,
+        Duration::from_millis(100),
+        EventKindType::DetectTrackResponseEvent,
+    )
+    .await;
 
     {
         // Lapstart
@@ -129,6 +173,7 @@ pub async fn drive_whole_map_with_sectors() {
             &elapsed_time_source_sender,
             &std::time::Duration::from_millis(30390),
         );
+        publish_position(&event_bus, &get_finishline_approach_postion());
         publish_position(&event_bus, &get_finishline_postion1());
         publish_position(&event_bus, &get_finishline_postion2());
         publish_position(&event_bus, &get_finishline_postion3());
@@ -159,7 +204,9 @@ pub async fn drive_whole_map_with_sectors() {
             std::time::Duration::new(10, 140000000)
         );
         assert_eq!(
-            **payload_ref!(lap_finished_event.kind, EventKind::LapFinishedEvent).unwrap(),
+            payload_ref!(lap_finished_event.kind, EventKind::LapFinishedEvent)
+                .unwrap()
+                .lap_time,
             std::time::Duration::new(30, 390000000)
         );
         assert_eq!(
@@ -171,13 +218,164 @@ pub async fn drive_whole_map_with_sectors() {
     stop_module(&event_bus, &mut laptimer_handle).await;
 }
 
+/// Returns the positions of a whole lap with sectors, one second apart.
+fn whole_lap_positions() -> Vec<GnssPosition> {
+    [
+        get_finishline_postion1(),
+        get_finishline_postion2(),
+        get_finishline_postion3(),
+        get_finishline_postion4(),
+        get_sector1_postion1(),
+        get_sector1_postion2(),
+        get_sector1_postion3(),
+        get_sector1_postion4(),
+        get_sector2_postion1(),
+        get_sector2_postion2(),
+        get_sector2_postion3(),
+        get_sector2_postion4(),
+        get_finishline_approach_postion(),
+        get_finishline_postion1(),
+        get_finishline_postion2(),
+        get_finishline_postion3(),
+        get_finishline_postion4(),
+    ]
+    .iter()
+    .enumerate()
+    .map(|(second, pos)| {
+        let time = NaiveTime::from_num_seconds_from_midnight_opt(second as u32, 0).unwrap();
+        GnssPosition::new(
+            pos.latitude(),
+            pos.longitude(),
+            pos.velocity(),
+            &time,
+            &pos.date(),
+        )
+    })
+    .collect()
+}
+
+#[tokio::test]
+#[test_log::test]
+pub async fn lap_finish_carries_wall_clock_time_of_finish_crossing() {
+    for wall_clock_finish in [false, true] {
+        let event_bus = EventBus::default();
+        let config = LaptimerConfig {
+            wall_clock_finish,
+            ..Default::default()
+        };
+        let mut rx = event_bus.subscribe();
+        let mut laptimer_handle = create_laptimer_with_config(
+            &event_bus,
+            ElapsedTestTimeSource::default(),
+            get_track(),
+            config,
+        );
+        wait_for_event(
+            &mut rx,
+            Duration::from_millis(100),
+            EventKindType::DetectTrackResponseEvent,
+        )
+        .await;
+
+        let positions = whole_lap_positions();
+        for pos in &positions {
+            publish_position(&event_bus, pos);
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let mut lap_events = vec![];
+        while let Ok(event) = rx.try_recv() {
+            if let EventKind::LapFinishedEvent(lap) = event.kind {
+                lap_events.push(lap.finish_time);
+            }
+        }
+        stop_module(&event_bus, &mut laptimer_handle).await;
+
+        // The finish line crossing is detected with the first position past the finish line,
+        // the second to last one of the lap.
+        let finish_time = positions[positions.len() - 2].timestamp();
+        if wall_clock_finish {
+            assert_eq!(lap_events, vec![Some(finish_time)]);
+        } else {
+            assert_eq!(lap_events, vec![None]);
+        }
+    }
+}
+
+/// Drives a whole lap, as single position events or as one batch, and returns the lap
+/// events with their times.
+async fn drive_whole_lap(batch: bool) -> Vec<(EventKindType, Option<Duration>)> {
+    let event_bus = EventBus::default();
+    let config = LaptimerConfig {
+        timing_source: TimingSource::GnssTimestamp,
+        ..Default::default()
+    };
+    let mut rx = event_bus.subscribe();
+    let mut laptimer_handle = create_laptimer_with_config(
+        &event_bus,
+        ElapsedTestTimeSource::default(),
+        get_track(),
+        config,
+    );
+    wait_for_event(
+        &mut rx,
+        Duration::from_millis(100),
+        EventKindType::DetectTrackResponseEvent,
+    )
+    .await;
+
+    if batch {
+        event_bus.publish(&Event {
+            kind: EventKind::GnssPositionBatchEvent(Arc::new(whole_lap_positions())),
+        });
+    } else {
+        for pos in whole_lap_positions() {
+            publish_position(&event_bus, &pos);
+        }
+    }
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let mut lap_events = vec![];
+    while let Ok(event) = rx.try_recv() {
+        match event.kind {
+            EventKind::LapStartedEvent => lap_events.push((EventKindType::LapStartedEvent, None)),
+            EventKind::SectorFinishedEvent(time) => {
+                lap_events.push((EventKindType::SectorFinishedEvent, Some(*time)))
+            }
+            EventKind::LapFinishedEvent(lap) => {
+                lap_events.push((EventKindType::LapFinishedEvent, Some(lap.lap_time)))
+            }
+            _ => (),
+        }
+    }
+    stop_module(&event_bus, &mut laptimer_handle).await;
+    lap_events
+}
+
+#[tokio::test]
+#[test_log::test]
+pub async fn position_batch_drives_lap_like_single_positions() {
+    let single = drive_whole_lap(false).await;
+    let batch = drive_whole_lap(true).await;
+    assert!(single.contains(&(
+        EventKindType::LapFinishedEvent,
+        Some(Duration::from_secs(13))
+    )));
+    assert_eq!(batch, single);
+}
+
 #[tokio::test]
 #[test_log::test]
 pub async fn announce_laptime_on_running_lap() {
     let event_bus = EventBus::default();
     let elapsed_time_source = ElapsedTestTimeSource::default();
     let elapsed_time_source_sender = elapsed_time_source.sender();
+    let mut rx = event_bus.subscribe();
     let mut laptimer_handle = create_laptimer(&event_bus, elapsed_time_source);
+    wait_for_event(
+        &mut rx,
+        Duration::from_millis(100),
+        EventKindType::DetectTrackResponseEvent,
+    )
+    .await;
 
     // Lapstart
     publish_position(&event_bus, &get_finishline_postion1());
@@ -203,3 +401,1355 @@ pub async fn announce_laptime_on_running_lap() {
 
     stop_module(&event_bus, &mut laptimer_handle).await;
 }
+
+/// Waits until the laptimer processed all published events and returns the types of the received events.
+async fn received_event_types(
+    rx: &mut tokio::sync::broadcast::Receiver<Event>,
+) -> Vec<EventKindType> {
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let mut event_types = vec![];
+    while let Ok(event) = rx.try_recv() {
+        event_types.push(EventKindType::from(&event.kind));
+    }
+    event_types
+}
+
+/// Drives through a marker that is used as start line and first sector, turns 27m behind
+/// it and drives back through it, and returns how often the marker was passed.
+async fn drive_jitter_around_marker(config: LaptimerConfig) -> usize {
+    let event_bus = EventBus::default();
+    let marker = get_track().startline;
+    let track = Track {
+        name: "Marker".to_string(),
+        startline: marker,
+        finishline: None,
+        sectors: vec![marker, get_track().sectors[0]],
+        detection_radius_m: None,
+    };
+    let mut rx = event_bus.subscribe();
+    let mut laptimer_handle =
+        create_laptimer_with_config(&event_bus, ElapsedTestTimeSource::default(), track, config);
+    wait_for_event(
+        &mut rx,
+        Duration::from_millis(100),
+        EventKindType::DetectTrackResponseEvent,
+    )
+    .await;
+
+    for distance in [-10.0, 10.0, 27.0, 10.0, -10.0] {
+        publish_position(&event_bus, &get_position_north_of(&marker, distance));
+    }
+    let event_types = received_event_types(&mut rx).await;
+    stop_module(&event_bus, &mut laptimer_handle).await;
+    event_types
+        .iter()
+        .filter(|event_type| {
+            **event_type == EventKindType::LapStartedEvent
+                || **event_type == EventKindType::SectorFinishedEvent
+        })
+        .count()
+}
+
+#[tokio::test]
+#[test_log::test]
+pub async fn marker_counted_twice_without_hysteresis() {
+    let passes = drive_jitter_around_marker(LaptimerConfig::default()).await;
+    assert_eq!(passes, 2);
+}
+
+#[tokio::test]
+#[test_log::test]
+pub async fn marker_counted_once_with_hysteresis() {
+    let config = LaptimerConfig {
+        enter_radius: 25.0,
+        exit_radius: 40.0,
+        ..Default::default()
+    };
+    let passes = drive_jitter_around_marker(config).await;
+    assert_eq!(passes, 1);
+}
+
+#[tokio::test]
+#[test_log::test]
+pub async fn first_fix_starts_lap_on_first_position() {
+    let event_bus = EventBus::default();
+    let elapsed_time_source = ElapsedTestTimeSource::default();
+    let elapsed_time_source_sender = elapsed_time_source.sender();
+    let start_beacon = Position::new(&52.0, &11.0);
+    let track = Track {
+        name: "Hillclimb".to_string(),
+        startline: start_beacon,
+        ..get_track()
+    };
+    let config = LaptimerConfig {
+        start_mode: StartMode::FirstFix,
+        ..Default::default()
+    };
+    let mut rx = event_bus.subscribe();
+    let mut laptimer_handle =
+        create_laptimer_with_config(&event_bus, elapsed_time_source, track, config);
+    wait_for_event(
+        &mut rx,
+        Duration::from_millis(100),
+        EventKindType::DetectTrackResponseEvent,
+    )
+    .await;
+
+    publish_position(&event_bus, &get_position_north_of(&start_beacon, 0.0));
+    wait_for_event(
+        &mut rx,
+        Duration::from_millis(100),
+        EventKindType::LapStartedEvent,
+    )
+    .await;
+
+    set_elapsed_time(
+        &elapsed_time_source_sender,
+        &std::time::Duration::from_millis(61500),
+    );
+    for pos in [
+        get_sector1_postion1(),
+        get_sector1_postion2(),
+        get_sector1_postion3(),
+        get_sector1_postion4(),
+        get_sector2_postion1(),
+        get_sector2_postion2(),
+        get_sector2_postion3(),
+        get_sector2_postion4(),
+    ] {
+        publish_position(&event_bus, &pos);
+    }
+    let mut rx = event_bus.subscribe();
+    for pos in [
+        get_finishline_approach_postion(),
+        get_finishline_postion1(),
+        get_finishline_postion2(),
+        get_finishline_postion3(),
+        get_finishline_postion4(),
+    ] {
+        publish_position(&event_bus, &pos);
+    }
+    let event = wait_for_event(
+        &mut rx,
+        Duration::from_millis(100),
+        EventKindType::LapFinishedEvent,
+    )
+    .await;
+    assert_eq!(
+        payload_ref!(event.kind, EventKind::LapFinishedEvent)
+            .unwrap()
+            .lap_time,
+        Duration::from_millis(61500)
+    );
+
+    stop_module(&event_bus, &mut laptimer_handle).await;
+}
+
+#[tokio::test]
+#[test_log::test]
+pub async fn approaching_finish_before_lap_finished() {
+    let event_bus = EventBus::default();
+    let mut rx = event_bus.subscribe();
+    let mut laptimer_handle = create_laptimer(&event_bus, ElapsedTestTimeSource::default());
+    wait_for_event(
+        &mut rx,
+        Duration::from_millis(100),
+        EventKindType::DetectTrackResponseEvent,
+    )
+    .await;
+
+    for pos in [
+        get_finishline_postion1(),
+        get_finishline_postion2(),
+        get_finishline_postion3(),
+        get_finishline_postion4(),
+        get_sector1_postion1(),
+        get_sector1_postion2(),
+        get_sector1_postion3(),
+        get_sector1_postion4(),
+        get_sector2_postion1(),
+        get_sector2_postion2(),
+        get_sector2_postion3(),
+        get_sector2_postion4(),
+    ] {
+        publish_position(&event_bus, &pos);
+    }
+    let _ = received_event_types(&mut rx).await;
+
+    let finishline = get_track().finishline.unwrap();
+    for distance in [300.0, 200.0, 90.0, 60.0] {
+        publish_position(&event_bus, &get_position_north_of(&finishline, distance));
+    }
+    for pos in [
+        get_finishline_postion1(),
+        get_finishline_postion2(),
+        get_finishline_postion3(),
+        get_finishline_postion4(),
+    ] {
+        publish_position(&event_bus, &pos);
+    }
+    let event_types = received_event_types(&mut rx).await;
+    stop_module(&event_bus, &mut laptimer_handle).await;
+
+    let approaching: Vec<usize> = event_types
+        .iter()
+        .enumerate()
+        .filter(|(_, event_type)| **event_type == EventKindType::ApproachingFinishEvent)
+        .map(|(index, _)| index)
+        .collect();
+    let lap_finished = event_types
+        .iter()
+        .position(|event_type| *event_type == EventKindType::LapFinishedEvent)
+        .expect("LapFinishedEvent not emitted");
+    assert_eq!(approaching.len(), 1);
+    assert!(approaching[0] < lap_finished);
+}
+
+#[tokio::test]
+#[test_log::test]
+pub async fn slow_positions_do_not_cross_marker() {
+    let event_bus = EventBus::default();
+    let marker = get_track().startline;
+    let config = LaptimerConfig {
+        min_velocity: 5.0,
+        ..Default::default()
+    };
+    let mut rx = event_bus.subscribe();
+    let mut laptimer_handle = create_laptimer_with_config(
+        &event_bus,
+        ElapsedTestTimeSource::default(),
+        get_track(),
+        config,
+    );
+    wait_for_event(
+        &mut rx,
+        Duration::from_millis(100),
+        EventKindType::DetectTrackResponseEvent,
+    )
+    .await;
+    let position = |distance: f64, velocity: f64| {
+        let pos = get_position_north_of(&marker, distance);
+        GnssPosition::new(
+            pos.latitude(),
+            pos.longitude(),
+            velocity,
+            &pos.time(),
+            &pos.date(),
+        )
+    };
+
+    for distance in [-20.0, 20.0] {
+        publish_position(&event_bus, &position(distance, 1.0));
+    }
+    let event_types = received_event_types(&mut rx).await;
+    assert!(
+        !event_types.contains(&EventKindType::LapStartedEvent),
+        "Lap started by slow positions"
+    );
+
+    for distance in [-20.0, 20.0] {
+        publish_position(&event_bus, &position(distance, 10.0));
+    }
+    let event_types = received_event_types(&mut rx).await;
+    assert!(
+        event_types.contains(&EventKindType::LapStartedEvent),
+        "Lap not started after speed rose"
+    );
+
+    stop_module(&event_bus, &mut laptimer_handle).await;
+}
+
+/// Drives a lap on a straight track with one sector 500m north of the start line and
+/// turns 30m before the start line on the way back, so the finish crossing is missed.
+/// Returns the types of the events received during the lap.
+async fn drive_lap_missing_finish(config: LaptimerConfig) -> Vec<EventKindType> {
+    let event_bus = EventBus::default();
+    let marker = get_track().startline;
+    let track = Track {
+        name: "Straight".to_string(),
+        startline: marker,
+        finishline: None,
+        sectors: vec![get_position_north_of(&marker, 500.0).to_position()],
+        detection_radius_m: None,
+    };
+    let mut rx = event_bus.subscribe();
+    let mut laptimer_handle =
+        create_laptimer_with_config(&event_bus, ElapsedTestTimeSource::default(), track, config);
+    wait_for_event(
+        &mut rx,
+        Duration::from_millis(100),
+        EventKindType::DetectTrackResponseEvent,
+    )
+    .await;
+
+    for distance in [
+        -10.0, 10.0, 200.0, 400.0, 490.0, 510.0, 300.0, 100.0, 35.0, 30.0, 35.0, 40.0,
+    ] {
+        publish_position(&event_bus, &get_position_north_of(&marker, distance));
+    }
+    let event_types = received_event_types(&mut rx).await;
+    stop_module(&event_bus, &mut laptimer_handle).await;
+    event_types
+}
+
+/// Drives over the first sector of a straight track 500m north of the start line at 20m/s,
+/// without any position near the start line, and returns the received events.
+async fn drive_lap_missing_start(config: LaptimerConfig) -> Vec<Event> {
+    let event_bus = EventBus::default();
+    let marker = get_track().startline;
+    let track = Track {
+        name: "Straight".to_string(),
+        startline: marker,
+        finishline: None,
+        sectors: vec![
+            get_position_north_of(&marker, 500.0).to_position(),
+            get_position_north_of(&marker, 1000.0).to_position(),
+        ],
+        detection_radius_m: None,
+    };
+    let mut rx = event_bus.subscribe();
+    let mut laptimer_handle =
+        create_laptimer_with_config(&event_bus, ElapsedTestTimeSource::default(), track, config);
+    wait_for_event(
+        &mut rx,
+        Duration::from_millis(100),
+        EventKindType::DetectTrackResponseEvent,
+    )
+    .await;
+
+    for distance in [300.0, 400.0, 476.0, 480.0, 522.0, 524.0, 600.0] {
+        let position = get_position_north_of(&marker, distance);
+        let position = GnssPosition::new(
+            position.latitude(),
+            position.longitude(),
+            20.0,
+            &position.time(),
+            &position.date(),
+        );
+        publish_position(&event_bus, &position);
+    }
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let mut events = vec![];
+    while let Ok(event) = rx.try_recv() {
+        events.push(event);
+    }
+    stop_module(&event_bus, &mut laptimer_handle).await;
+    events
+}
+
+#[tokio::test]
+#[test_log::test]
+pub async fn missed_start_crossing_without_start_recovery() {
+    let events = drive_lap_missing_start(LaptimerConfig::default()).await;
+    assert!(!events.iter().any(|event| {
+        matches!(
+            event.kind,
+            EventKind::LapStartedEvent | EventKind::SectorFinishedEvent(_)
+        )
+    }));
+}
+
+#[tokio::test]
+#[test_log::test]
+pub async fn start_recovery_starts_lap_at_first_sector() {
+    let config = LaptimerConfig {
+        start_recovery: true,
+        ..Default::default()
+    };
+    let events = drive_lap_missing_start(config).await;
+    let lap_events: Vec<&EventKind> = events
+        .iter()
+        .map(|event| &event.kind)
+        .filter(|kind| {
+            matches!(
+                kind,
+                EventKind::LapStartedEvent
+                    | EventKind::LapStartEstimatedEvent(_)
+                    | EventKind::SectorFinishedEvent(_)
+            )
+        })
+        .collect();
+    assert_eq!(lap_events.len(), 3);
+    assert!(matches!(lap_events[0], EventKind::LapStartedEvent));
+    // 500m from the start line to the first sector at 20m/s.
+    let estimated = payload_ref!(*lap_events[1], EventKind::LapStartEstimatedEvent).unwrap();
+    assert!(estimated.abs_diff(Duration::from_secs(25)) < Duration::from_millis(100));
+    let sector = payload_ref!(*lap_events[2], EventKind::SectorFinishedEvent).unwrap();
+    assert_eq!(**sector, **estimated);
+}
+
+#[tokio::test]
+#[test_log::test]
+pub async fn missed_finish_crossing_without_distance_fallback() {
+    let event_types = drive_lap_missing_finish(LaptimerConfig::default()).await;
+    assert!(event_types.contains(&EventKindType::SectorFinishedEvent));
+    assert!(!event_types.contains(&EventKindType::LapFinishedEvent));
+}
+
+#[tokio::test]
+#[test_log::test]
+pub async fn distance_fallback_completes_lap_with_missed_finish_crossing() {
+    let config = LaptimerConfig {
+        distance_fallback: Some(DistanceFallback::default()),
+        ..Default::default()
+    };
+    let event_types = drive_lap_missing_finish(config).await;
+    let lap_finished = event_types
+        .iter()
+        .filter(|event_type| **event_type == EventKindType::LapFinishedEvent)
+        .count();
+    let lap_started = event_types
+        .iter()
+        .filter(|event_type| **event_type == EventKindType::LapStartedEvent)
+        .count();
+    assert_eq!(lap_finished, 1);
+    assert_eq!(lap_started, 2);
+}
+
+#[tokio::test]
+#[test_log::test]
+pub async fn no_track_configured_after_grace_period() {
+    let event_bus = EventBus::default();
+    let config = LaptimerConfig {
+        no_track_grace_period: Duration::from_millis(150),
+        ..Default::default()
+    };
+    let mut rx = event_bus.subscribe();
+    let lp = SimpleLaptimer::new_with_config(
+        ElapsedTestTimeSource::default(),
+        config,
+        event_bus.context(),
+    );
+    let mut laptimer_handle = tokio::spawn(async move {
+        let mut laptimer = lp;
+        laptimer.run().await
+    });
+
+    assert!(
+        !received_event_types(&mut rx)
+            .await
+            .contains(&EventKindType::NoTrackConfiguredEvent),
+        "NoTrackConfiguredEvent emitted before the grace period elapsed"
+    );
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    let no_track_events = received_event_types(&mut rx)
+        .await
+        .into_iter()
+        .filter(|event_type| *event_type == EventKindType::NoTrackConfiguredEvent)
+        .count();
+    assert_eq!(no_track_events, 1);
+
+    event_bus.publish(&Event {
+        kind: EventKind::LaptimerStateRequestEvent(module_core::Request::empty_request(1, 2)),
+    });
+    let state_event = wait_for_event(
+        &mut rx,
+        Duration::from_millis(100),
+        EventKindType::LaptimerStateResponseEvent,
+    )
+    .await;
+    let state = &payload_ref!(state_event.kind, EventKind::LaptimerStateResponseEvent)
+        .unwrap()
+        .data;
+    assert_eq!(state.track_name, None);
+    assert!(state.no_track_configured);
+    assert!(!state.lap_running);
+
+    stop_module(&event_bus, &mut laptimer_handle).await;
+}
+
+#[tokio::test]
+#[test_log::test]
+pub async fn gnss_timestamps_as_timing_source() {
+    let event_bus = EventBus::default();
+    let config = LaptimerConfig {
+        timing_source: TimingSource::GnssTimestamp,
+        ..Default::default()
+    };
+    let mut rx = event_bus.subscribe();
+    let mut laptimer_handle = create_laptimer_with_config(
+        &event_bus,
+        ElapsedTestTimeSource::default(),
+        get_track(),
+        config,
+    );
+    wait_for_event(
+        &mut rx,
+        Duration::from_millis(100),
+        EventKindType::DetectTrackResponseEvent,
+    )
+    .await;
+
+    // Every marker is passed with positions 100ms apart, the pass is detected on the third
+    // one. The last sector is left towards the start/finish straight.
+    let crossings = [
+        (
+            0,
+            vec![
+                get_finishline_postion1(),
+                get_finishline_postion2(),
+                get_finishline_postion3(),
+                get_finishline_postion4(),
+            ],
+        ),
+        (
+            10_000,
+            vec![
+                get_sector1_postion1(),
+                get_sector1_postion2(),
+                get_sector1_postion3(),
+                get_sector1_postion4(),
+            ],
+        ),
+        (
+            20_130,
+            vec![
+                get_sector2_postion1(),
+                get_sector2_postion2(),
+                get_sector2_postion3(),
+                get_sector2_postion4(),
+                get_finishline_approach_postion(),
+            ],
+        ),
+        (
+            30_270,
+            vec![
+                get_finishline_postion1(),
+                get_finishline_postion2(),
+                get_finishline_postion3(),
+                get_finishline_postion4(),
+            ],
+        ),
+    ];
+    for (start_ms, positions) in crossings {
+        for (index, pos) in positions.iter().enumerate() {
+            let time =
+                NaiveTime::MIN + chrono::Duration::milliseconds(start_ms + index as i64 * 100);
+            publish_position(
+                &event_bus,
+                &GnssPosition::new(
+                    pos.latitude(),
+                    pos.longitude(),
+                    pos.velocity(),
+                    &time,
+                    &pos.date(),
+                ),
+            );
+        }
+    }
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let mut sector_times = vec![];
+    let mut lap_times = vec![];
+    while let Ok(event) = rx.try_recv() {
+        match event.kind {
+            EventKind::SectorFinishedEvent(duration) => sector_times.push(*duration),
+            EventKind::LapFinishedEvent(lap) => lap_times.push(lap.lap_time),
+            _ => (),
+        }
+    }
+    stop_module(&event_bus, &mut laptimer_handle).await;
+
+    assert_eq!(
+        sector_times,
+        vec![
+            Duration::from_millis(10_000),
+            Duration::from_millis(10_130),
+            Duration::from_millis(10_140)
+        ]
+    );
+    assert_eq!(lap_times, vec![Duration::from_millis(30_270)]);
+}
+
+#[tokio::test]
+#[test_log::test]
+pub async fn abandon_lap_after_max_lap_duration() {
+    let event_bus = EventBus::default();
+    let elapsed_time_source = ElapsedTestTimeSource::default();
+    let elapsed_time_source_sender = elapsed_time_source.sender();
+    let config = LaptimerConfig {
+        max_lap_duration: Some(Duration::from_secs(300)),
+        ..Default::default()
+    };
+    let mut rx = event_bus.subscribe();
+    let mut laptimer_handle =
+        create_laptimer_with_config(&event_bus, elapsed_time_source, get_track(), config);
+    wait_for_event(
+        &mut rx,
+        Duration::from_millis(100),
+        EventKindType::DetectTrackResponseEvent,
+    )
+    .await;
+
+    let finishline_positions = [
+        get_finishline_postion1(),
+        get_finishline_postion2(),
+        get_finishline_postion3(),
+        get_finishline_postion4(),
+    ];
+    for pos in &finishline_positions {
+        publish_position(&event_bus, pos);
+    }
+    wait_for_event(
+        &mut rx,
+        Duration::from_millis(100),
+        EventKindType::LapStartedEvent,
+    )
+    .await;
+
+    set_elapsed_time(&elapsed_time_source_sender, &Duration::from_secs(299));
+    let event_types = received_event_types(&mut rx).await;
+    assert!(!event_types.contains(&EventKindType::LapAbandonedEvent));
+
+    set_elapsed_time(&elapsed_time_source_sender, &Duration::from_secs(301));
+    let event_types = received_event_types(&mut rx).await;
+    assert_eq!(
+        event_types
+            .iter()
+            .filter(|event_type| **event_type == EventKindType::LapAbandonedEvent)
+            .count(),
+        1
+    );
+
+    // The lap timer waits for the next start line crossing again.
+    for pos in &finishline_positions {
+        publish_position(&event_bus, pos);
+    }
+    let event_types = received_event_types(&mut rx).await;
+    assert!(event_types.contains(&EventKindType::LapStartedEvent));
+    assert!(!event_types.contains(&EventKindType::SectorFinishedEvent));
+
+    stop_module(&event_bus, &mut laptimer_handle).await;
+}
+
+#[tokio::test]
+#[test_log::test]
+pub async fn auto_stop_after_two_laps() {
+    let event_bus = EventBus::default();
+    let elapsed_time_source = ElapsedTestTimeSource::default();
+    let elapsed_time_source_sender = elapsed_time_source.sender();
+    let mut rx = event_bus.subscribe();
+    let laptimer_handle = create_laptimer(&event_bus, elapsed_time_source);
+    wait_for_event(
+        &mut rx,
+        Duration::from_millis(100),
+        EventKindType::DetectTrackResponseEvent,
+    )
+    .await;
+    let auto_stop_handle = tokio::spawn({
+        let mut auto_stop = AutoStopModule::new(event_bus.context(), 2);
+        async move { auto_stop.run().await }
+    });
+
+    let finishline = [
+        get_finishline_approach_postion(),
+        get_finishline_postion1(),
+        get_finishline_postion2(),
+        get_finishline_postion3(),
+        get_finishline_postion4(),
+    ];
+    let sector1 = [
+        get_sector1_postion1(),
+        get_sector1_postion2(),
+        get_sector1_postion3(),
+        get_sector1_postion4(),
+    ];
+    let sector2 = [
+        get_sector2_postion1(),
+        get_sector2_postion2(),
+        get_sector2_postion3(),
+        get_sector2_postion4(),
+    ];
+    let mut elapsed_ms = 0;
+    for marker in std::iter::once(&finishline[..])
+        .chain([&sector1[..], &sector2[..], &finishline[..]].repeat(2))
+    {
+        elapsed_ms += 10000;
+        set_elapsed_time(
+            &elapsed_time_source_sender,
+            &Duration::from_millis(elapsed_ms),
+        );
+        for position in marker {
+            publish_position(&event_bus, position);
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+
+    let (laptimer_result, auto_stop_result) = tokio::time::timeout(Duration::from_secs(1), async {
+        tokio::join!(laptimer_handle, auto_stop_handle)
+    })
+    .await
+    .expect("Modules not stopped after two laps");
+    assert_eq!(laptimer_result.unwrap(), Ok(()));
+    assert_eq!(auto_stop_result.unwrap(), Ok(()));
+    let mut lap_finished = 0;
+    while let Ok(event) = rx.try_recv() {
+        if let EventKind::LapFinishedEvent(_) = event.kind {
+            lap_finished += 1;
+        }
+    }
+    assert_eq!(lap_finished, 2);
+}
+
+/// Starts a lap on a circuit without sectors, returns close to the start line and
+/// crosses it again, and returns the emitted event types before and after the vehicle
+/// drove a longer distance and crossed the finish line again.
+async fn drive_sectorless_circuit(
+    config: LaptimerConfig,
+) -> (Vec<EventKindType>, Vec<EventKindType>) {
+    let event_bus = EventBus::default();
+    let track = Track {
+        sectors: vec![],
+        ..get_track()
+    };
+    let startline = track.startline;
+    let mut rx = event_bus.subscribe();
+    let mut laptimer_handle =
+        create_laptimer_with_config(&event_bus, ElapsedTestTimeSource::default(), track, config);
+    let finishline = [
+        get_finishline_postion1(),
+        get_finishline_postion2(),
+        get_finishline_postion3(),
+        get_finishline_postion4(),
+    ];
+    wait_for_event(
+        &mut rx,
+        Duration::from_millis(100),
+        EventKindType::DetectTrackResponseEvent,
+    )
+    .await;
+
+    for position in &finishline {
+        publish_position(&event_bus, position);
+    }
+    for meters in [50.0, 100.0, 50.0] {
+        publish_position(&event_bus, &get_position_north_of(&startline, meters));
+    }
+    for position in &finishline {
+        publish_position(&event_bus, position);
+    }
+    let short_drive = received_event_types(&mut rx).await;
+
+    for meters in [300.0, 600.0, 300.0] {
+        publish_position(&event_bus, &get_position_north_of(&startline, meters));
+    }
+    for position in &finishline {
+        publish_position(&event_bus, position);
+    }
+    let long_drive = received_event_types(&mut rx).await;
+
+    stop_module(&event_bus, &mut laptimer_handle).await;
+    (short_drive, long_drive)
+}
+
+#[tokio::test]
+#[test_log::test]
+pub async fn finish_trips_early_on_sectorless_circuit_without_arming() {
+    let (short_drive, _) = drive_sectorless_circuit(LaptimerConfig::default()).await;
+    assert!(short_drive.contains(&EventKindType::LapStartedEvent));
+    assert!(short_drive.contains(&EventKindType::LapFinishedEvent));
+}
+
+#[tokio::test]
+#[test_log::test]
+pub async fn finish_armed_after_min_distance_on_sectorless_circuit() {
+    let (short_drive, long_drive) = drive_sectorless_circuit(LaptimerConfig {
+        finish_arming: FinishArming::MinDistance(500.0),
+        ..Default::default()
+    })
+    .await;
+    assert!(short_drive.contains(&EventKindType::LapStartedEvent));
+    assert!(!short_drive.contains(&EventKindType::LapFinishedEvent));
+    assert_eq!(
+        long_drive
+            .iter()
+            .filter(|event_type| **event_type == EventKindType::LapFinishedEvent)
+            .count(),
+        1
+    );
+    assert!(long_drive.contains(&EventKindType::LapStartedEvent));
+}
+
+/// Drives over the start line, forward or backward, and returns the received event types.
+async fn drive_over_startline(config: LaptimerConfig, backward: bool) -> Vec<EventKindType> {
+    let event_bus = EventBus::default();
+    let mut rx = event_bus.subscribe();
+    let mut laptimer_handle = create_laptimer_with_config(
+        &event_bus,
+        ElapsedTestTimeSource::default(),
+        get_track(),
+        config,
+    );
+    wait_for_event(
+        &mut rx,
+        Duration::from_millis(100),
+        EventKindType::DetectTrackResponseEvent,
+    )
+    .await;
+
+    let mut positions = vec![
+        get_finishline_postion1(),
+        get_finishline_postion2(),
+        get_finishline_postion3(),
+        get_finishline_postion4(),
+    ];
+    if backward {
+        positions.reverse();
+    }
+    for pos in positions {
+        publish_position(&event_bus, &pos);
+    }
+    let event_types = received_event_types(&mut rx).await;
+    stop_module(&event_bus, &mut laptimer_handle).await;
+    event_types
+}
+
+#[tokio::test]
+#[test_log::test]
+pub async fn backward_crossing_of_startline_is_ignored() {
+    let config = LaptimerConfig {
+        ignore_backward_crossings: true,
+        ..Default::default()
+    };
+    let event_types = drive_over_startline(config, true).await;
+    assert!(!event_types.contains(&EventKindType::LapStartedEvent));
+
+    let event_types = drive_over_startline(config, false).await;
+    assert!(event_types.contains(&EventKindType::LapStartedEvent));
+
+    // Without the option the direction of the crossing doesn't matter.
+    let event_types = drive_over_startline(LaptimerConfig::default(), true).await;
+    assert!(event_types.contains(&EventKindType::LapStartedEvent));
+}
+
+/// Drives at high speed along a straight point to point track with positions 80m apart,
+/// none of them within 25m of a marker, and returns the received event types.
+async fn drive_sparse_samples(config: LaptimerConfig) -> Vec<EventKindType> {
+    let event_bus = EventBus::default();
+    let origin = Position::new(&52.0, &11.0);
+    let north_of = |meters: f64| get_position_north_of(&origin, meters).to_position();
+    let track = Track {
+        name: "Straight".to_string(),
+        startline: origin,
+        finishline: Some(north_of(1000.0)),
+        sectors: vec![north_of(500.0)],
+        detection_radius_m: None,
+    };
+    let mut rx = event_bus.subscribe();
+    let mut laptimer_handle =
+        create_laptimer_with_config(&event_bus, ElapsedTestTimeSource::default(), track, config);
+    wait_for_event(
+        &mut rx,
+        Duration::from_millis(100),
+        EventKindType::DetectTrackResponseEvent,
+    )
+    .await;
+
+    // Slightly off the markers, so the positions pass them laterally offset.
+    let lane = Position::new(&52.0, &11.00005);
+    for meters in (-270..=1130).step_by(80) {
+        publish_position(&event_bus, &get_position_north_of(&lane, meters as f64));
+    }
+    let event_types = received_event_types(&mut rx).await;
+    stop_module(&event_bus, &mut laptimer_handle).await;
+    event_types
+}
+
+#[tokio::test]
+#[test_log::test]
+pub async fn segment_crossing_detects_markers_between_sparse_samples() {
+    let config = LaptimerConfig {
+        crossing_detection: CrossingDetection::LineSegment { half_width: 15.0 },
+        ..Default::default()
+    };
+    let lap_events: Vec<EventKindType> = drive_sparse_samples(config)
+        .await
+        .into_iter()
+        .filter(|event_type| {
+            matches!(
+                event_type,
+                EventKindType::LapStartedEvent
+                    | EventKindType::SectorFinishedEvent
+                    | EventKindType::LapFinishedEvent
+            )
+        })
+        .collect();
+    assert_eq!(
+        lap_events,
+        vec![
+            EventKindType::LapStartedEvent,
+            EventKindType::SectorFinishedEvent,
+            EventKindType::SectorFinishedEvent,
+            EventKindType::LapFinishedEvent,
+            // Tracks with sectors start the next lap right at the finish line.
+            EventKindType::LapStartedEvent,
+        ]
+    );
+
+    // The proximity detection misses the markers between the samples.
+    let config = LaptimerConfig {
+        crossing_detection: CrossingDetection::Proximity,
+        ..Default::default()
+    };
+    let event_types = drive_sparse_samples(config).await;
+    assert!(!event_types.contains(&EventKindType::LapStartedEvent));
+}
+
+#[tokio::test]
+#[test_log::test]
+pub async fn segment_crossing_detects_start_after_two_positions() {
+    let event_bus = EventBus::default();
+    let origin = Position::new(&52.0, &11.0);
+    let track = Track {
+        name: "Straight".to_string(),
+        startline: origin,
+        finishline: None,
+        sectors: vec![],
+        detection_radius_m: None,
+    };
+    let config = LaptimerConfig {
+        crossing_detection: CrossingDetection::LineSegment { half_width: 15.0 },
+        ..Default::default()
+    };
+    let mut rx = event_bus.subscribe();
+    let mut laptimer_handle =
+        create_laptimer_with_config(&event_bus, ElapsedTestTimeSource::default(), track, config);
+    wait_for_event(
+        &mut rx,
+        Duration::from_millis(100),
+        EventKindType::DetectTrackResponseEvent,
+    )
+    .await;
+
+    for meters in [-40.0, 40.0] {
+        publish_position(&event_bus, &get_position_north_of(&origin, meters));
+    }
+    let event_types = received_event_types(&mut rx).await;
+    assert!(event_types.contains(&EventKindType::LapStartedEvent));
+
+    stop_module(&event_bus, &mut laptimer_handle).await;
+}
+
+#[tokio::test]
+#[test_log::test]
+pub async fn segment_crossing_in_local_frame_detects_markers() {
+    for half_width in [15.0, 2.0] {
+        let config = LaptimerConfig {
+            crossing_detection: CrossingDetection::LineSegment { half_width },
+            local_frame: true,
+            ..Default::default()
+        };
+        let event_types = drive_sparse_samples(config).await;
+        assert_eq!(
+            event_types.contains(&EventKindType::LapFinishedEvent),
+            half_width == 15.0
+        );
+    }
+}
+
+#[tokio::test]
+#[test_log::test]
+pub async fn segment_crossing_ignores_markers_beside_the_path() {
+    let config = LaptimerConfig {
+        crossing_detection: CrossingDetection::LineSegment { half_width: 2.0 },
+        ..Default::default()
+    };
+    let event_types = drive_sparse_samples(config).await;
+    assert!(!event_types.contains(&EventKindType::LapStartedEvent));
+}
+
+#[tokio::test]
+#[test_log::test]
+pub async fn marker_not_detected_outside_of_detection_range() {
+    for (range, expected_passes) in [(25.0, 1), (5.0, 0)] {
+        let event_bus = EventBus::default();
+        let marker = get_track().startline;
+        let track = Track {
+            name: "Marker".to_string(),
+            startline: marker,
+            finishline: None,
+            sectors: vec![get_track().sectors[0]],
+            detection_radius_m: None,
+        };
+        let mut rx = event_bus.subscribe();
+        let laptimer = SimpleLaptimer::new_with_detection_range(
+            ElapsedTestTimeSource::default(),
+            range,
+            event_bus.context(),
+        );
+        let mut laptimer_handle = spawn_laptimer(&event_bus, laptimer, track);
+        wait_for_event(
+            &mut rx,
+            Duration::from_millis(100),
+            EventKindType::DetectTrackResponseEvent,
+        )
+        .await;
+
+        // Drives from west to east, passing the marker 20m to the north.
+        let passing_point = get_position_north_of(&marker, 20.0);
+        for east in [-6.0, -3.0, 0.0, 3.0, 6.0] {
+            let longitude = passing_point.longitude()
+                + east / (111300.0 * passing_point.latitude().to_radians().cos());
+            publish_position(
+                &event_bus,
+                &GnssPosition::new(
+                    passing_point.latitude(),
+                    longitude,
+                    0.0,
+                    &passing_point.time(),
+                    &passing_point.date(),
+                ),
+            );
+        }
+        let passes = received_event_types(&mut rx)
+            .await
+            .into_iter()
+            .filter(|event_type| *event_type == EventKindType::LapStartedEvent)
+            .count();
+        stop_module(&event_bus, &mut laptimer_handle).await;
+        assert_eq!(passes, expected_passes, "range {range}");
+    }
+}
+
+#[tokio::test]
+#[test_log::test]
+pub async fn new_best_lap_only_on_improvements() {
+    let event_bus = EventBus::default();
+    let elapsed_time_source = ElapsedTestTimeSource::default();
+    let elapsed_time_source_sender = elapsed_time_source.sender();
+    let mut best_laps_rx = event_bus.subscribe();
+    let mut laptimer_handle = create_laptimer(&event_bus, elapsed_time_source);
+    wait_for_event(
+        &mut best_laps_rx,
+        Duration::from_millis(100),
+        EventKindType::DetectTrackResponseEvent,
+    )
+    .await;
+
+    let mut rx = event_bus.subscribe();
+    publish_position(&event_bus, &get_finishline_postion1());
+    publish_position(&event_bus, &get_finishline_postion2());
+    publish_position(&event_bus, &get_finishline_postion3());
+    publish_position(&event_bus, &get_finishline_postion4());
+    wait_for_event(
+        &mut rx,
+        Duration::from_millis(100),
+        EventKindType::LapStartedEvent,
+    )
+    .await;
+
+    for seconds in [60, 58, 59, 55] {
+        let mut rx = event_bus.subscribe();
+        set_elapsed_time(&elapsed_time_source_sender, &Duration::from_secs(seconds));
+        // The first four positions of the whole lap are the start of the lap.
+        for pos in whole_lap_positions().iter().skip(4) {
+            publish_position(&event_bus, pos);
+        }
+        // The next lap time must only be set after this lap finished.
+        assert!(
+            received_event_types(&mut rx)
+                .await
+                .contains(&EventKindType::LapFinishedEvent)
+        );
+    }
+
+    let mut best_laps = vec![];
+    while let Ok(event) = best_laps_rx.try_recv() {
+        if let EventKind::NewBestLapEvent(best_lap) = event.kind {
+            best_laps.push(*best_lap);
+        }
+    }
+    assert_eq!(
+        best_laps,
+        vec![
+            BestLap {
+                lap_time: Duration::from_secs(60),
+                delta: None,
+            },
+            BestLap {
+                lap_time: Duration::from_secs(58),
+                delta: Some(Duration::from_secs(2)),
+            },
+            BestLap {
+                lap_time: Duration::from_secs(55),
+                delta: Some(Duration::from_secs(3)),
+            },
+        ]
+    );
+
+    stop_module(&event_bus, &mut laptimer_handle).await;
+}
+
+/// Starts a lap and drives through the first sector with the given gap before the first
+/// position past the sector, the time must be paused. Returns the events received after the lap start.
+async fn drive_sector_with_position_gap(
+    config: LaptimerConfig,
+    gap: Duration,
+) -> Vec<EventKindType> {
+    let event_bus = EventBus::default();
+    let mut rx = event_bus.subscribe();
+    let mut laptimer_handle = create_laptimer_with_config(
+        &event_bus,
+        ElapsedTestTimeSource::default(),
+        get_track(),
+        config,
+    );
+    wait_for_event(
+        &mut rx,
+        Duration::from_millis(100),
+        EventKindType::DetectTrackResponseEvent,
+    )
+    .await;
+
+    publish_position(&event_bus, &get_finishline_postion1());
+    publish_position(&event_bus, &get_finishline_postion2());
+    publish_position(&event_bus, &get_finishline_postion3());
+    publish_position(&event_bus, &get_finishline_postion4());
+    wait_for_event(
+        &mut rx,
+        Duration::from_millis(100),
+        EventKindType::LapStartedEvent,
+    )
+    .await;
+
+    let mut rx = event_bus.subscribe();
+    publish_position(&event_bus, &get_sector1_postion1());
+    publish_position(&event_bus, &get_sector1_postion2());
+    // The state response confirms that the positions before the gap are processed.
+    let mut state_rx = event_bus.subscribe();
+    event_bus.publish(&Event {
+        kind: EventKind::LaptimerStateRequestEvent(module_core::Request::empty_request(1, 2)),
+    });
+    wait_for_event(
+        &mut state_rx,
+        Duration::from_millis(100),
+        EventKindType::LaptimerStateResponseEvent,
+    )
+    .await;
+    tokio::time::advance(gap).await;
+    publish_position(&event_bus, &get_sector1_postion3());
+    publish_position(&event_bus, &get_sector1_postion4());
+    let event_types = received_event_types(&mut rx).await;
+
+    stop_module(&event_bus, &mut laptimer_handle).await;
+    event_types
+}
+
+#[tokio::test]
+#[test_log::test]
+pub async fn no_sector_detected_across_position_gap() {
+    tokio::time::pause();
+    let event_types = drive_sector_with_position_gap(
+        LaptimerConfig {
+            max_position_gap: Some(Duration::from_millis(200)),
+            ..Default::default()
+        },
+        Duration::from_millis(300),
+    )
+    .await;
+    assert!(!event_types.contains(&EventKindType::SectorFinishedEvent));
+
+    let event_types =
+        drive_sector_with_position_gap(LaptimerConfig::default(), Duration::from_secs(4)).await;
+    assert!(!event_types.contains(&EventKindType::SectorFinishedEvent));
+
+    let event_types =
+        drive_sector_with_position_gap(LaptimerConfig::default(), Duration::from_secs(2)).await;
+    assert!(event_types.contains(&EventKindType::SectorFinishedEvent));
+
+    let event_types = drive_sector_with_position_gap(
+        LaptimerConfig {
+            max_position_gap: None,
+            ..Default::default()
+        },
+        Duration::from_millis(300),
+    )
+    .await;
+    assert!(event_types.contains(&EventKindType::SectorFinishedEvent));
+}
+
+#[tokio::test]
+#[test_log::test]
+pub async fn lap_timed_on_point_to_point_track_without_sectors() {
+    let event_bus = EventBus::default();
+    let origin = Position::new(&52.0, &11.0);
+    let north_of = |meters: f64| get_position_north_of(&origin, meters);
+    let track = Track {
+        name: "Straight".to_string(),
+        startline: origin,
+        finishline: Some(north_of(1000.0).to_position()),
+        sectors: vec![],
+        detection_radius_m: None,
+    };
+    let mut rx = event_bus.subscribe();
+    let mut laptimer_handle = create_laptimer_with_config(
+        &event_bus,
+        ElapsedTestTimeSource::default(),
+        track,
+        LaptimerConfig::default(),
+    );
+    wait_for_event(
+        &mut rx,
+        Duration::from_millis(100),
+        EventKindType::DetectTrackResponseEvent,
+    )
+    .await;
+
+    let over_start = [-18.0, -6.0, 4.0, 16.0];
+    let to_finish = (1..25).map(|step| step as f64 * 40.0);
+    let over_finish = over_start.iter().map(|meters| 1000.0 + meters);
+    // Turning around behind the finish line and crossing it again.
+    let back_over_finish = [1040.0, 1016.0, 1004.0, 994.0, 982.0, 960.0];
+    for meters in over_start
+        .into_iter()
+        .chain(to_finish)
+        .chain(over_finish)
+        .chain(back_over_finish)
+    {
+        publish_position(&event_bus, &north_of(meters));
+    }
+
+    let lap_events: Vec<EventKindType> = received_event_types(&mut rx)
+        .await
+        .into_iter()
+        .filter(|event_type| {
+            matches!(
+                event_type,
+                EventKindType::LapStartedEvent | EventKindType::LapFinishedEvent
+            )
+        })
+        .collect();
+    assert_eq!(
+        lap_events,
+        vec![
+            EventKindType::LapStartedEvent,
+            EventKindType::LapFinishedEvent
+        ]
+    );
+
+    stop_module(&event_bus, &mut laptimer_handle).await;
+}
+
+/// Drives along the given path on a straight point to point track with its start line at the
+/// origin, a sector 500m and the finish line 1000m north of it. The path is given as meters
+/// north and east of the origin, the received event types are returned.
+async fn drive_on_straight_track(
+    config: LaptimerConfig,
+    path: &[(f64, f64)],
+) -> Vec<EventKindType> {
+    let event_bus = EventBus::default();
+    let origin = Position::new(&52.0, &11.0);
+    let north_of = |meters: f64| get_position_north_of(&origin, meters).to_position();
+    let track = Track {
+        name: "Straight".to_string(),
+        startline: origin,
+        finishline: Some(north_of(1000.0)),
+        sectors: vec![north_of(500.0)],
+        detection_radius_m: None,
+    };
+    let mut rx = event_bus.subscribe();
+    let mut laptimer_handle =
+        create_laptimer_with_config(&event_bus, ElapsedTestTimeSource::default(), track, config);
+    wait_for_event(
+        &mut rx,
+        Duration::from_millis(100),
+        EventKindType::DetectTrackResponseEvent,
+    )
+    .await;
+
+    let meters_per_degree_longitude = 111300.0 * origin.latitude.to_radians().cos();
+    for (north, east) in path {
+        let mut pos = get_position_north_of(&origin, *north);
+        pos = GnssPosition::new(
+            pos.latitude(),
+            origin.longitude + east / meters_per_degree_longitude,
+            pos.velocity(),
+            &pos.time(),
+            &pos.date(),
+        );
+        publish_position(&event_bus, &pos);
+    }
+    let event_types = received_event_types(&mut rx).await;
+    stop_module(&event_bus, &mut laptimer_handle).await;
+    event_types
+}
+
+#[tokio::test]
+#[test_log::test]
+pub async fn oblique_crossing_rejected_by_max_heading_deviation() {
+    // Crosses the start line with a heading of 60° to the driving direction of the track.
+    let (sin, cos) = 60f64.to_radians().sin_cos();
+    let path: Vec<(f64, f64)> = [-18.0, -6.0, 4.0, 16.0]
+        .iter()
+        .map(|meters| (meters * cos, meters * sin))
+        .collect();
+
+    let strict = LaptimerConfig {
+        ignore_backward_crossings: true,
+        max_heading_deviation: 45.0,
+        ..Default::default()
+    };
+    let event_types = drive_on_straight_track(strict, &path).await;
+    assert!(!event_types.contains(&EventKindType::LapStartedEvent));
+
+    let lenient = LaptimerConfig {
+        ignore_backward_crossings: true,
+        ..Default::default()
+    };
+    let event_types = drive_on_straight_track(lenient, &path).await;
+    assert!(event_types.contains(&EventKindType::LapStartedEvent));
+}
+
+#[tokio::test]
+#[test_log::test]
+pub async fn backward_crossing_of_sector_is_ignored() {
+    // Starts the lap, drives around the sector without passing it and reverses through it.
+    let path: Vec<(f64, f64)> = [-18.0, -6.0, 4.0, 16.0, 100.0, 300.0]
+        .into_iter()
+        .map(|north| (north, 0.0))
+        .chain([(520.0, 40.0)])
+        .chain([540.0, 516.0, 504.0, 494.0, 482.0, 440.0].map(|north| (north, 0.0)))
+        .collect();
+
+    let config = LaptimerConfig {
+        ignore_backward_crossings: true,
+        ..Default::default()
+    };
+    let event_types = drive_on_straight_track(config, &path).await;
+    assert!(event_types.contains(&EventKindType::LapStartedEvent));
+    assert!(!event_types.contains(&EventKindType::SectorFinishedEvent));
+
+    let event_types = drive_on_straight_track(LaptimerConfig::default(), &path).await;
+    assert!(event_types.contains(&EventKindType::SectorFinishedEvent));
+}
+
+#[tokio::test]
+#[test_log::test]
+pub async fn start_recovery_keeps_start_line_engaged_during_jitter() {
+    // Crosses the start line backward and jitters forward over it again without leaving
+    // the exit radius.
+    let path: Vec<(f64, f64)> = [16.0, 4.0, -6.0, -18.0, -30.0, -18.0, -6.0, 4.0, 16.0]
+        .map(|north| (north, 0.0))
+        .to_vec();
+    let config = LaptimerConfig {
+        exit_radius: 40.0,
+        start_recovery: true,
+        ignore_backward_crossings: true,
+        ..Default::default()
+    };
+    let event_types = drive_on_straight_track(config, &path).await;
+    assert!(!event_types.contains(&EventKindType::LapStartedEvent));
+    assert!(!event_types.contains(&EventKindType::LapStartEstimatedEvent));
+}
+
+#[test]
+fn declared_event_interface_contains_positions_and_finished_laps() {
+    let event_bus = EventBus::default();
+    let laptimer =
+        SimpleLaptimer::new_with_source(ElapsedTestTimeSource::default(), event_bus.context());
+
+    let interface = laptimer.event_interface();
+
+    assert!(interface.consumes(EventKindType::GnssPositionEvent));
+    assert!(interface.produces(EventKindType::LapFinishedEvent));
+    assert!(!interface.produces(EventKindType::GnssPositionEvent));
+}
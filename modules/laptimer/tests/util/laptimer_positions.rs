@@ -5,6 +5,18 @@
 use crate::*;
 use chrono::{NaiveDate, NaiveTime};
 
+/// Returns a position on the start/finish straight about 85m before the finish line, so a
+/// vehicle coming from the last sector approaches the finish line along the straight.
+pub fn get_finishline_approach_postion() -> common::position::GnssPosition {
+    GnssPosition::new(
+        52.0268156,
+        11.2815007,
+        0.0,
+        &NaiveTime::parse_from_str("00:00:00.000", "%H:%M:%S%.3f").unwrap(),
+        &NaiveDate::parse_from_str("01.01.1970", "%d.%m.%Y").unwrap(),
+    )
+}
+
 pub fn get_finishline_postion1() -> common::position::GnssPosition {
     GnssPosition::new(
         52.0270444,
@@ -124,3 +136,20 @@ pub fn get_sector2_postion4() -> common::position::GnssPosition {
         &NaiveDate::parse_from_str("01.01.1970", "%d.%m.%Y").unwrap(),
     )
 }
+
+/// Returns a position that lies `meters` north of the given marker.
+///
+/// The offset uses the same meter/degree ratio as `algorithm::calculate_distance`,
+/// so the distance between the marker and the returned position is `meters`.
+pub fn get_position_north_of(
+    marker: &common::position::Position,
+    meters: f64,
+) -> common::position::GnssPosition {
+    GnssPosition::new(
+        marker.latitude + meters / 111300.0,
+        marker.longitude,
+        0.0,
+        &NaiveTime::parse_from_str("00:00:00.000", "%H:%M:%S%.3f").unwrap(),
+        &NaiveDate::parse_from_str("01.01.1970", "%d.%m.%Y").unwrap(),
+    )
+}
@@ -0,0 +1,97 @@
+// SPDX-FileCopyrightText: 2026 All contributors
+//
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use crate::CorsConfig;
+use rocket::{
+    Request, Response,
+    fairing::{Fairing, Info, Kind},
+    http::{Header, Method, Status},
+};
+use std::io::Cursor;
+
+/// Time in seconds browsers may cache the result of a preflight request.
+const PREFLIGHT_MAX_AGE: u32 = 3600;
+
+/// Fairing that adds the CORS headers for the allowed origins to the responses.
+///
+/// Preflight requests of allowed origins are answered with `204 No Content`, so no
+/// `OPTIONS` routes are needed.
+pub(crate) struct Cors {
+    config: CorsConfig,
+}
+
+impl Cors {
+    pub(crate) fn new(config: CorsConfig) -> Self {
+        Cors { config }
+    }
+
+    /// Returns `true` if the origin matches one of the allowed origins.
+    ///
+    /// `*` matches every origin and an allowed origin with the port `*` matches the origin
+    /// with any or without a port.
+    fn is_allowed(&self, origin: &str) -> bool {
+        self.config.allowed_origins.iter().any(|allowed| {
+            if allowed == "*" || allowed == origin {
+                return true;
+            }
+            match allowed.strip_suffix(":*") {
+                Some(base) => origin.strip_prefix(base).is_some_and(|port| {
+                    port.is_empty()
+                        || port
+                            .strip_prefix(':')
+                            .is_some_and(|port| port.parse::<u16>().is_ok())
+                }),
+                None => false,
+            }
+        })
+    }
+}
+
+#[rocket::async_trait]
+impl Fairing for Cors {
+    fn info(&self) -> Info {
+        Info {
+            name: "CORS",
+            kind: Kind::Response,
+        }
+    }
+
+    async fn on_response<'r>(&self, req: &'r Request<'_>, res: &mut Response<'r>) {
+        let Some(origin) = req.headers().get_one("Origin") else {
+            return;
+        };
+        if !self.is_allowed(origin) {
+            debug!("Rejected CORS request of origin {}", origin);
+            return;
+        }
+        res.set_header(Header::new(
+            "Access-Control-Allow-Origin",
+            origin.to_string(),
+        ));
+        res.set_header(Header::new("Vary", "Origin"));
+        let preflight = req.method() == Method::Options
+            && req
+                .headers()
+                .get_one("Access-Control-Request-Method")
+                .is_some();
+        if preflight {
+            res.set_header(Header::new(
+                "Access-Control-Allow-Methods",
+                self.config.allowed_methods.join(", "),
+            ));
+            if let Some(headers) = req.headers().get_one("Access-Control-Request-Headers") {
+                res.set_header(Header::new(
+                    "Access-Control-Allow-Headers",
+                    headers.to_string(),
+                ));
+            }
+            res.set_header(Header::new(
+                "Access-Control-Max-Age",
+                PREFLIGHT_MAX_AGE.to_string(),
+            ));
+            res.set_status(Status::NoContent);
+            res.set_sized_body(0, Cursor::new(""));
+        }
+    }
+}
@@ -0,0 +1,99 @@
+// SPDX-FileCopyrightText: 2026 All contributors
+//
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use chrono::{DateTime, Utc};
+use module_core::{HEARTBEAT_INTERVAL, ModuleHeartbeat, SaveFailure};
+use rocket::{
+    State,
+    serde::{Serialize, json::Json},
+};
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
+/// Number of missed heartbeats after which a module is no longer reported as alive.
+const MISSED_HEARTBEATS: u32 = 3;
+
+/// Last-seen activity of the modules, collected from their heartbeats, and the last failed
+/// save of a session.
+#[derive(Debug, Default)]
+pub(crate) struct ModuleActivity {
+    last_seen: HashMap<String, DateTime<Utc>>,
+    last_save_failure: Option<SaveFailureStatus>,
+}
+
+impl ModuleActivity {
+    /// Records the heartbeat of a module, replacing its previous one.
+    pub(crate) fn record(&mut self, heartbeat: &ModuleHeartbeat) {
+        self.last_seen
+            .insert(heartbeat.name.clone(), heartbeat.timestamp);
+    }
+
+    /// Records a failed save of a session, replacing the previous one.
+    pub(crate) fn record_save_failure(&mut self, failure: &SaveFailure) {
+        self.last_save_failure = Some(SaveFailureStatus {
+            session_id: failure.session_id.to_string(),
+            error: failure.error.to_string(),
+            timestamp: Utc::now(),
+        });
+    }
+}
+
+/// A failed save of a session reported by the storage.
+#[derive(Clone, Debug, Serialize)]
+#[serde(crate = "rocket::serde")]
+struct SaveFailureStatus {
+    session_id: String,
+    error: String,
+    timestamp: DateTime<Utc>,
+}
+
+/// Activity of a single module.
+#[derive(Debug, Serialize)]
+#[serde(crate = "rocket::serde")]
+struct ModuleStatus {
+    name: String,
+    last_seen: DateTime<Utc>,
+    alive: bool,
+}
+
+/// Response structure for the module diagnostics.
+#[derive(Debug, Serialize)]
+#[serde(crate = "rocket::serde")]
+pub(crate) struct ModulesResponse {
+    modules: Vec<ModuleStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_save_failure: Option<SaveFailureStatus>,
+}
+
+/// Lists the modules that published a heartbeat, sorted by name.
+///
+/// Route: GET /v1/diag/modules
+///
+/// A module is reported as alive if its last heartbeat is not older than
+/// three heartbeat intervals. The last failed save of a session is reported if
+/// there was one since the start.
+#[get("/v1/diag/modules")]
+pub(crate) fn get_modules(activity: &State<Arc<RwLock<ModuleActivity>>>) -> Json<ModulesResponse> {
+    let activity = activity
+        .read()
+        .unwrap_or_else(|activity| activity.into_inner());
+    let now = Utc::now();
+    let timeout = HEARTBEAT_INTERVAL * MISSED_HEARTBEATS;
+    let mut modules: Vec<ModuleStatus> = activity
+        .last_seen
+        .iter()
+        .map(|(name, last_seen)| ModuleStatus {
+            name: name.clone(),
+            last_seen: *last_seen,
+            alive: !(now - *last_seen).to_std().is_ok_and(|age| age > timeout),
+        })
+        .collect();
+    modules.sort_by(|a, b| a.name.cmp(&b.name));
+    Json(ModulesResponse {
+        modules,
+        last_save_failure: activity.last_save_failure.clone(),
+    })
+}
@@ -0,0 +1,65 @@
+// SPDX-FileCopyrightText: 2026 All contributors
+//
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use crate::{RestCtx, lap_export::load_session};
+use algorithm::cumulative_distances;
+use common::lap::Lap;
+use rocket::{
+    State,
+    http::Status,
+    serde::{Serialize, json::Json},
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// A point of the elevation profile of a lap.
+#[derive(Debug, Serialize)]
+#[serde(crate = "rocket::serde")]
+pub(crate) struct ElevationPoint {
+    /// Distance in meters along the lap from its first log point.
+    distance_m: f64,
+    /// Altitude in meters above mean sea level.
+    altitude_m: f64,
+}
+
+/// Returns the elevation profile of the log points of a lap.
+///
+/// The distance is accumulated over all log points, log points without altitude are left out.
+fn elevation_profile(lap: &Lap) -> Vec<ElevationPoint> {
+    cumulative_distances(&lap.log_points)
+        .into_iter()
+        .zip(&lap.log_points)
+        .filter_map(|(distance_m, point)| {
+            point.altitude().map(|altitude_m| ElevationPoint {
+                distance_m,
+                altitude_m,
+            })
+        })
+        .collect()
+}
+
+/// Returns the elevation profile of a lap, i.e. the altitude over the driven distance.
+///
+/// Route: GET /v1/sessions/<id>/laps/<lap>/elevation
+///
+/// Errors:
+/// - 404 if the session or the lap doesn't exist.
+/// - 500 if the session can't be loaded.
+#[get("/v1/sessions/<id>/laps/<lap>/elevation")]
+pub(crate) async fn get_lap_elevation(
+    id: &str,
+    lap: usize,
+    ctx: &State<Arc<Mutex<RestCtx>>>,
+) -> Result<Json<Vec<ElevationPoint>>, Status> {
+    let session = load_session(id, ctx).await?;
+    let session = session.read().map_err(|e| {
+        error!("Failed to acquire read lock on session {}: {}", id, e);
+        Status::InternalServerError
+    })?;
+    session
+        .laps
+        .get(lap)
+        .map(|lap| Json(elevation_profile(lap)))
+        .ok_or(Status::NotFound)
+}
@@ -0,0 +1,97 @@
+// SPDX-FileCopyrightText: 2026 All contributors
+//
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use rocket::{
+    Request,
+    http::Status,
+    response::{self, Responder},
+    serde::{Serialize, json::Json},
+};
+
+/// Machine-readable error of a failed request.
+#[derive(Debug, Serialize)]
+#[serde(crate = "rocket::serde")]
+struct ErrorDetail {
+    /// Snake case name of the HTTP status, e.g. `not_found`.
+    code: String,
+    /// Human-readable description of the error.
+    message: String,
+}
+
+/// Envelope of the body of every 4xx and 5xx response.
+#[derive(Debug, Serialize)]
+#[serde(crate = "rocket::serde")]
+struct ErrorBody {
+    error: ErrorDetail,
+}
+
+/// An error response with a JSON body of the form
+/// `{"error": {"code": "not_found", "message": "..."}}`.
+#[derive(Debug)]
+pub(crate) struct ApiError {
+    status: Status,
+    message: String,
+}
+
+impl ApiError {
+    /// Creates an error response with the given status and message.
+    pub(crate) fn new(status: Status, message: impl Into<String>) -> Self {
+        ApiError {
+            status,
+            message: message.into(),
+        }
+    }
+
+    /// Creates the error response of a failed storage request about the given subject,
+    /// e.g. `session foo`, see [`storage_error_status`] for the status.
+    pub(crate) fn storage(e: std::io::ErrorKind, subject: &str) -> Self {
+        let message = match e {
+            std::io::ErrorKind::NotFound => format!("{subject} not found"),
+            std::io::ErrorKind::TimedOut => format!("storage did not respond for {subject}"),
+            _ => format!("failed to load {subject}: {e}"),
+        };
+        ApiError::new(storage_error_status(e), message)
+    }
+}
+
+impl<'r> Responder<'r, 'static> for ApiError {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
+        let body = ErrorBody {
+            error: ErrorDetail {
+                code: error_code(self.status),
+                message: self.message,
+            },
+        };
+        response::Response::build_from(Json(body).respond_to(req)?)
+            .status(self.status)
+            .ok()
+    }
+}
+
+/// Maps a storage error to the HTTP status of the response.
+///
+/// A missing entry is reported as 404 and a storage that didn't respond in time as 503.
+pub(crate) fn storage_error_status(e: std::io::ErrorKind) -> Status {
+    match e {
+        std::io::ErrorKind::NotFound => Status::NotFound,
+        std::io::ErrorKind::TimedOut => Status::ServiceUnavailable,
+        _ => Status::InternalServerError,
+    }
+}
+
+/// Returns the snake case name of the status, e.g. `service_unavailable`.
+fn error_code(status: Status) -> String {
+    status
+        .reason()
+        .map_or_else(|| status.code.to_string(), |reason| reason.to_lowercase())
+        .replace([' ', '-'], "_")
+        .replace('\'', "")
+}
+
+/// Wraps every error status without a body, e.g. of routes returning a bare [`Status`] or
+/// `None`, in the JSON error envelope.
+#[catch(default)]
+pub(crate) fn default_catcher(status: Status, _req: &Request) -> ApiError {
+    ApiError::new(status, status.reason().unwrap_or("Unknown Error"))
+}
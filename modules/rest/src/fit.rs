@@ -0,0 +1,224 @@
+// SPDX-FileCopyrightText: 2026 All contributors
+//
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+//! A minimal encoder for the Garmin FIT file format.
+//!
+//! Only the messages needed to hand a session to common analysis tools are written:
+//! a `file_id` message, one `record` message per log point and one `lap` message per lap.
+
+use crate::{RestCtx, lap_export::load_session};
+use chrono::{NaiveDateTime, TimeDelta};
+use common::session::Session;
+use rocket::{
+    State,
+    http::{ContentType, Status},
+};
+use std::{sync::Arc, time::Duration};
+use tokio::sync::Mutex;
+
+/// Size of the FIT file header in bytes.
+const HEADER_SIZE: u8 = 14;
+
+/// FIT protocol version 2.0.
+const PROTOCOL_VERSION: u8 = 0x20;
+
+/// FIT profile version 21.40.
+const PROFILE_VERSION: u16 = 2140;
+
+/// Seconds between the UNIX epoch and the FIT epoch (1989-12-31T00:00:00Z).
+const FIT_EPOCH_OFFSET: i64 = 631_065_600;
+
+/// Global message numbers of the FIT profile.
+const MESG_FILE_ID: u16 = 0;
+const MESG_LAP: u16 = 19;
+const MESG_RECORD: u16 = 20;
+
+/// Local message types used for the definitions in the produced file.
+const LOCAL_FILE_ID: u8 = 0;
+const LOCAL_RECORD: u8 = 1;
+const LOCAL_LAP: u8 = 2;
+
+/// FIT base types.
+const BASE_ENUM: u8 = 0x00;
+const BASE_UINT16: u8 = 0x84;
+const BASE_SINT32: u8 = 0x85;
+const BASE_UINT32: u8 = 0x86;
+
+/// `file` enum value of an activity file.
+const FILE_TYPE_ACTIVITY: u8 = 4;
+
+/// `manufacturer` value reserved for development.
+const MANUFACTURER_DEVELOPMENT: u16 = 255;
+
+/// Nibble lookup table of the FIT CRC-16.
+const CRC_TABLE: [u16; 16] = [
+    0x0000, 0xCC01, 0xD801, 0x1400, 0xF001, 0x3C00, 0x2800, 0xE401, 0xA001, 0x6C00, 0x7800, 0xB401,
+    0x5000, 0x9C01, 0x8801, 0x4400,
+];
+
+/// Calculates the FIT CRC-16 of `bytes`.
+fn crc16(bytes: &[u8]) -> u16 {
+    bytes.iter().fold(0, |mut crc, byte| {
+        for nibble in [byte & 0x0F, byte >> 4] {
+            let tmp = CRC_TABLE[(crc & 0x0F) as usize];
+            crc = (crc >> 4) & 0x0FFF;
+            crc = crc ^ tmp ^ CRC_TABLE[nibble as usize];
+        }
+        crc
+    })
+}
+
+/// Converts a timestamp into seconds since the FIT epoch.
+///
+/// Timestamps before the FIT epoch are clamped to the epoch itself.
+fn fit_timestamp(timestamp: NaiveDateTime) -> u32 {
+    let seconds = timestamp.and_utc().timestamp() - FIT_EPOCH_OFFSET;
+    seconds.clamp(0, u32::MAX as i64) as u32
+}
+
+/// Converts degrees into FIT semicircles.
+fn semicircles(degrees: f64) -> i32 {
+    (degrees * (2f64.powi(31) / 180.0)).round() as i32
+}
+
+/// Converts a duration into the FIT representation with a scale of 1000.
+fn fit_millis(duration: Duration) -> u32 {
+    duration.as_millis().min(u32::MAX as u128) as u32
+}
+
+/// Writes the data records of a FIT file.
+struct FitWriter {
+    data: Vec<u8>,
+}
+
+impl FitWriter {
+    fn new() -> Self {
+        FitWriter { data: Vec::new() }
+    }
+
+    /// Writes a definition message for `global` using the local message type `local`.
+    ///
+    /// Each field is given as `(field number, size, base type)`, all fields are little endian.
+    fn define(&mut self, local: u8, global: u16, fields: &[(u8, u8, u8)]) {
+        self.data.push(0x40 | local);
+        self.data.push(0); // reserved
+        self.data.push(0); // little endian
+        self.data.extend_from_slice(&global.to_le_bytes());
+        self.data.push(fields.len() as u8);
+        for (number, size, base_type) in fields {
+            self.data.extend_from_slice(&[*number, *size, *base_type]);
+        }
+    }
+
+    /// Starts a data message of the local message type `local`.
+    fn message(&mut self, local: u8) -> &mut Vec<u8> {
+        self.data.push(local);
+        &mut self.data
+    }
+
+    /// Returns the complete file, i.e. header, data records and file CRC.
+    fn finish(self) -> Vec<u8> {
+        let mut file = Vec::with_capacity(HEADER_SIZE as usize + self.data.len() + 2);
+        file.push(HEADER_SIZE);
+        file.push(PROTOCOL_VERSION);
+        file.extend_from_slice(&PROFILE_VERSION.to_le_bytes());
+        file.extend_from_slice(&(self.data.len() as u32).to_le_bytes());
+        file.extend_from_slice(b".FIT");
+        let header_crc = crc16(&file);
+        file.extend_from_slice(&header_crc.to_le_bytes());
+        file.extend_from_slice(&self.data);
+        let file_crc = crc16(&file);
+        file.extend_from_slice(&file_crc.to_le_bytes());
+        file
+    }
+}
+
+/// Encodes a session as FIT activity file.
+///
+/// Every log point becomes a `record` message with position, speed and timestamp. Every lap
+/// becomes a `lap` message, its start is the session start plus the time of all previous laps.
+pub(crate) fn encode_session(session: &Session) -> Vec<u8> {
+    let session_start = NaiveDateTime::new(session.date, session.time);
+    let mut writer = FitWriter::new();
+
+    writer.define(
+        LOCAL_FILE_ID,
+        MESG_FILE_ID,
+        &[(0, 1, BASE_ENUM), (1, 2, BASE_UINT16), (4, 4, BASE_UINT32)],
+    );
+    let msg = writer.message(LOCAL_FILE_ID);
+    msg.push(FILE_TYPE_ACTIVITY);
+    msg.extend_from_slice(&MANUFACTURER_DEVELOPMENT.to_le_bytes());
+    msg.extend_from_slice(&fit_timestamp(session_start).to_le_bytes());
+
+    writer.define(
+        LOCAL_RECORD,
+        MESG_RECORD,
+        &[
+            (253, 4, BASE_UINT32),
+            (0, 4, BASE_SINT32),
+            (1, 4, BASE_SINT32),
+            (6, 2, BASE_UINT16),
+        ],
+    );
+    for point in session.laps.iter().flat_map(|lap| &lap.log_points) {
+        let speed = (point.velocity() * 1000.0)
+            .round()
+            .clamp(0.0, u16::MAX as f64) as u16;
+        let msg = writer.message(LOCAL_RECORD);
+        msg.extend_from_slice(&fit_timestamp(point.timestamp()).to_le_bytes());
+        msg.extend_from_slice(&semicircles(point.latitude()).to_le_bytes());
+        msg.extend_from_slice(&semicircles(point.longitude()).to_le_bytes());
+        msg.extend_from_slice(&speed.to_le_bytes());
+    }
+
+    writer.define(
+        LOCAL_LAP,
+        MESG_LAP,
+        &[
+            (254, 2, BASE_UINT16),
+            (253, 4, BASE_UINT32),
+            (2, 4, BASE_UINT32),
+            (7, 4, BASE_UINT32),
+            (8, 4, BASE_UINT32),
+        ],
+    );
+    let mut lap_start = session_start;
+    for (index, lap) in session.laps.iter().enumerate() {
+        let laptime: Duration = lap.sectors.iter().sum();
+        let lap_end = lap_start + TimeDelta::from_std(laptime).unwrap_or_default();
+        let msg = writer.message(LOCAL_LAP);
+        msg.extend_from_slice(&(index.min(u16::MAX as usize) as u16).to_le_bytes());
+        msg.extend_from_slice(&fit_timestamp(lap_end).to_le_bytes());
+        msg.extend_from_slice(&fit_timestamp(lap_start).to_le_bytes());
+        msg.extend_from_slice(&fit_millis(laptime).to_le_bytes());
+        msg.extend_from_slice(&fit_millis(laptime).to_le_bytes());
+        lap_start = lap_end;
+    }
+
+    writer.finish()
+}
+
+/// Returns a session as FIT activity file.
+///
+/// Route: GET /v1/sessions/<id>/fit
+///
+/// Errors:
+/// - 404 if the session doesn't exist.
+/// - 500 if the session can't be loaded.
+#[get("/v1/sessions/<id>/fit")]
+pub(crate) async fn get_session_fit(
+    id: &str,
+    ctx: &State<Arc<Mutex<RestCtx>>>,
+) -> Result<(ContentType, Vec<u8>), Status> {
+    let session = load_session(id, ctx).await?;
+    let session = session.read().map_err(|e| {
+        error!("Failed to acquire read lock on session {}: {}", id, e);
+        Status::InternalServerError
+    })?;
+    Ok((
+        ContentType::new("application", "vnd.ant.fit"),
+        encode_session(&session),
+    ))
+}
@@ -0,0 +1,71 @@
+// SPDX-FileCopyrightText: 2026 All contributors
+//
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use crate::{RestCtx, lap_export::load_session};
+use algorithm::align_laps;
+use common::position::GnssPosition;
+use rocket::{
+    State,
+    http::Status,
+    serde::{Serialize, json::Json},
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// A sample of lap `a` with the sample of lap `b` at the nearest distance.
+#[derive(Debug, Serialize)]
+#[serde(crate = "rocket::serde")]
+pub(crate) struct CompareSample {
+    /// Distance from the start of lap `a` in meters.
+    distance: f64,
+    a: GnssPosition,
+    b: Option<GnssPosition>,
+    /// Time delta of lap `b` to lap `a` in seconds, positive if lap `b` is slower.
+    delta: Option<f64>,
+}
+
+/// Response structure for the comparison of two laps.
+#[derive(Debug, Serialize)]
+#[serde(crate = "rocket::serde")]
+pub(crate) struct LapCompareResponse {
+    a: usize,
+    b: usize,
+    samples: Vec<CompareSample>,
+}
+
+/// Aligns two laps of a session by distance, e.g. to overlay their telemetry on a chart.
+///
+/// Route: GET /v1/sessions/<id>/laps/compare?<a>&<b>
+///
+/// Returns a sample for every log point of lap `a` with the log point of lap `b` at the
+/// nearest distance from the start of the lap and the time delta between both laps.
+///
+/// Errors:
+/// - 404 if the session or one of the laps doesn't exist.
+/// - 500 if the session can't be loaded.
+#[get("/v1/sessions/<id>/laps/compare?<a>&<b>")]
+pub(crate) async fn get_lap_compare(
+    id: &str,
+    a: usize,
+    b: usize,
+    ctx: &State<Arc<Mutex<RestCtx>>>,
+) -> Result<Json<LapCompareResponse>, Status> {
+    let session = load_session(id, ctx).await?;
+    let session = session.read().map_err(|e| {
+        error!("Failed to acquire read lock on session {}: {}", id, e);
+        Status::InternalServerError
+    })?;
+    let lap_a = &session.laps.get(a).ok_or(Status::NotFound)?.log_points;
+    let lap_b = &session.laps.get(b).ok_or(Status::NotFound)?.log_points;
+    let samples = align_laps(lap_a, lap_b)
+        .into_iter()
+        .map(|sample| CompareSample {
+            distance: sample.distance,
+            a: lap_a[sample.index],
+            b: sample.other_index.map(|index| lap_b[index]),
+            delta: sample.time_delta,
+        })
+        .collect();
+    Ok(Json(LapCompareResponse { a, b, samples }))
+}
@@ -0,0 +1,232 @@
+// SPDX-FileCopyrightText: 2026 All contributors
+//
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use crate::{RestCtx, error::storage_error_status, request_session};
+use common::{lap::Lap, position::GnssPosition, session::Session};
+use rocket::{
+    State,
+    futures::{Stream, StreamExt, stream},
+    http::{Accept, ContentType, Status},
+    response::stream::TextStream,
+    serde::json::Json,
+};
+use std::{
+    fmt::Write,
+    sync::{Arc, RwLock},
+};
+use tokio::sync::Mutex;
+
+/// Format of the timestamps in the exported lap files.
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.3fZ";
+
+/// The export formats of a lap.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum LapFormat {
+    Json,
+    Gpx,
+    Csv,
+}
+
+impl LapFormat {
+    /// Selects the format by the preferred media type of the `Accept` header.
+    ///
+    /// Defaults to JSON if no header is present or the preferred type is no export format.
+    fn from_accept(accept: Option<&Accept>) -> Self {
+        let Some(accept) = accept else {
+            return LapFormat::Json;
+        };
+        let preferred = accept.preferred().media_type();
+        if preferred.top() == "application" && preferred.sub() == "gpx+xml" {
+            LapFormat::Gpx
+        } else if preferred.top() == "text" && preferred.sub() == "csv" {
+            LapFormat::Csv
+        } else {
+            LapFormat::Json
+        }
+    }
+
+    fn content_type(self) -> ContentType {
+        match self {
+            LapFormat::Json => ContentType::JSON,
+            LapFormat::Gpx => ContentType::new("application", "gpx+xml"),
+            LapFormat::Csv => ContentType::CSV,
+        }
+    }
+}
+
+/// Escapes the XML special characters of a text.
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// Converts the log points of a lap into a GPX 1.1 track.
+///
+/// The track is named after the track embedded in the session and the lap number, so the
+/// export doesn't depend on the stored track, which may have been deleted in the meantime.
+pub(crate) fn lap_to_gpx(session: &Session, index: usize, lap: &Lap) -> String {
+    let mut gpx = String::new();
+    let _ = writeln!(gpx, r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+    let _ = writeln!(
+        gpx,
+        r#"<gpx version="1.1" creator="rapid" xmlns="http://www.topografix.com/GPX/1/1">"#
+    );
+    let _ = writeln!(gpx, "  <trk>");
+    let _ = writeln!(
+        gpx,
+        "    <name>{} Lap {}</name>",
+        escape_xml(&session.track.name),
+        index + 1
+    );
+    let _ = writeln!(gpx, "    <trkseg>");
+    for point in &lap.log_points {
+        let _ = writeln!(
+            gpx,
+            r#"      <trkpt lat="{}" lon="{}"><time>{}</time></trkpt>"#,
+            point.latitude(),
+            point.longitude(),
+            point.timestamp().format(TIMESTAMP_FORMAT)
+        );
+    }
+    let _ = writeln!(gpx, "    </trkseg>");
+    let _ = writeln!(gpx, "  </trk>");
+    let _ = writeln!(gpx, "</gpx>");
+    gpx
+}
+
+/// Converts the log points of a lap into CSV with a header line.
+pub(crate) fn lap_to_csv(lap: &Lap) -> String {
+    let mut csv = String::from("time,latitude,longitude,velocity\n");
+    for point in &lap.log_points {
+        let _ = writeln!(
+            csv,
+            "{},{},{},{}",
+            point.timestamp().format(TIMESTAMP_FORMAT),
+            point.latitude(),
+            point.longitude(),
+            point.velocity()
+        );
+    }
+    csv
+}
+
+/// Loads a session, mapping the storage errors with [`storage_error_status`].
+pub(crate) async fn load_session(
+    id: &str,
+    ctx: &Arc<Mutex<RestCtx>>,
+) -> Result<Arc<RwLock<Session>>, Status> {
+    request_session(id, ctx).await.map_err(|e| {
+        if e != std::io::ErrorKind::NotFound {
+            error!("Failed to load session {}: {:?}", id, e);
+        }
+        storage_error_status(e)
+    })
+}
+
+/// Exports a single lap of a session.
+///
+/// Route: GET /v1/sessions/<id>/laps/<index>
+///
+/// The format is negotiated with the `Accept` header: `application/gpx+xml` returns a
+/// GPX track, `text/csv` the log points as CSV and any other or no header the lap as JSON.
+///
+/// Errors:
+/// - 404 if the session or the lap doesn't exist.
+/// - 500 if the session can't be loaded or serialized.
+#[get("/v1/sessions/<id>/laps/<index>")]
+pub(crate) async fn get_lap(
+    id: &str,
+    index: usize,
+    accept: Option<&Accept>,
+    ctx: &State<Arc<Mutex<RestCtx>>>,
+) -> Result<(ContentType, String), Status> {
+    let session = load_session(id, ctx).await?;
+    let session = session.read().map_err(|e| {
+        error!("Failed to acquire read lock on session {}: {}", id, e);
+        Status::InternalServerError
+    })?;
+    let lap = session.laps.get(index).ok_or(Status::NotFound)?;
+    let format = LapFormat::from_accept(accept);
+    let body = match format {
+        LapFormat::Json => serde_json::to_string(lap).map_err(|e| {
+            error!("Failed to serialize lap {} of session {}: {}", index, id, e);
+            Status::InternalServerError
+        })?,
+        LapFormat::Gpx => lap_to_gpx(&session, index, lap),
+        LapFormat::Csv => lap_to_csv(lap),
+    };
+    Ok((format.content_type(), body))
+}
+
+/// Retrieves a single log point of a lap, e.g. for a timeline scrubber.
+///
+/// Route: GET /v1/sessions/<id>/laps/<lap>/points/<point_index>
+///
+/// Errors:
+/// - 404 if the session, the lap or the log point doesn't exist.
+/// - 500 if the session can't be loaded.
+#[get("/v1/sessions/<id>/laps/<lap>/points/<point_index>")]
+pub(crate) async fn get_lap_point(
+    id: &str,
+    lap: usize,
+    point_index: usize,
+    ctx: &State<Arc<Mutex<RestCtx>>>,
+) -> Result<Json<GnssPosition>, Status> {
+    let session = load_session(id, ctx).await?;
+    let session = session.read().map_err(|e| {
+        error!("Failed to acquire read lock on session {}: {}", id, e);
+        Status::InternalServerError
+    })?;
+    session
+        .laps
+        .get(lap)
+        .and_then(|lap| lap.log_points.get(point_index))
+        .map(|point| Json(*point))
+        .ok_or(Status::NotFound)
+}
+
+/// Streams the log points of a lap as newline-delimited JSON, one log point per line.
+///
+/// Route: GET /v1/sessions/<id>/laps/<lap>/points.ndjson
+///
+/// Lets clients process the log points incrementally instead of parsing the whole lap.
+///
+/// Errors:
+/// - 404 if the session or the lap doesn't exist.
+/// - 500 if the session can't be loaded.
+#[get("/v1/sessions/<id>/laps/<lap>/points.ndjson")]
+pub(crate) async fn get_lap_points_ndjson(
+    id: &str,
+    lap: usize,
+    ctx: &State<Arc<Mutex<RestCtx>>>,
+) -> Result<(ContentType, TextStream<impl Stream<Item = String>>), Status> {
+    let session = load_session(id, ctx).await?;
+    let log_points = {
+        let session = session.read().map_err(|e| {
+            error!("Failed to acquire read lock on session {}: {}", id, e);
+            Status::InternalServerError
+        })?;
+        session
+            .laps
+            .get(lap)
+            .map(|lap| lap.log_points.clone())
+            .ok_or(Status::NotFound)?
+    };
+    let lines = stream::iter(log_points).filter_map(|point| async move {
+        match serde_json::to_string(&point) {
+            Ok(json) => Some(json + "\n"),
+            Err(e) => {
+                error!("Failed to serialize log point {:?}: {}", point, e);
+                None
+            }
+        }
+    });
+    Ok((
+        ContentType::new("application", "x-ndjson"),
+        TextStream(lines),
+    ))
+}
@@ -0,0 +1,70 @@
+// SPDX-FileCopyrightText: 2026 All contributors
+//
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use crate::RestCtx;
+use module_core::{EventKind, EventKindType, LaptimerStateInfo, Request, payload_ref};
+use rocket::{
+    State,
+    http::Status,
+    serde::{Serialize, json::Json},
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Response structure for the lap timer state.
+#[derive(Debug, Serialize)]
+#[serde(crate = "rocket::serde")]
+pub(crate) struct LaptimerStateResponse {
+    track: Option<String>,
+    no_track_configured: bool,
+    lap_running: bool,
+}
+
+/// Requests the state of the lap timer and waits for the response.
+async fn request_laptimer_state(
+    ctx: &Arc<Mutex<RestCtx>>,
+) -> Result<LaptimerStateInfo, std::io::ErrorKind> {
+    match RestCtx::request(
+        ctx,
+        EventKindType::LaptimerStateResponseEvent,
+        |req_id, addr| EventKind::LaptimerStateRequestEvent(Request::empty_request(req_id, addr)),
+    )
+    .await
+    {
+        Ok(event) => match payload_ref!(event.kind, EventKind::LaptimerStateResponseEvent) {
+            Some(resp) => Ok(resp.data.clone()),
+            None => {
+                error!("Received invalid LaptimerStateResponseEvent payload");
+                Err(std::io::ErrorKind::InvalidData)
+            }
+        },
+        Err(e) => {
+            error!(
+                "Error while waiting for LaptimerStateResponseEvent: {:?}",
+                e
+            );
+            Err(std::io::ErrorKind::TimedOut)
+        }
+    }
+}
+
+/// Returns the state of the lap timer.
+///
+/// Route: GET /v1/laptimer/state
+///
+/// Errors:
+/// - 500 if the lap timer doesn't answer.
+#[get("/v1/laptimer/state")]
+pub(crate) async fn get_laptimer_state(
+    ctx: &State<Arc<Mutex<RestCtx>>>,
+) -> Result<Json<LaptimerStateResponse>, Status> {
+    let state = request_laptimer_state(ctx)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+    Ok(Json(LaptimerStateResponse {
+        track: state.track_name,
+        no_track_configured: state.no_track_configured,
+        lap_running: state.lap_running,
+    }))
+}
@@ -2,12 +2,41 @@
 //
 // SPDX-License-Identifier: GPL-2.0-or-later
 
-use crate::live_session::ws_live_session_handler;
+use crate::{
+    cors::Cors,
+    diag::{ModuleActivity, get_modules},
+    elevation::get_lap_elevation,
+    error::{ApiError, default_catcher},
+    fit::get_session_fit,
+    lap_compare::get_lap_compare,
+    lap_export::{get_lap, get_lap_point, get_lap_points_ndjson},
+    laptimer_state::get_laptimer_state,
+    live_protocol::get_live_session_schema,
+    live_session::{
+        LiveEvent, LiveInitialSync, LiveKeepAlive, LiveSessionMetrics, shared_message,
+        ws_live_session_handler,
+    },
+    metrics::get_metrics,
+    raw_session::get_raw_session,
+    recent_laps::get_recent_laps,
+    rolling_average::get_rolling_average,
+    session_compare::get_session_compare,
+    session_import::import_session,
+    session_infos::get_session_infos,
+    sim::post_sim_position,
+    tracks::{get_track, get_track_sectors, put_track_sectors},
+    version::{Features, get_version},
+};
 use async_trait::async_trait;
 use common::session::{Session, SessionInfo};
-use module_core::{Event, EventKind, EventKindType, Module, ModuleCtx, Request, payload_ref};
+use module_core::{
+    Event, EventBusMetrics, EventKind, EventKindType, Module, ModuleCtx, ModuleCtxError, Request,
+    payload_ref,
+};
+use rand::Rng;
 use rocket::{
     State,
+    http::Status,
     response::content,
     serde::{Serialize, json::Json},
 };
@@ -16,19 +45,186 @@ use std::{
     env,
     net::Ipv4Addr,
     sync::{Arc, RwLock},
+    time::Duration,
 };
-use tokio::sync::Mutex;
+use tokio::sync::{Mutex, broadcast};
 #[macro_use]
 extern crate rocket;
 
+/// Module for the CORS headers of the responses.
+mod cors;
+
+/// Module for reporting the liveness of the modules.
+mod diag;
+
+/// Module for the elevation profile of laps.
+mod elevation;
+
+/// Module for the JSON error responses.
+mod error;
+
+/// Module for the FIT export of sessions.
+mod fit;
+
+/// Module for comparing two laps of a session.
+mod lap_compare;
+
+/// Module for exporting single laps in different formats and their log points.
+mod lap_export;
+
+/// Module for reporting the state of the lap timer.
+mod laptimer_state;
+
+/// Module for the message types and the schema of the live session protocol.
+mod live_protocol;
+
 /// Module for handling live session WebSocket connections.
 mod live_session;
 
+/// Module for exporting the event bus metrics.
+pub mod metrics;
+
+/// Module for downloading raw session files with HTTP range support.
+mod raw_session;
+
+/// Module for listing the most recent laps across all sessions.
+mod recent_laps;
+
+/// Module for computing the rolling average lap time of a session.
+mod rolling_average;
+
+/// Module for classifying live sector times against the best sectors.
+mod sector_bests;
+
+/// Module for comparing the lap times of two sessions.
+mod session_compare;
+
+/// Module for importing sessions from other devices.
+mod session_import;
+
+/// Module for fetching the infos of selected sessions.
+mod session_infos;
+
+/// Module for injecting simulated GNSS positions.
+mod sim;
+
+/// Module for loading and editing stored tracks.
+mod tracks;
+
+/// Module for reporting the server version and capabilities.
+mod version;
+
+/// Configuration of the [`Rest`] module.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RestConfig {
+    /// Path prefix under which all routes, including the WebSocket route, are mounted.
+    ///
+    /// E.g. `/laptimer` serves the sessions at `/laptimer/v1/sessions`, so the server can be
+    /// placed behind a reverse proxy alongside other services. Defaults to `/`.
+    pub base_path: String,
+    /// Number of decimals the coordinates of live `position` messages are rounded to.
+    ///
+    /// Only the streamed messages are rounded, stored sessions keep the full precision.
+    /// Defaults to 6, which is roughly 0.1 m.
+    pub position_precision: u32,
+    /// Enables the simulation routes, e.g. `POST /v1/sim/position` to inject GNSS positions.
+    ///
+    /// Intended for testing without a GPS, must not be enabled in production. Defaults to
+    /// `false`.
+    pub simulation: bool,
+    /// Cross-origin resource sharing of the routes, e.g. for browser-based dashboards.
+    pub cors: CorsConfig,
+    /// Interval of the WebSocket Ping frames sent on live session connections.
+    ///
+    /// Keeps idle connections open through proxies. A connection that didn't answer a Ping
+    /// with a Pong until the next Ping is due is considered dead and closed. `None` disables
+    /// the Pings. Defaults to 30 seconds.
+    pub live_ping_interval: Option<Duration>,
+    /// Number of attempts to fetch the current session as initial state of a new live
+    /// session connection.
+    ///
+    /// Keeps clients from staying without a baseline when the active session is briefly
+    /// unavailable, e.g. while the modules start. An attempt fails if no response arrives
+    /// within the [`request_timeout`](Self::request_timeout). After the last failed attempt
+    /// or without a current session, the connection is synchronized with the next lap start.
+    /// Defaults to 3.
+    pub live_initial_sync_attempts: u32,
+    /// Delay before the second attempt to fetch the initial state of a live session
+    /// connection, doubled for every further attempt like the
+    /// [`request_backoff`](Self::request_backoff), see
+    /// [`live_initial_sync_attempts`](Self::live_initial_sync_attempts). Defaults to
+    /// 1 second.
+    pub live_initial_sync_interval: Duration,
+    /// Number of attempts of a request to another module, e.g. to load a session from the
+    /// storage.
+    ///
+    /// A request is published again if no response arrived within the
+    /// [`request_timeout`](Self::request_timeout), e.g. because the event was dropped while
+    /// the event bus was lagging. Only idempotent requests are repeated, saving an imported
+    /// session is attempted once. Defaults to 3.
+    pub request_attempts: u32,
+    /// Time to wait for the response of a single request attempt. Defaults to 5 seconds.
+    pub request_timeout: Duration,
+    /// Delay before the second attempt of a request, doubled for every further attempt.
+    ///
+    /// Every delay is randomly scaled by a factor between 0.5 and 1.5, so concurrent requests
+    /// don't repeat in lockstep. Defaults to 100 milliseconds.
+    pub request_backoff: Duration,
+}
+
+/// Cross-origin resource sharing (CORS) configuration of the [`Rest`] module.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CorsConfig {
+    /// Origins whose browser scripts may access the routes, e.g. `https://dashboard.example`.
+    ///
+    /// `*` allows every origin and the port `*` every port of an origin, e.g.
+    /// `http://localhost:*`. Defaults to every port of `localhost` and `127.0.0.1`.
+    pub allowed_origins: Vec<String>,
+    /// Methods that may be used in cross-origin requests.
+    ///
+    /// Defaults to `GET`, `POST`, `PUT` and `DELETE`.
+    pub allowed_methods: Vec<String>,
+}
+
+impl Default for CorsConfig {
+    fn default() -> Self {
+        CorsConfig {
+            allowed_origins: ["http://localhost:*", "http://127.0.0.1:*"]
+                .map(String::from)
+                .to_vec(),
+            allowed_methods: ["GET", "POST", "PUT", "DELETE"].map(String::from).to_vec(),
+        }
+    }
+}
+
+impl Default for RestConfig {
+    fn default() -> Self {
+        RestConfig {
+            base_path: "/".to_string(),
+            position_precision: 6,
+            simulation: false,
+            cors: CorsConfig::default(),
+            live_ping_interval: Some(Duration::from_secs(30)),
+            live_initial_sync_attempts: 3,
+            live_initial_sync_interval: Duration::from_secs(1),
+            request_attempts: 3,
+            request_timeout: Duration::from_secs(5),
+            request_backoff: Duration::from_millis(100),
+        }
+    }
+}
+
 /// Represents the REST module, providing RESTful API functionality.
 ///
 /// This struct encapsulates the shared context and methods for managing the REST server.
 pub struct Rest {
     ctx: Arc<Mutex<RestCtx>>,
+    module_ctx: ModuleCtx,
+    metrics: Arc<RwLock<EventBusMetrics>>,
+    activity: Arc<RwLock<ModuleActivity>>,
+    live_metrics: Arc<LiveSessionMetrics>,
+    live_sender: broadcast::Sender<LiveEvent>,
+    config: RestConfig,
 }
 
 /// Internal context for the REST module.
@@ -38,7 +234,36 @@ pub(crate) struct RestCtx {
     ctx: ModuleCtx,
     module_addr: u64,
     request_id: u64,
-    connections: HashMap<String, bool>,
+    connections: HashMap<String, LiveConnection>,
+    live_sender: broadcast::Sender<LiveEvent>,
+    retry: RequestRetry,
+}
+
+/// Retry policy of the requests to other modules, see [`RestConfig::request_attempts`].
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct RequestRetry {
+    attempts: u32,
+    timeout: Duration,
+    backoff: Duration,
+}
+
+impl RequestRetry {
+    /// Returns the policy with a single attempt, for requests that aren't idempotent.
+    fn once(self) -> Self {
+        RequestRetry {
+            attempts: 1,
+            ..self
+        }
+    }
+}
+
+/// State of a live session WebSocket connection.
+#[derive(Debug, Default)]
+pub(crate) struct LiveConnection {
+    /// Whether the connection received the current session and is synchronized.
+    synced: bool,
+    /// Sequence number of the next outbound message.
+    seq: u64,
 }
 
 impl RestCtx {
@@ -52,15 +277,78 @@ impl RestCtx {
         id
     }
 
+    /// Publishes a request and waits for the response of type `response_type`.
+    ///
+    /// `request` builds the request event from the request ID and the module address. The
+    /// request is published again with the same ID if no response arrives within the
+    /// configured timeout, so a late response of an earlier attempt is accepted as well.
+    /// Must only be used for idempotent requests.
+    ///
+    /// The context is only locked to allocate the request ID, the request is sent and awaited
+    /// on an own [`ModuleCtx`], so concurrent requests don't wait for each other.
+    async fn request(
+        ctx: &Arc<Mutex<RestCtx>>,
+        response_type: EventKindType,
+        request: impl Fn(u64, u64) -> EventKind,
+    ) -> Result<Event, ModuleCtxError> {
+        RestCtx::request_with(ctx, |retry| retry, response_type, request).await
+    }
+
+    /// Publishes a request like [`RestCtx::request`] with the retry policy derived from the
+    /// configured one by `retry`, e.g. [`RequestRetry::once`] for a request that must not be
+    /// repeated.
+    async fn request_with(
+        ctx: &Arc<Mutex<RestCtx>>,
+        retry: impl FnOnce(RequestRetry) -> RequestRetry,
+        response_type: EventKindType,
+        request: impl Fn(u64, u64) -> EventKind,
+    ) -> Result<Event, ModuleCtxError> {
+        let (mut module_ctx, req_id, addr, retry) = {
+            let mut ctx_lock = ctx.lock().await;
+            let req_id = ctx_lock.request_id();
+            (
+                ctx_lock.ctx.clone(),
+                req_id,
+                ctx_lock.module_addr,
+                retry(ctx_lock.retry),
+            )
+        };
+        let mut backoff = retry.backoff;
+        for attempt in 1..=retry.attempts.max(1) {
+            module_ctx.publish_event(request(req_id, addr))?;
+            debug!("Sent request with id {} (attempt {})", req_id, attempt);
+            let response = tokio::time::timeout(
+                retry.timeout,
+                module_ctx.wait_for_event(req_id, addr, &response_type),
+            )
+            .await;
+            match response {
+                Ok(response) => return response,
+                Err(_) if attempt < retry.attempts => {
+                    let delay = backoff.mul_f64(rand::rng().random_range(0.5..1.5));
+                    warn!(
+                        "No {:?} for request {} within {:?}, retrying in {:?}",
+                        response_type, req_id, retry.timeout, delay
+                    );
+                    tokio::time::sleep(delay).await;
+                    backoff *= 2;
+                }
+                Err(_) => break,
+            }
+        }
+        Err(ModuleCtxError::ReceiveTimeout)
+    }
+
     /// Register a new connection in the internal registry.
     ///
     /// Inserts the given connection ID with an initial state of `false`
-    /// (e.g., not yet synchronized). If the ID already exists, its value is
-    /// replaced.
+    /// (e.g., not yet synchronized) and a message sequence starting at 0.
+    /// If the ID already exists, its value is replaced.
     ///
     /// - conn_id: Unique identifier for the client/connection.
     pub fn register_connection(&mut self, conn_id: &str) {
-        self.connections.insert(conn_id.to_owned(), false);
+        self.connections
+            .insert(conn_id.to_owned(), LiveConnection::default());
     }
 
     /// Unregisters a connection from the internal registry.
@@ -80,7 +368,7 @@ impl RestCtx {
     /// - conn_id: Unique identifier for the client/connection.
     pub fn is_connection_synced(&self, conn_id: &str) -> bool {
         match self.connections.get(conn_id) {
-            Some(synced) => *synced,
+            Some(connection) => connection.synced,
             None => false,
         }
     }
@@ -94,7 +382,25 @@ impl RestCtx {
     /// - synced: New synchronization state to set for the connection.
     pub fn set_connection_synced(&mut self, conn_id: &str, synced: bool) {
         if let Some(entry) = self.connections.get_mut(conn_id) {
-            *entry = synced;
+            entry.synced = synced;
+        }
+    }
+
+    /// Returns the sequence number for the next outbound message of a connection.
+    ///
+    /// Increments the connection's counter and returns its previous value, so
+    /// consecutive messages carry monotonically increasing numbers starting at 0.
+    /// Returns 0 for unknown connections.
+    ///
+    /// - conn_id: Unique identifier for the client/connection.
+    pub fn next_seq(&mut self, conn_id: &str) -> u64 {
+        match self.connections.get_mut(conn_id) {
+            Some(connection) => {
+                let seq = connection.seq;
+                connection.seq += 1;
+                seq
+            }
+            None => 0,
         }
     }
 }
@@ -110,13 +416,32 @@ impl Rest {
     /// # Returns
     /// A new `Rest` instance.
     pub fn new(ctx: ModuleCtx) -> Self {
+        Rest::new_with_config(ctx, RestConfig::default())
+    }
+
+    /// Creates a new `Rest` instance with a custom configuration.
+    pub fn new_with_config(ctx: ModuleCtx, config: RestConfig) -> Self {
+        let (live_sender, _) = broadcast::channel(LIVE_CHANNEL_CAPACITY);
         Rest {
+            // The request handlers only receive while waiting for a response.
             ctx: Arc::new(Mutex::new(RestCtx {
-                ctx,
+                ctx: ctx.clone().without_priority_lane(),
                 module_addr: 0xff,
                 request_id: 0,
                 connections: HashMap::new(),
+                live_sender: live_sender.clone(),
+                retry: RequestRetry {
+                    attempts: config.request_attempts,
+                    timeout: config.request_timeout,
+                    backoff: config.request_backoff,
+                },
             })),
+            metrics: Arc::new(RwLock::new(EventBusMetrics::default())),
+            activity: Arc::new(RwLock::new(ModuleActivity::default())),
+            live_metrics: Arc::new(LiveSessionMetrics::default()),
+            module_ctx: ctx,
+            live_sender,
+            config,
         }
     }
 }
@@ -136,7 +461,21 @@ impl Module for Rest {
     /// An asynchronous task handle for the running REST server.
     async fn run(&mut self) -> Result<(), ()> {
         let ctx = self.ctx.clone();
-        let rocket = match launch_rest_server(ctx.clone()).await {
+        let base_path = normalize_base_path(&self.config.base_path);
+        if rocket::http::uri::Origin::parse(&base_path).is_err() {
+            error!("Invalid REST base path: {}", self.config.base_path);
+            return Err(());
+        }
+        let rocket = match launch_rest_server(
+            ctx.clone(),
+            self.metrics.clone(),
+            self.activity.clone(),
+            self.live_metrics.clone(),
+            &base_path,
+            &self.config,
+        )
+        .await
+        {
             Ok(rocket) => rocket,
             Err(e) => {
                 error!("Failed to launch REST server: {}", e);
@@ -152,14 +491,44 @@ impl Module for Rest {
             }
         });
 
-        let lock_guard = self.ctx.lock().await;
-        let mut receiver = lock_guard.ctx.receiver.resubscribe();
-        drop(lock_guard);
-
         loop {
-            let event = receiver.recv().await;
+            let event = self.module_ctx.recv().await;
             match event {
                 Ok(event) => {
+                    {
+                        let mut metrics = self
+                            .metrics
+                            .write()
+                            .unwrap_or_else(|metrics| metrics.into_inner());
+                        metrics.record(&event.kind);
+                        metrics.set_subscribers(self.module_ctx.sender.receiver_count());
+                    }
+                    match &event.kind {
+                        EventKind::ModuleHeartbeatEvent(heartbeat) => self
+                            .activity
+                            .write()
+                            .unwrap_or_else(|activity| activity.into_inner())
+                            .record(heartbeat),
+                        EventKind::SaveFailedEvent(failure) => self
+                            .activity
+                            .write()
+                            .unwrap_or_else(|activity| activity.into_inner())
+                            .record_save_failure(failure),
+                        _ => (),
+                    }
+                    // The messages for the live session connections are serialized once here
+                    // instead of in every connection.
+                    if self.live_sender.receiver_count() > 0 {
+                        let message = shared_message(
+                            &event.kind,
+                            self.config.position_precision,
+                            &self.live_metrics,
+                        );
+                        let _ = self.live_sender.send(LiveEvent {
+                            kind: event.kind.clone(),
+                            message,
+                        });
+                    }
                     if let EventKind::QuitEvent = event.kind {
                         info!("Shutting down REST module and server.");
                         shutdown.notify();
@@ -189,40 +558,12 @@ impl Module for Rest {
 /// # Returns
 /// * `Vec<String>` - The received session IDs.
 async fn request_session_ids(ctx: &Arc<Mutex<RestCtx>>) -> Arc<Vec<SessionInfo>> {
-    let mut ctx_lock = ctx.lock().await;
-    let req_id = ctx_lock.request_id();
-    let addr = ctx_lock.module_addr;
-    let _ = ctx_lock.ctx.sender.send(Event {
-        kind: EventKind::LoadStoredSessionIdsRequestEvent(
-            Request {
-                sender_addr: ctx_lock.module_addr,
-                id: req_id,
-                data: (),
-            }
-            .into(),
-        ),
-    });
-    if ctx_lock
-        .ctx
-        .publish_event(EventKind::LoadStoredSessionIdsRequestEvent(Request::new(
-            ctx_lock.module_addr,
-            req_id,
-            (),
-        )))
-        .is_err()
-    {
-        error!("Failed to publish LoadStoredSessionIdsRequestEvent");
-        Arc::new(Vec::<String>::new());
-    }
-    debug!("Sent LoadStoredSessionIdsRequestEvent with id {}", req_id);
-    match ctx_lock
-        .ctx
-        .wait_for_event(
-            req_id,
-            addr,
-            &EventKindType::LoadStoredSessionIdsResponseEvent,
-        )
-        .await
+    match RestCtx::request(
+        ctx,
+        EventKindType::LoadStoredSessionIdsResponseEvent,
+        |req_id, addr| EventKind::LoadStoredSessionIdsRequestEvent(Request::new(req_id, addr, ())),
+    )
+    .await
     {
         Ok(event) => match payload_ref!(event.kind, EventKind::LoadStoredSessionIdsResponseEvent) {
             Some(resp) => resp.data.clone(),
@@ -246,7 +587,7 @@ async fn request_session_ids(ctx: &Arc<Mutex<RestCtx>>) -> Arc<Vec<SessionInfo>>
 /// Contains a vector of session ID strings returned by the REST API.
 #[derive(Debug, Serialize)]
 #[serde(crate = "rocket::serde")]
-struct SessionIdsResponse {
+pub(crate) struct SessionIdsResponse {
     total: usize,
     sessions: Vec<SessionInfo>,
 }
@@ -284,24 +625,12 @@ async fn request_session(
     id: &str,
     ctx: &Arc<Mutex<RestCtx>>,
 ) -> Result<Arc<RwLock<Session>>, std::io::ErrorKind> {
-    let mut ctx_lock = ctx.lock().await;
-    let req_id = ctx_lock.request_id();
-    let addr = ctx_lock.module_addr;
-    let _ = ctx_lock.ctx.sender.send(Event {
-        kind: EventKind::LoadSessionRequestEvent(
-            Request {
-                sender_addr: ctx_lock.module_addr,
-                id: req_id,
-                data: id.to_string(),
-            }
-            .into(),
-        ),
-    });
-    debug!("Sent LoadSessionRequestEvent with id {}", req_id);
-    match ctx_lock
-        .ctx
-        .wait_for_event(req_id, addr, &EventKindType::LoadSessionResponseEvent)
-        .await
+    match RestCtx::request(
+        ctx,
+        EventKindType::LoadSessionResponseEvent,
+        |req_id, addr| EventKind::LoadSessionRequestEvent(Request::new(req_id, addr, id.into())),
+    )
+    .await
     {
         Ok(event) => match payload_ref!(event.kind, EventKind::LoadSessionResponseEvent) {
             Some(resp) => resp.data.clone(),
@@ -328,35 +657,27 @@ async fn request_session(
 /// * `ctx` - Shared context containing the event sender and receiver.
 ///
 /// # Returns
-/// * `Result<Arc<RwLock<Session>>, std::io::ErrorKind>` - The loaded session or an error.
+/// * `Result<content::RawJson<String>, ApiError>` - The session as JSON, 404 if the session
+///   doesn't exist or 503 if the storage didn't respond.
 #[get("/v1/sessions/<id>")]
 async fn get_session(
     id: &str,
     ctx: &State<Arc<Mutex<RestCtx>>>,
-) -> Option<content::RawJson<String>> {
-    let session = request_session(id, ctx).await;
-    match &session {
-        Ok(session_lock) => {
-            let session_guard = match session_lock.read() {
-                Ok(guard) => guard,
-                Err(e) => {
-                    error!("Failed to acquire read lock on session {}: {}", id, e);
-                    return None;
-                }
-            };
-            Session::to_json(&session_guard).map_or_else(
-                |e| {
-                    error!("Failed to serialize session to JSON: {}", e);
-                    None
-                },
-                |json| Some(content::RawJson(json)),
-            )
-        }
-        Err(e) => {
-            error!("Failed to load session {}: {:?}", id, e);
-            None
-        }
-    }
+) -> Result<content::RawJson<String>, ApiError> {
+    let session_lock = request_session(id, ctx).await.map_err(|e| {
+        error!("Failed to load session {}: {:?}", id, e);
+        ApiError::storage(e, &format!("session {id}"))
+    })?;
+    let session_guard = session_lock.read().map_err(|e| {
+        error!("Failed to acquire read lock on session {}: {}", id, e);
+        ApiError::new(Status::InternalServerError, "session is poisoned")
+    })?;
+    Session::to_json(&session_guard)
+        .map(content::RawJson)
+        .map_err(|e| {
+            error!("Failed to serialize session to JSON: {}", e);
+            ApiError::new(Status::InternalServerError, "failed to serialize session")
+        })
 }
 
 /// Delete a session identified by `id`.
@@ -375,28 +696,13 @@ async fn get_session(
 /// - Returns InternalServerError if waiting for the response fails or
 ///   the received event payload is invalid.
 #[delete("/v1/sessions/<id>")]
-async fn delete_session(
-    id: &str,
-    ctx: &State<Arc<Mutex<RestCtx>>>,
-) -> Result<(), rocket::http::Status> {
-    let mut ctx_lock = ctx.lock().await;
-    let req_id = ctx_lock.request_id();
-    let addr = ctx_lock.module_addr;
-    let _ = ctx_lock.ctx.sender.send(Event {
-        kind: EventKind::DeleteSessionRequestEvent(
-            Request {
-                sender_addr: ctx_lock.module_addr,
-                id: req_id,
-                data: id.to_string(),
-            }
-            .into(),
-        ),
-    });
-    debug!("Sent DeleteSessionRequestEvent with id {}", req_id);
-    match ctx_lock
-        .ctx
-        .wait_for_event(req_id, addr, &EventKindType::DeleteSessionResponseEvent)
-        .await
+async fn delete_session(id: &str, ctx: &State<Arc<Mutex<RestCtx>>>) -> Result<(), Status> {
+    match RestCtx::request(
+        ctx,
+        EventKindType::DeleteSessionResponseEvent,
+        |req_id, addr| EventKind::DeleteSessionRequestEvent(Request::new(req_id, addr, id.into())),
+    )
+    .await
     {
         Ok(event) => match payload_ref!(event.kind, EventKind::DeleteSessionResponseEvent) {
             Some(_) => {
@@ -405,7 +711,7 @@ async fn delete_session(
             }
             None => {
                 error!("Received invalid DeleteSessionResponseEvent payload");
-                Err(rocket::http::Status::InternalServerError)
+                Err(Status::InternalServerError)
             }
         },
         Err(e) => {
@@ -413,11 +719,19 @@ async fn delete_session(
                 "Error while waiting for DeleteSessionResponseEvent: {:?}",
                 e
             );
-            Err(rocket::http::Status::InternalServerError)
+            Err(Status::InternalServerError)
         }
     }
 }
 
+/// Normalizes a configured base path to the form `/<prefix>` or `/`.
+fn normalize_base_path(base_path: &str) -> String {
+    format!("/{}", base_path.trim_matches('/'))
+}
+
+/// Capacity of the channel that forwards the events to the live session connections.
+const LIVE_CHANNEL_CAPACITY: usize = 100;
+
 /// The default port used for the REST server.
 static DEFAULT_PORT: u16 = 27015;
 
@@ -425,12 +739,18 @@ static DEFAULT_PORT: u16 = 27015;
 ///
 /// This function sets up the Rocket server with address and port from environment variables,
 /// or uses defaults if not provided. It configures logging and color settings, and mounts
-/// the session endpoint.
+/// all endpoints under the given base path. The simulation routes are only mounted if
+/// they're enabled in the configuration, and the CORS headers are added to all responses.
 ///
 /// # Returns
 /// A configured instance of `rocket::Rocket<rocket::Build>`.
 async fn launch_rest_server(
     ctx: Arc<Mutex<RestCtx>>,
+    metrics: Arc<RwLock<EventBusMetrics>>,
+    activity: Arc<RwLock<ModuleActivity>>,
+    live_metrics: Arc<LiveSessionMetrics>,
+    base_path: &str,
+    config: &RestConfig,
 ) -> Result<rocket::Rocket<rocket::Ignite>, rocket::Error> {
     // TODO: Change this when introducing the whole configuration concept.
     // Then this should be started after the configuration is loaded from the configuration module.
@@ -445,17 +765,54 @@ async fn launch_rest_server(
         .merge(("log_level", "critical"))
         .merge(("cli_colors", false));
 
-    rocket::custom(figment)
+    let mut rocket = rocket::custom(figment).attach(Cors::new(config.cors.clone()));
+    if config.simulation {
+        warn!("REST simulation routes are enabled");
+        rocket = rocket.mount(base_path, rocket::routes![post_sim_position]);
+    }
+    rocket
         .mount(
-            "/",
+            base_path,
             rocket::routes![
                 get_session_ids,
                 get_session,
                 delete_session,
-                ws_live_session_handler
+                get_raw_session,
+                get_session_fit,
+                get_lap,
+                get_lap_point,
+                get_lap_points_ndjson,
+                get_lap_elevation,
+                get_lap_compare,
+                get_rolling_average,
+                get_session_compare,
+                import_session,
+                get_recent_laps,
+                get_metrics,
+                get_version,
+                get_laptimer_state,
+                ws_live_session_handler,
+                get_live_session_schema,
+                get_modules,
+                get_track,
+                get_track_sectors,
+                put_track_sectors,
+                get_session_infos
             ],
         )
+        .register(base_path, rocket::catchers![default_catcher])
         .manage(ctx)
+        .manage(metrics)
+        .manage(activity)
+        .manage(live_metrics)
+        .manage(LiveKeepAlive {
+            ping_interval: config.live_ping_interval,
+        })
+        .manage(LiveInitialSync {
+            attempts: config.live_initial_sync_attempts,
+            interval: config.live_initial_sync_interval,
+        })
+        .manage(Features::new(config))
         .ignite()
         .await
 }
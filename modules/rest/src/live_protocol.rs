@@ -0,0 +1,255 @@
+// SPDX-FileCopyrightText: 2026 All contributors
+//
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use crate::sector_bests::SectorClassification;
+use chrono::{NaiveDate, NaiveTime};
+use common::serde::{date, duration, time};
+use common::session::Session;
+use rocket::response::content;
+use serde::{Serialize, Serializer};
+use serde_json::{Value, json};
+use std::time::Duration;
+
+/// The events sent by the server on the live session WebSocket.
+///
+/// The variant name in snake case is the event name and the fields are the `data` payload.
+/// New variants must be added to [`live_session_schema`] as well.
+#[derive(Debug, Serialize)]
+#[serde(tag = "event", content = "data", rename_all = "snake_case")]
+pub(crate) enum OutboundEvent<'a> {
+    /// The session the connection is synchronized with.
+    CurrentSession { session: &'a Session },
+
+    /// The running time of the current lap.
+    CurrentLaptime {
+        #[serde(with = "duration")]
+        time: Duration,
+    },
+
+    /// A new lap started.
+    LapStarted {},
+
+    /// A sector finished with the given sector time and its classification.
+    SectorFinished {
+        #[serde(with = "duration")]
+        time: Duration,
+        classification: SectorClassification,
+    },
+
+    /// A lap finished with the given lap time.
+    LapFinished {
+        #[serde(with = "duration")]
+        time: Duration,
+    },
+
+    /// The vehicle is approaching the finish line, the distance is given in meters.
+    ApproachingFinish { distance: f64 },
+
+    /// No track got configured within the grace period of the lap timer.
+    NoTrackConfigured {},
+
+    /// The current GNSS position of the vehicle, the velocity is given in meters per second.
+    Position {
+        latitude: RoundedCoordinate,
+        longitude: RoundedCoordinate,
+        velocity: f64,
+        #[serde(with = "time")]
+        time: NaiveTime,
+        #[serde(with = "date")]
+        date: NaiveDate,
+    },
+
+    /// The longitudinal acceleration of the vehicle in m/s², negative values indicate braking.
+    Acceleration { acceleration: f64 },
+}
+
+/// A coordinate in decimal degrees that is serialized rounded to a number of decimals.
+#[derive(Debug)]
+pub(crate) struct RoundedCoordinate {
+    pub value: f64,
+    pub decimals: u32,
+}
+
+impl Serialize for RoundedCoordinate {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let factor = 10f64.powi(self.decimals as i32);
+        serializer.serialize_f64((self.value * factor).round() / factor)
+    }
+}
+
+/// An outbound event serialized without the sequence number of a connection.
+///
+/// A message is sent as `{"event": <name>, "data": <payload>, "seq": <sequence number>}`.
+/// The event is serialized once and shared by all connections, which only append their
+/// own sequence number.
+#[derive(Debug)]
+pub(crate) struct SharedMessage {
+    /// The serialized event without the closing brace, empty if the serialization failed.
+    prefix: String,
+}
+
+impl SharedMessage {
+    /// Serializes the event for all connections.
+    pub(crate) fn new(event: &OutboundEvent<'_>) -> Self {
+        let prefix = match serde_json::to_string(event) {
+            Ok(mut json) => {
+                json.pop();
+                json
+            }
+            Err(e) => {
+                error!("Failed to serialize live session message: {}", e);
+                String::new()
+            }
+        };
+        SharedMessage { prefix }
+    }
+
+    /// Returns the JSON string of the message with the sequence number of a connection.
+    pub(crate) fn with_seq(&self, seq: u64) -> String {
+        if self.prefix.is_empty() {
+            return "{}".to_string();
+        }
+        format!("{},\"seq\":{}}}", self.prefix, seq)
+    }
+}
+
+/// Serializes an outbound event with its sequence number into a JSON string.
+///
+/// Used for the messages that differ per connection, the others are serialized once as
+/// [`SharedMessage`].
+pub(crate) fn serialize_message(event: OutboundEvent<'_>, seq: u64) -> String {
+    SharedMessage::new(&event).with_seq(seq)
+}
+
+/// Returns the schema of an outbound event with the given name and data schema.
+fn event_schema(name: &str, description: &str, data: Value) -> Value {
+    json!({
+        "description": description,
+        "properties": {
+            "event": { "const": name },
+            "data": data,
+        },
+    })
+}
+
+/// Returns the schema of a data object with a single duration field `time`.
+fn time_data_schema() -> Value {
+    json!({
+        "type": "object",
+        "required": ["time"],
+        "properties": { "time": { "$ref": "#/$defs/duration" } },
+    })
+}
+
+/// Returns the schema of an empty data object.
+fn empty_data_schema() -> Value {
+    json!({ "type": "object", "maxProperties": 0 })
+}
+
+/// Returns the JSON schema (draft 2020-12) of the messages sent on the live session WebSocket.
+pub(crate) fn live_session_schema() -> Value {
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "Rapid live session protocol",
+        "description": "Messages sent by the server on /v1/live_session. Clients send no commands, all messages except close frames are ignored.",
+        "type": "object",
+        "required": ["event", "seq", "data"],
+        "properties": {
+            "event": { "type": "string" },
+            "seq": { "type": "integer", "minimum": 0 },
+            "data": { "type": "object" },
+        },
+        "oneOf": [
+            event_schema(
+                "current_session",
+                "The session the connection is synchronized with.",
+                json!({
+                    "type": "object",
+                    "required": ["session"],
+                    "properties": { "session": { "$ref": "#/$defs/session" } },
+                }),
+            ),
+            event_schema(
+                "current_laptime",
+                "The running time of the current lap.",
+                time_data_schema(),
+            ),
+            event_schema("lap_started", "A new lap started.", empty_data_schema()),
+            event_schema(
+                "sector_finished",
+                "A sector finished with the given sector time and its classification against the prior times of the sector.",
+                json!({
+                    "type": "object",
+                    "required": ["time", "classification"],
+                    "properties": {
+                        "time": { "$ref": "#/$defs/duration" },
+                        "classification": { "enum": ["session_best", "personal_best", "none"] },
+                    },
+                }),
+            ),
+            event_schema(
+                "lap_finished",
+                "A lap finished with the given lap time.",
+                time_data_schema(),
+            ),
+            event_schema(
+                "approaching_finish",
+                "The vehicle is approaching the finish line, the distance is given in meters.",
+                json!({
+                    "type": "object",
+                    "required": ["distance"],
+                    "properties": { "distance": { "type": "number" } },
+                }),
+            ),
+            event_schema(
+                "no_track_configured",
+                "No track got configured within the grace period of the lap timer.",
+                empty_data_schema(),
+            ),
+            event_schema(
+                "position",
+                "The current GNSS position of the vehicle, the velocity is given in meters per second.",
+                json!({
+                    "type": "object",
+                    "required": ["latitude", "longitude", "velocity", "time", "date"],
+                    "properties": {
+                        "latitude": { "type": "number" },
+                        "longitude": { "type": "number" },
+                        "velocity": { "type": "number" },
+                        "time": { "type": "string" },
+                        "date": { "type": "string" },
+                    },
+                }),
+            ),
+            event_schema(
+                "acceleration",
+                "The longitudinal acceleration of the vehicle in m/s², negative values indicate braking.",
+                json!({
+                    "type": "object",
+                    "required": ["acceleration"],
+                    "properties": { "acceleration": { "type": "number" } },
+                }),
+            ),
+        ],
+        "$defs": {
+            "duration": {
+                "type": "string",
+                "pattern": "^[0-9]{2}:[0-9]{2}:[0-9]{2}\\.[0-9]{3}$",
+                "description": "Duration in the format HH:MM:SS.mmm",
+            },
+            "session": {
+                "type": "object",
+                "description": "Session in the format of GET /v1/sessions/{id}",
+            },
+        },
+    })
+}
+
+/// Returns the JSON schema of the live session WebSocket protocol.
+///
+/// Route: GET /v1/live_session/schema
+#[get("/v1/live_session/schema")]
+pub(crate) fn get_live_session_schema() -> content::RawJson<String> {
+    content::RawJson(live_session_schema().to_string())
+}
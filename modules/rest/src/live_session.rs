@@ -2,10 +2,11 @@
 //
 // SPDX-License-Identifier: GPL-2.0-or-later
 
-use crate::RestCtx;
+use crate::live_protocol::{OutboundEvent, RoundedCoordinate, SharedMessage, serialize_message};
 use crate::rocket::futures::StreamExt;
 use crate::rocket::futures::TryStreamExt;
-use common::serde::duration;
+use crate::sector_bests::SectorBests;
+use crate::{RequestRetry, RestCtx};
 use common::session::Session;
 use module_core::EventKind;
 use module_core::EventKindType;
@@ -14,125 +15,137 @@ use module_core::payload_ref;
 use rand::{Rng, distr::Alphanumeric, rng};
 use rocket::State;
 use rocket_ws::Message;
-use serde::Serialize;
 use std::sync::Arc;
 use std::sync::RwLock;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
 use tokio::sync::Mutex;
+use tokio::time::{Instant, Interval, interval_at};
 
-#[derive(Serialize)]
-struct LaptimeEvent<'a> {
-    event: &'a str,
-    data: LaptimeData<'a>,
+/// An event of the event bus forwarded to the live session connections.
+#[derive(Clone, Debug)]
+pub(crate) struct LiveEvent {
+    pub kind: EventKind,
+    /// The message of the event if it's the same for all connections.
+    pub message: Option<Arc<SharedMessage>>,
 }
 
-#[derive(Serialize)]
-struct LaptimeData<'a> {
-    #[serde(with = "duration")]
-    time: &'a std::time::Duration,
+/// Keep-alive of the live session connections, see [`crate::RestConfig::live_ping_interval`].
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct LiveKeepAlive {
+    pub ping_interval: Option<Duration>,
 }
 
-#[derive(Serialize)]
-struct CurrentSessionEvent<'a> {
-    event: &'a str,
-    data: CurrentSessionData<'a>,
+/// Retries of the initial state of the live session connections, see
+/// [`crate::RestConfig::live_initial_sync_attempts`].
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct LiveInitialSync {
+    pub attempts: u32,
+    pub interval: Duration,
 }
 
-#[derive(Serialize)]
-struct CurrentSessionData<'a> {
-    session: &'a Session,
+/// Waits for the next tick of the ping timer, forever if the pings are disabled.
+async fn next_ping(ping_timer: &mut Option<Interval>) {
+    match ping_timer {
+        Some(timer) => {
+            timer.tick().await;
+        }
+        None => std::future::pending().await,
+    }
 }
 
-#[derive(Serialize)]
-struct EmptyEvent<'a> {
-    event: &'a str,
-    data: serde_json::Value,
+/// Counters of the live session broadcast.
+#[derive(Debug, Default)]
+pub(crate) struct LiveSessionMetrics {
+    serialized_messages: AtomicU64,
 }
 
-/// Serializes a laptime event payload to a JSON string.
-///
-/// Constructs a `LaptimeEvent` with the provided event name and lap time and
-/// returns its JSON representation.
-///
-/// Arguments:
-/// - laptime: Lap time duration to include in the payload.
-/// - event: Event identifier/name.
-///
-/// Returns the JSON string for `LaptimeEvent`.
-fn serialize_laptime_event(laptime: &std::time::Duration, event: &str) -> String {
-    let event = LaptimeEvent {
-        event,
-        data: LaptimeData { time: laptime },
-    };
-    match serde_json::to_string(&event) {
-        Ok(json) => json,
-        Err(e) => {
-            error!("Failed to serialize laptime event: {}", e);
-            "{}".to_string()
-        }
+impl LiveSessionMetrics {
+    /// Returns the number of messages serialized for the broadcast to all connections.
+    pub(crate) fn serialized_messages(&self) -> u64 {
+        self.serialized_messages.load(Ordering::Relaxed)
     }
 }
 
-/// Serialize an empty event into a JSON string.
+/// Serializes the message of an event that is the same for all connections.
 ///
-/// Creates an `EmptyEvent` with the provided `event` name and an empty `data` object,
-/// then serializes it into a compact JSON string.
-///
-/// Arguments:
-/// - `event`: Name or type of the event.
-///
-/// Returns:
-/// - JSON string representing the empty event.
-fn serialize_empty_event(event: &str) -> String {
-    let event = EmptyEvent {
-        event,
-        data: serde_json::Value::Object(serde_json::Map::new()),
-    };
-    match serde_json::to_string(&event) {
-        Ok(json) => json,
-        Err(e) => {
-            error!("Failed to serialize laptime event: {}", e);
-            "{}".to_string()
+/// Returns `None` for events without a message and for events whose message depends on
+/// the state of the connection, e.g. the sector classification.
+pub(crate) fn shared_message(
+    kind: &EventKind,
+    position_precision: u32,
+    metrics: &LiveSessionMetrics,
+) -> Option<Arc<SharedMessage>> {
+    let event = match kind {
+        EventKind::CurrentLaptimeEvent(laptime) => {
+            OutboundEvent::CurrentLaptime { time: **laptime }
         }
-    }
+        EventKind::LapStartedEvent => OutboundEvent::LapStarted {},
+        EventKind::LapFinishedEvent(lap) => OutboundEvent::LapFinished { time: lap.lap_time },
+        EventKind::ApproachingFinishEvent(distance) => OutboundEvent::ApproachingFinish {
+            distance: *distance,
+        },
+        EventKind::NoTrackConfiguredEvent => OutboundEvent::NoTrackConfigured {},
+        EventKind::GnssPositionEvent(position) => OutboundEvent::Position {
+            latitude: RoundedCoordinate {
+                value: position.latitude(),
+                decimals: position_precision,
+            },
+            longitude: RoundedCoordinate {
+                value: position.longitude(),
+                decimals: position_precision,
+            },
+            velocity: position.velocity(),
+            time: position.time(),
+            date: position.date(),
+        },
+        EventKind::AccelerationEvent(acceleration) => OutboundEvent::Acceleration {
+            acceleration: *acceleration,
+        },
+        _ => return None,
+    };
+    metrics.serialized_messages.fetch_add(1, Ordering::Relaxed);
+    Some(Arc::new(SharedMessage::new(&event)))
 }
 
 /// Serializes the current session event into a JSON string.
-/// Constructs a `CurrentSessionEvent` with the provided session and
-///
-/// returns its JSON representation.
-fn serialize_current_session_event(session: &Arc<RwLock<Session>>) -> String {
+fn serialize_current_session_event(session: &Arc<RwLock<Session>>, seq: u64) -> String {
     let session = session.read().unwrap_or_else(|s| s.into_inner());
-    let event = CurrentSessionEvent {
-        event: "current_session",
-        data: CurrentSessionData { session: &session },
-    };
-    match serde_json::to_string(&event) {
-        Ok(json) => json,
-        Err(e) => {
-            error!("Failed to serialize current session event: {}", e);
-            "{}".to_string()
-        }
-    }
+    serialize_message(OutboundEvent::CurrentSession { session: &session }, seq)
+}
+
+/// Returns the sector tracker initialized with the finished laps of the session.
+fn sector_bests_of(session: &Arc<RwLock<Session>>) -> SectorBests {
+    SectorBests::from_session(&session.read().unwrap_or_else(|s| s.into_inner()))
 }
 
 /// WebSocket handler that streams live session updates to clients.
 ///
 /// Route: GET /v1/live_session
-/// Subscribes to the server event bus and forwards JSON messages.
-/// Sends "current_laptime, lap_started," events as Message::Text and terminates on QuitEvent,
-/// client close, or errors.
+/// Subscribes to the live events forwarded by the REST module and sends them as JSON messages.
+/// Messages that are the same for all connections are serialized once by the REST module,
+/// the handler only adds the sequence number of the connection.
+/// Terminates on QuitEvent, client close, or errors.
+/// Idle connections are kept open with periodic Ping frames and closed if a Ping isn't
+/// answered with a Pong until the next Ping is due.
 ///
 /// Params:
 /// - ws: Upgraded WebSocket connection.
 /// - ctx: Shared RestCtx state for accessing the event receiver.
+/// - keep_alive: Interval of the Ping frames.
+/// - initial_sync: Retries of the initial current session request.
 ///
 /// Returns a rocket_ws::Stream that yields WebSocket messages.
 #[get("/v1/live_session")]
 pub(crate) fn ws_live_session_handler(
     ws: rocket_ws::WebSocket,
     ctx: &State<Arc<Mutex<RestCtx>>>,
+    keep_alive: &State<LiveKeepAlive>,
+    initial_sync: &State<LiveInitialSync>,
 ) -> rocket_ws::Stream! ['static] {
     let ctx = ctx.inner().clone();
+    let ping_interval = keep_alive.ping_interval;
+    let initial_sync = *initial_sync.inner();
     rocket_ws::Stream! { ws =>
         let ctx = ctx.clone();
         let mut stream_ws = ws.into_stream();
@@ -140,15 +153,17 @@ pub(crate) fn ws_live_session_handler(
 
         let mut event_receiver = {
             let guard = ctx.lock().await;
-            guard.ctx.receiver.resubscribe()
+            guard.live_sender.subscribe()
         };
 
         ctx.lock().await.register_connection(&session_id);
+        let mut sector_bests = SectorBests::default();
         info!("WebSocket \"/v1/live_session\" connection established with session_id: {}", session_id);
 
-        match request_current_session(&ctx).await {
+        match request_initial_session(&ctx, initial_sync).await {
             Ok(session_ptr) => {
-                yield Message::Text(serialize_current_session_event(&session_ptr));
+                sector_bests = sector_bests_of(&session_ptr);
+                yield Message::Text(serialize_current_session_event(&session_ptr, ctx.lock().await.next_seq(&session_id)));
                 ctx.lock().await.set_connection_synced(&session_id, true);
             }
             Err(e) => {
@@ -156,29 +171,42 @@ pub(crate) fn ws_live_session_handler(
             }
         }
 
+        let mut ping_timer = ping_interval
+            .map(|interval| interval_at(Instant::now() + interval, interval));
+        let mut awaiting_pong = false;
         loop {
             tokio::select!{
+                _ = next_ping(&mut ping_timer) => {
+                    if awaiting_pong {
+                        ctx.lock().await.unregister_connection(&session_id);
+                        info!("Closing WebSocket live session {} without Pong", session_id);
+                        break;
+                    }
+                    awaiting_pong = true;
+                    yield Message::Ping(Vec::new());
+                }
+
                 event = event_receiver.recv() => {
                     match event {
-                        Ok(event) => {
-                            match event.kind {
+                        Ok(LiveEvent { kind, message }) => {
+                            match kind {
                                 EventKind::QuitEvent => {
                                     ctx.lock().await.unregister_connection(&session_id);
                                     info!("Shutting down WebSocket live session handler due to QuitEvent");
                                     break;
                                 }
-                                EventKind::CurrentLaptimeEvent(laptime) => {
-                                    if ctx.lock().await.is_connection_synced(&session_id) {
-                                        yield Message::Text(serialize_laptime_event(&laptime, "current_laptime"));
-                                    }
-                                }
                                 EventKind::LapStartedEvent => {
                                     if ctx.lock().await.is_connection_synced(&session_id) {
-                                        yield Message::Text(serialize_empty_event("lap_started"));
+                                        sector_bests.lap_started();
+                                        if let Some(message) = message {
+                                            yield Message::Text(message.with_seq(ctx.lock().await.next_seq(&session_id)));
+                                        }
                                     }else{
-                                        match request_current_session(&ctx).await {
+                                        match request_current_session(&ctx, |retry| retry).await {
                                             Ok(session_ptr) => {
-                                                yield Message::Text(serialize_current_session_event(&session_ptr));
+                                                sector_bests = sector_bests_of(&session_ptr);
+                                                sector_bests.lap_started();
+                                                yield Message::Text(serialize_current_session_event(&session_ptr, ctx.lock().await.next_seq(&session_id)));
                                                 ctx.lock().await.set_connection_synced(&session_id, true);
                                             }
                                             Err(e) => {
@@ -188,17 +216,28 @@ pub(crate) fn ws_live_session_handler(
                                         }
                                     }
                                 }
-                                EventKind::LapFinishedEvent (laptimer)=> {
-                                    if ctx.lock().await.is_connection_synced(&session_id) {
-                                        yield Message::Text(serialize_laptime_event(&laptimer, "lap_finished"));
+                                EventKind::SectorFinishedEvent(sector)
+                                    if ctx.lock().await.is_connection_synced(&session_id) => {
+                                    yield Message::Text(serialize_message(OutboundEvent::SectorFinished { time: *sector, classification: sector_bests.sector_finished(*sector) }, ctx.lock().await.next_seq(&session_id)));
+                                }
+                                EventKind::NoTrackConfiguredEvent => {
+                                    // Without a track there is no session, so the event is sent to unsynchronized connections too.
+                                    if let Some(message) = message {
+                                        yield Message::Text(message.with_seq(ctx.lock().await.next_seq(&session_id)));
                                     }
                                 }
-                                EventKind::SectorFinishedEvent(sector) => {
-                                    if ctx.lock().await.is_connection_synced(&session_id) {
-                                        yield Message::Text(serialize_laptime_event(&sector, "sector_finished"));
+                                EventKind::SessionChangedEvent(session_ptr) => {
+                                    sector_bests = sector_bests_of(&session_ptr);
+                                    debug!("Resynchronizing WebSocket connection {} due to session change", session_id);
+                                    yield Message::Text(serialize_current_session_event(&session_ptr, ctx.lock().await.next_seq(&session_id)));
+                                    ctx.lock().await.set_connection_synced(&session_id, true);
+                                }
+                                _ => {
+                                    if let Some(message) = message
+                                        && ctx.lock().await.is_connection_synced(&session_id) {
+                                        yield Message::Text(message.with_seq(ctx.lock().await.next_seq(&session_id)));
                                     }
                                 }
-                                _ => {}
                             }
                         }
                         Err(e) => {
@@ -216,6 +255,9 @@ pub(crate) fn ws_live_session_handler(
                             info!("WebSocket client disconnected from live session");
                             break;
                         }
+                        Ok(Message::Pong(_)) => {
+                            awaiting_pong = false;
+                        }
                         Ok(_) => {
                         }
                         Err(e) => {
@@ -246,6 +288,22 @@ pub fn generate_connection_id() -> String {
     id
 }
 
+/// Requests the current session as initial state of a connection.
+///
+/// The request is repeated with the configured attempts if the current session isn't
+/// answered within the request timeout, the first time after the configured interval.
+async fn request_initial_session(
+    ctx: &Arc<Mutex<RestCtx>>,
+    sync: LiveInitialSync,
+) -> Result<Arc<RwLock<Session>>, std::io::ErrorKind> {
+    request_current_session(ctx, |retry| RequestRetry {
+        attempts: sync.attempts,
+        backoff: sync.interval,
+        ..retry
+    })
+    .await
+}
+
 /// Requests the current session from the event bus.
 ///
 /// Sends a CurrentSessionRequestEvent and waits for the corresponding response.
@@ -255,32 +313,22 @@ pub fn generate_connection_id() -> String {
 ///
 /// Arguments:
 /// - ctx: Shared RestCtx for publishing and receiving events.
+/// - retry: Derives the retry policy of the request from the configured one.
 ///
 /// Return:
 /// - Ok(Arc<RwLock<Session>>) on success.
 /// - Err(std::io::ErrorKind) on failure.
 async fn request_current_session(
     ctx: &Arc<Mutex<RestCtx>>,
+    retry: impl FnOnce(RequestRetry) -> RequestRetry,
 ) -> Result<Arc<RwLock<Session>>, std::io::ErrorKind> {
-    let mut ctx = ctx.lock().await;
-    let req_id = ctx.request_id();
-    let _ = ctx.ctx.publish_event(EventKind::CurrentSessionRequestEvent(
-        Request {
-            id: req_id,
-            sender_addr: ctx.module_addr,
-            data: (),
-        }
-        .into(),
-    ));
-    info!(
-        "Published CurrentSessionRequestEvent with req_id: {}, addr {:?}",
-        req_id, ctx.module_addr
-    );
-    let addr = ctx.module_addr;
-    match ctx
-        .ctx
-        .wait_for_event(req_id, addr, &EventKindType::CurrentSessionResponseEvent)
-        .await
+    match RestCtx::request_with(
+        ctx,
+        retry,
+        EventKindType::CurrentSessionResponseEvent,
+        |req_id, addr| EventKind::CurrentSessionRequestEvent(Request::empty_request(req_id, addr)),
+    )
+    .await
     {
         Ok(event) => {
             let session = match payload_ref!(event.kind, EventKind::CurrentSessionResponseEvent) {
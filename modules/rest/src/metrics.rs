@@ -0,0 +1,86 @@
+// SPDX-FileCopyrightText: 2026 All contributors
+//
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use crate::live_session::LiveSessionMetrics;
+use module_core::{EventBusMetrics, EventKindType};
+use rocket::{State, http::ContentType};
+use std::{
+    fmt::Write,
+    sync::{Arc, RwLock},
+};
+
+/// Converts an event kind into its Prometheus counter name.
+///
+/// The CamelCase kind name is converted into snake_case and suffixed with `s_total`,
+/// e.g. `GnssPositionEvent` becomes `gnss_position_events_total`.
+fn counter_name(kind: EventKindType) -> String {
+    let name: &'static str = kind.into();
+    let mut counter = String::with_capacity(name.len() + 8);
+    for (index, c) in name.chars().enumerate() {
+        if c.is_ascii_uppercase() {
+            if index > 0 {
+                counter.push('_');
+            }
+            counter.push(c.to_ascii_lowercase());
+        } else {
+            counter.push(c);
+        }
+    }
+    counter.push_str("s_total");
+    counter
+}
+
+/// Renders the event bus metrics in the Prometheus text exposition format.
+///
+/// Every event kind is exported as a counter of published events, and the number of
+/// bus subscribers is exported as the gauge `event_bus_subscribers`.
+pub fn render_prometheus(m: &EventBusMetrics) -> String {
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "# HELP event_bus_subscribers Number of active event bus subscribers."
+    );
+    let _ = writeln!(out, "# TYPE event_bus_subscribers gauge");
+    let _ = writeln!(out, "event_bus_subscribers {}", m.subscribers());
+    for (kind, count) in m.published_per_kind() {
+        let name = counter_name(kind);
+        let _ = writeln!(
+            out,
+            "# HELP {name} Number of {kind:?} events published on the event bus."
+        );
+        let _ = writeln!(out, "# TYPE {name} counter");
+        let _ = writeln!(out, "{name} {count}");
+    }
+    out
+}
+
+/// Renders the live session metrics in the Prometheus text exposition format.
+fn render_live_session_metrics(m: &LiveSessionMetrics) -> String {
+    let mut out = String::new();
+    let name = "live_session_serialized_messages_total";
+    let _ = writeln!(
+        out,
+        "# HELP {name} Number of live session messages serialized for all connections."
+    );
+    let _ = writeln!(out, "# TYPE {name} counter");
+    let _ = writeln!(out, "{name} {}", m.serialized_messages());
+    out
+}
+
+/// Returns the event bus and live session metrics as Prometheus text.
+///
+/// Route: GET /v1/metrics
+#[get("/v1/metrics")]
+pub(crate) fn get_metrics(
+    metrics: &State<Arc<RwLock<EventBusMetrics>>>,
+    live_metrics: &State<Arc<LiveSessionMetrics>>,
+) -> (ContentType, String) {
+    let metrics = metrics
+        .read()
+        .unwrap_or_else(|metrics| metrics.into_inner());
+    (
+        ContentType::new("text", "plain").with_params([("version", "0.0.4")]),
+        render_prometheus(&metrics) + &render_live_session_metrics(live_metrics),
+    )
+}
@@ -0,0 +1,219 @@
+// SPDX-FileCopyrightText: 2026 All contributors
+//
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use crate::{RestCtx, error::storage_error_status};
+use module_core::{EventKind, EventKindType, Request, payload_ref};
+use rocket::{
+    State,
+    http::{ContentType, Status},
+    request::{FromRequest, Outcome},
+    response::{self, Responder, Response},
+};
+use std::{io::Cursor, io::SeekFrom, path::PathBuf, sync::Arc};
+use tokio::{
+    io::{AsyncReadExt, AsyncSeekExt},
+    sync::Mutex,
+};
+
+/// The raw value of the `Range` request header, if present.
+pub(crate) struct RangeHeader(Option<String>);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for RangeHeader {
+    type Error = std::convert::Infallible;
+
+    async fn from_request(req: &'r rocket::Request<'_>) -> Outcome<Self, Self::Error> {
+        Outcome::Success(RangeHeader(
+            req.headers().get_one("Range").map(|range| range.to_owned()),
+        ))
+    }
+}
+
+/// Result of evaluating a `Range` header against the size of the requested file.
+#[derive(Debug, PartialEq)]
+enum ByteRange {
+    /// The whole file shall be served, either because no or an unsupported range was requested.
+    Full,
+
+    /// The inclusive byte range `start..=end` shall be served.
+    Partial { start: u64, end: u64 },
+
+    /// The requested range lies outside of the file.
+    Unsatisfiable,
+}
+
+/// Evaluates a `Range` header value for a file of `len` bytes.
+///
+/// Supports a single range of the forms `bytes=<start>-<end>`, `bytes=<start>-` and
+/// `bytes=-<suffix length>`. Malformed headers and multiple ranges are ignored, so the
+/// whole file is served as permitted by RFC 9110.
+fn parse_range(header: &str, len: u64) -> ByteRange {
+    let Some(spec) = header.trim().strip_prefix("bytes=") else {
+        return ByteRange::Full;
+    };
+    if spec.contains(',') {
+        return ByteRange::Full;
+    }
+    let Some((start, end)) = spec.split_once('-') else {
+        return ByteRange::Full;
+    };
+    let (start, end) = (start.trim(), end.trim());
+    if start.is_empty() {
+        return match end.parse::<u64>() {
+            Ok(0) => ByteRange::Unsatisfiable,
+            Ok(_) if len == 0 => ByteRange::Unsatisfiable,
+            Ok(suffix) => ByteRange::Partial {
+                start: len.saturating_sub(suffix),
+                end: len - 1,
+            },
+            Err(_) => ByteRange::Full,
+        };
+    }
+    let Ok(start) = start.parse::<u64>() else {
+        return ByteRange::Full;
+    };
+    let end = if end.is_empty() {
+        u64::MAX
+    } else {
+        match end.parse::<u64>() {
+            Ok(end) if end >= start => end,
+            _ => return ByteRange::Full,
+        }
+    };
+    if start >= len {
+        return ByteRange::Unsatisfiable;
+    }
+    ByteRange::Partial {
+        start,
+        end: end.min(len - 1),
+    }
+}
+
+/// A (possibly partial) raw session file response.
+pub(crate) struct RawSession {
+    status: Status,
+    content_range: Option<String>,
+    gzip: bool,
+    body: Vec<u8>,
+}
+
+impl<'r> Responder<'r, 'static> for RawSession {
+    fn respond_to(self, _: &'r rocket::Request<'_>) -> response::Result<'static> {
+        let mut builder = Response::build();
+        builder
+            .status(self.status)
+            .header(ContentType::JSON)
+            .raw_header("Accept-Ranges", "bytes")
+            .sized_body(self.body.len(), Cursor::new(self.body));
+        if let Some(content_range) = self.content_range {
+            builder.raw_header("Content-Range", content_range);
+        }
+        if self.gzip {
+            builder.raw_header("Content-Encoding", "gzip");
+        }
+        builder.ok()
+    }
+}
+
+/// Requests the path of the raw session file from the storage and waits for the response.
+async fn request_session_file_path(
+    id: &str,
+    ctx: &Arc<Mutex<RestCtx>>,
+) -> Result<PathBuf, std::io::ErrorKind> {
+    match RestCtx::request(
+        ctx,
+        EventKindType::SessionFilePathResponseEvent,
+        |req_id, addr| {
+            EventKind::SessionFilePathRequestEvent(Request::new(req_id, addr, id.into()))
+        },
+    )
+    .await
+    {
+        Ok(event) => match payload_ref!(event.kind, EventKind::SessionFilePathResponseEvent) {
+            Some(resp) => resp.data.clone(),
+            None => {
+                error!("Received invalid SessionFilePathResponseEvent payload");
+                Err(std::io::ErrorKind::InvalidData)
+            }
+        },
+        Err(e) => {
+            error!(
+                "Error while waiting for SessionFilePathResponseEvent: {:?}",
+                e
+            );
+            Err(std::io::ErrorKind::TimedOut)
+        }
+    }
+}
+
+/// Reads the byte range `start..end` from the file at `path`.
+async fn read_range(path: &PathBuf, start: u64, end: u64) -> std::io::Result<Vec<u8>> {
+    let mut file = tokio::fs::File::open(path).await?;
+    file.seek(SeekFrom::Start(start)).await?;
+    let mut body = Vec::with_capacity((end - start) as usize);
+    file.take(end - start).read_to_end(&mut body).await?;
+    Ok(body)
+}
+
+/// Downloads the raw stored file of a session.
+///
+/// Route: GET /v1/sessions/<id>/raw
+///
+/// Honors a single byte range in the `Range` header and answers with
+/// `206 Partial Content` and a matching `Content-Range` header. Without a
+/// (supported) range the whole file is returned with `200 OK`. Finalized sessions are stored
+/// compressed and served with `Content-Encoding: gzip`, the ranges apply to the compressed file.
+///
+/// Errors:
+/// - 404 if the session doesn't exist.
+/// - 416 if the requested range lies outside of the file.
+/// - 500 if the file can't be read.
+#[get("/v1/sessions/<id>/raw")]
+pub(crate) async fn get_raw_session(
+    id: &str,
+    range: RangeHeader,
+    ctx: &State<Arc<Mutex<RestCtx>>>,
+) -> Result<RawSession, Status> {
+    let path = request_session_file_path(id, ctx)
+        .await
+        .map_err(storage_error_status)?;
+    let gzip = path.extension().is_some_and(|ext| ext == "gz");
+    let len = tokio::fs::metadata(&path)
+        .await
+        .map_err(|e| {
+            error!("Failed to read metadata of {:?}: {}", path, e);
+            Status::InternalServerError
+        })?
+        .len();
+    let range = range
+        .0
+        .map_or(ByteRange::Full, |header| parse_range(&header, len));
+    let (status, start, end, content_range) = match range {
+        ByteRange::Full => (Status::Ok, 0, len, None),
+        ByteRange::Partial { start, end } => (
+            Status::PartialContent,
+            start,
+            end + 1,
+            Some(format!("bytes {start}-{end}/{len}")),
+        ),
+        ByteRange::Unsatisfiable => {
+            return Ok(RawSession {
+                status: Status::RangeNotSatisfiable,
+                content_range: Some(format!("bytes */{len}")),
+                gzip,
+                body: vec![],
+            });
+        }
+    };
+    let body = read_range(&path, start, end).await.map_err(|e| {
+        error!("Failed to read session file {:?}: {}", path, e);
+        Status::InternalServerError
+    })?;
+    Ok(RawSession {
+        status,
+        content_range,
+        gzip,
+        body,
+    })
+}
@@ -0,0 +1,105 @@
+// SPDX-FileCopyrightText: 2026 All contributors
+//
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use crate::{RestCtx, request_session, request_session_ids};
+use chrono::NaiveDateTime;
+use common::{id::SessionId, position::Position, serde::duration};
+use rocket::{
+    State,
+    serde::{Serialize, json::Json},
+};
+use std::{sync::Arc, time::Duration};
+use tokio::sync::Mutex;
+
+/// Number of laps returned if no limit is requested.
+const DEFAULT_LIMIT: usize = 10;
+
+/// Maximum number of laps that can be requested at once.
+const MAX_LIMIT: usize = 100;
+
+/// Highest velocity of a lap and the position it was reached at.
+#[derive(Debug, Serialize)]
+#[serde(crate = "rocket::serde")]
+pub(crate) struct MaxSpeed {
+    velocity: f64,
+    position: Position,
+}
+
+/// A lap of a stored session.
+#[derive(Debug, Serialize)]
+#[serde(crate = "rocket::serde")]
+pub(crate) struct RecentLap {
+    session_id: SessionId,
+    lap_index: usize,
+    #[serde(with = "duration")]
+    time: Duration,
+    date: NaiveDateTime,
+    /// Omitted for laps without log points.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_speed: Option<MaxSpeed>,
+}
+
+/// Response structure for the recent laps.
+#[derive(Debug, Serialize)]
+#[serde(crate = "rocket::serde")]
+pub(crate) struct RecentLapsResponse {
+    total: usize,
+    laps: Vec<RecentLap>,
+}
+
+/// Returns the most recent laps across all stored sessions.
+///
+/// Route: GET /v1/laps/recent?limit=<limit>
+///
+/// The laps are ordered by recency, the most recent lap first. Sessions are loaded from
+/// the newest to the oldest until enough laps are collected, so only as many sessions as
+/// needed are loaded. `limit` defaults to 10 and is capped at 100.
+///
+/// Sessions that can't be loaded are skipped.
+#[get("/v1/laps/recent?<limit>")]
+pub(crate) async fn get_recent_laps(
+    limit: Option<usize>,
+    ctx: &State<Arc<Mutex<RestCtx>>>,
+) -> Json<RecentLapsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT);
+    let mut infos = (*request_session_ids(ctx).await).clone();
+    infos.sort_by_key(|info| std::cmp::Reverse(info.date));
+
+    let mut laps = Vec::with_capacity(limit);
+    for info in infos.iter().filter(|info| info.laps > 0) {
+        if laps.len() >= limit {
+            break;
+        }
+        let session = match request_session(info.id.as_str(), ctx).await {
+            Ok(session) => session,
+            Err(e) => {
+                error!(
+                    "Failed to load session {} for recent laps: {:?}",
+                    info.id, e
+                );
+                continue;
+            }
+        };
+        let session = session.read().unwrap_or_else(|e| e.into_inner());
+        let date = NaiveDateTime::new(session.date, session.time);
+        for (lap_index, lap) in session.laps.iter().enumerate().rev() {
+            if laps.len() >= limit {
+                break;
+            }
+            laps.push(RecentLap {
+                session_id: info.id.clone(),
+                lap_index,
+                time: lap.sectors.iter().sum(),
+                date,
+                max_speed: lap
+                    .max_speed()
+                    .map(|(velocity, position)| MaxSpeed { velocity, position }),
+            });
+        }
+    }
+    Json(RecentLapsResponse {
+        total: laps.len(),
+        laps,
+    })
+}
@@ -0,0 +1,66 @@
+// SPDX-FileCopyrightText: 2026 All contributors
+//
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use crate::{RestCtx, lap_export::load_session};
+use common::serde::duration_list;
+use rocket::{
+    State,
+    http::Status,
+    serde::{Serialize, json::Json},
+};
+use std::{sync::Arc, time::Duration};
+use tokio::sync::Mutex;
+
+/// Response structure for the rolling average lap times of a session.
+#[derive(Debug, Serialize)]
+#[serde(crate = "rocket::serde")]
+pub(crate) struct RollingAverageResponse {
+    window: usize,
+    #[serde(with = "duration_list")]
+    averages: Vec<Duration>,
+}
+
+/// Returns the average lap times of every `window` consecutive laps.
+///
+/// The average at index `i` covers the laps `i` to `i + window - 1`, so a session with `n`
+/// laps has `n - window + 1` averages and none if `window` exceeds the number of laps.
+fn rolling_averages(laptimes: &[Duration], window: usize) -> Vec<Duration> {
+    laptimes
+        .windows(window)
+        .map(|laps| laps.iter().sum::<Duration>() / window as u32)
+        .collect()
+}
+
+/// Returns the rolling average lap time of a session, e.g. to analyze the consistency.
+///
+/// Route: GET /v1/sessions/<id>/rolling?<window>
+///
+/// Errors:
+/// - 400 if the window is 0.
+/// - 404 if the session doesn't exist.
+/// - 500 if the session can't be loaded.
+#[get("/v1/sessions/<id>/rolling?<window>")]
+pub(crate) async fn get_rolling_average(
+    id: &str,
+    window: usize,
+    ctx: &State<Arc<Mutex<RestCtx>>>,
+) -> Result<Json<RollingAverageResponse>, Status> {
+    if window == 0 {
+        return Err(Status::BadRequest);
+    }
+    let session = load_session(id, ctx).await?;
+    let session = session.read().map_err(|e| {
+        error!("Failed to acquire read lock on session {}: {}", id, e);
+        Status::InternalServerError
+    })?;
+    let laptimes: Vec<Duration> = session
+        .laps
+        .iter()
+        .map(|lap| lap.sectors.iter().sum())
+        .collect();
+    Ok(Json(RollingAverageResponse {
+        window,
+        averages: rolling_averages(&laptimes, window),
+    }))
+}
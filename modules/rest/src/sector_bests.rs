@@ -0,0 +1,84 @@
+// SPDX-FileCopyrightText: 2026 All contributors
+//
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use common::session::Session;
+use serde::Serialize;
+use std::time::Duration;
+
+/// Classification of a finished sector against the prior times of the same sector.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum SectorClassification {
+    /// Faster than every prior time of the sector in the session, or its first time.
+    SessionBest,
+
+    /// Faster than the previous time of the sector, but not the session best.
+    PersonalBest,
+
+    /// Neither, or the sector is unknown because the lap started before the connection.
+    None,
+}
+
+/// Best and previous time of a sector.
+#[derive(Clone, Copy, Debug)]
+struct SectorTimes {
+    best: Duration,
+    previous: Duration,
+}
+
+/// Tracks the sector times of a live session to classify finished sectors.
+#[derive(Debug, Default)]
+pub(crate) struct SectorBests {
+    sectors: Vec<SectorTimes>,
+    current: Option<usize>,
+}
+
+impl SectorBests {
+    /// Creates a tracker with the sector times of the finished laps of a session.
+    ///
+    /// The sector of the running lap is unknown until the next lap starts.
+    pub(crate) fn from_session(session: &Session) -> Self {
+        let mut bests = SectorBests::default();
+        for lap in &session.laps {
+            for (index, time) in lap.sectors.iter().enumerate() {
+                bests.record(index, *time);
+            }
+        }
+        bests
+    }
+
+    /// Marks the start of a new lap, the next finished sector is the first one.
+    pub(crate) fn lap_started(&mut self) {
+        self.current = Some(0);
+    }
+
+    /// Classifies the time of the finished sector and records it.
+    pub(crate) fn sector_finished(&mut self, time: Duration) -> SectorClassification {
+        let Some(index) = self.current else {
+            return SectorClassification::None;
+        };
+        self.current = Some(index + 1);
+        let classification = match self.sectors.get(index) {
+            None => SectorClassification::SessionBest,
+            Some(sector) if time < sector.best => SectorClassification::SessionBest,
+            Some(sector) if time < sector.previous => SectorClassification::PersonalBest,
+            Some(_) => SectorClassification::None,
+        };
+        self.record(index, time);
+        classification
+    }
+
+    fn record(&mut self, index: usize, time: Duration) {
+        match self.sectors.get_mut(index) {
+            Some(sector) => {
+                sector.best = sector.best.min(time);
+                sector.previous = time;
+            }
+            None => self.sectors.push(SectorTimes {
+                best: time,
+                previous: time,
+            }),
+        }
+    }
+}
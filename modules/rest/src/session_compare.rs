@@ -0,0 +1,109 @@
+// SPDX-FileCopyrightText: 2026 All contributors
+//
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use crate::{RestCtx, lap_export::load_session};
+use common::{format::format_lap_time, session::Session};
+use rocket::{
+    State,
+    http::Status,
+    serde::{Serialize, json::Json},
+};
+use std::{sync::Arc, time::Duration};
+use tokio::sync::Mutex;
+
+/// Lap time statistics of one of the compared sessions.
+#[derive(Debug, Serialize)]
+#[serde(crate = "rocket::serde")]
+pub(crate) struct SessionSummary {
+    id: String,
+    track: String,
+    laps: usize,
+    /// Omitted for sessions without laps.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    best_lap: Option<String>,
+    /// Omitted for sessions without laps.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    average_lap: Option<String>,
+}
+
+/// Response structure for the comparison of two sessions.
+#[derive(Debug, Serialize)]
+#[serde(crate = "rocket::serde")]
+pub(crate) struct SessionCompareResponse {
+    a: SessionSummary,
+    b: SessionSummary,
+    same_track: bool,
+    /// Delta of the best lap of session `b` to the one of session `a` in seconds, positive
+    /// if session `b` is slower. Omitted if one of the sessions has no laps.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    best_lap_delta: Option<f64>,
+}
+
+/// Returns the best and the average lap time of a session, `None` for sessions without laps.
+fn lap_statistics(session: &Session) -> Option<(Duration, Duration)> {
+    let laptimes: Vec<Duration> = session
+        .laps
+        .iter()
+        .map(|lap| lap.sectors.iter().sum())
+        .collect();
+    let best = *laptimes.iter().min()?;
+    let average = laptimes.iter().sum::<Duration>() / laptimes.len() as u32;
+    Some((best, average))
+}
+
+/// Loads a session and summarizes its lap times, also returns the best lap time.
+async fn summarize(
+    id: &str,
+    ctx: &Arc<Mutex<RestCtx>>,
+) -> Result<(SessionSummary, Option<Duration>), Status> {
+    let session = load_session(id, ctx).await?;
+    let session = session.read().map_err(|e| {
+        error!("Failed to acquire read lock on session {}: {}", id, e);
+        Status::InternalServerError
+    })?;
+    let statistics = lap_statistics(&session);
+    let summary = SessionSummary {
+        id: id.to_string(),
+        track: session.track.name.clone(),
+        laps: session.laps.len(),
+        best_lap: statistics.map(|(best, _)| format_lap_time(&best)),
+        average_lap: statistics.map(|(_, average)| format_lap_time(&average)),
+    };
+    Ok((summary, statistics.map(|(best, _)| best)))
+}
+
+/// Compares the lap times of two whole sessions, e.g. of two track days.
+///
+/// Route: GET /v1/sessions/compare?<a>&<b>
+///
+/// Sessions on different tracks are still compared, but `same_track` is `false`.
+///
+/// Errors:
+/// - 404 if one of the sessions doesn't exist.
+/// - 500 if one of the sessions can't be loaded.
+#[get("/v1/sessions/compare?<a>&<b>")]
+pub(crate) async fn get_session_compare(
+    a: &str,
+    b: &str,
+    ctx: &State<Arc<Mutex<RestCtx>>>,
+) -> Result<Json<SessionCompareResponse>, Status> {
+    let (summary_a, best_a) = summarize(a, ctx).await?;
+    let (summary_b, best_b) = summarize(b, ctx).await?;
+    let same_track = summary_a.track == summary_b.track;
+    if !same_track {
+        warn!(
+            "Comparing session {} on track {} with session {} on track {}",
+            a, summary_a.track, b, summary_b.track
+        );
+    }
+    let best_lap_delta = best_a
+        .zip(best_b)
+        .map(|(best_a, best_b)| best_b.as_secs_f64() - best_a.as_secs_f64());
+    Ok(Json(SessionCompareResponse {
+        a: summary_a,
+        b: summary_b,
+        same_track,
+        best_lap_delta,
+    }))
+}
@@ -0,0 +1,159 @@
+// SPDX-FileCopyrightText: 2026 All contributors
+//
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use crate::{RequestRetry, RestCtx};
+use common::{id::SessionId, position::Position, session::Session};
+use module_core::{EventKind, EventKindType, Request, payload_ref};
+use rocket::{
+    Data, Route, State,
+    data::ToByteUnit,
+    http::Status,
+    response::status::Created,
+    serde::{Serialize, json::Json},
+};
+use std::sync::{Arc, RwLock};
+use tokio::sync::Mutex;
+
+/// Maximum accepted size of an imported session in mebibytes.
+const MAX_IMPORT_SIZE_MIB: u64 = 32;
+
+/// Response structure for an imported session.
+#[derive(Debug, Serialize)]
+#[serde(crate = "rocket::serde")]
+pub(crate) struct ImportSessionResponse {
+    id: SessionId,
+}
+
+/// Returns `true` if the coordinates are valid WGS84 degrees.
+fn is_valid_coordinate(latitude: f64, longitude: f64) -> bool {
+    (-90.0..=90.0).contains(&latitude) && (-180.0..=180.0).contains(&longitude)
+}
+
+/// Validates a session that shall be imported.
+///
+/// The track needs a name and valid coordinates for all of its points. Every lap needs at
+/// least one sector time and valid coordinates for all of its log points.
+///
+/// Returns a description of the first problem found.
+fn validate_session(session: &Session) -> Result<(), String> {
+    let track = &session.track;
+    if track.name.trim().is_empty() {
+        return Err("track name is empty".to_string());
+    }
+    let track_points: Vec<&Position> = std::iter::once(&track.startline)
+        .chain(track.finishline.iter())
+        .chain(track.sectors.iter())
+        .collect();
+    if let Some(point) = track_points
+        .iter()
+        .find(|point| !is_valid_coordinate(point.latitude, point.longitude))
+    {
+        return Err(format!("invalid track coordinate {point:?}"));
+    }
+    for (index, lap) in session.laps.iter().enumerate() {
+        if lap.sectors.is_empty() {
+            return Err(format!("lap {index} has no sector times"));
+        }
+        if let Some(point) = lap
+            .log_points
+            .iter()
+            .find(|point| !is_valid_coordinate(point.latitude(), point.longitude()))
+        {
+            return Err(format!("lap {index} has invalid log point {point:?}"));
+        }
+    }
+    Ok(())
+}
+
+/// Imports the session via the storage and waits for the response.
+///
+/// The import isn't idempotent, so it is attempted only once. Fails with
+/// [`std::io::ErrorKind::AlreadyExists`] if the storage already has a session with the same id.
+async fn request_import_session(
+    session: Session,
+    ctx: &Arc<Mutex<RestCtx>>,
+) -> Result<SessionId, std::io::ErrorKind> {
+    let session = Arc::new(RwLock::new(session));
+    match RestCtx::request_with(
+        ctx,
+        RequestRetry::once,
+        EventKindType::ImportSessionResponseEvent,
+        |req_id, addr| {
+            EventKind::ImportSessionRequestEvent(Request::new(req_id, addr, session.clone()))
+        },
+    )
+    .await
+    {
+        Ok(event) => match payload_ref!(event.kind, EventKind::ImportSessionResponseEvent) {
+            Some(resp) => resp.data.clone(),
+            None => {
+                error!("Received invalid ImportSessionResponseEvent payload");
+                Err(std::io::ErrorKind::InvalidData)
+            }
+        },
+        Err(e) => {
+            error!(
+                "Error while waiting for ImportSessionResponseEvent: {:?}",
+                e
+            );
+            Err(std::io::ErrorKind::TimedOut)
+        }
+    }
+}
+
+/// Imports a session, e.g. when migrating data from another device.
+///
+/// Route: POST /v1/sessions/import
+///
+/// The body is a full session JSON object as returned by `GET /v1/sessions/<id>`.
+/// The session is validated and stored, and the id of the stored session is returned
+/// with `201 Created`.
+///
+/// Errors:
+/// - 400 if the body is no valid session or the session fails validation.
+/// - 409 if a session with the same id is already stored, it isn't overwritten.
+/// - 413 if the body exceeds the maximum import size.
+/// - 500 if the session can't be stored.
+#[post("/v1/sessions/import", data = "<body>")]
+pub(crate) async fn import_session(
+    body: Data<'_>,
+    route: &Route,
+    ctx: &State<Arc<Mutex<RestCtx>>>,
+) -> Result<Created<Json<ImportSessionResponse>>, Status> {
+    let body = body
+        .open(MAX_IMPORT_SIZE_MIB.mebibytes())
+        .into_string()
+        .await
+        .map_err(|e| {
+            debug!("Failed to read session import body: {}", e);
+            Status::BadRequest
+        })?;
+    if !body.is_complete() {
+        return Err(Status::PayloadTooLarge);
+    }
+    let session = Session::from_json(&body).map_err(|e| {
+        debug!("Rejected session import with malformed body: {}", e);
+        Status::BadRequest
+    })?;
+    validate_session(&session).map_err(|e| {
+        debug!("Rejected invalid session import: {}", e);
+        Status::BadRequest
+    })?;
+    let id = request_import_session(session, ctx)
+        .await
+        .map_err(|e| match e {
+            std::io::ErrorKind::AlreadyExists => {
+                debug!("Rejected import of an already stored session");
+                Status::Conflict
+            }
+            e => {
+                error!("Failed to store imported session: {:?}", e);
+                Status::InternalServerError
+            }
+        })?;
+    info!("Imported session with id {}", id);
+    let base_path = route.uri.base().trim_end_matches('/');
+    Ok(Created::new(format!("{base_path}/v1/sessions/{id}"))
+        .body(Json(ImportSessionResponse { id })))
+}
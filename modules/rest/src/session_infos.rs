@@ -0,0 +1,42 @@
+// SPDX-FileCopyrightText: 2026 All contributors
+//
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use crate::{RestCtx, SessionIdsResponse, request_session_ids};
+use rocket::{
+    State,
+    serde::{Deserialize, json::Json},
+};
+use std::{collections::HashSet, sync::Arc};
+use tokio::sync::Mutex;
+
+/// Request structure for fetching the infos of selected sessions.
+#[derive(Debug, Deserialize)]
+#[serde(crate = "rocket::serde")]
+pub(crate) struct SessionInfosRequest {
+    ids: Vec<String>,
+}
+
+/// Retrieves the infos of the sessions with the given IDs.
+///
+/// Route: POST /v1/sessions/info
+///
+/// The body is a JSON object with the list of requested session IDs. The infos are
+/// returned in the same format and order as by `GET /v1/sessions`, unknown IDs are skipped.
+#[post("/v1/sessions/info", format = "json", data = "<request>")]
+pub(crate) async fn get_session_infos(
+    request: Json<SessionInfosRequest>,
+    ctx: &State<Arc<Mutex<RestCtx>>>,
+) -> Json<SessionIdsResponse> {
+    let ids: HashSet<&str> = request.ids.iter().map(String::as_str).collect();
+    let sessions: Vec<_> = request_session_ids(ctx)
+        .await
+        .iter()
+        .filter(|info| ids.contains(info.id.as_str()))
+        .cloned()
+        .collect();
+    Json(SessionIdsResponse {
+        total: sessions.len(),
+        sessions,
+    })
+}
@@ -0,0 +1,34 @@
+// SPDX-FileCopyrightText: 2026 All contributors
+//
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use crate::RestCtx;
+use common::position::GnssPosition;
+use module_core::EventKind;
+use rocket::{State, http::Status, serde::json::Json};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Publishes the posted position as [`EventKind::GnssPositionEvent`].
+///
+/// Injects positions into the pipeline as if they were received from a GNSS receiver, e.g.
+/// for black-box tests without a GPS. Only mounted if the simulation is enabled in the
+/// [`crate::RestConfig`].
+#[post("/v1/sim/position", format = "json", data = "<position>")]
+pub(crate) async fn post_sim_position(
+    position: Json<GnssPosition>,
+    ctx: &State<Arc<Mutex<RestCtx>>>,
+) -> Status {
+    let ctx_lock = ctx.lock().await;
+    match ctx_lock
+        .ctx
+        .publish_event(EventKind::GnssPositionEvent(Arc::new(
+            position.into_inner(),
+        ))) {
+        Ok(()) => Status::NoContent,
+        Err(e) => {
+            error!("Failed to publish simulated position. Error: {e:?}");
+            Status::InternalServerError
+        }
+    }
+}
@@ -0,0 +1,151 @@
+// SPDX-FileCopyrightText: 2026 All contributors
+//
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use crate::{RestCtx, error::storage_error_status};
+use common::{
+    position::Position,
+    track::{SectorDistance, Track},
+};
+use module_core::{EventKind, EventKindType, Request, payload_ref};
+use rocket::{
+    State,
+    http::Status,
+    serde::{Serialize, json::Json},
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Requests a stored track from the storage and waits for the response.
+async fn request_track(id: &str, ctx: &Arc<Mutex<RestCtx>>) -> Result<Track, std::io::ErrorKind> {
+    match RestCtx::request(
+        ctx,
+        EventKindType::LoadTrackResponseEvent,
+        |req_id, addr| EventKind::LoadTrackRequestEvent(Request::new(req_id, addr, id.into())),
+    )
+    .await
+    {
+        Ok(event) => match payload_ref!(event.kind, EventKind::LoadTrackResponseEvent) {
+            Some(resp) => resp.data.clone(),
+            None => {
+                error!("Received invalid LoadTrackResponseEvent payload");
+                Err(std::io::ErrorKind::InvalidData)
+            }
+        },
+        Err(e) => {
+            error!("Error while waiting for LoadTrackResponseEvent: {:?}", e);
+            Err(std::io::ErrorKind::TimedOut)
+        }
+    }
+}
+
+/// Saves the track under the given id via the storage and waits for the response.
+async fn request_save_track(
+    id: &str,
+    track: Track,
+    ctx: &Arc<Mutex<RestCtx>>,
+) -> Result<(), std::io::ErrorKind> {
+    match RestCtx::request(
+        ctx,
+        EventKindType::SaveTrackResponseEvent,
+        |req_id, addr| {
+            EventKind::SaveTrackRequestEvent(Request::new(req_id, addr, (id.into(), track.clone())))
+        },
+    )
+    .await
+    {
+        Ok(event) => match payload_ref!(event.kind, EventKind::SaveTrackResponseEvent) {
+            Some(resp) => resp.data,
+            None => {
+                error!("Received invalid SaveTrackResponseEvent payload");
+                Err(std::io::ErrorKind::InvalidData)
+            }
+        },
+        Err(e) => {
+            error!("Error while waiting for SaveTrackResponseEvent: {:?}", e);
+            Err(std::io::ErrorKind::TimedOut)
+        }
+    }
+}
+
+/// Retrieves a stored track by its ID.
+///
+/// Route: GET /v1/tracks/<id>
+///
+/// Errors:
+/// - 404 if the track doesn't exist.
+/// - 500 if the track can't be loaded.
+#[get("/v1/tracks/<id>")]
+pub(crate) async fn get_track(
+    id: &str,
+    ctx: &State<Arc<Mutex<RestCtx>>>,
+) -> Result<Json<Track>, Status> {
+    request_track(id, ctx)
+        .await
+        .map(Json)
+        .map_err(storage_error_status)
+}
+
+/// Response structure for the sectors of a track with their distances.
+#[derive(Debug, Serialize)]
+#[serde(crate = "rocket::serde")]
+pub(crate) struct TrackSectorsResponse {
+    length_meters: f64,
+    sectors: Vec<SectorDistance>,
+}
+
+/// Retrieves the sectors of a stored track with their lengths and distances from the start.
+///
+/// Route: GET /v1/tracks/<id>/sectors
+///
+/// The distances are derived from the track geometry with [`Track::sector_distances`],
+/// the last sector ends at the finish line.
+///
+/// Errors:
+/// - 404 if the track doesn't exist.
+/// - 500 if the track can't be loaded.
+#[get("/v1/tracks/<id>/sectors")]
+pub(crate) async fn get_track_sectors(
+    id: &str,
+    ctx: &State<Arc<Mutex<RestCtx>>>,
+) -> Result<Json<TrackSectorsResponse>, Status> {
+    let track = request_track(id, ctx).await.map_err(storage_error_status)?;
+    Ok(Json(TrackSectorsResponse {
+        length_meters: track.length_meters(),
+        sectors: track.sector_distances(),
+    }))
+}
+
+/// Replaces the sectors of a stored track, e.g. to fine-tune a learned track.
+///
+/// Route: PUT /v1/tracks/<id>/sectors
+///
+/// The body is the JSON array of the new sector positions. The sectors are validated
+/// with [`Track::validate_sectors`], the track is stored again and the updated track
+/// is returned.
+///
+/// Errors:
+/// - 400 if the sectors fail validation.
+/// - 404 if the track doesn't exist.
+/// - 500 if the track can't be loaded or stored.
+#[put("/v1/tracks/<id>/sectors", format = "json", data = "<sectors>")]
+pub(crate) async fn put_track_sectors(
+    id: &str,
+    sectors: Json<Vec<Position>>,
+    ctx: &State<Arc<Mutex<RestCtx>>>,
+) -> Result<Json<Track>, Status> {
+    let mut track = request_track(id, ctx).await.map_err(storage_error_status)?;
+    track.sectors = sectors.into_inner();
+    track.validate_sectors().map_err(|e| {
+        debug!("Rejected sectors of track {}: {}", id, e);
+        Status::BadRequest
+    })?;
+    request_save_track(id, track.clone(), ctx)
+        .await
+        .map_err(|e| {
+            error!("Failed to store track {}: {:?}", id, e);
+            Status::InternalServerError
+        })?;
+    info!("Updated sectors of track {}", id);
+    Ok(Json(track))
+}
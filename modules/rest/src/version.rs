@@ -0,0 +1,73 @@
+// SPDX-FileCopyrightText: 2026 All contributors
+//
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use crate::RestConfig;
+use rocket::{
+    State,
+    serde::{Serialize, json::Json},
+};
+
+/// Features provided by the routes that are always mounted.
+///
+/// Must be extended when a route for a new feature is mounted, so clients can
+/// negotiate the supported functionality.
+const FEATURES: &[&str] = &[
+    "sessions",
+    "raw_session",
+    "lap_export",
+    "session_import",
+    "recent_laps",
+    "metrics",
+    "laptimer_state",
+    "live_session",
+    "live_session_schema",
+    "diag_modules",
+    "track_sectors",
+    "session_infos",
+    "lap_points",
+    "track_sector_distances",
+    "lap_compare",
+    "error_body",
+    "lap_points_ndjson",
+    "rolling_average",
+    "lap_elevation",
+    "fit_export",
+    "session_compare",
+];
+
+/// Features of the routes mounted for the [`RestConfig`] at launch, advertised by
+/// [`get_version`].
+#[derive(Clone, Debug)]
+pub(crate) struct Features(Vec<&'static str>);
+
+impl Features {
+    /// Returns the [`FEATURES`] and the features of the routes enabled in the configuration,
+    /// e.g. `simulation` for the simulation routes.
+    pub(crate) fn new(config: &RestConfig) -> Self {
+        let mut features = FEATURES.to_vec();
+        if config.simulation {
+            features.push("simulation");
+        }
+        Features(features)
+    }
+}
+
+/// Response structure for the server version and its capabilities.
+#[derive(Debug, Serialize)]
+#[serde(crate = "rocket::serde")]
+pub(crate) struct VersionResponse {
+    version: &'static str,
+    features: Vec<&'static str>,
+}
+
+/// Returns the server version and the supported features.
+///
+/// Route: GET /v1/version
+#[get("/v1/version")]
+pub(crate) fn get_version(features: &State<Features>) -> Json<VersionResponse> {
+    Json(VersionResponse {
+        version: env!("CARGO_PKG_VERSION"),
+        features: features.0.clone(),
+    })
+}
@@ -3,17 +3,47 @@
 // SPDX-License-Identifier: GPL-2.0-or-later
 
 use module_core::{Module, ModuleCtx};
-use rest::Rest;
-use tokio::task::JoinHandle;
+use rest::{Rest, RestConfig};
+use std::time::Duration;
+use tokio::{net::TcpStream, task::JoinHandle};
 
 /// Creates and runs the REST module in a separate Tokio task.
+///
+/// Waits until the REST server accepts connections before returning, so tests
+/// don't race the server startup.
 /// # Arguments
 /// * `ctx` - The module context to be used by the REST module.
 /// # Returns
 /// A JoinHandle that resolves to a Result indicating the success or failure of the module's execution
-pub fn create_module(ctx: ModuleCtx) -> JoinHandle<Result<(), ()>> {
-    tokio::spawn(async move {
-        let mut rest = Rest::new(ctx);
+pub async fn create_module(ctx: ModuleCtx) -> JoinHandle<Result<(), ()>> {
+    create_module_with_config(ctx, RestConfig::default()).await
+}
+
+/// Creates and runs the REST module with a custom configuration in a separate Tokio task.
+///
+/// Waits until the REST server accepts connections before returning.
+#[allow(dead_code)]
+pub async fn create_module_with_config(
+    ctx: ModuleCtx,
+    config: RestConfig,
+) -> JoinHandle<Result<(), ()>> {
+    let handle = tokio::spawn(async move {
+        let mut rest = Rest::new_with_config(ctx, config);
         rest.run().await
-    })
+    });
+    wait_for_server(27015).await;
+    handle
+}
+
+/// Polls the given local port until the REST server accepts connections.
+///
+/// Panics if the server is not reachable within one second.
+pub async fn wait_for_server(port: u16) {
+    for _ in 0..100 {
+        if TcpStream::connect(("127.0.0.1", port)).await.is_ok() {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("REST server on port {port} not reachable");
 }
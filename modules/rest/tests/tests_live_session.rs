@@ -4,36 +4,36 @@
 
 mod test_utils;
 
-use common::test_helper::session::get_session;
+use chrono::{NaiveDate, NaiveTime};
+use common::{format::format_lap_time, position::GnssPosition, test_helper::session::get_session};
 use futures_util::{StreamExt, stream::SplitStream};
 use module_core::{
-    Event, EventBus, EventKind, EventKindType, Response,
-    test_helper::stop_module,
+    Event, EventBus, EventKind, EventKindType, LapFinished, Response,
     test_helper::{register_response_event, unregister_response_event},
+    test_helper::{stop_module, wait_for_event},
 };
+use rest::RestConfig;
 use serial_test::serial;
 use std::{
     sync::{Arc, RwLock},
     time::Duration,
 };
-use test_utils::create_module;
+use test_utils::{create_module, create_module_with_config};
 use tokio_tungstenite::{WebSocketStream, connect_async, tungstenite::Message};
 
-fn get_current_laptime_msg(laptime: Duration, event: &str) -> serde_json::Value {
+fn get_current_laptime_msg(laptime: Duration, event: &str, seq: u64) -> serde_json::Value {
     let event = format!(
-        r#"{{ "event": "{}", "data": {{ "time": "{:02}:{:02}:{:02}.{:03}" }} }}"#,
+        r#"{{ "event": "{}", "seq": {}, "data": {{ "time": "{}" }} }}"#,
         event,
-        laptime.as_secs() / 3600,
-        (laptime.as_secs() % 3600) / 60,
-        laptime.as_secs() % 60,
-        laptime.subsec_millis()
+        seq,
+        format_lap_time(&laptime)
     );
     serde_json::from_str(&event).unwrap()
 }
 
-fn get_lap_started_msg() -> serde_json::Value {
-    let event = r#"{"event": "lap_started", "data":{}}"#;
-    serde_json::from_str(event).unwrap()
+fn get_lap_started_msg(seq: u64) -> serde_json::Value {
+    let event = format!(r#"{{"event": "lap_started", "seq": {}, "data":{{}}}}"#, seq);
+    serde_json::from_str(&event).unwrap()
 }
 
 fn register_current_session_response_event(eb: &EventBus) {
@@ -77,7 +77,7 @@ fn unregister_current_session_response_event(eb: &EventBus) {
 #[serial]
 async fn test_current_laptime() {
     let eb = EventBus::default();
-    let mut rest = create_module(eb.context());
+    let mut rest = create_module(eb.context()).await;
     register_current_session_response_event(&eb);
 
     let (ws_stream, _) = connect_async("ws://localhost:27015/v1/live_session")
@@ -92,7 +92,7 @@ async fn test_current_laptime() {
     let msg = read_next_websocket_event(&mut read).await;
     match msg {
         tokio_tungstenite::tungstenite::Message::Text(text) => {
-            let expected = get_current_laptime_msg(Duration::from_millis(1), "current_laptime");
+            let expected = get_current_laptime_msg(Duration::from_millis(1), "current_laptime", 1);
             let msg = serde_json::from_slice::<serde_json::Value>(text.as_bytes()).unwrap();
             assert_eq!(msg, expected, "Laptime message does not match expected");
         }
@@ -108,7 +108,7 @@ async fn test_current_laptime() {
 #[serial]
 async fn test_lap_finished_event() {
     let eb = EventBus::default();
-    let mut rest = create_module(eb.context());
+    let mut rest = create_module(eb.context()).await;
     register_current_session_response_event(&eb);
 
     let (ws_stream, _) = connect_async("ws://localhost:27015/v1/live_session")
@@ -118,12 +118,12 @@ async fn test_lap_finished_event() {
     let _ = read_next_websocket_event(&mut read).await; // Consume the current_session event
 
     eb.publish(&Event {
-        kind: EventKind::LapFinishedEvent(Duration::from_millis(1).into()),
+        kind: EventKind::LapFinishedEvent(LapFinished::from(Duration::from_millis(1)).into()),
     });
     let msg = read_next_websocket_event(&mut read).await;
     match msg {
         tokio_tungstenite::tungstenite::Message::Text(text) => {
-            let expected = get_current_laptime_msg(Duration::from_millis(1), "lap_finished");
+            let expected = get_current_laptime_msg(Duration::from_millis(1), "lap_finished", 1);
             let msg = serde_json::from_slice::<serde_json::Value>(text.as_bytes()).unwrap();
             assert_eq!(msg, expected, "Laptime message does not match expected");
         }
@@ -139,7 +139,7 @@ async fn test_lap_finished_event() {
 #[serial]
 async fn test_sector_finished() {
     let eb = EventBus::default();
-    let mut rest = create_module(eb.context());
+    let mut rest = create_module(eb.context()).await;
     register_current_session_response_event(&eb);
 
     let (ws_stream, _) = connect_async("ws://localhost:27015/v1/live_session")
@@ -154,7 +154,12 @@ async fn test_sector_finished() {
     let msg = read_next_websocket_event(&mut read).await;
     match msg {
         tokio_tungstenite::tungstenite::Message::Text(text) => {
-            let expected = get_current_laptime_msg(Duration::from_millis(1), "sector_finished");
+            // Without a lap start since the connection the sector is unknown.
+            let expected = serde_json::json!({
+                "event": "sector_finished",
+                "seq": 1,
+                "data": { "time": "00:00:00.001", "classification": "none" }
+            });
             let msg = serde_json::from_slice::<serde_json::Value>(text.as_bytes()).unwrap();
             assert_eq!(msg, expected, "Laptime message does not match expected");
         }
@@ -165,12 +170,49 @@ async fn test_sector_finished() {
     stop_module(&eb, &mut rest).await;
 }
 
+#[tokio::test]
+#[test_log::test]
+#[serial]
+async fn test_approaching_finish() {
+    let eb = EventBus::default();
+    let mut rest = create_module(eb.context()).await;
+    register_current_session_response_event(&eb);
+
+    let (ws_stream, _) = connect_async("ws://localhost:27015/v1/live_session")
+        .await
+        .expect("Failed to connect to WebSocket");
+    let (_, mut read) = ws_stream.split();
+    let _ = read_next_websocket_event(&mut read).await; // Consume the current_session event
+
+    eb.publish(&Event {
+        kind: EventKind::ApproachingFinishEvent(87.5),
+    });
+    let msg = read_next_websocket_event(&mut read).await;
+    match msg {
+        tokio_tungstenite::tungstenite::Message::Text(text) => {
+            let expected: serde_json::Value = serde_json::from_str(
+                r#"{"event": "approaching_finish", "seq": 1, "data": {"distance": 87.5}}"#,
+            )
+            .unwrap();
+            let msg = serde_json::from_slice::<serde_json::Value>(text.as_bytes()).unwrap();
+            assert_eq!(
+                msg, expected,
+                "Approaching finish message does not match expected"
+            );
+        }
+        _ => panic!("Unexpected message type received. Msg: {:?}", msg),
+    }
+
+    unregister_current_session_response_event(&eb);
+    stop_module(&eb, &mut rest).await;
+}
+
 #[tokio::test]
 #[test_log::test]
 #[serial]
 async fn test_lap_started_event() {
     let eb = EventBus::default();
-    let mut rest = create_module(eb.context());
+    let mut rest = create_module(eb.context()).await;
     register_current_session_response_event(&eb);
 
     let (ws_stream, _) = connect_async("ws://localhost:27015/v1/live_session")
@@ -185,7 +227,7 @@ async fn test_lap_started_event() {
     let msg = read_next_websocket_event(&mut read).await;
     match msg {
         tokio_tungstenite::tungstenite::Message::Text(text) => {
-            let expected = get_lap_started_msg();
+            let expected = get_lap_started_msg(1);
             let msg = serde_json::from_slice::<serde_json::Value>(text.as_bytes()).unwrap();
             assert_eq!(msg, expected, "Laptime message does not match expected");
         }
@@ -201,7 +243,7 @@ async fn test_lap_started_event() {
 #[serial]
 async fn test_current_session_event_on_connect() {
     let eb = EventBus::default();
-    let mut rest = create_module(eb.context());
+    let mut rest = create_module(eb.context()).await;
     register_current_session_response_event(&eb);
 
     let (ws_stream, _) = connect_async("ws://localhost:27015/v1/live_session")
@@ -214,6 +256,7 @@ async fn test_current_session_event_on_connect() {
         tokio_tungstenite::tungstenite::Message::Text(text) => {
             let expected = serde_json::json!({
                 "event": "current_session",
+                "seq": 0,
                 "data": {
                     "session": get_session()
                 }
@@ -227,3 +270,419 @@ async fn test_current_session_event_on_connect() {
     unregister_current_session_response_event(&eb);
     stop_module(&eb, &mut rest).await;
 }
+
+#[tokio::test]
+#[test_log::test]
+#[serial]
+async fn test_resync_on_session_changed() {
+    let eb = EventBus::default();
+    let mut rest = create_module(eb.context()).await;
+    register_current_session_response_event(&eb);
+
+    let (ws_stream, _) = connect_async("ws://localhost:27015/v1/live_session")
+        .await
+        .expect("Failed to connect to WebSocket");
+    let (_, mut read) = ws_stream.split();
+    let _ = read_next_websocket_event(&mut read).await; // Consume the current_session event
+
+    let mut new_session = get_session();
+    new_session.track.name = "Most".to_string();
+    let expected = serde_json::json!({
+        "event": "current_session",
+        "seq": 1,
+        "data": {
+            "session": new_session
+        }
+    });
+    eb.publish(&Event {
+        kind: EventKind::SessionChangedEvent(Arc::new(RwLock::new(new_session))),
+    });
+    let msg = read_next_websocket_event(&mut read).await;
+    match msg {
+        tokio_tungstenite::tungstenite::Message::Text(text) => {
+            let msg = serde_json::from_slice::<serde_json::Value>(text.as_bytes()).unwrap();
+            assert_eq!(msg, expected, "Session message does not match expected");
+        }
+        _ => panic!("Unexpected message type received. Msg: {:?}", msg),
+    }
+
+    unregister_current_session_response_event(&eb);
+    stop_module(&eb, &mut rest).await;
+}
+
+#[tokio::test]
+#[test_log::test]
+#[serial]
+async fn test_consecutive_messages_carry_increasing_seq() {
+    let eb = EventBus::default();
+    let mut rest = create_module(eb.context()).await;
+    register_current_session_response_event(&eb);
+
+    let (ws_stream, _) = connect_async("ws://localhost:27015/v1/live_session")
+        .await
+        .expect("Failed to connect to WebSocket");
+    let (_, mut read) = ws_stream.split();
+
+    let events = [
+        EventKind::LapStartedEvent,
+        EventKind::CurrentLaptimeEvent(Duration::from_millis(1).into()),
+        EventKind::SectorFinishedEvent(Duration::from_millis(2).into()),
+        EventKind::LapFinishedEvent(LapFinished::from(Duration::from_millis(3)).into()),
+    ];
+    let mut seqs = Vec::new();
+    for index in 0..=events.len() {
+        if index > 0 {
+            eb.publish(&Event {
+                kind: events[index - 1].clone(),
+            });
+        }
+        match read_next_websocket_event(&mut read).await {
+            tokio_tungstenite::tungstenite::Message::Text(text) => {
+                let msg = serde_json::from_slice::<serde_json::Value>(text.as_bytes()).unwrap();
+                seqs.push(msg["seq"].as_u64().expect("Message without seq"));
+            }
+            msg => panic!("Unexpected message type received. Msg: {:?}", msg),
+        }
+    }
+    assert_eq!(seqs, vec![0, 1, 2, 3, 4]);
+
+    unregister_current_session_response_event(&eb);
+    stop_module(&eb, &mut rest).await;
+}
+
+#[tokio::test]
+#[test_log::test]
+#[serial]
+async fn test_no_track_configured_without_session() {
+    let eb = EventBus::default();
+    let mut rest = create_module(eb.context()).await;
+    if register_response_event(
+        EventKindType::CurrentSessionRequestEvent,
+        Event {
+            kind: EventKind::CurrentSessionResponseEvent(Response::new(0, 0xff, None)),
+        },
+        eb.context(),
+    )
+    .is_err()
+    {
+        panic!("Failed to register CurrentSessionResponseEvent");
+    }
+
+    let (ws_stream, _) = connect_async("ws://localhost:27015/v1/live_session")
+        .await
+        .expect("Failed to connect to WebSocket");
+    let (_, mut read) = ws_stream.split();
+    // Give the handler time to fail the initial synchronization.
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    eb.publish(&Event {
+        kind: EventKind::NoTrackConfiguredEvent,
+    });
+    let msg = read_next_websocket_event(&mut read).await;
+    match msg {
+        tokio_tungstenite::tungstenite::Message::Text(text) => {
+            let expected = serde_json::json!({
+                "event": "no_track_configured",
+                "seq": 0,
+                "data": {}
+            });
+            let msg = serde_json::from_slice::<serde_json::Value>(text.as_bytes()).unwrap();
+            assert_eq!(msg, expected, "No track message does not match expected");
+        }
+        _ => panic!("Unexpected message type received. Msg: {:?}", msg),
+    }
+
+    unregister_current_session_response_event(&eb);
+    stop_module(&eb, &mut rest).await;
+}
+
+#[tokio::test]
+#[test_log::test]
+#[serial]
+async fn test_position_rounded_to_configured_precision() {
+    let eb = EventBus::default();
+    let config = RestConfig {
+        position_precision: 3,
+        ..Default::default()
+    };
+    let mut rest = create_module_with_config(eb.context(), config).await;
+    register_current_session_response_event(&eb);
+
+    let (ws_stream, _) = connect_async("ws://localhost:27015/v1/live_session")
+        .await
+        .expect("Failed to connect to WebSocket");
+    let (_, mut read) = ws_stream.split();
+    let _ = read_next_websocket_event(&mut read).await; // Consume the current_session event
+
+    eb.publish(&Event {
+        kind: EventKind::GnssPositionEvent(
+            GnssPosition::new(
+                52.0258334567,
+                11.2791667891,
+                27.5,
+                &NaiveTime::from_hms_milli_opt(13, 0, 0, 100).unwrap(),
+                &NaiveDate::from_ymd_opt(1970, 1, 1).unwrap(),
+            )
+            .into(),
+        ),
+    });
+    let msg = read_next_websocket_event(&mut read).await;
+    match msg {
+        tokio_tungstenite::tungstenite::Message::Text(text) => {
+            let expected = serde_json::json!({
+                "event": "position",
+                "seq": 1,
+                "data": {
+                    "latitude": 52.026,
+                    "longitude": 11.279,
+                    "velocity": 27.5,
+                    "time": "13:00:00.100",
+                    "date": "01.01.1970"
+                }
+            });
+            let msg = serde_json::from_slice::<serde_json::Value>(text.as_bytes()).unwrap();
+            assert_eq!(msg, expected, "Position message does not match expected");
+        }
+        _ => panic!("Unexpected message type received. Msg: {:?}", msg),
+    }
+
+    unregister_current_session_response_event(&eb);
+    stop_module(&eb, &mut rest).await;
+}
+
+#[tokio::test]
+#[test_log::test]
+#[serial]
+async fn test_sector_finished_classification() {
+    let eb = EventBus::default();
+    let mut rest = create_module(eb.context()).await;
+    // The synced session has one lap with sector times of 25.144s.
+    register_current_session_response_event(&eb);
+
+    let (ws_stream, _) = connect_async("ws://localhost:27015/v1/live_session")
+        .await
+        .expect("Failed to connect to WebSocket");
+    let (_, mut read) = ws_stream.split();
+    let _ = read_next_websocket_event(&mut read).await; // Consume the current_session event
+
+    let laps = [
+        [(25_000, "session_best"), (26_000, "none")],
+        [(25_500, "none"), (25_600, "personal_best")],
+        [(25_200, "personal_best"), (25_100, "session_best")],
+    ];
+    for lap in laps {
+        eb.publish(&Event {
+            kind: EventKind::LapStartedEvent,
+        });
+        let _ = read_next_websocket_event(&mut read).await; // Consume the lap_started event
+        for (millis, classification) in lap {
+            eb.publish(&Event {
+                kind: EventKind::SectorFinishedEvent(Duration::from_millis(millis).into()),
+            });
+            let msg = match read_next_websocket_event(&mut read).await {
+                Message::Text(text) => {
+                    serde_json::from_slice::<serde_json::Value>(text.as_bytes()).unwrap()
+                }
+                msg => panic!("Unexpected message type received. Msg: {:?}", msg),
+            };
+            assert_eq!(msg["event"], "sector_finished");
+            assert_eq!(
+                msg["data"]["classification"], classification,
+                "Sector time {millis}ms"
+            );
+        }
+    }
+
+    unregister_current_session_response_event(&eb);
+    stop_module(&eb, &mut rest).await;
+}
+
+#[tokio::test]
+#[test_log::test]
+#[serial]
+async fn test_broadcast_serialized_once_per_event() {
+    let eb = EventBus::default();
+    let mut rest = create_module(eb.context()).await;
+    // Answers every connection, the registered response events only match the first request.
+    let ctx = eb.context();
+    let mut rx = eb.subscribe();
+    let responder = tokio::spawn(async move {
+        while let Ok(event) = rx.recv().await {
+            if let EventKind::CurrentSessionRequestEvent(request) = event.kind {
+                let _ = ctx.publish_event(EventKind::CurrentSessionResponseEvent(Response::new(
+                    request.id,
+                    request.sender_addr,
+                    Some(Arc::new(RwLock::new(get_session()))),
+                )));
+            }
+        }
+    });
+
+    let mut reads = Vec::new();
+    for _ in 0..3 {
+        let (ws_stream, _) = connect_async("ws://localhost:27015/v1/live_session")
+            .await
+            .expect("Failed to connect to WebSocket");
+        let (_, mut read) = ws_stream.split();
+        let _ = read_next_websocket_event(&mut read).await; // Consume the current_session event
+        reads.push(read);
+    }
+
+    let laptimes = [1, 2, 3].map(Duration::from_millis);
+    for laptime in laptimes {
+        eb.publish(&Event {
+            kind: EventKind::CurrentLaptimeEvent(laptime.into()),
+        });
+    }
+    for read in reads.iter_mut() {
+        for (seq, laptime) in laptimes.iter().enumerate() {
+            match read_next_websocket_event(read).await {
+                Message::Text(text) => {
+                    let msg = serde_json::from_slice::<serde_json::Value>(text.as_bytes()).unwrap();
+                    let expected =
+                        get_current_laptime_msg(*laptime, "current_laptime", seq as u64 + 1);
+                    assert_eq!(msg, expected);
+                }
+                msg => panic!("Unexpected message type received. Msg: {:?}", msg),
+            }
+        }
+    }
+
+    let body = reqwest::get("http://localhost:27015/v1/metrics")
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+    assert!(
+        body.lines()
+            .any(|line| line == "live_session_serialized_messages_total 3"),
+        "Unexpected metrics: {body}"
+    );
+
+    responder.abort();
+    stop_module(&eb, &mut rest).await;
+}
+
+#[tokio::test]
+#[test_log::test]
+#[serial]
+async fn get_live_session_schema() {
+    let eb = EventBus::default();
+    let mut rest = create_module(eb.context()).await;
+
+    let response = reqwest::get("http://localhost:27015/v1/live_session/schema")
+        .await
+        .unwrap();
+    assert!(response.status().is_success());
+    let schema: serde_json::Value = response.json().await.unwrap();
+    let events = schema["oneOf"].as_array().expect("oneOf is no array");
+    for name in ["current_laptime", "lap_finished"] {
+        let event = events
+            .iter()
+            .find(|event| event["properties"]["event"]["const"] == name)
+            .unwrap_or_else(|| panic!("Event {name} not described"));
+        assert_eq!(
+            event["properties"]["data"]["properties"]["time"]["$ref"],
+            "#/$defs/duration"
+        );
+    }
+    assert!(schema["$defs"]["duration"].is_object());
+
+    stop_module(&eb, &mut rest).await;
+}
+
+#[tokio::test]
+#[test_log::test]
+#[serial]
+async fn send_pings_on_idle_connection() {
+    let eb = EventBus::default();
+    let config = RestConfig {
+        live_ping_interval: Some(Duration::from_millis(200)),
+        ..Default::default()
+    };
+    let mut rest = create_module_with_config(eb.context(), config).await;
+    register_current_session_response_event(&eb);
+
+    let (ws_stream, _) = connect_async("ws://localhost:27015/v1/live_session")
+        .await
+        .expect("Failed to connect to WebSocket");
+    let (_, mut read) = ws_stream.split();
+    let _ = read_next_websocket_event(&mut read).await; // Consume the current_session event
+
+    let connected = tokio::time::Instant::now();
+    for ping in 1..=3 {
+        let msg = tokio::time::timeout(Duration::from_millis(500), read.next())
+            .await
+            .expect("No ping received")
+            .expect("Connection closed")
+            .expect("Error reading message");
+        assert!(matches!(msg, Message::Ping(_)), "{msg:?}");
+        // The Pongs are answered by the client, so the connection stays open.
+        let elapsed = connected.elapsed();
+        assert!(
+            elapsed >= Duration::from_millis(200 * ping - 50)
+                && elapsed <= Duration::from_millis(200 * ping + 150),
+            "Ping {ping} after {elapsed:?}"
+        );
+    }
+
+    unregister_current_session_response_event(&eb);
+    stop_module(&eb, &mut rest).await;
+}
+
+#[tokio::test]
+#[test_log::test]
+#[serial]
+async fn test_initial_current_session_request_retried() {
+    let eb = EventBus::default();
+    let config = RestConfig {
+        live_initial_sync_interval: Duration::from_millis(20),
+        request_timeout: Duration::from_millis(200),
+        ..Default::default()
+    };
+    let mut rest = create_module_with_config(eb.context(), config).await;
+    // Misses the first current session request, answers the following ones.
+    let ctx = eb.context();
+    let mut rx = eb.subscribe();
+    let responder = tokio::spawn(async move {
+        let mut requests = 0;
+        while let Ok(event) = rx.recv().await {
+            if let EventKind::CurrentSessionRequestEvent(request) = event.kind {
+                requests += 1;
+                if requests > 1 {
+                    let session = Some(Arc::new(RwLock::new(get_session())));
+                    let _ = ctx.publish_event(EventKind::CurrentSessionResponseEvent(
+                        Response::new(request.id, request.sender_addr, session),
+                    ));
+                }
+            }
+        }
+    });
+
+    let mut requests = eb.subscribe();
+    let (ws_stream, _) = connect_async("ws://localhost:27015/v1/live_session")
+        .await
+        .expect("Failed to connect to WebSocket");
+    let (_, mut read) = ws_stream.split();
+    for _ in 0..2 {
+        wait_for_event(
+            &mut requests,
+            Duration::from_millis(500),
+            EventKindType::CurrentSessionRequestEvent,
+        )
+        .await;
+    }
+
+    match read_next_websocket_event(&mut read).await {
+        Message::Text(text) => {
+            let msg = serde_json::from_slice::<serde_json::Value>(text.as_bytes()).unwrap();
+            assert_eq!(msg["event"], "current_session");
+            assert_eq!(msg["data"]["session"], serde_json::json!(get_session()));
+        }
+        msg => panic!("Unexpected message type received. Msg: {:?}", msg),
+    }
+
+    responder.abort();
+    stop_module(&eb, &mut rest).await;
+}
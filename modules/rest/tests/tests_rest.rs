@@ -5,23 +5,37 @@
 mod test_utils;
 
 use common::{
+    id::{SessionId, TrackId},
+    lap::Lap,
+    position::{GnssPosition, Position},
     session::{Session, SessionInfo},
-    test_helper::session::get_session,
+    test_helper::{
+        session::{get_session, get_session_as_json},
+        track::get_track,
+    },
+    track::Track,
 };
+use laptimer::SimpleLaptimer;
 use module_core::{
-    Event, EventBus, EventKind, EventKindType, Response,
-    test_helper::{register_response_event, stop_module},
+    Event, EventBus, EventBusConfig, EventKind, EventKindType, LaptimerStateInfo, Module,
+    ModuleHeartbeat, Response, SaveFailure,
+    test_helper::{register_response_event, stop_module, wait_for_event},
 };
+use rest::{CorsConfig, RestConfig};
 use serial_test::serial;
-use std::sync::{Arc, RwLock};
-use test_utils::create_module;
+use std::{
+    path::PathBuf,
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+use test_utils::{create_module, create_module_with_config};
 
 #[tokio::test]
 #[test_log::test]
 #[serial]
 async fn get_session_request_ids() {
     let eb = EventBus::default();
-    let mut rest = create_module(eb.context());
+    let mut rest = create_module(eb.context()).await;
     let expected_body = include_str!("response_request_session_info.json").trim();
     if register_response_event(
         EventKindType::LoadStoredSessionIdsRequestEvent,
@@ -32,13 +46,13 @@ async fn get_session_request_ids() {
                     receiver_addr: 0xff,
                     data: Arc::new(vec![
                         SessionInfo {
-                            id: "session_1".to_string(),
+                            id: "session_1".into(),
                             date: chrono::NaiveDateTime::default(),
                             track_name: "".to_string(),
                             laps: 0,
                         },
                         SessionInfo {
-                            id: "session_2".to_string(),
+                            id: "session_2".into(),
                             date: chrono::NaiveDateTime::default(),
                             track_name: "".to_string(),
                             laps: 0,
@@ -71,7 +85,7 @@ async fn get_session_request_ids() {
 #[serial]
 async fn request_session() {
     let eb = EventBus::default();
-    let mut rest = create_module(eb.context());
+    let mut rest = create_module(eb.context()).await;
     if register_response_event(
         EventKindType::LoadSessionRequestEvent,
         Event {
@@ -102,12 +116,186 @@ async fn request_session() {
     stop_module(&eb, &mut rest).await;
 }
 
+/// Requests a session from a storage that fails with the given error and returns the
+/// status and the JSON body of the response.
+async fn request_session_failing_with(
+    error: std::io::ErrorKind,
+) -> (reqwest::StatusCode, serde_json::Value) {
+    let eb = EventBus::default();
+    let mut rest = create_module(eb.context()).await;
+    register_response_event(
+        EventKindType::LoadSessionRequestEvent,
+        Event {
+            kind: EventKind::LoadSessionResponseEvent(Response::new(0, 0xff, Err(error))),
+        },
+        eb.context(),
+    )
+    .expect("Failed to register LoadSessionResponseEvent");
+
+    let response = reqwest::get("http://localhost:27015/v1/sessions/session_1")
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = response.json().await.unwrap();
+    stop_module(&eb, &mut rest).await;
+    (status, body)
+}
+
+#[tokio::test]
+#[test_log::test]
+#[serial]
+async fn missing_session_returns_error_body() {
+    let (status, body) = request_session_failing_with(std::io::ErrorKind::NotFound).await;
+    assert_eq!(status, reqwest::StatusCode::NOT_FOUND);
+    assert_eq!(body["error"]["code"], "not_found");
+    assert_eq!(body["error"]["message"], "session session_1 not found");
+}
+
+#[tokio::test]
+#[test_log::test]
+#[serial]
+async fn dropped_storage_request_is_retried() {
+    let eb = EventBus::default();
+    let config = RestConfig {
+        request_attempts: 2,
+        request_timeout: Duration::from_millis(200),
+        request_backoff: Duration::from_millis(10),
+        ..Default::default()
+    };
+    let mut rest = create_module_with_config(eb.context(), config).await;
+    // The storage misses the first request, e.g. because it lagged behind the event bus.
+    let ctx = eb.context();
+    let mut rx = eb.subscribe();
+    let storage = tokio::spawn(async move {
+        let mut request_ids = Vec::new();
+        while let Ok(event) = rx.recv().await {
+            match event.kind {
+                EventKind::QuitEvent => break,
+                EventKind::LoadSessionRequestEvent(req) => {
+                    request_ids.push(req.id);
+                    if request_ids.len() > 1 {
+                        let session = Arc::new(RwLock::new(get_session()));
+                        let _ = ctx.publish_event(EventKind::LoadSessionResponseEvent(
+                            Response::new(req.id, req.sender_addr, Ok(session)),
+                        ));
+                    }
+                }
+                _ => (),
+            }
+        }
+        request_ids
+    });
+
+    let response = reqwest::get("http://localhost:27015/v1/sessions/session_1")
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let body: serde_json::Value = response.json().await.unwrap();
+    let expected: serde_json::Value = serde_json::from_str(get_session_as_json()).unwrap();
+    assert_eq!(body, expected);
+
+    stop_module(&eb, &mut rest).await;
+    // Both attempts carry the same request ID, so a late response would also be accepted.
+    let request_ids = storage.await.unwrap();
+    assert_eq!(request_ids.len(), 2);
+    assert_eq!(request_ids[0], request_ids[1]);
+}
+
+#[tokio::test]
+#[test_log::test]
+#[serial]
+async fn unanswered_request_does_not_block_other_requests() {
+    let eb = EventBus::default();
+    let config = RestConfig {
+        request_attempts: 1,
+        request_timeout: Duration::from_secs(1),
+        ..Default::default()
+    };
+    let mut rest = create_module_with_config(eb.context(), config).await;
+    // The storage never answers, the lap timer answers right away.
+    let ctx = eb.context();
+    let mut rx = eb.subscribe();
+    let mut requests = eb.subscribe();
+    let laptimer = tokio::spawn(async move {
+        while let Ok(event) = rx.recv().await {
+            match event.kind {
+                EventKind::QuitEvent => break,
+                EventKind::LaptimerStateRequestEvent(req) => {
+                    let _ =
+                        ctx.publish_event(EventKind::LaptimerStateResponseEvent(Response::new(
+                            req.id,
+                            req.sender_addr,
+                            LaptimerStateInfo {
+                                track_name: None,
+                                no_track_configured: true,
+                                lap_running: false,
+                            },
+                        )));
+                }
+                _ => (),
+            }
+        }
+    });
+
+    let session = tokio::spawn(reqwest::get("http://localhost:27015/v1/sessions/session_1"));
+    wait_for_event(
+        &mut requests,
+        Duration::from_millis(500),
+        EventKindType::LoadSessionRequestEvent,
+    )
+    .await;
+    let state = tokio::time::timeout(
+        Duration::from_millis(500),
+        reqwest::get("http://localhost:27015/v1/laptimer/state"),
+    )
+    .await
+    .expect("Laptimer state request blocked by the pending session request")
+    .unwrap();
+    assert!(state.status().is_success());
+
+    let session = session.await.unwrap().unwrap();
+    assert_eq!(session.status(), reqwest::StatusCode::SERVICE_UNAVAILABLE);
+    stop_module(&eb, &mut rest).await;
+    laptimer.await.unwrap();
+}
+
+#[tokio::test]
+#[test_log::test]
+#[serial]
+async fn storage_timeout_returns_error_body() {
+    let (status, body) = request_session_failing_with(std::io::ErrorKind::TimedOut).await;
+    assert_eq!(status, reqwest::StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(body["error"]["code"], "service_unavailable");
+    assert_eq!(
+        body["error"]["message"],
+        "storage did not respond for session session_1"
+    );
+}
+
+#[tokio::test]
+#[test_log::test]
+#[serial]
+async fn unknown_route_returns_error_body() {
+    let eb = EventBus::default();
+    let mut rest = create_module(eb.context()).await;
+
+    let response = reqwest::get("http://localhost:27015/v1/unknown")
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["error"]["code"], "not_found");
+    assert_eq!(body["error"]["message"], "Not Found");
+
+    stop_module(&eb, &mut rest).await;
+}
+
 #[tokio::test]
 #[test_log::test]
 #[serial]
 async fn test_delete_session() {
     let eb = EventBus::default();
-    let mut rest = create_module(eb.context());
+    let mut rest = create_module(eb.context()).await;
     let resp = Response::new(0, 0xff, Ok(()));
     if register_response_event(
         EventKindType::DeleteSessionRequestEvent,
@@ -131,3 +319,1494 @@ async fn test_delete_session() {
     assert!(response.status().is_success());
     stop_module(&eb, &mut rest).await;
 }
+
+#[tokio::test]
+#[test_log::test]
+#[serial]
+async fn dropped_delete_request_is_retried() {
+    let eb = EventBus::default();
+    let config = RestConfig {
+        request_attempts: 2,
+        request_timeout: Duration::from_millis(200),
+        request_backoff: Duration::from_millis(10),
+        ..Default::default()
+    };
+    let mut rest = create_module_with_config(eb.context(), config).await;
+    // The storage misses the first request.
+    let ctx = eb.context();
+    let mut rx = eb.subscribe();
+    let storage = tokio::spawn(async move {
+        let mut attempts = 0;
+        while let Ok(event) = rx.recv().await {
+            match event.kind {
+                EventKind::QuitEvent => break,
+                EventKind::DeleteSessionRequestEvent(req) => {
+                    attempts += 1;
+                    if attempts > 1 {
+                        let _ = ctx.publish_event(EventKind::DeleteSessionResponseEvent(
+                            Response::new(req.id, req.sender_addr, Ok(())),
+                        ));
+                    }
+                }
+                _ => (),
+            }
+        }
+        attempts
+    });
+
+    let response = reqwest::Client::new()
+        .delete("http://localhost:27015/v1/sessions/session_1")
+        .send()
+        .await
+        .unwrap();
+    assert!(response.status().is_success());
+
+    stop_module(&eb, &mut rest).await;
+    assert_eq!(storage.await.unwrap(), 2);
+}
+
+#[tokio::test]
+#[test_log::test]
+#[serial]
+async fn get_event_bus_metrics() {
+    let eb = EventBus::default();
+    let mut rest = create_module(eb.context()).await;
+    let position = GnssPosition::new(
+        52.025833,
+        11.279166,
+        0.0,
+        &chrono::NaiveTime::default(),
+        &chrono::NaiveDate::default(),
+    );
+    for _ in 0..2 {
+        eb.publish(&Event {
+            kind: EventKind::GnssPositionEvent(Arc::new(position)),
+        });
+    }
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    let response = reqwest::get("http://localhost:27015/v1/metrics")
+        .await
+        .unwrap();
+    assert!(response.status().is_success());
+    assert!(
+        response.headers()["content-type"]
+            .to_str()
+            .unwrap()
+            .starts_with("text/plain")
+    );
+    let body = response.text().await.unwrap();
+    assert!(body.contains("# TYPE gnss_position_events_total counter\n"));
+    assert!(
+        body.lines()
+            .any(|line| line == "gnss_position_events_total 2")
+    );
+    assert!(
+        body.lines()
+            .any(|line| line == "lap_finished_events_total 0")
+    );
+    assert!(body.contains("# TYPE event_bus_subscribers gauge\n"));
+    stop_module(&eb, &mut rest).await;
+}
+
+#[tokio::test]
+#[test_log::test]
+#[serial]
+async fn get_raw_session_byte_range() {
+    let eb = EventBus::default();
+    let mut rest = create_module(eb.context()).await;
+    let folder = PathBuf::from("/tmp/rapid-rusty/get_raw_session_byte_range");
+    std::fs::create_dir_all(&folder).unwrap();
+    let file = folder.join("session_1.session");
+    let content = get_session_as_json();
+    std::fs::write(&file, content).unwrap();
+    if register_response_event(
+        EventKindType::SessionFilePathRequestEvent,
+        Event {
+            kind: EventKind::SessionFilePathResponseEvent(Response::new(0, 0xff, Ok(file))),
+        },
+        eb.context(),
+    )
+    .is_err()
+    {
+        panic!("Failed to register SessionFilePathResponseEvent");
+    }
+
+    let response = reqwest::Client::new()
+        .get("http://localhost:27015/v1/sessions/session_1/raw")
+        .header("Range", "bytes=10-29")
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), reqwest::StatusCode::PARTIAL_CONTENT);
+    assert_eq!(
+        response.headers()["content-range"],
+        format!("bytes 10-29/{}", content.len())
+    );
+    assert_eq!(response.headers()["content-length"], "20");
+    assert_eq!(response.headers()["accept-ranges"], "bytes");
+    assert_eq!(response.bytes().await.unwrap(), content.as_bytes()[10..30]);
+    stop_module(&eb, &mut rest).await;
+}
+
+#[tokio::test]
+#[test_log::test]
+#[serial]
+async fn get_version() {
+    let eb = EventBus::default();
+    let mut rest = create_module(eb.context()).await;
+
+    let response = reqwest::get("http://localhost:27015/v1/version")
+        .await
+        .unwrap();
+    assert!(response.status().is_success());
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["version"], env!("CARGO_PKG_VERSION"));
+    let features = body["features"].as_array().expect("features is no array");
+    assert!(features.contains(&serde_json::Value::from("live_session")));
+    assert!(!features.contains(&serde_json::Value::from("simulation")));
+
+    stop_module(&eb, &mut rest).await;
+}
+
+#[tokio::test]
+#[test_log::test]
+#[serial]
+async fn version_advertises_enabled_simulation() {
+    let eb = EventBus::default();
+    let mut rest = create_module_with_config(
+        eb.context(),
+        RestConfig {
+            simulation: true,
+            ..Default::default()
+        },
+    )
+    .await;
+
+    let response = reqwest::get("http://localhost:27015/v1/version")
+        .await
+        .unwrap();
+    assert!(response.status().is_success());
+    let body: serde_json::Value = response.json().await.unwrap();
+    let features = body["features"].as_array().expect("features is no array");
+    assert!(features.contains(&serde_json::Value::from("simulation")));
+
+    stop_module(&eb, &mut rest).await;
+}
+
+/// Spawns a minimal in-memory storage with the given sessions that stores the sessions
+/// of save requests and serves them for load and session id requests.
+fn spawn_in_memory_storage(
+    eb: &EventBus,
+    sessions: Vec<(String, Session)>,
+) -> tokio::task::JoinHandle<()> {
+    let ctx = eb.context();
+    let mut rx = eb.subscribe();
+    tokio::spawn(async move {
+        let mut sessions: std::collections::HashMap<_, _> = sessions
+            .into_iter()
+            .map(|(id, session)| (SessionId::from(id), Arc::new(RwLock::new(session))))
+            .collect();
+        while let Ok(event) = rx.recv().await {
+            match event.kind {
+                EventKind::QuitEvent => break,
+                EventKind::LoadStoredSessionIdsRequestEvent(req) => {
+                    let infos = sessions
+                        .iter()
+                        .map(|(id, session)| {
+                            let session = session.read().unwrap();
+                            SessionInfo::new(
+                                id.clone(),
+                                chrono::NaiveDateTime::new(session.date, session.time),
+                                session.track.name.clone(),
+                                session.laps.len(),
+                            )
+                        })
+                        .collect();
+                    let _ = ctx.publish_event(EventKind::LoadStoredSessionIdsResponseEvent(
+                        Response::new(req.id, req.sender_addr, Arc::new(infos)),
+                    ));
+                }
+                EventKind::ImportSessionRequestEvent(req) => {
+                    let id = {
+                        let session = req.data.read().unwrap();
+                        SessionId::new(format!(
+                            "{}_{}_{}",
+                            session.track.name, session.date, session.time
+                        ))
+                    };
+                    let data = if sessions.contains_key(&id) {
+                        Err(std::io::ErrorKind::AlreadyExists)
+                    } else {
+                        sessions.insert(id.clone(), req.data.clone());
+                        Ok(id)
+                    };
+                    let _ = ctx.publish_event(EventKind::ImportSessionResponseEvent(
+                        Response::new(req.id, req.sender_addr, data),
+                    ));
+                }
+                EventKind::LoadSessionRequestEvent(req) => {
+                    let data = sessions
+                        .get(&req.data)
+                        .cloned()
+                        .ok_or(std::io::ErrorKind::NotFound);
+                    let _ = ctx.publish_event(EventKind::LoadSessionResponseEvent(Response::new(
+                        req.id,
+                        req.sender_addr,
+                        data,
+                    )));
+                }
+                _ => (),
+            }
+        }
+    })
+}
+
+#[tokio::test]
+#[test_log::test]
+#[serial]
+async fn get_infos_of_selected_sessions() {
+    let eb = EventBus::default();
+    let mut rest = create_module(eb.context()).await;
+    let storage = spawn_in_memory_storage(
+        &eb,
+        ["session_a", "session_b", "session_c"]
+            .iter()
+            .map(|id| (id.to_string(), get_session()))
+            .collect(),
+    );
+
+    let response = reqwest::Client::new()
+        .post("http://localhost:27015/v1/sessions/info")
+        .json(&serde_json::json!({ "ids": ["session_c", "unknown", "session_a"] }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["total"], 2);
+    let mut ids: Vec<&str> = body["sessions"]
+        .as_array()
+        .expect("sessions is no array")
+        .iter()
+        .map(|info| info["id"].as_str().unwrap())
+        .collect();
+    ids.sort();
+    assert_eq!(ids, vec!["session_a", "session_c"]);
+
+    stop_module(&eb, &mut rest).await;
+    storage.await.unwrap();
+}
+
+#[tokio::test]
+#[test_log::test]
+#[serial]
+async fn import_session_and_fetch_it_back() {
+    let eb = EventBus::default();
+    let mut rest = create_module(eb.context()).await;
+    let storage = spawn_in_memory_storage(&eb, vec![]);
+    let client = reqwest::Client::new();
+
+    let response = client
+        .post("http://localhost:27015/v1/sessions/import")
+        .body(get_session_as_json())
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::CREATED);
+    let body: serde_json::Value = response.json().await.unwrap();
+    let id = body["id"].as_str().expect("id is no string").to_owned();
+
+    let response = client
+        .get(format!("http://localhost:27015/v1/sessions/{id}"))
+        .send()
+        .await
+        .unwrap();
+    assert!(response.status().is_success());
+    let session = Session::from_json(&response.text().await.unwrap()).unwrap();
+    assert_eq!(session, get_session());
+
+    stop_module(&eb, &mut rest).await;
+    storage.await.unwrap();
+}
+
+#[tokio::test]
+#[test_log::test]
+#[serial]
+async fn import_of_stored_session_conflicts() {
+    let eb = EventBus::default();
+    let mut rest = create_module(eb.context()).await;
+    let storage = spawn_in_memory_storage(&eb, vec![]);
+    let client = reqwest::Client::new();
+    let import = || {
+        client
+            .post("http://localhost:27015/v1/sessions/import")
+            .body(get_session_as_json())
+            .send()
+    };
+
+    let response = import().await.unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::CREATED);
+    let response = import().await.unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::CONFLICT);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["error"]["code"], "conflict");
+
+    let response = client
+        .get("http://localhost:27015/v1/sessions")
+        .send()
+        .await
+        .unwrap();
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["total"], 1);
+
+    stop_module(&eb, &mut rest).await;
+    storage.await.unwrap();
+}
+
+#[tokio::test]
+#[test_log::test]
+#[serial]
+async fn unanswered_import_is_not_retried_and_does_not_block() {
+    let eb = EventBus::default();
+    let config = RestConfig {
+        request_attempts: 3,
+        request_timeout: Duration::from_millis(500),
+        request_backoff: Duration::from_millis(10),
+        ..Default::default()
+    };
+    let mut rest = create_module_with_config(eb.context(), config).await;
+    // The storage never answers an import, the lap timer answers right away.
+    let ctx = eb.context();
+    let mut rx = eb.subscribe();
+    let modules = tokio::spawn(async move {
+        let mut import_requests = 0;
+        while let Ok(event) = rx.recv().await {
+            match event.kind {
+                EventKind::QuitEvent => break,
+                EventKind::ImportSessionRequestEvent(_) => import_requests += 1,
+                EventKind::LaptimerStateRequestEvent(req) => {
+                    let _ =
+                        ctx.publish_event(EventKind::LaptimerStateResponseEvent(Response::new(
+                            req.id,
+                            req.sender_addr,
+                            LaptimerStateInfo {
+                                track_name: None,
+                                no_track_configured: true,
+                                lap_running: false,
+                            },
+                        )));
+                }
+                _ => (),
+            }
+        }
+        import_requests
+    });
+
+    let mut requests = eb.subscribe();
+    let import = tokio::spawn(
+        reqwest::Client::new()
+            .post("http://localhost:27015/v1/sessions/import")
+            .body(get_session_as_json())
+            .send(),
+    );
+    wait_for_event(
+        &mut requests,
+        Duration::from_millis(500),
+        EventKindType::ImportSessionRequestEvent,
+    )
+    .await;
+    let state = tokio::time::timeout(
+        Duration::from_millis(250),
+        reqwest::get("http://localhost:27015/v1/laptimer/state"),
+    )
+    .await
+    .expect("Laptimer state request blocked by the pending import")
+    .unwrap();
+    assert!(state.status().is_success());
+
+    let import = import.await.unwrap().unwrap();
+    assert_eq!(import.status(), reqwest::StatusCode::INTERNAL_SERVER_ERROR);
+    stop_module(&eb, &mut rest).await;
+    assert_eq!(modules.await.unwrap(), 1);
+}
+
+#[tokio::test]
+#[test_log::test]
+#[serial]
+async fn import_session_rejects_invalid_body() {
+    let eb = EventBus::default();
+    let mut rest = create_module(eb.context()).await;
+    let client = reqwest::Client::new();
+    let invalid_coordinate = get_session_as_json().replacen("52.025833", "152.025833", 1);
+
+    for body in ["{ no session", invalid_coordinate.as_str()] {
+        let response = client
+            .post("http://localhost:27015/v1/sessions/import")
+            .body(body.to_owned())
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+    }
+
+    stop_module(&eb, &mut rest).await;
+}
+
+#[tokio::test]
+#[test_log::test]
+#[serial]
+async fn get_laptimer_state() {
+    let eb = EventBus::default();
+    let mut rest = create_module(eb.context()).await;
+    if register_response_event(
+        EventKindType::LaptimerStateRequestEvent,
+        Event {
+            kind: EventKind::LaptimerStateResponseEvent(Response::new(
+                0,
+                0xff,
+                LaptimerStateInfo {
+                    track_name: None,
+                    no_track_configured: true,
+                    lap_running: false,
+                },
+            )),
+        },
+        eb.context(),
+    )
+    .is_err()
+    {
+        panic!("Failed to register LaptimerStateResponseEvent");
+    }
+
+    let response = reqwest::get("http://localhost:27015/v1/laptimer/state")
+        .await
+        .unwrap();
+    assert!(response.status().is_success());
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(
+        body,
+        serde_json::json!({
+            "track": null,
+            "no_track_configured": true,
+            "lap_running": false
+        })
+    );
+
+    stop_module(&eb, &mut rest).await;
+}
+
+#[tokio::test]
+#[test_log::test]
+#[serial]
+async fn get_modules_reports_module_after_heartbeat() {
+    let eb = EventBus::default();
+    let mut rest = create_module(eb.context()).await;
+
+    let response = reqwest::get("http://localhost:27015/v1/diag/modules")
+        .await
+        .unwrap();
+    assert!(response.status().is_success());
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body, serde_json::json!({ "modules": [] }));
+
+    eb.context()
+        .publish_heartbeat("storage")
+        .expect("Failed to publish heartbeat");
+    let stale = chrono::Utc::now() - chrono::Duration::minutes(1);
+    eb.context()
+        .publish_event(EventKind::ModuleHeartbeatEvent(Arc::new(ModuleHeartbeat {
+            name: "gnss".to_string(),
+            timestamp: stale,
+        })))
+        .expect("Failed to publish heartbeat");
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    let response = reqwest::get("http://localhost:27015/v1/diag/modules")
+        .await
+        .unwrap();
+    assert!(response.status().is_success());
+    let body: serde_json::Value = response.json().await.unwrap();
+    let modules = body["modules"].as_array().expect("modules is no array");
+    assert_eq!(modules.len(), 2);
+    assert_eq!(modules[0]["name"], "gnss");
+    assert_eq!(modules[0]["alive"], false);
+    assert_eq!(modules[1]["name"], "storage");
+    assert_eq!(modules[1]["alive"], true);
+
+    stop_module(&eb, &mut rest).await;
+}
+
+#[tokio::test]
+#[test_log::test]
+#[serial]
+async fn get_modules_reports_last_save_failure() {
+    let eb = EventBus::default();
+    let mut rest = create_module(eb.context()).await;
+
+    for session_id in ["first", "second"] {
+        eb.context()
+            .publish_event(EventKind::SaveFailedEvent(Arc::new(SaveFailure {
+                session_id: SessionId::from(session_id),
+                error: std::io::ErrorKind::StorageFull,
+            })))
+            .expect("Failed to publish save failure");
+    }
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    let response = reqwest::get("http://localhost:27015/v1/diag/modules")
+        .await
+        .unwrap();
+    assert!(response.status().is_success());
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["last_save_failure"]["session_id"], "second");
+    assert_eq!(body["last_save_failure"]["error"], "no storage space");
+
+    stop_module(&eb, &mut rest).await;
+}
+
+#[tokio::test]
+#[test_log::test]
+#[serial]
+async fn get_recent_laps_ordered_by_recency() {
+    let eb = EventBus::default();
+    let mut rest = create_module(eb.context()).await;
+    let lap = |seconds: u64| Lap {
+        sectors: vec![Duration::from_secs(seconds)],
+        log_points: vec![],
+    };
+    let mut older = get_session();
+    older.laps = vec![lap(61), lap(62)];
+    let mut newer = get_session();
+    newer.date = newer.date.succ_opt().unwrap();
+    newer.laps = vec![lap(71), lap(72)];
+    newer.laps[1].log_points = vec![
+        GnssPosition::new(52.0, 11.0, 30.0, &newer.time, &newer.date),
+        GnssPosition::new(52.5, 11.5, 55.5, &newer.time, &newer.date),
+        GnssPosition::new(53.0, 12.0, 40.0, &newer.time, &newer.date),
+    ];
+    let storage = spawn_in_memory_storage(
+        &eb,
+        vec![("older".to_string(), older), ("newer".to_string(), newer)],
+    );
+
+    let response = reqwest::get("http://localhost:27015/v1/laps/recent?limit=3")
+        .await
+        .unwrap();
+    assert!(response.status().is_success());
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(
+        body,
+        serde_json::json!({
+            "total": 3,
+            "laps": [
+                {
+                    "session_id": "newer",
+                    "lap_index": 1,
+                    "time": "00:01:12.000",
+                    "date": "1970-01-02T13:00:00",
+                    "max_speed": {"velocity": 55.5, "position": {"latitude": 52.5, "longitude": 11.5}}
+                },
+                {"session_id": "newer", "lap_index": 0, "time": "00:01:11.000", "date": "1970-01-02T13:00:00"},
+                {"session_id": "older", "lap_index": 1, "time": "00:01:02.000", "date": "1970-01-01T13:00:00"}
+            ]
+        })
+    );
+
+    stop_module(&eb, &mut rest).await;
+    storage.await.unwrap();
+}
+
+#[tokio::test]
+#[test_log::test]
+#[serial]
+async fn routes_are_mounted_under_base_path() {
+    let eb = EventBus::default();
+    let config = RestConfig {
+        base_path: "/laptimer".to_string(),
+        ..Default::default()
+    };
+    let mut rest = create_module_with_config(eb.context(), config).await;
+    let storage = spawn_in_memory_storage(&eb, vec![]);
+    let client = reqwest::Client::new();
+
+    let response = client
+        .get("http://localhost:27015/laptimer/v1/sessions")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["total"], 0);
+
+    let response = client
+        .get("http://localhost:27015/v1/sessions")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+
+    let response = client
+        .post("http://localhost:27015/laptimer/v1/sessions/import")
+        .body(get_session_as_json())
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::CREATED);
+    let location = response.headers()["Location"].to_str().unwrap().to_owned();
+    assert!(
+        location.starts_with("/laptimer/v1/sessions/"),
+        "Location {location} is not prefixed"
+    );
+
+    stop_module(&eb, &mut rest).await;
+    storage.await.unwrap();
+}
+
+#[tokio::test]
+#[test_log::test]
+#[serial]
+async fn get_lap_negotiates_export_format() {
+    let eb = EventBus::default();
+    let mut rest = create_module(eb.context()).await;
+    let storage = spawn_in_memory_storage(&eb, vec![("session".to_string(), get_session())]);
+    let client = reqwest::Client::new();
+    let get_lap = |accept: Option<&'static str>| {
+        let mut request = client.get("http://localhost:27015/v1/sessions/session/laps/0");
+        if let Some(accept) = accept {
+            request = request.header("Accept", accept);
+        }
+        async move {
+            let response = request.send().await.unwrap();
+            assert_eq!(response.status(), reqwest::StatusCode::OK);
+            let content_type = response.headers()["Content-Type"]
+                .to_str()
+                .unwrap()
+                .to_owned();
+            (content_type, response.text().await.unwrap())
+        }
+    };
+
+    for accept in [None, Some("application/json"), Some("*/*")] {
+        let (content_type, body) = get_lap(accept).await;
+        assert!(content_type.starts_with("application/json"));
+        let lap: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert!(lap["sectors"].is_array());
+        assert_eq!(lap["log_points"].as_array().unwrap().len(), 2);
+    }
+
+    let (content_type, body) = get_lap(Some("application/gpx+xml")).await;
+    assert_eq!(content_type, "application/gpx+xml");
+    assert!(body.contains("<gpx version=\"1.1\""));
+    assert_eq!(body.matches("<trkpt ").count(), 2);
+
+    let (content_type, body) = get_lap(Some("text/csv")).await;
+    assert!(content_type.starts_with("text/csv"));
+    let mut lines = body.lines();
+    assert_eq!(lines.next(), Some("time,latitude,longitude,velocity"));
+    assert_eq!(lines.count(), 2);
+
+    let response = client
+        .get("http://localhost:27015/v1/sessions/session/laps/1")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+
+    stop_module(&eb, &mut rest).await;
+    storage.await.unwrap();
+}
+
+#[tokio::test]
+#[test_log::test]
+#[serial]
+async fn get_single_log_point_of_lap() {
+    let eb = EventBus::default();
+    let mut rest = create_module(eb.context()).await;
+    let mut session = get_session();
+    let first = session.laps[0].log_points[0];
+    session.laps[0].log_points[1] =
+        GnssPosition::new(52.026649, 11.282535, 27.5, &first.time(), &first.date());
+    let storage = spawn_in_memory_storage(&eb, vec![("session".to_string(), session)]);
+
+    let response = reqwest::get("http://localhost:27015/v1/sessions/session/laps/0/points/1")
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let point: GnssPosition = response.json().await.unwrap();
+    assert_eq!(point.latitude(), 52.026649);
+    assert_eq!(point.longitude(), 11.282535);
+    assert_eq!(point.velocity(), 27.5);
+
+    for url in [
+        "http://localhost:27015/v1/sessions/session/laps/0/points/2",
+        "http://localhost:27015/v1/sessions/session/laps/1/points/0",
+        "http://localhost:27015/v1/sessions/unknown/laps/0/points/0",
+    ] {
+        let response = reqwest::get(url).await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND, "{url}");
+    }
+
+    stop_module(&eb, &mut rest).await;
+    storage.await.unwrap();
+}
+
+#[tokio::test]
+#[test_log::test]
+#[serial]
+async fn stream_log_points_of_lap_as_ndjson() {
+    let eb = EventBus::default();
+    let mut rest = create_module(eb.context()).await;
+    let storage = spawn_in_memory_storage(&eb, vec![("session".to_string(), get_session())]);
+
+    let response = reqwest::get("http://localhost:27015/v1/sessions/session/laps/0/points.ndjson")
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    assert_eq!(
+        response.headers()["Content-Type"].to_str().unwrap(),
+        "application/x-ndjson"
+    );
+    let body = response.text().await.unwrap();
+    let points: Vec<GnssPosition> = body
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(points, get_session().laps[0].log_points);
+    assert!(body.ends_with('\n'));
+
+    for url in [
+        "http://localhost:27015/v1/sessions/session/laps/1/points.ndjson",
+        "http://localhost:27015/v1/sessions/unknown/laps/0/points.ndjson",
+    ] {
+        let response = reqwest::get(url).await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND, "{url}");
+    }
+
+    stop_module(&eb, &mut rest).await;
+    storage.await.unwrap();
+}
+
+#[tokio::test]
+#[test_log::test]
+#[serial]
+async fn export_lap_of_session_without_stored_track() {
+    let eb = EventBus::default();
+    let mut rest = create_module(eb.context()).await;
+    // The in-memory storage has no tracks, so the session's track is absent from storage.
+    let storage = spawn_in_memory_storage(&eb, vec![("session".to_string(), get_session())]);
+    let mut rx = eb.subscribe();
+    let client = reqwest::Client::new();
+
+    let response = client
+        .get("http://localhost:27015/v1/sessions/session/laps/0")
+        .header("Accept", "application/gpx+xml")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let body = response.text().await.unwrap();
+    assert!(body.contains(&format!("<name>{} Lap 1</name>", get_session().track.name)));
+    while let Ok(event) = rx.try_recv() {
+        let event_type = EventKindType::from(&event.kind);
+        assert_ne!(event_type, EventKindType::LoadStoredTrackIdsRequest);
+        assert_ne!(event_type, EventKindType::LoadAllStoredTracksRequestEvent);
+    }
+
+    stop_module(&eb, &mut rest).await;
+    storage.await.unwrap();
+}
+
+/// Spawns a minimal in-memory storage with the given tracks that serves them for load
+/// requests and replaces them on save requests.
+fn spawn_in_memory_track_storage(
+    eb: &EventBus,
+    tracks: Vec<(String, Track)>,
+) -> tokio::task::JoinHandle<()> {
+    let ctx = eb.context();
+    let mut rx = eb.subscribe();
+    tokio::spawn(async move {
+        let mut tracks: std::collections::HashMap<_, _> = tracks
+            .into_iter()
+            .map(|(id, track)| (TrackId::from(id), track))
+            .collect();
+        while let Ok(event) = rx.recv().await {
+            match event.kind {
+                EventKind::QuitEvent => break,
+                EventKind::LoadTrackRequestEvent(req) => {
+                    let data = tracks
+                        .get(&req.data)
+                        .cloned()
+                        .ok_or(std::io::ErrorKind::NotFound);
+                    let _ = ctx.publish_event(EventKind::LoadTrackResponseEvent(Response::new(
+                        req.id,
+                        req.sender_addr,
+                        data,
+                    )));
+                }
+                EventKind::SaveTrackRequestEvent(req) => {
+                    let (id, track) = req.data.clone();
+                    tracks.insert(id, track);
+                    let _ = ctx.publish_event(EventKind::SaveTrackResponseEvent(Response::new(
+                        req.id,
+                        req.sender_addr,
+                        Ok(()),
+                    )));
+                }
+                _ => (),
+            }
+        }
+    })
+}
+
+#[tokio::test]
+#[test_log::test]
+#[serial]
+async fn update_track_sectors() {
+    let eb = EventBus::default();
+    let mut rest = create_module(eb.context()).await;
+    let storage = spawn_in_memory_track_storage(&eb, vec![("osl".to_string(), get_track())]);
+    let client = reqwest::Client::new();
+    let sectors = vec![get_track().sectors[1]];
+
+    let response = client
+        .put("http://localhost:27015/v1/tracks/osl/sectors")
+        .json(&sectors)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let track: Track = response.json().await.unwrap();
+    assert_eq!(track.sectors, sectors);
+
+    let response = reqwest::get("http://localhost:27015/v1/tracks/osl")
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let track: Track = response.json().await.unwrap();
+    assert_eq!(track.name, get_track().name);
+    assert_eq!(track.sectors, sectors);
+
+    let response = client
+        .put("http://localhost:27015/v1/tracks/unknown/sectors")
+        .json(&sectors)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+
+    stop_module(&eb, &mut rest).await;
+    storage.await.unwrap();
+}
+
+#[tokio::test]
+#[test_log::test]
+#[serial]
+async fn get_track_sectors_with_cumulative_distances() {
+    let eb = EventBus::default();
+    let mut rest = create_module(eb.context()).await;
+    let storage = spawn_in_memory_track_storage(&eb, vec![("osl".to_string(), get_track())]);
+
+    let response = reqwest::get("http://localhost:27015/v1/tracks/osl/sectors")
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let body: serde_json::Value = response.json().await.unwrap();
+    let sectors = body["sectors"].as_array().expect("sectors is no array");
+    let track = get_track();
+    assert_eq!(sectors.len(), track.sectors.len() + 1);
+    let mut previous = 0.0;
+    for sector in sectors {
+        let cumulative = sector["cumulative_distance"].as_f64().unwrap();
+        let segment = sector["segment_length"].as_f64().unwrap();
+        assert!(cumulative > previous, "{cumulative} not after {previous}");
+        assert!((cumulative - previous - segment).abs() < 1e-6);
+        previous = cumulative;
+    }
+    let finishline: Position =
+        serde_json::from_value(sectors.last().unwrap()["position"].clone()).unwrap();
+    assert_eq!(finishline, track.finishline.unwrap());
+    assert!((previous - track.length_meters()).abs() < 1e-6);
+    assert!((body["length_meters"].as_f64().unwrap() - track.length_meters()).abs() < 1e-6);
+
+    let response = reqwest::get("http://localhost:27015/v1/tracks/unknown/sectors")
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+
+    stop_module(&eb, &mut rest).await;
+    storage.await.unwrap();
+}
+
+#[tokio::test]
+#[test_log::test]
+#[serial]
+async fn reject_invalid_track_sectors() {
+    let eb = EventBus::default();
+    let mut rest = create_module(eb.context()).await;
+    let storage = spawn_in_memory_track_storage(&eb, vec![("osl".to_string(), get_track())]);
+    let client = reqwest::Client::new();
+    let mut too_close = get_track().sectors[0];
+    too_close.latitude += 0.00001;
+
+    for sectors in [
+        vec![],
+        vec![get_track().sectors[0], too_close],
+        vec![Position::new(&95.0, &11.0)],
+    ] {
+        let response = client
+            .put("http://localhost:27015/v1/tracks/osl/sectors")
+            .json(&sectors)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+    }
+
+    let response = reqwest::get("http://localhost:27015/v1/tracks/osl")
+        .await
+        .unwrap();
+    let track: Track = response.json().await.unwrap();
+    assert_eq!(track, get_track());
+
+    stop_module(&eb, &mut rest).await;
+    storage.await.unwrap();
+}
+
+#[tokio::test]
+#[test_log::test]
+#[serial]
+async fn simulated_positions_start_lap() {
+    let eb = EventBus::default();
+    let mut rest = create_module_with_config(
+        eb.context(),
+        RestConfig {
+            simulation: true,
+            ..Default::default()
+        },
+    )
+    .await;
+    if register_response_event(
+        EventKindType::DetectTrackRequestEvent,
+        Event {
+            kind: EventKind::DetectTrackResponseEvent(Response::new(10, 22, vec![get_track()])),
+        },
+        eb.context(),
+    )
+    .is_err()
+    {
+        panic!("Failed to register DetectTrackResponseEvent");
+    }
+    let laptimer_ctx = eb.context();
+    let mut laptimer = tokio::spawn(async move {
+        let mut laptimer = SimpleLaptimer::new(laptimer_ctx);
+        laptimer.run().await
+    });
+    let mut rx = eb.subscribe();
+
+    let client = reqwest::Client::new();
+    let time = chrono::NaiveTime::from_hms_opt(13, 0, 0).unwrap();
+    let date = chrono::NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+    // Positions before and behind the startline of the test track.
+    for (latitude, longitude) in [
+        (52.0270444, 11.2805431),
+        (52.0270730, 11.2804234),
+        (52.0271084, 11.2802563),
+        (52.0271438, 11.2800835),
+    ] {
+        let position = GnssPosition::new(latitude, longitude, 30.0, &time, &date);
+        let response = client
+            .post("http://localhost:27015/v1/sim/position")
+            .json(&position)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::NO_CONTENT);
+    }
+    wait_for_event(
+        &mut rx,
+        Duration::from_millis(500),
+        EventKindType::LapStartedEvent,
+    )
+    .await;
+
+    stop_module(&eb, &mut laptimer).await;
+    stop_module(&eb, &mut rest).await;
+}
+
+#[tokio::test]
+#[test_log::test]
+#[serial]
+async fn simulation_routes_disabled_by_default() {
+    let eb = EventBus::default();
+    let mut rest = create_module(eb.context()).await;
+
+    let time = chrono::NaiveTime::from_hms_opt(13, 0, 0).unwrap();
+    let date = chrono::NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+    let response = reqwest::Client::new()
+        .post("http://localhost:27015/v1/sim/position")
+        .json(&GnssPosition::new(52.0, 11.0, 0.0, &time, &date))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+
+    stop_module(&eb, &mut rest).await;
+}
+
+#[tokio::test]
+#[test_log::test]
+#[serial]
+async fn cors_preflight_of_localhost() {
+    let eb = EventBus::default();
+    let mut rest = create_module(eb.context()).await;
+    let client = reqwest::Client::new();
+
+    let response = client
+        .request(
+            reqwest::Method::OPTIONS,
+            "http://localhost:27015/v1/sessions",
+        )
+        .header("Origin", "http://localhost:3000")
+        .header("Access-Control-Request-Method", "DELETE")
+        .header("Access-Control-Request-Headers", "content-type")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::NO_CONTENT);
+    let headers = response.headers();
+    assert_eq!(
+        headers["access-control-allow-origin"],
+        "http://localhost:3000"
+    );
+    assert!(
+        headers["access-control-allow-methods"]
+            .to_str()
+            .unwrap()
+            .contains("DELETE")
+    );
+    assert_eq!(headers["access-control-allow-headers"], "content-type");
+
+    stop_module(&eb, &mut rest).await;
+}
+
+#[tokio::test]
+#[test_log::test]
+#[serial]
+async fn cors_headers_of_configured_origin() {
+    let eb = EventBus::default();
+    let mut rest = create_module_with_config(
+        eb.context(),
+        RestConfig {
+            cors: CorsConfig {
+                allowed_origins: vec!["https://dashboard.example".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    )
+    .await;
+    let client = reqwest::Client::new();
+
+    let response = client
+        .get("http://localhost:27015/v1/version")
+        .header("Origin", "https://dashboard.example")
+        .send()
+        .await
+        .unwrap();
+    assert!(response.status().is_success());
+    assert_eq!(
+        response.headers()["access-control-allow-origin"],
+        "https://dashboard.example"
+    );
+
+    for origin in ["http://localhost:3000", "https://other.example"] {
+        let response = client
+            .get("http://localhost:27015/v1/version")
+            .header("Origin", origin)
+            .send()
+            .await
+            .unwrap();
+        assert!(response.status().is_success());
+        assert!(
+            !response
+                .headers()
+                .contains_key("access-control-allow-origin")
+        );
+    }
+
+    stop_module(&eb, &mut rest).await;
+}
+
+/// Returns a lap driven north from the test track's start line with a log point every
+/// `step` meters up to `length` meters at the given constant velocity.
+fn lap_driven_north(step: f64, length: f64, velocity: f64) -> Lap {
+    let start = chrono::NaiveDate::from_ymd_opt(2026, 1, 1)
+        .unwrap()
+        .and_hms_opt(13, 0, 0)
+        .unwrap();
+    let startline = get_track().startline;
+    let log_points = (0..=(length / step) as usize)
+        .map(|index| {
+            let distance = index as f64 * step;
+            let timestamp =
+                start + chrono::TimeDelta::milliseconds((distance / velocity * 1000.0) as i64);
+            GnssPosition::new(
+                startline.latitude + distance / 111300.0,
+                startline.longitude,
+                velocity,
+                &timestamp.time(),
+                &timestamp.date(),
+            )
+        })
+        .collect();
+    Lap {
+        sectors: vec![Duration::from_secs_f64(length / velocity)],
+        log_points,
+    }
+}
+
+#[tokio::test]
+#[test_log::test]
+#[serial]
+async fn compare_laps_aligned_by_distance() {
+    let eb = EventBus::default();
+    let mut rest = create_module(eb.context()).await;
+    let mut session = get_session();
+    // Lap 1 is faster and its log ends before the end of lap 0.
+    session.laps = vec![
+        lap_driven_north(100.0, 1000.0, 10.0),
+        lap_driven_north(50.0, 900.0, 12.5),
+    ];
+    let storage = spawn_in_memory_storage(&eb, vec![("session".to_string(), session)]);
+
+    let response = reqwest::get("http://localhost:27015/v1/sessions/session/laps/compare?a=0&b=1")
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["a"], 0);
+    assert_eq!(body["b"], 1);
+    let samples = body["samples"].as_array().unwrap();
+    assert_eq!(samples.len(), 11);
+    for (index, sample) in samples.iter().enumerate() {
+        let distance = index as f64 * 100.0;
+        assert!((sample["distance"].as_f64().unwrap() - distance).abs() < 0.5);
+        if distance <= 900.0 {
+            // Lap 1 needs 2 s less per 100 m.
+            let delta = sample["delta"].as_f64().unwrap();
+            assert!(
+                (delta + distance * 0.02).abs() < 0.01,
+                "delta {delta} at {distance} m"
+            );
+            let b = sample["b"]["latitude"].as_f64().unwrap();
+            assert!((b - sample["a"]["latitude"].as_f64().unwrap()).abs() < 1e-6);
+        } else {
+            assert!(sample["b"].is_null());
+            assert!(sample["delta"].is_null());
+        }
+    }
+
+    for url in [
+        "http://localhost:27015/v1/sessions/session/laps/compare?a=0&b=2",
+        "http://localhost:27015/v1/sessions/unknown/laps/compare?a=0&b=1",
+    ] {
+        let response = reqwest::get(url).await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND, "{url}");
+    }
+
+    stop_module(&eb, &mut rest).await;
+    storage.await.unwrap();
+}
+
+#[tokio::test]
+#[test_log::test]
+#[serial]
+async fn rolling_average_of_lap_times() {
+    let eb = EventBus::default();
+    let mut rest = create_module(eb.context()).await;
+    let mut session = get_session();
+    session.laps = [70, 72, 74, 68, 77]
+        .into_iter()
+        .map(|seconds| Lap {
+            sectors: vec![Duration::from_secs(seconds - 30), Duration::from_secs(30)],
+            log_points: vec![],
+        })
+        .collect();
+    let storage = spawn_in_memory_storage(&eb, vec![("session".to_string(), session)]);
+
+    let response = reqwest::get("http://localhost:27015/v1/sessions/session/rolling?window=3")
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(
+        body,
+        serde_json::json!({
+            "window": 3,
+            "averages": ["00:01:12.000", "00:01:11.333", "00:01:13.000"]
+        })
+    );
+
+    let response = reqwest::get("http://localhost:27015/v1/sessions/session/rolling?window=6")
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body, serde_json::json!({"window": 6, "averages": []}));
+
+    for (url, status) in [
+        (
+            "http://localhost:27015/v1/sessions/session/rolling?window=0",
+            reqwest::StatusCode::BAD_REQUEST,
+        ),
+        (
+            "http://localhost:27015/v1/sessions/unknown/rolling?window=3",
+            reqwest::StatusCode::NOT_FOUND,
+        ),
+    ] {
+        let response = reqwest::get(url).await.unwrap();
+        assert_eq!(response.status(), status, "{url}");
+    }
+
+    stop_module(&eb, &mut rest).await;
+    storage.await.unwrap();
+}
+
+fn session_with_laps(track: &str, laptimes: &[u64]) -> Session {
+    let mut session = get_session();
+    session.track.name = track.to_string();
+    session.laps = laptimes
+        .iter()
+        .map(|seconds| Lap {
+            sectors: vec![Duration::from_secs(seconds - 30), Duration::from_secs(30)],
+            log_points: vec![],
+        })
+        .collect();
+    session
+}
+
+#[tokio::test]
+#[test_log::test]
+#[serial]
+async fn compare_lap_times_of_two_sessions() {
+    let eb = EventBus::default();
+    let mut rest = create_module(eb.context()).await;
+    let storage = spawn_in_memory_storage(
+        &eb,
+        vec![
+            (
+                "day1".to_string(),
+                session_with_laps("Oschersleben", &[70, 72, 74]),
+            ),
+            (
+                "day2".to_string(),
+                session_with_laps("Oschersleben", &[68, 77]),
+            ),
+            ("other".to_string(), session_with_laps("Other", &[80])),
+        ],
+    );
+
+    let response = reqwest::get("http://localhost:27015/v1/sessions/compare?a=day1&b=day2")
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(
+        body,
+        serde_json::json!({
+            "a": {
+                "id": "day1",
+                "track": "Oschersleben",
+                "laps": 3,
+                "best_lap": "00:01:10.000",
+                "average_lap": "00:01:12.000"
+            },
+            "b": {
+                "id": "day2",
+                "track": "Oschersleben",
+                "laps": 2,
+                "best_lap": "00:01:08.000",
+                "average_lap": "00:01:12.500"
+            },
+            "same_track": true,
+            "best_lap_delta": -2.0
+        })
+    );
+
+    // Sessions on different tracks are still compared.
+    let response = reqwest::get("http://localhost:27015/v1/sessions/compare?a=day1&b=other")
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["same_track"], false);
+    assert_eq!(body["best_lap_delta"], 10.0);
+
+    let response = reqwest::get("http://localhost:27015/v1/sessions/compare?a=day1&b=unknown")
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+
+    stop_module(&eb, &mut rest).await;
+    storage.await.unwrap();
+}
+
+#[tokio::test]
+#[test_log::test]
+#[serial]
+async fn elevation_profile_of_lap() {
+    let eb = EventBus::default();
+    let mut rest = create_module(eb.context()).await;
+    let time = chrono::NaiveTime::from_hms_opt(13, 0, 0).unwrap();
+    let date = chrono::NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+    let mut session = get_session();
+    session.laps = vec![Lap {
+        sectors: vec![Duration::from_secs(70)],
+        log_points: [
+            (52.0000, Some(87.5)),
+            (52.0005, Some(88.0)),
+            (52.0010, None),
+            (52.0015, Some(86.25)),
+        ]
+        .into_iter()
+        .map(|(latitude, altitude)| {
+            let position = GnssPosition::new(latitude, 11.0, 30.0, &time, &date);
+            match altitude {
+                Some(altitude) => position.with_altitude(altitude),
+                None => position,
+            }
+        })
+        .collect(),
+    }];
+    let storage = spawn_in_memory_storage(&eb, vec![("session".to_string(), session)]);
+
+    let response = reqwest::get("http://localhost:27015/v1/sessions/session/laps/0/elevation")
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let body: serde_json::Value = response.json().await.unwrap();
+    let profile = body.as_array().unwrap();
+    let altitudes: Vec<f64> = profile
+        .iter()
+        .map(|point| point["altitude_m"].as_f64().unwrap())
+        .collect();
+    assert_eq!(altitudes, vec![87.5, 88.0, 86.25]);
+    let distances: Vec<f64> = profile
+        .iter()
+        .map(|point| point["distance_m"].as_f64().unwrap())
+        .collect();
+    assert_eq!(distances[0], 0.0);
+    assert!(distances.windows(2).all(|pair| pair[0] < pair[1]));
+    // The point without altitude is left out, but its distance is still covered.
+    assert!((distances[1] - 55.65).abs() < 0.01, "{distances:?}");
+    assert!((distances[2] - 166.95).abs() < 0.01, "{distances:?}");
+
+    for url in [
+        "http://localhost:27015/v1/sessions/session/laps/1/elevation",
+        "http://localhost:27015/v1/sessions/unknown/laps/0/elevation",
+    ] {
+        let response = reqwest::get(url).await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND, "{url}");
+    }
+
+    stop_module(&eb, &mut rest).await;
+    storage.await.unwrap();
+}
+
+/// Counts the data messages per global message number of the data records of a FIT file.
+fn count_fit_messages(data: &[u8]) -> std::collections::HashMap<u16, usize> {
+    let mut definitions = std::collections::HashMap::new();
+    let mut counts = std::collections::HashMap::new();
+    let mut offset = 0;
+    while offset < data.len() {
+        let header = data[offset];
+        let local = header & 0x0F;
+        if header & 0x40 != 0 {
+            let global = u16::from_le_bytes([data[offset + 3], data[offset + 4]]);
+            let fields = data[offset + 5] as usize;
+            let size = (0..fields)
+                .map(|field| data[offset + 6 + field * 3 + 1] as usize)
+                .sum::<usize>();
+            definitions.insert(local, (global, size));
+            offset += 6 + fields * 3;
+        } else {
+            let (global, size) = definitions[&local];
+            *counts.entry(global).or_insert(0) += 1;
+            offset += 1 + size;
+        }
+    }
+    counts
+}
+
+#[tokio::test]
+#[test_log::test]
+#[serial]
+async fn session_exported_as_fit_file() {
+    let eb = EventBus::default();
+    let mut rest = create_module(eb.context()).await;
+    let session = get_session();
+    let expected_records: usize = session.laps.iter().map(|lap| lap.log_points.len()).sum();
+    let expected_laps = session.laps.len();
+    let storage = spawn_in_memory_storage(&eb, vec![("session".to_string(), session)]);
+
+    let response = reqwest::get("http://localhost:27015/v1/sessions/session/fit")
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    assert_eq!(
+        response.headers()["content-type"].to_str().unwrap(),
+        "application/vnd.ant.fit"
+    );
+    let bytes = response.bytes().await.unwrap();
+    assert_eq!(bytes[0], 14);
+    assert_eq!(&bytes[8..12], b".FIT");
+    let data_size = u32::from_le_bytes(bytes[4..8].try_into().unwrap()) as usize;
+    assert_eq!(bytes.len(), 14 + data_size + 2);
+    let counts = count_fit_messages(&bytes[14..14 + data_size]);
+    assert_eq!(counts[&20], expected_records);
+    assert_eq!(counts[&19], expected_laps);
+    assert_eq!(counts[&0], 1);
+
+    let response = reqwest::get("http://localhost:27015/v1/sessions/unknown/fit")
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+
+    stop_module(&eb, &mut rest).await;
+    storage.await.unwrap();
+}
+
+#[tokio::test]
+#[test_log::test]
+#[serial]
+async fn quit_survives_flood_of_positions() {
+    let eb = EventBus::new_with_config(EventBusConfig {
+        capacity: 16,
+        ..Default::default()
+    });
+    let mut rest = create_module(eb.context()).await;
+    let position = Arc::new(GnssPosition::new(
+        52.0,
+        11.0,
+        30.0,
+        &chrono::NaiveTime::default(),
+        &chrono::NaiveDate::default(),
+    ));
+
+    // The REST module can't run in between, so the broadcast channel drops the QuitEvent.
+    eb.publish(&Event {
+        kind: EventKind::QuitEvent,
+    });
+    for _ in 0..100 {
+        eb.publish(&Event {
+            kind: EventKind::GnssPositionEvent(position.clone()),
+        });
+    }
+    tokio::time::timeout(Duration::from_secs(1), &mut rest)
+        .await
+        .expect("REST module missed the QuitEvent")
+        .unwrap()
+        .unwrap();
+}
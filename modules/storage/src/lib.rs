@@ -8,47 +8,371 @@
 
 use chrono::NaiveDateTime;
 use common::{
+    id::{SessionId, TrackId},
     session::{Session, SessionInfo},
     track::Track,
 };
+use flate2::{Compression, read::GzDecoder, write::GzEncoder};
 use module_core::{
     DeleteSessionRequestPtr, DeleteSessionResponsePtr, EmptyRequestPtr, Event, EventKind,
+    EventKindType, FinalizeSessionRequestPtr, FinalizeSessionResponsePtr, HEARTBEAT_INTERVAL,
     LoadSessionRequestPtr, LoadSessionResponsePtr, LoadStoredTrackIdsResponsePtr,
-    LoadStoredTracksReponsePtr, ModuleCtx, Response, SaveSessionRequestPtr, SaveSessionResponsePtr,
-    StoredSessionIdsResponsePtr,
+    LoadStoredTracksReponsePtr, LoadTrackRequestPtr, LoadTrackResponsePtr, ModuleCtx,
+    ModuleInterface, Response, SaveFailedPtr, SaveFailure, SaveSessionRequestPtr,
+    SaveSessionResponsePtr, SaveTrackRequestPtr, SaveTrackResponsePtr, SessionFilePathRequestPtr,
+    SessionFilePathResponsePtr, StoredSessionIdsResponsePtr,
 };
 use std::{
-    fs::{DirBuilder, exists},
-    io::{self},
+    collections::HashMap,
+    fs::{DirBuilder, File, OpenOptions, TryLockError, exists},
+    io::{self, Read, Write},
     path::{Path, PathBuf},
     sync::{Arc, RwLock},
+    time::Duration,
 };
 use tokio::{
     fs::read_dir,
     io::{AsyncReadExt, AsyncWriteExt},
+    time::Instant,
 };
 use tracing::{debug, error, info};
 
+/// Naming scheme used to derive the id (and therefore the file names) of a stored session.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum IdScheme {
+    /// `<track>_<DD>_<MM>_<YYYY>_<HH>_<MM>_<SS>_<mmm>`, e.g. `oschersleben_01_01_1970_13_00_00_000`.
+    #[default]
+    TrackDateTime,
+
+    /// `<YYYYMMDD>_<HHMMSS>_<track>`, e.g. `19700101_130000_oschersleben`.
+    ///
+    /// Sorts the sessions chronologically in file browsers. The id has a resolution of
+    /// seconds, the [`FilesSystemStorage`] appends `_2`, `_3`, … to the id of a session
+    /// started within the same second as an already stored one, e.g.
+    /// `19700101_130000_oschersleben_2`.
+    DateTimeTrack,
+
+    /// `<YYYYMMDD>_<HHMMSS>_<mmm>_<track>`, e.g. `19700101_130000_000_oschersleben`.
+    ///
+    /// Like [`IdScheme::DateTimeTrack`] but keeps the milliseconds of the session start.
+    DateTimeMillisTrack,
+}
+
+impl IdScheme {
+    /// Returns the id of the given session according to this naming scheme.
+    pub fn session_id(&self, session: &Session) -> SessionId {
+        let track = session.track.name.to_lowercase();
+        let id = match self {
+            IdScheme::TrackDateTime => format!(
+                "{}_{}_{}",
+                track,
+                session.date.format("%d_%m_%Y"),
+                session.time.format("%H_%M_%S_%3f")
+            ),
+            IdScheme::DateTimeTrack => format!(
+                "{}_{}_{}",
+                session.date.format("%Y%m%d"),
+                session.time.format("%H%M%S"),
+                track
+            ),
+            IdScheme::DateTimeMillisTrack => format!(
+                "{}_{}_{}",
+                session.date.format("%Y%m%d"),
+                session.time.format("%H%M%S_%3f"),
+                track
+            ),
+        };
+        SessionId::from(id)
+    }
+}
+
+/// Reasons why a stored session file can't be read.
+#[derive(Debug)]
+pub enum SessionFileError {
+    /// The session file doesn't exist.
+    NotFound,
+
+    /// The session file isn't valid UTF-8, e.g. because it was only partially written.
+    /// Carries the number of bytes that are valid UTF-8.
+    InvalidUtf8(usize),
+
+    /// The session file is valid UTF-8 but no valid session JSON.
+    InvalidJson(serde_json::Error),
+
+    /// The content of the session file doesn't match its stored checksum, e.g. because
+    /// the flash silently corrupted it.
+    ChecksumMismatch {
+        /// The checksum stored next to the session file.
+        expected: String,
+        /// The checksum of the read session file.
+        actual: String,
+    },
+
+    /// Any other I/O error while reading the session file.
+    Io(io::Error),
+}
+
+impl SessionFileError {
+    /// Returns the [`io::ErrorKind`] reported in the responses of the storage.
+    ///
+    /// Invalid UTF-8, invalid JSON and checksum mismatches are all reported as
+    /// [`io::ErrorKind::InvalidData`].
+    pub fn kind(&self) -> io::ErrorKind {
+        match self {
+            SessionFileError::NotFound => io::ErrorKind::NotFound,
+            SessionFileError::InvalidUtf8(_)
+            | SessionFileError::InvalidJson(_)
+            | SessionFileError::ChecksumMismatch { .. } => io::ErrorKind::InvalidData,
+            SessionFileError::Io(e) => e.kind(),
+        }
+    }
+}
+
+impl std::fmt::Display for SessionFileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SessionFileError::NotFound => write!(f, "file missing"),
+            SessionFileError::InvalidUtf8(valid_up_to) => {
+                write!(f, "not valid UTF-8 after byte {valid_up_to}")
+            }
+            SessionFileError::InvalidJson(e) => write!(f, "bad JSON: {e}"),
+            SessionFileError::ChecksumMismatch { expected, actual } => {
+                write!(f, "checksum mismatch, expected {expected} but got {actual}")
+            }
+            SessionFileError::Io(e) => write!(f, "I/O error: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for SessionFileError {}
+
+/// Extension of the session files that are compressed after their finalization.
+pub const COMPRESSED_SESSION_EXTENSION: &str = "session.gz";
+
+/// Returns the path of the compressed counterpart of the session file at `path`.
+fn compressed_session_path(path: &Path) -> PathBuf {
+    let mut compressed = path.as_os_str().to_owned();
+    compressed.push(".gz");
+    PathBuf::from(compressed)
+}
+
+/// Reads the content of the session file at `path`.
+///
+/// Files with the `.gz` extension are decompressed. If the file at `path` doesn't exist, its
+/// compressed counterpart `<path>.gz` is read instead, so finalized sessions are found under
+/// the path of the uncompressed session file.
+async fn read_session_bytes(path: &Path) -> Result<Vec<u8>, SessionFileError> {
+    let read = |path: PathBuf| async move {
+        let bytes = tokio::fs::read(&path).await?;
+        if path.extension().is_some_and(|ext| ext == "gz") {
+            return gunzip(&bytes);
+        }
+        Ok(bytes)
+    };
+    let result = match read(path.to_path_buf()).await {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => read(compressed_session_path(path)).await,
+        result => result,
+    };
+    result.map_err(|e| match e.kind() {
+        io::ErrorKind::NotFound => SessionFileError::NotFound,
+        _ => SessionFileError::Io(e),
+    })
+}
+
+/// Compresses `bytes` with gzip.
+fn gzip(bytes: &[u8]) -> io::Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(bytes)?;
+    encoder.finish()
+}
+
+/// Decompresses the gzip compressed `bytes`.
+fn gunzip(bytes: &[u8]) -> io::Result<Vec<u8>> {
+    let mut decompressed = Vec::new();
+    GzDecoder::new(bytes).read_to_end(&mut decompressed)?;
+    Ok(decompressed)
+}
+
+/// Reads and parses the session file at `path`.
+///
+/// The file content is validated explicitly, so a missing file, a file that isn't valid
+/// UTF-8 and a file with bad JSON are reported as distinct [`SessionFileError`]s.
+/// Compressed session files are read transparently, see [`COMPRESSED_SESSION_EXTENSION`].
+pub async fn read_session_file(path: impl AsRef<Path>) -> Result<Session, SessionFileError> {
+    let bytes = read_session_bytes(path.as_ref()).await?;
+    parse_session(&bytes)
+}
+
+/// Reads the session file at `path` and verifies it against the checksum file at
+/// `checksum_path` before parsing it.
+///
+/// Session files without a checksum file, e.g. stored by older versions, are parsed
+/// without verification. The checksum of compressed session files is the one of their
+/// decompressed content.
+pub async fn read_verified_session_file(
+    path: impl AsRef<Path>,
+    checksum_path: impl AsRef<Path>,
+) -> Result<Session, SessionFileError> {
+    let bytes = read_session_bytes(path.as_ref()).await?;
+    match tokio::fs::read_to_string(checksum_path).await {
+        Ok(expected) => {
+            let expected = expected.trim().to_owned();
+            let actual = session_checksum(&bytes);
+            if expected != actual {
+                return Err(SessionFileError::ChecksumMismatch { expected, actual });
+            }
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => (),
+        Err(e) => return Err(SessionFileError::Io(e)),
+    }
+    parse_session(&bytes)
+}
+
+/// Returns the checksum stored next to every saved session file, the CRC32 (IEEE) of
+/// `bytes` as 8 lowercase hex digits.
+pub fn session_checksum(bytes: &[u8]) -> String {
+    let mut crc = !0u32;
+    for byte in bytes {
+        crc ^= u32::from(*byte);
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    format!("{:08x}", !crc)
+}
+
+/// Returns the [`SessionInfo`] of a session stored with the given id.
+fn session_info(id: SessionId, session: &Session) -> SessionInfo {
+    SessionInfo::new(
+        id,
+        NaiveDateTime::new(session.date, session.time),
+        session.track.name.clone(),
+        session.laps.len(),
+    )
+}
+
+/// Parses the content of a session file.
+fn parse_session(bytes: &[u8]) -> Result<Session, SessionFileError> {
+    let json =
+        std::str::from_utf8(bytes).map_err(|e| SessionFileError::InvalidUtf8(e.valid_up_to()))?;
+    Session::from_json(json).map_err(SessionFileError::InvalidJson)
+}
+
+/// Reads a written session file back and verifies it.
+///
+/// The file must contain exactly the `expected` serialized session and deserialize into a
+/// session, otherwise an [`io::ErrorKind::InvalidData`] error is returned.
+pub async fn verify_session_file(path: impl AsRef<Path>, expected: &str) -> io::Result<()> {
+    let bytes = tokio::fs::read(path).await?;
+    if bytes != expected.as_bytes() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "written session differs from the saved one",
+        ));
+    }
+    parse_session(&bytes)
+        .map(|_| ())
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
 /// A file system–based implementation of a storage.
 ///
 /// This struct is responsible for persisting session and track data as files in a specified root directory.
 /// Each session is stored as a separate file with the `.session` extension in the folder session.
+/// Finalized sessions are compressed into a file with the `.session.gz` extension.
 /// Each session is track as a separate file with the `.track` extension in the folder track.
 ///
 /// ## Important
 ///
-/// `FilesSystemStorage` **does not implement any internal synchronization or locking mechanisms**.
-/// Therefore, **only one instance should be used per `root_dir` in the application at any time**.
-/// Creating multiple instances pointing to the same directory may result in data races,
-/// file corruption, or unexpected behavior.
+/// `FilesSystemStorage` **does not implement any internal synchronization mechanisms**.
+/// Therefore, **only one instance may be used per `root_dir` at any time**, multiple instances
+/// pointing to the same directory would corrupt the files. This is enforced with an advisory
+/// lock on the file [`LOCK_FILE`] in the `root_dir`, which is held as long as the instance
+/// lives, so a second instance fails to be created, even in another process.
 pub struct FilesSystemStorage {
     session_root_dir: String,
     track_root_dir: String,
     module_ctx: ModuleCtx,
+    id_scheme: IdScheme,
+    strict_mode: bool,
+    open_session_timeout: Duration,
+    pretty_json: bool,
+    save_coalescing: Duration,
+    verify_on_write: bool,
+    verify_checksum: bool,
+    max_tracks: usize,
+    shutdown_grace: Duration,
+    pending_saves: HashMap<SessionId, PendingSave>,
+    _lock: File,
+}
+
+/// Default of [`FilesSystemStorage::with_shutdown_grace`].
+pub const DEFAULT_SHUTDOWN_GRACE: Duration = Duration::from_millis(50);
+
+/// Name of the lock file in the root dir that guards against multiple instances, see
+/// [`FilesSystemStorage`].
+pub const LOCK_FILE: &str = ".lock";
+
+/// Name of the sidecar file in the track folder with the last use of every stored track.
+const TRACK_USAGE_FILE: &str = "last_used.json";
+
+/// A serialized session whose write is delayed to coalesce it with later saves.
+#[derive(Debug)]
+struct PendingSave {
+    session: String,
+    session_info: String,
+    deadline: Instant,
+}
+
+/// Serializes a session for storing, indented if `pretty` is set and compact otherwise.
+pub fn session_to_json(session: &Session, pretty: bool) -> serde_json::Result<String> {
+    if pretty {
+        Session::to_json_pretty(session)
+    } else {
+        Session::to_json(session)
+    }
+}
+
+/// Removes the file at `path`, a missing file is no error.
+async fn remove_file_if_exists(path: &str) -> io::Result<()> {
+    match tokio::fs::remove_file(path).await {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+/// Acquires the exclusive lock of the storage in the root dir.
+///
+/// Fails with [`io::ErrorKind::WouldBlock`] if another instance holds the lock.
+fn lock_root_dir(root_dir: &Path) -> io::Result<File> {
+    DirBuilder::new().recursive(true).create(root_dir)?;
+    let lock_file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(root_dir.join(LOCK_FILE))?;
+    match lock_file.try_lock() {
+        Ok(()) => Ok(lock_file),
+        Err(TryLockError::WouldBlock) => Err(io::Error::new(
+            io::ErrorKind::WouldBlock,
+            format!(
+                "storage folder {} is used by another instance",
+                root_dir.to_string_lossy()
+            ),
+        )),
+        Err(TryLockError::Error(e)) => Err(e),
+    }
 }
 
 impl FilesSystemStorage {
-    pub fn new(root_dir: &PathBuf, ctx: ModuleCtx) -> Self {
+    /// Creates a storage in the given root dir, the session and track folders are created
+    /// if they don't exist.
+    ///
+    /// Fails with [`io::ErrorKind::WouldBlock`] if another instance uses the root dir, see
+    /// [`FilesSystemStorage`], or with the error of the lock file if it can't be created.
+    pub fn new(root_dir: &PathBuf, ctx: ModuleCtx) -> io::Result<Self> {
+        let lock = lock_root_dir(root_dir)?;
         let mut session_file_path = std::path::PathBuf::from(&root_dir);
         session_file_path.push("session");
         let mut track_file_path = PathBuf::from(&root_dir);
@@ -60,6 +384,13 @@ impl FilesSystemStorage {
                 e
             );
         }
+        if let Err(e) = DirBuilder::new().recursive(true).create(&track_file_path) {
+            error!(
+                "Failed to create track dir folder {}. Error: {}",
+                track_file_path.to_string_lossy(),
+                e
+            );
+        }
         info!(
             "Using session storage folder: {}",
             session_file_path.to_string_lossy()
@@ -68,11 +399,170 @@ impl FilesSystemStorage {
             "Using track storage folder: {}",
             track_file_path.to_string_lossy()
         );
-        FilesSystemStorage {
+        Ok(FilesSystemStorage {
             session_root_dir: session_file_path.to_string_lossy().to_string(),
             track_root_dir: track_file_path.to_string_lossy().to_string(),
             module_ctx: ctx,
+            id_scheme: IdScheme::default(),
+            strict_mode: false,
+            open_session_timeout: Duration::ZERO,
+            pretty_json: cfg!(debug_assertions),
+            save_coalescing: Duration::ZERO,
+            verify_on_write: false,
+            verify_checksum: false,
+            max_tracks: 0,
+            shutdown_grace: DEFAULT_SHUTDOWN_GRACE,
+            pending_saves: HashMap::new(),
+            _lock: lock,
+        })
+    }
+
+    /// Sets the naming scheme used for the ids and file names of newly stored sessions.
+    ///
+    /// Defaults to [`IdScheme::TrackDateTime`].
+    pub fn with_id_scheme(mut self, id_scheme: IdScheme) -> Self {
+        self.id_scheme = id_scheme;
+        self
+    }
+
+    /// Enables or disables the strict mode.
+    ///
+    /// In strict mode every failed save additionally publishes an
+    /// [`EventKind::SaveFailedEvent`], so a session can't silently fail to persist.
+    /// Disabled by default.
+    pub fn with_strict_mode(mut self, strict_mode: bool) -> Self {
+        self.strict_mode = strict_mode;
+        self
+    }
+
+    /// Sets the time after which a stored session that is still open is considered abandoned.
+    ///
+    /// On startup, every open session whose file wasn't modified for this time is finalized
+    /// and compressed, so recordings interrupted by e.g. a crash aren't left open. Defaults to zero, which
+    /// finalizes all open sessions on startup.
+    pub fn with_open_session_timeout(mut self, timeout: Duration) -> Self {
+        self.open_session_timeout = timeout;
+        self
+    }
+
+    /// Stores the sessions as indented JSON if enabled and as compact JSON otherwise.
+    ///
+    /// Defaults to indented JSON in debug builds, so stored sessions are easy to inspect
+    /// during development, and to compact JSON in release builds.
+    pub fn with_pretty_json(mut self, pretty_json: bool) -> Self {
+        self.pretty_json = pretty_json;
+        self
+    }
+
+    /// Sets the window in which saves of the same session are coalesced into a single write.
+    ///
+    /// A saved session is written when the window after its first save elapsed, with the
+    /// content of the latest save, e.g. to save flash writes when an auto-save and the
+    /// save at a finished lap happen in quick succession. Pending writes are flushed before
+    /// sessions are listed, loaded or deleted and on shutdown. Save errors of coalesced
+    /// writes can't be reported in the save response, they are logged and published as
+    /// [`EventKind::SaveFailedEvent`] in strict mode. Defaults to zero, which disables the
+    /// coalescing.
+    pub fn with_save_coalescing(mut self, window: Duration) -> Self {
+        self.save_coalescing = window;
+        self
+    }
+
+    /// Enables or disables the verification of written sessions.
+    ///
+    /// With verification every session file is read back after it was written and synced,
+    /// and the save fails with [`io::ErrorKind::InvalidData`] if the file differs from the
+    /// saved session or can't be deserialized, see [`verify_session_file`]. Intended for
+    /// critical recordings, where the caller must know that a save is untrustworthy.
+    /// Disabled by default.
+    pub fn with_verify_on_write(mut self, verify_on_write: bool) -> Self {
+        self.verify_on_write = verify_on_write;
+        self
+    }
+
+    /// Enables or disables the verification of the session checksums on load.
+    ///
+    /// Every saved session gets a companion `<id>.sum` file with its checksum, see
+    /// [`session_checksum`]. With verification a session whose content doesn't match its
+    /// checksum fails to load with [`io::ErrorKind::InvalidData`], so silent corruption of
+    /// the flash is detected. Sessions without a checksum file are loaded unverified.
+    /// Disabled by default.
+    pub fn with_verify_checksum(mut self, verify_checksum: bool) -> Self {
+        self.verify_checksum = verify_checksum;
+        self
+    }
+
+    /// Sets the maximum number of stored tracks.
+    ///
+    /// When storing a track exceeds the limit, the least recently used tracks are deleted.
+    /// A track counts as used when it's stored, loaded or detected. The last use of the
+    /// tracks is kept in the sidecar file `last_used.json` in the track folder, tracks
+    /// without an entry fall back to the modification time of their file. Defaults to
+    /// zero, which doesn't limit the number of tracks.
+    pub fn with_max_tracks(mut self, max_tracks: usize) -> Self {
+        self.max_tracks = max_tracks;
+        self
+    }
+
+    /// Sets how long requests are still handled after the [`EventKind::QuitEvent`].
+    ///
+    /// Modules that stop on the same event, e.g. the active session saving its final state,
+    /// can only publish their last requests after the event. The grace restarts after every
+    /// save and finalization, so a slow write doesn't cut off the requests behind it.
+    /// Defaults to [`DEFAULT_SHUTDOWN_GRACE`].
+    pub fn with_shutdown_grace(mut self, grace: Duration) -> Self {
+        self.shutdown_grace = grace;
+        self
+    }
+
+    /// Finalizes and compresses all uncompressed stored sessions that are older than the open
+    /// session timeout.
+    ///
+    /// These are sessions left open by an interrupted recording, and finalized sessions whose
+    /// compression was interrupted. Sessions that can't be read or parsed are logged and
+    /// skipped.
+    ///
+    /// Returns:
+    /// - `Ok(count)` with the number of finalized sessions.
+    ///
+    /// Errors:
+    /// - Propagates errors from listing the session folder and writing finalized sessions.
+    async fn finalize_open_sessions(&self) -> io::Result<usize> {
+        let mut finalized = 0;
+        for id in self.ids(&self.session_root_dir, "session").await? {
+            let id = SessionId::from(id);
+            let file_path = self.get_session_file_path(&id);
+            let age = tokio::fs::metadata(&file_path)
+                .await
+                .and_then(|metadata| metadata.modified())
+                .map(|modified| modified.elapsed().unwrap_or_default());
+            match age {
+                Ok(age) if age < self.open_session_timeout => continue,
+                Ok(_) => (),
+                Err(e) => {
+                    error!(
+                        "Failed to read modification time of {}. Error: {}",
+                        file_path, e
+                    );
+                    continue;
+                }
+            }
+            let session = match read_session_file(&file_path).await {
+                Ok(session) => session,
+                Err(e) => {
+                    error!("Failed to load session \"{}\". Error: {}", file_path, e);
+                    continue;
+                }
+            };
+            if session.finalized {
+                info!("Compressing finalized session {}", id);
+            } else {
+                info!("Finalizing abandoned session {}", id);
+            }
+            self.compress_session(&id, session).await?;
+            finalized += 1;
         }
+        Ok(finalized)
     }
 
     /// Persists a session and its derived metadata, returning the session `id`.
@@ -92,40 +582,150 @@ impl FilesSystemStorage {
     ///
     /// Errors:
     /// - Propagates errors from serialization and underlying file I/O operations.
-    async fn save(&self, session: &RwLock<Session>) -> std::io::Result<String> {
-        let json_session;
-        let id;
-        let json_session_info;
-        {
-            let session = session.read().unwrap_or_else(|e| e.into_inner());
-            json_session = Session::to_json(&session)?; // TODO! this sould be done async
-            id = FilesSystemStorage::get_id(&session);
-            let session_info = SessionInfo::new(
-                id.clone(),
-                NaiveDateTime::new(session.date, session.time),
-                session.track.name.clone(),
-                session.laps.len(),
-            );
-            json_session_info = SessionInfo::to_json(&session_info)?; // TODO! this sould be done async
-        }
+    async fn save(&self, session: &RwLock<Session>) -> std::io::Result<SessionId> {
+        let (id, json_session, json_session_info) = self.serialize(session)?;
         self.save_session(&id, &json_session).await?;
         self.save_session_info(&id, &json_session_info).await?;
         Ok(id)
     }
 
+    /// Serializes a session and its `SessionInfo`, returning `(id, session, session_info)`.
+    fn serialize(&self, session: &RwLock<Session>) -> io::Result<(SessionId, String, String)> {
+        let session = session.read().unwrap_or_else(|e| e.into_inner());
+        let json_session = session_to_json(&session, self.pretty_json)?; // TODO! this sould be done async
+        let id = self.get_id(&session);
+        let session_info = session_info(id.clone(), &session);
+        let json_session_info = SessionInfo::to_json(&session_info)?; // TODO! this sould be done async
+        Ok((id, json_session, json_session_info))
+    }
+
+    /// Buffers a session until the coalescing window after its first buffered save elapsed,
+    /// returning the session `id`.
+    ///
+    /// A buffered session with the same id is replaced, so only the latest save is written.
+    fn buffer_save(&mut self, session: &RwLock<Session>) -> io::Result<SessionId> {
+        let (id, session, session_info) = self.serialize(session)?;
+        match self.pending_saves.get_mut(&id) {
+            Some(pending) => {
+                debug!("Coalescing save of session {} with the pending one", id);
+                pending.session = session;
+                pending.session_info = session_info;
+            }
+            None => {
+                let deadline = Instant::now() + self.save_coalescing;
+                self.pending_saves.insert(
+                    id.clone(),
+                    PendingSave {
+                        session,
+                        session_info,
+                        deadline,
+                    },
+                );
+            }
+        }
+        Ok(id)
+    }
+
+    /// Writes the buffered sessions whose coalescing window elapsed, or all if `all` is set.
+    async fn flush_pending_saves(&mut self, all: bool) {
+        let now = Instant::now();
+        let due: Vec<SessionId> = self
+            .pending_saves
+            .iter()
+            .filter(|(_, pending)| all || pending.deadline <= now)
+            .map(|(id, _)| id.clone())
+            .collect();
+        for id in due {
+            let Some(pending) = self.pending_saves.remove(&id) else {
+                continue;
+            };
+            let result = match self.save_session(&id, &pending.session).await {
+                Ok(()) => self.save_session_info(&id, &pending.session_info).await,
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(()) => debug!("Stored session with id {} in {}", id, self.session_root_dir),
+                Err(e) => {
+                    error!("Failed to store coalesced session {}. Error: {}", id, e);
+                    if self.strict_mode {
+                        let _ = self.module_ctx.sender.send(Event {
+                            kind: EventKind::SaveFailedEvent(SaveFailedPtr::new(SaveFailure {
+                                session_id: id,
+                                error: e.kind(),
+                            })),
+                        });
+                    }
+                }
+            }
+        }
+    }
+
     /// Saves the session payload for the given `id`.
     ///
     /// The target file path is resolved via `get_session_file_path(id)`. The file is
     /// created or truncated, the UTF-8 bytes of `session` are written, and the data is
-    /// flushed to disk via `sync_all`.
+    /// flushed to disk via `sync_all`. Afterwards the checksum of the session is written
+    /// to the `<id>.sum` file, see `save_checksum`.
     ///
     /// Errors:
     /// - Propagates I/O errors from file creation, writing, or syncing.
     /// - Returns `io::ErrorKind::NotFound` if the parent directory does not exist.
-    async fn save_session(&self, id: &str, session: &str) -> io::Result<()> {
+    /// - Returns `io::ErrorKind::InvalidData` if verification is enabled and fails.
+    async fn save_session(&self, id: &SessionId, session: &str) -> io::Result<()> {
         let file_path = self.get_session_file_path(id);
         self.save_bytes(&file_path, session.as_bytes()).await?;
-        Ok(())
+        if self.verify_on_write {
+            verify_session_file(&file_path, session).await?;
+        }
+        self.save_checksum(id, session).await?;
+        // A session that is recorded again mustn't be shadowed by its older compressed file.
+        remove_file_if_exists(&self.get_compressed_session_file_path(id)).await
+    }
+
+    /// Finalizes the stored session with the given `id` and compresses it.
+    ///
+    /// The session is marked as finalized and written gzip compressed to `<id>.session.gz`,
+    /// afterwards the uncompressed `<id>.session` file is removed. The checksum file is
+    /// updated with the checksum of the uncompressed content.
+    ///
+    /// Errors:
+    /// - Returns `io::ErrorKind::NotFound` if the session doesn't exist.
+    /// - Returns `io::ErrorKind::InvalidData` if the session can't be parsed.
+    /// - Propagates I/O errors from writing the compressed file and removing the old one.
+    async fn finalize_session(&self, id: &SessionId) -> io::Result<()> {
+        let session = read_session_file(self.get_session_file_path(id))
+            .await
+            .map_err(|e| io::Error::new(e.kind(), e.to_string()))?;
+        self.compress_session(id, session).await
+    }
+
+    /// Writes the session finalized and compressed, see `finalize_session`.
+    async fn compress_session(&self, id: &SessionId, mut session: Session) -> io::Result<()> {
+        let file_path = self.get_session_file_path(id);
+        session.finalized = true;
+        let json = session_to_json(&session, self.pretty_json)?;
+        self.save_bytes(
+            &self.get_compressed_session_file_path(id),
+            &gzip(json.as_bytes())?,
+        )
+        .await?;
+        self.save_checksum(id, &json).await?;
+        remove_file_if_exists(&file_path).await
+    }
+
+    /// Saves the checksum of the session payload for the given `id`.
+    ///
+    /// The checksum is written to a temporary file that is renamed to `<id>.sum`, so the
+    /// checksum file is replaced atomically and never contains a partial checksum.
+    ///
+    /// Errors:
+    /// - Propagates I/O errors from writing and renaming the checksum file.
+    async fn save_checksum(&self, id: &SessionId, session: &str) -> io::Result<()> {
+        let root_dir = Path::new(&self.session_root_dir);
+        let tmp_path = self.file_path(id.as_str(), root_dir, "sum.tmp");
+        self.save_bytes(&tmp_path, session_checksum(session.as_bytes()).as_bytes())
+            .await?;
+        tokio::fs::rename(tmp_path, self.file_path(id.as_str(), root_dir, "sum")).await
     }
 
     /// Saves the session metadata/info payload for the given `id`.
@@ -137,7 +737,7 @@ impl FilesSystemStorage {
     /// Errors:
     /// - Propagates I/O errors from file creation, writing, or syncing.
     /// - Returns `io::ErrorKind::NotFound` if the parent directory does not exist.
-    async fn save_session_info(&self, id: &str, session_info: &str) -> io::Result<()> {
+    async fn save_session_info(&self, id: &SessionId, session_info: &str) -> io::Result<()> {
         let file_path = self.get_session_info_file_path(id);
         self.save_bytes(&file_path, session_info.as_bytes()).await?;
         Ok(())
@@ -175,9 +775,9 @@ impl FilesSystemStorage {
     /// Errors:
     /// - Propagates I/O errors from `tokio::fs::remove_file`.
     /// - May return `io::ErrorKind::NotFound` if the file is absent.
-    async fn delete_info(&self, id: &str) -> io::Result<()> {
+    async fn delete_info(&self, id: &SessionId) -> io::Result<()> {
         let mut file_path = std::path::PathBuf::from(&self.session_root_dir);
-        file_path.push(id);
+        file_path.push(id.as_str());
         file_path.set_extension("info");
         if exists(&file_path).is_ok() {
             tokio::fs::remove_file(file_path).await?;
@@ -186,13 +786,23 @@ impl FilesSystemStorage {
         Err(io::Error::from(io::ErrorKind::NotFound))
     }
 
-    async fn delete(&self, id: &str) -> io::Result<()> {
-        let file_path = self.get_session_file_path(id);
-        if exists(&file_path).is_ok() {
-            tokio::fs::remove_file(file_path).await?;
-            return Ok(());
+    /// Deletes the session file of the given `id`, compressed or not.
+    ///
+    /// Errors:
+    /// - Returns `io::ErrorKind::NotFound` if neither file exists.
+    /// - Propagates I/O errors from `tokio::fs::remove_file`.
+    async fn delete(&self, id: &SessionId) -> io::Result<()> {
+        let mut result = Err(io::Error::from(io::ErrorKind::NotFound));
+        for file_path in [
+            self.get_session_file_path(id),
+            self.get_compressed_session_file_path(id),
+        ] {
+            if let Ok(true) = exists(&file_path) {
+                tokio::fs::remove_file(file_path).await?;
+                result = Ok(());
+            }
         }
-        Err(io::Error::from(io::ErrorKind::NotFound))
+        result
     }
 
     /// Load all persisted `SessionInfo` entries from the session root directory.
@@ -201,6 +811,8 @@ impl FilesSystemStorage {
     /// - Scans `self.session_root_dir` for files with the `.info` extension.
     /// - Reads each file, deserializes JSON into `SessionInfo`, and collects valid entries.
     /// - Logs and skips files that fail to load or parse; non-file entries are ignored.
+    /// - Derives the `SessionInfo` of `.session` files without `.info` file from the session,
+    ///   e.g. of sessions copied into the folder from another device.
     /// - Sorts the resulting list by `id` in ascending order.
     ///
     /// Returns:
@@ -249,6 +861,23 @@ impl FilesSystemStorage {
                     }
                 }
             }
+            for id in self.session_ids().await? {
+                let id = SessionId::from(id);
+                if infos.iter().any(|info| info.id == id) {
+                    continue;
+                }
+                let file_path = self.get_session_file_path(&id);
+                match read_session_file(&file_path).await {
+                    Ok(session) => {
+                        debug!("Derived session info of session {} without info file", id);
+                        infos.push(session_info(id, &session));
+                    }
+                    Err(e) => error!(
+                        "Failed to load session without info file {}. Error: {}",
+                        file_path, e
+                    ),
+                }
+            }
             infos.sort_by(|a, b| a.id.cmp(&b.id));
             return Ok(Arc::new(infos));
         }
@@ -256,8 +885,11 @@ impl FilesSystemStorage {
         Err(io::Error::from(io::ErrorKind::NotFound))
     }
 
+    /// Returns the sorted ids of all files with the given extension in the given folder.
+    ///
+    /// An existing but empty folder results in an empty list, a missing folder in an error.
     async fn ids(&self, dir: &str, extension: &str) -> io::Result<Vec<String>> {
-        if exists(dir).is_ok() {
+        if let Ok(true) = exists(dir) {
             let mut dirs = read_dir(dir).await?;
             let mut result = vec![];
             while let Some(entry) = dirs.next_entry().await? {
@@ -280,10 +912,24 @@ impl FilesSystemStorage {
             result.sort();
             return Ok(result);
         }
-        error!("Not folder found in {}", self.session_root_dir);
+        error!("Folder {} not found", dir);
         Err(io::Error::from(io::ErrorKind::NotFound))
     }
 
+    /// Returns the sorted ids of all stored sessions, compressed or not.
+    async fn session_ids(&self) -> io::Result<Vec<String>> {
+        let mut ids = self.ids(&self.session_root_dir, "session").await?;
+        ids.extend(
+            self.ids(&self.session_root_dir, "gz")
+                .await?
+                .into_iter()
+                .filter_map(|id| id.strip_suffix(".session").map(str::to_owned)),
+        );
+        ids.sort();
+        ids.dedup();
+        Ok(ids)
+    }
+
     /// Handle a request to load stored session identifiers and reply with the result.
     ///
     /// Behavior:
@@ -313,8 +959,12 @@ impl FilesSystemStorage {
         });
     }
 
-    async fn handle_save_request(&self, req: &SaveSessionRequestPtr) {
-        let result = self.save(&req.data).await;
+    async fn handle_save_request(&mut self, req: &SaveSessionRequestPtr) {
+        let result = if self.save_coalescing.is_zero() {
+            self.save(&req.data).await
+        } else {
+            self.buffer_save(&req.data)
+        };
         let data = match result {
             Ok(id) => {
                 debug!("Stored session with id {} in {}", id, self.session_root_dir);
@@ -327,6 +977,9 @@ impl FilesSystemStorage {
                     self.session_root_dir,
                     e
                 );
+                if self.strict_mode {
+                    self.publish_save_failed(&req.data, e.kind());
+                }
                 Err(e.kind())
             }
         };
@@ -341,19 +994,83 @@ impl FilesSystemStorage {
         });
     }
 
+    /// Stores an imported session unless a session with the same id is already stored.
+    ///
+    /// The import isn't coalesced, the session is written before the response. An existing
+    /// session, compressed or not, is kept and the import fails with
+    /// [`io::ErrorKind::AlreadyExists`].
+    async fn handle_import_request(&self, req: &SaveSessionRequestPtr) {
+        let id = self.get_id(&req.data.read().unwrap_or_else(|e| e.into_inner()));
+        let stored = [
+            self.get_session_file_path(&id),
+            self.get_compressed_session_file_path(&id),
+        ]
+        .iter()
+        .any(|file_path| matches!(exists(file_path), Ok(true)));
+        let data = if stored {
+            info!("Rejected import of session {}, it is already stored", id);
+            Err(io::ErrorKind::AlreadyExists)
+        } else {
+            match self.save(&req.data).await {
+                Ok(id) => {
+                    debug!(
+                        "Imported session with id {} in {}",
+                        id, self.session_root_dir
+                    );
+                    Ok(id)
+                }
+                Err(e) => {
+                    error!("Failed to import session {}. Error: {}", id, e);
+                    Err(e.kind())
+                }
+            }
+        };
+
+        let resp = SaveSessionResponsePtr::new(Response {
+            id: req.id,
+            receiver_addr: req.sender_addr,
+            data,
+        });
+        let _ = self.module_ctx.sender.send(Event {
+            kind: EventKind::ImportSessionResponseEvent(resp),
+        });
+    }
+
+    /// Publishes a [`EventKind::SaveFailedEvent`] for the given session.
+    fn publish_save_failed(&self, session: &RwLock<Session>, error: io::ErrorKind) {
+        let session_id = self.get_id(&session.read().unwrap_or_else(|e| e.into_inner()));
+        error!("Failed to persist session {session_id} in strict mode. Error: {error}");
+        let _ = self.module_ctx.sender.send(Event {
+            kind: EventKind::SaveFailedEvent(SaveFailedPtr::new(SaveFailure { session_id, error })),
+        });
+    }
+
     async fn handle_load_request(&self, req: &LoadSessionRequestPtr) {
-        let file_path = self.file_path(&req.data, Path::new(&self.session_root_dir), "session");
-        let data = match self
-            .load_file(&file_path)
+        let root_dir = Path::new(&self.session_root_dir);
+        let file_path = self.get_session_file_path(&req.data);
+        let session = if self.verify_checksum {
+            read_verified_session_file(
+                &file_path,
+                self.file_path(req.data.as_str(), root_dir, "sum"),
+            )
             .await
-            .and_then(|json| Session::from_json(&json).map_err(|e| e.into()))
-        {
+        } else {
+            read_session_file(&file_path).await
+        };
+        let data = match session {
             Ok(session) => {
                 debug!("Load session with filename {}", file_path);
                 Ok(Arc::new(RwLock::new(session)))
             }
-            Err(e) => {
+            Err(SessionFileError::NotFound) => {
                 debug!(
+                    "Failed to load session with filename {}. Error: file missing",
+                    file_path
+                );
+                Err(io::ErrorKind::NotFound)
+            }
+            Err(e) => {
+                error!(
                     "Failed to load session with filename {}. Error: {}",
                     file_path, e
                 );
@@ -376,7 +1093,7 @@ impl FilesSystemStorage {
     /// Workflow:
     /// - Extract the session `id` from the request.
     /// - Attempt to delete the session info/metadata first.
-    /// - If that succeeds, attempt to delete the session data itself.
+    /// - If that succeeds, attempt to delete the session data itself and its checksum.
     /// - Build and send a `DeleteSessionResponseEvent` containing the outcome.
     ///
     /// The response echoes the original request id and sender address, and carries
@@ -386,6 +1103,16 @@ impl FilesSystemStorage {
         let mut result = self.delete_info(id).await.map_err(|e| e.kind());
         if result.is_ok() {
             result = self.delete(id).await.or(result);
+            let checksum_path =
+                self.file_path(id.as_str(), Path::new(&self.session_root_dir), "sum");
+            if let Err(e) = tokio::fs::remove_file(&checksum_path).await
+                && e.kind() != io::ErrorKind::NotFound
+            {
+                error!(
+                    "Failed to delete checksum file {}. Error: {}",
+                    checksum_path, e
+                );
+            }
         }
         let resp = DeleteSessionResponsePtr::new(Response {
             id: req.id,
@@ -397,12 +1124,68 @@ impl FilesSystemStorage {
         });
     }
 
+    /// Handle a request for the raw file path of a stored session and reply with the result.
+    ///
+    /// Responds with the path of the `.session` file if it exists, otherwise with the path of
+    /// the compressed `.session.gz` file if it exists, otherwise with
+    /// `io::ErrorKind::NotFound`.
+    async fn handle_session_file_path_request(&self, req: &SessionFilePathRequestPtr) {
+        let mut data = Err(io::ErrorKind::NotFound);
+        for file_path in [
+            self.get_session_file_path(&req.data),
+            self.get_compressed_session_file_path(&req.data),
+        ] {
+            match tokio::fs::try_exists(&file_path).await {
+                Ok(true) => {
+                    data = Ok(PathBuf::from(file_path));
+                    break;
+                }
+                Ok(false) => (),
+                Err(e) => {
+                    data = Err(e.kind());
+                    break;
+                }
+            }
+        }
+        debug!("Resolved session file path for {}: {:?}", req.data, data);
+        let resp = SessionFilePathResponsePtr::new(Response {
+            id: req.id,
+            receiver_addr: req.sender_addr,
+            data,
+        });
+        let _ = self.module_ctx.sender.send(Event {
+            kind: EventKind::SessionFilePathResponseEvent(resp),
+        });
+    }
+
+    /// Handle a finalize-session request and reply with the result, see `finalize_session`.
+    async fn handle_finalize_request(&self, req: &FinalizeSessionRequestPtr) {
+        let data = match self.finalize_session(&req.data).await {
+            Ok(()) => {
+                info!("Finalized and compressed session {}", req.data);
+                Ok(())
+            }
+            Err(e) => {
+                error!("Failed to finalize session {}. Error: {}", req.data, e);
+                Err(e.kind())
+            }
+        };
+        let resp = FinalizeSessionResponsePtr::new(Response {
+            id: req.id,
+            receiver_addr: req.sender_addr,
+            data,
+        });
+        let _ = self.module_ctx.sender.send(Event {
+            kind: EventKind::FinalizeSessionResponseEvent(resp),
+        });
+    }
+
     async fn handle_load_stored_track_ids_request(&self, req: &EmptyRequestPtr) {
         let ids = self.ids(&self.track_root_dir, "track").await;
         let data = match ids {
             Ok(ids) => {
                 debug!("Load track ids {:?} from {}", ids, self.track_root_dir);
-                ids
+                ids.into_iter().map(TrackId::from).collect()
             }
             Err(_) => vec![],
         };
@@ -417,11 +1200,14 @@ impl FilesSystemStorage {
         });
     }
 
-    async fn handle_all_load_stored_track_request(&self, req: &EmptyRequestPtr) {
-        let mut tracks: Vec<Track> = vec![];
+    /// Loads all stored tracks together with their ids.
+    ///
+    /// Tracks that can't be loaded or parsed are logged and skipped.
+    async fn load_tracks(&self) -> Vec<(TrackId, Track)> {
+        let mut tracks = vec![];
         if let Ok(ids) = self.ids(&self.track_root_dir, "track").await {
-            for id in ids.iter() {
-                let file_path = self.file_path(id, Path::new(&self.track_root_dir), "track");
+            for id in ids {
+                let file_path = self.file_path(&id, Path::new(&self.track_root_dir), "track");
                 match self
                     .load_file(&file_path)
                     .await
@@ -429,7 +1215,7 @@ impl FilesSystemStorage {
                 {
                     Ok(track) => {
                         debug!("Load track from \"{file_path}\".");
-                        tracks.push(track);
+                        tracks.push((TrackId::from(id), track));
                     }
                     Err(e) => {
                         error!("Failed to load track \"{file_path}\". Error: {e}");
@@ -438,7 +1224,16 @@ impl FilesSystemStorage {
                 }
             }
         }
+        tracks
+    }
 
+    async fn handle_all_load_stored_track_request(&self, req: &EmptyRequestPtr) {
+        let tracks = self
+            .load_tracks()
+            .await
+            .into_iter()
+            .map(|(_, track)| track)
+            .collect();
         let resp = LoadStoredTracksReponsePtr::new(Response {
             id: req.id,
             receiver_addr: req.sender_addr,
@@ -450,20 +1245,204 @@ impl FilesSystemStorage {
         });
     }
 
+    /// Handle a request to load a single track and reply with the result.
+    ///
+    /// Responds with `io::ErrorKind::NotFound` if no track with the requested id is stored.
+    async fn handle_load_track_request(&self, req: &LoadTrackRequestPtr) {
+        let file_path = self.file_path(req.data.as_str(), Path::new(&self.track_root_dir), "track");
+        let data = self
+            .load_file(&file_path)
+            .await
+            .and_then(|json| Track::from_json(&json).map_err(|e| e.into()))
+            .map_err(|e| {
+                debug!("Failed to load track \"{file_path}\". Error: {e}");
+                e.kind()
+            });
+        if data.is_ok() {
+            self.touch_tracks(std::slice::from_ref(&req.data)).await;
+        }
+        let resp = LoadTrackResponsePtr::new(Response {
+            id: req.id,
+            receiver_addr: req.sender_addr,
+            data,
+        });
+        let _ = self.module_ctx.sender.send(Event {
+            kind: EventKind::LoadTrackResponseEvent(resp),
+        });
+    }
+
+    /// Handle a request to store a track and reply with the result.
+    ///
+    /// The track is stored under the requested id, replacing an existing track. If the
+    /// maximum number of tracks is exceeded afterwards, the least recently used tracks are
+    /// evicted.
+    async fn handle_save_track_request(&self, req: &SaveTrackRequestPtr) {
+        let (id, track) = &req.data;
+        let file_path = self.file_path(id.as_str(), Path::new(&self.track_root_dir), "track");
+        let data = match Track::to_json(track) {
+            Ok(json) => self.save_bytes(&file_path, json.as_bytes()).await,
+            Err(e) => Err(e.into()),
+        }
+        .map(|_| debug!("Stored track with id {id} in {}", self.track_root_dir))
+        .map_err(|e| {
+            error!("Failed to store track \"{file_path}\". Error: {e}");
+            e.kind()
+        });
+        if data.is_ok() {
+            self.touch_tracks(std::slice::from_ref(id)).await;
+            if let Err(e) = self.evict_tracks(id).await {
+                error!("Failed to evict least recently used tracks. Error: {e}");
+            }
+        }
+        let resp = SaveTrackResponsePtr::new(Response {
+            id: req.id,
+            receiver_addr: req.sender_addr,
+            data,
+        });
+        let _ = self.module_ctx.sender.send(Event {
+            kind: EventKind::SaveTrackResponseEvent(resp),
+        });
+    }
+
+    /// Marks the stored tracks that are equal to the detected tracks as used.
+    async fn handle_detect_track_response(&self, tracks: &[Track]) {
+        if tracks.is_empty() {
+            return;
+        }
+        let ids: Vec<TrackId> = self
+            .load_tracks()
+            .await
+            .into_iter()
+            .filter(|(_, track)| tracks.contains(track))
+            .map(|(id, _)| id)
+            .collect();
+        self.touch_tracks(&ids).await;
+    }
+
+    /// Loads the last use of the stored tracks as milliseconds since the unix epoch.
+    ///
+    /// A missing or unreadable sidecar file results in an empty map.
+    async fn load_track_usage(&self) -> HashMap<TrackId, i64> {
+        let file_path = Path::new(&self.track_root_dir).join(TRACK_USAGE_FILE);
+        match self.load_file(&file_path.to_string_lossy()).await {
+            Ok(json) => serde_json::from_str(&json).unwrap_or_else(|e| {
+                error!("Failed to parse track usage file. Error: {e}");
+                HashMap::new()
+            }),
+            Err(_) => HashMap::new(),
+        }
+    }
+
+    async fn save_track_usage(&self, usage: &HashMap<TrackId, i64>) -> io::Result<()> {
+        let file_path = Path::new(&self.track_root_dir).join(TRACK_USAGE_FILE);
+        let json = serde_json::to_string(usage)?;
+        self.save_bytes(&file_path.to_string_lossy(), json.as_bytes())
+            .await
+    }
+
+    /// Records the current time as last use of the given tracks.
+    ///
+    /// Only done with a track limit, otherwise the last use is never needed.
+    async fn touch_tracks(&self, ids: &[TrackId]) {
+        if self.max_tracks == 0 || ids.is_empty() {
+            return;
+        }
+        let mut usage = self.load_track_usage().await;
+        let now = chrono::Utc::now().timestamp_millis();
+        for id in ids {
+            usage.insert(id.clone(), now);
+        }
+        if let Err(e) = self.save_track_usage(&usage).await {
+            error!("Failed to store track usage. Error: {e}");
+        }
+    }
+
+    /// Deletes the least recently used tracks until the maximum number of tracks is met.
+    ///
+    /// The track with the id `keep` is never evicted.
+    async fn evict_tracks(&self, keep: &TrackId) -> io::Result<()> {
+        if self.max_tracks == 0 {
+            return Ok(());
+        }
+        let ids: Vec<TrackId> = self
+            .ids(&self.track_root_dir, "track")
+            .await?
+            .into_iter()
+            .map(TrackId::from)
+            .collect();
+        if ids.len() <= self.max_tracks {
+            return Ok(());
+        }
+        let mut usage = self.load_track_usage().await;
+        let mut candidates = vec![];
+        for id in ids.iter().filter(|id| *id != keep) {
+            let last_used = match usage.get(id) {
+                Some(last_used) => *last_used,
+                None => {
+                    let file_path =
+                        self.file_path(id.as_str(), Path::new(&self.track_root_dir), "track");
+                    tokio::fs::metadata(&file_path)
+                        .await?
+                        .modified()
+                        .map(chrono::DateTime::<chrono::Utc>::from)?
+                        .timestamp_millis()
+                }
+            };
+            candidates.push((last_used, id));
+        }
+        candidates.sort();
+        for (_, id) in candidates.into_iter().take(ids.len() - self.max_tracks) {
+            let file_path = self.file_path(id.as_str(), Path::new(&self.track_root_dir), "track");
+            tokio::fs::remove_file(&file_path).await?;
+            usage.remove(id);
+            info!("Evicted least recently used track {id}");
+        }
+        self.save_track_usage(&usage).await
+    }
+
     /// Returns the unique identifier of the session.
     ///
-    /// This method consumes the `Session` instance and returns its `id` as a `String`.
-    /// Typically, the ID is used to identify and retrieve sessions from storage.
+    /// The identifier is derived from the session according to the configured [`IdScheme`].
+    /// Typically, the ID is used to identify and retrieve sessions from storage. An id of the
+    /// [`IdScheme::DateTimeTrack`] that is taken by another session gets a numbered suffix.
     ///
     /// # Returns
-    /// A `String` containing the session's unique identifier.
-    fn get_id(session: &Session) -> String {
-        format!(
-            "{}_{}_{}",
-            session.track.name.to_lowercase(),
-            session.date.format("%d_%m_%Y"),
-            session.time.format("%H_%M_%S_%3f")
-        )
+    /// A [`SessionId`] containing the session's unique identifier.
+    fn get_id(&self, session: &Session) -> SessionId {
+        let id = self.id_scheme.session_id(session);
+        if self.id_scheme != IdScheme::DateTimeTrack || !self.is_id_taken(&id, session) {
+            return id;
+        }
+        let mut suffix = 2;
+        loop {
+            let candidate = SessionId::from(format!("{id}_{suffix}"));
+            if !self.is_id_taken(&candidate, session) {
+                return candidate;
+            }
+            suffix += 1;
+        }
+    }
+
+    /// Returns whether another session than the given one is stored or buffered with the id.
+    ///
+    /// Sessions are compared by their start and track. A session info that can't be read
+    /// keeps the id taken, so the stored session is never overwritten.
+    fn is_id_taken(&self, id: &SessionId, session: &Session) -> bool {
+        let stored = match self.pending_saves.get(id) {
+            Some(pending) => SessionInfo::from_json(&pending.session_info),
+            None => match std::fs::read_to_string(self.get_session_info_file_path(id)) {
+                Ok(json) => SessionInfo::from_json(&json),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => return false,
+                Err(_) => return true,
+            },
+        };
+        match stored {
+            Ok(info) => {
+                info.date != NaiveDateTime::new(session.date, session.time)
+                    || info.track_name != session.track.name
+            }
+            Err(_) => true,
+        }
     }
 
     /// Constructs the full file path for a session based on its ID.
@@ -478,25 +1457,36 @@ impl FilesSystemStorage {
     ///
     /// # Arguments
     ///
-    /// * `id` - A string slice representing the session identifier.
+    /// * `id` - The session identifier.
     ///
     /// # Returns
     ///
     /// A `String` containing the complete file path to the session file.
-    fn get_session_file_path(&self, id: &str) -> String {
+    fn get_session_file_path(&self, id: &SessionId) -> String {
         let mut file_path = std::path::PathBuf::from(&self.session_root_dir);
-        file_path.push(id);
+        file_path.push(id.as_str());
         file_path.set_extension("session");
         file_path.to_string_lossy().to_string()
     }
 
+    /// Build the path to the compressed session file for the given session `id`.
+    ///
+    /// The path is constructed as: `<session_root_dir>/<id>.session.gz`.
+    fn get_compressed_session_file_path(&self, id: &SessionId) -> String {
+        self.file_path(
+            id.as_str(),
+            Path::new(&self.session_root_dir),
+            COMPRESSED_SESSION_EXTENSION,
+        )
+    }
+
     /// Build the absolute path to the session info file for the given session `id`.
     ///
     /// The path is constructed as: `<session_root_dir>/<id>.info`.
     /// Returns the path as an owned `String` (via lossy conversion from `OsStr`).
-    fn get_session_info_file_path(&self, id: &str) -> String {
+    fn get_session_info_file_path(&self, id: &SessionId) -> String {
         let mut file_path = std::path::PathBuf::from(&self.session_root_dir);
-        file_path.push(id);
+        file_path.push(id.as_str());
         file_path.set_extension("info");
         file_path.to_string_lossy().to_string()
     }
@@ -511,35 +1501,133 @@ impl FilesSystemStorage {
 
 #[async_trait::async_trait]
 impl module_core::Module for FilesSystemStorage {
+    fn event_interface(&self) -> ModuleInterface {
+        ModuleInterface {
+            consumes: vec![
+                EventKindType::QuitEvent,
+                EventKindType::LoadStoredSessionIdsRequestEvent,
+                EventKindType::SaveSessionRequestEvent,
+                EventKindType::ImportSessionRequestEvent,
+                EventKindType::LoadSessionRequestEvent,
+                EventKindType::DeleteSessionRequestEvent,
+                EventKindType::FinalizeSessionRequestEvent,
+                EventKindType::SessionFilePathRequestEvent,
+                EventKindType::LoadStoredTrackIdsRequest,
+                EventKindType::LoadAllStoredTracksRequestEvent,
+                EventKindType::LoadTrackRequestEvent,
+                EventKindType::SaveTrackRequestEvent,
+                EventKindType::DetectTrackResponseEvent,
+            ],
+            produces: vec![
+                EventKindType::ModuleHeartbeatEvent,
+                EventKindType::LoadStoredSessionIdsResponseEvent,
+                EventKindType::SaveSessionResponseEvent,
+                EventKindType::ImportSessionResponseEvent,
+                EventKindType::LoadSessionResponseEvent,
+                EventKindType::DeleteSessionResponseEvent,
+                EventKindType::FinalizeSessionResponseEvent,
+                EventKindType::SessionFilePathResponseEvent,
+                EventKindType::LoadStoredTrackIdsResponseEvent,
+                EventKindType::LoadAllStoredTracksResponseEvent,
+                EventKindType::LoadTrackResponseEvent,
+                EventKindType::SaveTrackResponseEvent,
+                EventKindType::SaveFailedEvent,
+            ],
+        }
+    }
+
     async fn run(&mut self) -> Result<(), ()> {
+        if let Err(e) = self.finalize_open_sessions().await {
+            error!("Failed to finalize open sessions. Error: {}", e);
+        }
+        let mut heartbeat = tokio::time::interval(HEARTBEAT_INTERVAL);
+        let mut shutdown: Option<Instant> = None;
         let mut run = true;
         while run {
+            let next_flush = self
+                .pending_saves
+                .values()
+                .map(|pending| pending.deadline)
+                .min();
             tokio::select! {
-                event = self.module_ctx.receiver.recv() => {
+                _ = heartbeat.tick() => {
+                    let _ = self.module_ctx.publish_heartbeat("storage");
+                }
+                _ = tokio::time::sleep_until(next_flush.unwrap_or_else(Instant::now)), if next_flush.is_some() => {
+                    self.flush_pending_saves(false).await;
+                }
+                _ = tokio::time::sleep_until(shutdown.unwrap_or_else(Instant::now)), if shutdown.is_some() => {
+                    self.flush_pending_saves(true).await;
+                    run = false;
+                }
+                event = self.module_ctx.recv() => {
                     match event {
                         Ok(event) => {
+                            let is_session_write = matches!(
+                                event.kind,
+                                EventKind::SaveSessionRequestEvent(_)
+                                    | EventKind::ImportSessionRequestEvent(_)
+                                    | EventKind::FinalizeSessionRequestEvent(_)
+                            );
+                            // Readers of the sessions must see the latest saves.
+                            if matches!(
+                                event.kind,
+                                EventKind::QuitEvent
+                                    | EventKind::LoadStoredSessionIdsRequestEvent(_)
+                                    | EventKind::ImportSessionRequestEvent(_)
+                                    | EventKind::LoadSessionRequestEvent(_)
+                                    | EventKind::DeleteSessionRequestEvent(_)
+                                    | EventKind::FinalizeSessionRequestEvent(_)
+                                    | EventKind::SessionFilePathRequestEvent(_)
+                            ) {
+                                self.flush_pending_saves(true).await;
+                            }
                             match event.kind {
-                                EventKind::QuitEvent => run = false,
+                                EventKind::QuitEvent => {
+                                    shutdown.get_or_insert_with(|| Instant::now() + self.shutdown_grace);
+                                },
                                 EventKind::LoadStoredSessionIdsRequestEvent(request) => {
                                     self.handle_load_stored_ids_request(&request).await;
                                 },
                                 EventKind::SaveSessionRequestEvent(request) => {
                                     self.handle_save_request(&request).await;
                                 },
+                                EventKind::ImportSessionRequestEvent(request) => {
+                                    self.handle_import_request(&request).await;
+                                },
                                 EventKind::LoadSessionRequestEvent(request) => {
                                     self.handle_load_request(&request).await;
                                 },
                                 EventKind::DeleteSessionRequestEvent(request) => {
                                     self.handle_delete_request(&request).await;
                                 },
+                                EventKind::FinalizeSessionRequestEvent(request) => {
+                                    self.handle_finalize_request(&request).await;
+                                },
                                 EventKind::LoadStoredTrackIdsRequest(request) => {
                                     self.handle_load_stored_track_ids_request(&request).await;
                                 }
                                 EventKind::LoadAllStoredTracksRequestEvent(request) => {
                                     self.handle_all_load_stored_track_request(&request).await;
                                 }
+                                EventKind::SessionFilePathRequestEvent(request) => {
+                                    self.handle_session_file_path_request(&request).await;
+                                }
+                                EventKind::LoadTrackRequestEvent(request) => {
+                                    self.handle_load_track_request(&request).await;
+                                }
+                                EventKind::SaveTrackRequestEvent(request) => {
+                                    self.handle_save_track_request(&request).await;
+                                }
+                                EventKind::DetectTrackResponseEvent(response) => {
+                                    self.handle_detect_track_response(&response.data).await;
+                                }
                                 _ => ()
                             }
+                            // A slow write mustn't use up the grace of the requests behind it.
+                            if is_session_write && shutdown.is_some() {
+                                shutdown = Some(Instant::now() + self.shutdown_grace);
+                            }
                         }
                         Err(e) => println!("Error: {}", e),
                     }
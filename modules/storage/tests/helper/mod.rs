@@ -22,10 +22,114 @@ pub fn setup_empty_test_folder(folder_name: &str) {
 }
 
 pub fn create_storage_module(folder: &str, event_bus: &EventBus) -> JoinHandle<Result<(), ()>> {
+    create_storage_module_with_id_scheme(folder, event_bus, IdScheme::default())
+}
+
+#[allow(dead_code)]
+pub fn create_storage_module_with_id_scheme(
+    folder: &str,
+    event_bus: &EventBus,
+    id_scheme: IdScheme,
+) -> JoinHandle<Result<(), ()>> {
+    let ctx = event_bus.context();
+    let folder = PathBuf::from(get_path(folder));
+    tokio::spawn(async move {
+        let mut storage = FilesSystemStorage::new(&folder, ctx)
+            .unwrap()
+            .with_id_scheme(id_scheme);
+        storage.run().await
+    })
+}
+
+#[allow(dead_code)]
+pub fn create_strict_storage_module(
+    folder: &str,
+    event_bus: &EventBus,
+) -> JoinHandle<Result<(), ()>> {
+    let ctx = event_bus.context();
+    let folder = PathBuf::from(get_path(folder));
+    tokio::spawn(async move {
+        let mut storage = FilesSystemStorage::new(&folder, ctx)
+            .unwrap()
+            .with_strict_mode(true);
+        storage.run().await
+    })
+}
+
+#[allow(dead_code)]
+pub fn create_storage_module_with_open_session_timeout(
+    folder: &str,
+    event_bus: &EventBus,
+    timeout: std::time::Duration,
+) -> JoinHandle<Result<(), ()>> {
+    let ctx = event_bus.context();
+    let folder = PathBuf::from(get_path(folder));
+    tokio::spawn(async move {
+        let mut storage = FilesSystemStorage::new(&folder, ctx)
+            .unwrap()
+            .with_open_session_timeout(timeout);
+        storage.run().await
+    })
+}
+
+#[allow(dead_code)]
+pub fn create_storage_module_with_save_coalescing(
+    folder: &str,
+    event_bus: &EventBus,
+    window: std::time::Duration,
+) -> JoinHandle<Result<(), ()>> {
+    let ctx = event_bus.context();
+    let folder = PathBuf::from(get_path(folder));
+    tokio::spawn(async move {
+        let mut storage = FilesSystemStorage::new(&folder, ctx)
+            .unwrap()
+            .with_save_coalescing(window);
+        storage.run().await
+    })
+}
+
+#[allow(dead_code)]
+pub fn create_storage_module_with_verify_on_write(
+    folder: &str,
+    event_bus: &EventBus,
+) -> JoinHandle<Result<(), ()>> {
+    let ctx = event_bus.context();
+    let folder = PathBuf::from(get_path(folder));
+    tokio::spawn(async move {
+        let mut storage = FilesSystemStorage::new(&folder, ctx)
+            .unwrap()
+            .with_verify_on_write(true);
+        storage.run().await
+    })
+}
+
+#[allow(dead_code)]
+pub fn create_storage_module_with_max_tracks(
+    folder: &str,
+    event_bus: &EventBus,
+    max_tracks: usize,
+) -> JoinHandle<Result<(), ()>> {
+    let ctx = event_bus.context();
+    let folder = PathBuf::from(get_path(folder));
+    tokio::spawn(async move {
+        let mut storage = FilesSystemStorage::new(&folder, ctx)
+            .unwrap()
+            .with_max_tracks(max_tracks);
+        storage.run().await
+    })
+}
+
+#[allow(dead_code)]
+pub fn create_storage_module_with_verify_checksum(
+    folder: &str,
+    event_bus: &EventBus,
+) -> JoinHandle<Result<(), ()>> {
     let ctx = event_bus.context();
     let folder = PathBuf::from(get_path(folder));
     tokio::spawn(async move {
-        let mut storage = FilesSystemStorage::new(&folder, ctx);
+        let mut storage = FilesSystemStorage::new(&folder, ctx)
+            .unwrap()
+            .with_verify_checksum(true);
         storage.run().await
     })
 }
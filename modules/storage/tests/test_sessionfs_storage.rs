@@ -2,8 +2,12 @@
 //
 // SPDX-License-Identifier: GPL-2.0-or-later
 
-use chrono::NaiveDateTime;
-use common::{session::SessionInfo, test_helper::session::get_session};
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use common::{
+    id::SessionId,
+    session::{Session, SessionInfo},
+    test_helper::session::get_session,
+};
 use core::panic;
 use module_core::{
     EmptyRequestPtr, Event, EventBus, EventKind, EventKindType, Request, SaveSessionRequestPtr,
@@ -18,7 +22,16 @@ use std::{
 use std::{os::unix::fs::MetadataExt, time::Duration};
 
 mod helper;
-use helper::{create_storage_module, get_path, setup_empty_test_folder};
+use helper::{
+    create_storage_module, create_storage_module_with_id_scheme,
+    create_storage_module_with_open_session_timeout, create_storage_module_with_save_coalescing,
+    create_storage_module_with_verify_checksum, create_storage_module_with_verify_on_write,
+    create_strict_storage_module, get_path, setup_empty_test_folder,
+};
+use storage::{
+    FilesSystemStorage, IdScheme, LOCK_FILE, SessionFileError, read_session_file,
+    read_verified_session_file, session_checksum, session_to_json, verify_session_file,
+};
 
 fn create_empty_session(id: &str, folder_name: &str) {
     let file = format!("{}/session/{id}.session", get_path(folder_name));
@@ -131,11 +144,11 @@ pub async fn read_stored_session_ids() {
     .await;
     let payload =
         &**payload_ref!(ids_event.kind, EventKind::LoadStoredSessionIdsResponseEvent).unwrap();
-    assert_eq!(payload.data[0].id, exp_ids[0]);
+    assert_eq!(payload.data[0].id, SessionId::from(exp_ids[0].as_str()));
     assert_eq!(payload.data[0].date, get_session_time());
     assert_eq!(payload.data[0].track_name, "Oschersleben");
     assert_eq!(payload.data[0].laps, 12_usize);
-    assert_eq!(payload.data[1].id, exp_ids[1]);
+    assert_eq!(payload.data[1].id, SessionId::from(exp_ids[1].as_str()));
     assert_eq!(payload.data[1].date, get_session_time());
     assert_eq!(payload.data[1].track_name, "Oschersleben");
     assert_eq!(payload.data[1].laps, 12_usize);
@@ -152,7 +165,7 @@ pub async fn save_load_not_existing_session() {
     let test_folder_name = "save_load_session_not_existing";
     setup_empty_test_folder("save_load_session_not_existing");
     let mut storage = create_storage_module(test_folder_name, &event_bus);
-    let exp_id = "oschersleben_01_01_1970_13_00_00_000".to_owned();
+    let exp_id = SessionId::from("oschersleben_01_01_1970_13_00_00_000");
 
     event_bus.publish(&Event {
         kind: EventKind::SaveSessionRequestEvent(SaveSessionRequestPtr::new(Request {
@@ -215,7 +228,7 @@ pub async fn delete_existing_session() {
             Request {
                 id: 13,
                 sender_addr: 20,
-                data: session_ids[0].clone(),
+                data: session_ids[0].as_str().into(),
             }
             .into(),
         ),
@@ -270,7 +283,645 @@ pub async fn update_existing_session() {
         if session_size > 0 {
             break;
         }
+        tokio::time::sleep(Duration::from_millis(10)).await;
     }
     assert_ne!(0, session_size);
     stop_module(&event_bus, &mut storage).await;
 }
+
+async fn request_save(event_bus: &EventBus, id: u64, session: Session) -> SessionId {
+    let mut rx = event_bus.subscribe();
+    event_bus.publish(&Event {
+        kind: EventKind::SaveSessionRequestEvent(SaveSessionRequestPtr::new(Request {
+            id,
+            sender_addr: 20,
+            data: Arc::new(RwLock::new(session)),
+        })),
+    });
+    let save_resp = wait_for_event(
+        &mut rx,
+        Duration::from_secs(1),
+        EventKindType::SaveSessionResponseEvent,
+    )
+    .await;
+    payload_ref!(save_resp.kind, EventKind::SaveSessionResponseEvent)
+        .unwrap()
+        .data
+        .clone()
+        .unwrap()
+}
+
+async fn save_session(event_bus: &EventBus, id: u64, date: &str, time: &str) -> SessionId {
+    let mut session = get_session();
+    session.date = NaiveDate::parse_from_str(date, "%d.%m.%Y").unwrap();
+    session.time = NaiveTime::parse_from_str(time, "%H:%M:%S%.3f").unwrap();
+    request_save(event_bus, id, session).await
+}
+
+#[tokio::test]
+pub async fn save_sessions_with_date_time_track_id_scheme() {
+    let event_bus = EventBus::default();
+    let test_folder_name = "save_sessions_with_date_time_track_id_scheme";
+    setup_empty_test_folder(test_folder_name);
+    let mut storage =
+        create_storage_module_with_id_scheme(test_folder_name, &event_bus, IdScheme::DateTimeTrack);
+
+    let newer_id = save_session(&event_bus, 1, "01.01.1970", "13:00:00.000").await;
+    let older_id = save_session(&event_bus, 2, "31.12.1969", "23:00:00.000").await;
+    assert_eq!(newer_id, SessionId::from("19700101_130000_oschersleben"));
+    assert_eq!(older_id, SessionId::from("19691231_230000_oschersleben"));
+
+    let mut file_ids = get_session_ids(test_folder_name);
+    file_ids.sort();
+    assert_eq!(file_ids, vec![older_id.to_string(), newer_id.to_string()]);
+
+    let mut rx = event_bus.subscribe();
+    event_bus.publish(&Event {
+        kind: EventKind::LoadStoredSessionIdsRequestEvent(Request::empty_request(3, 20)),
+    });
+    let ids_event = wait_for_event(
+        &mut rx,
+        Duration::from_millis(100),
+        EventKindType::LoadStoredSessionIdsResponseEvent,
+    )
+    .await;
+    let infos = &payload_ref!(ids_event.kind, EventKind::LoadStoredSessionIdsResponseEvent)
+        .unwrap()
+        .data;
+    let info_ids: Vec<&str> = infos.iter().map(|info| info.id.as_str()).collect();
+    assert_eq!(info_ids, vec![older_id.as_str(), newer_id.as_str()]);
+
+    stop_module(&event_bus, &mut storage).await;
+}
+
+#[tokio::test]
+pub async fn date_time_track_ids_of_sessions_started_in_same_second_differ() {
+    let event_bus = EventBus::default();
+    let test_folder_name = "date_time_track_ids_of_sessions_started_in_same_second_differ";
+    setup_empty_test_folder(test_folder_name);
+    let mut storage =
+        create_storage_module_with_id_scheme(test_folder_name, &event_bus, IdScheme::DateTimeTrack);
+
+    let first_id = save_session(&event_bus, 1, "01.01.1970", "13:00:00.100").await;
+    let second_id = save_session(&event_bus, 2, "01.01.1970", "13:00:00.600").await;
+    assert_eq!(first_id, SessionId::from("19700101_130000_oschersleben"));
+    assert_eq!(second_id, SessionId::from("19700101_130000_oschersleben_2"));
+
+    // Saving a session again keeps its id.
+    let first_id_again = save_session(&event_bus, 3, "01.01.1970", "13:00:00.100").await;
+    assert_eq!(first_id_again, first_id);
+
+    let mut file_ids = get_session_ids(test_folder_name);
+    file_ids.sort();
+    assert_eq!(file_ids, vec![first_id.to_string(), second_id.to_string()]);
+
+    stop_module(&event_bus, &mut storage).await;
+}
+
+async fn request_import(
+    event_bus: &EventBus,
+    id: u64,
+    session: Session,
+) -> Result<SessionId, std::io::ErrorKind> {
+    let mut rx = event_bus.subscribe();
+    event_bus.publish(&Event {
+        kind: EventKind::ImportSessionRequestEvent(SaveSessionRequestPtr::new(Request {
+            id,
+            sender_addr: 20,
+            data: Arc::new(RwLock::new(session)),
+        })),
+    });
+    let import_resp = wait_for_event(
+        &mut rx,
+        Duration::from_secs(1),
+        EventKindType::ImportSessionResponseEvent,
+    )
+    .await;
+    payload_ref!(import_resp.kind, EventKind::ImportSessionResponseEvent)
+        .unwrap()
+        .data
+        .clone()
+}
+
+#[tokio::test]
+pub async fn import_does_not_overwrite_stored_session() {
+    let event_bus = EventBus::default();
+    let test_folder_name = "import_does_not_overwrite_stored_session";
+    setup_empty_test_folder(test_folder_name);
+    let mut storage = create_storage_module(test_folder_name, &event_bus);
+
+    let id = request_import(&event_bus, 1, get_session()).await.unwrap();
+    let mut other_session = get_session();
+    other_session.laps.clear();
+    assert_eq!(
+        request_import(&event_bus, 2, other_session).await,
+        Err(std::io::ErrorKind::AlreadyExists)
+    );
+    let stored = load_session(&event_bus, &id).await;
+    assert_eq!(*stored.read().unwrap(), get_session());
+
+    stop_module(&event_bus, &mut storage).await;
+}
+
+#[tokio::test]
+pub async fn resolve_session_file_path() {
+    let event_bus = EventBus::default();
+    let test_folder_name = "resolve_session_file_path";
+    let session_ids = init_none_empty_test(test_folder_name);
+    let mut storage = create_storage_module(test_folder_name, &event_bus);
+
+    for (req_id, id) in [(1, session_ids[0].as_str()), (2, "not_existing")] {
+        let mut rx = event_bus.subscribe();
+        event_bus.publish(&Event {
+            kind: EventKind::SessionFilePathRequestEvent(Request::new(req_id, 20, id.into())),
+        });
+        let path_resp = wait_for_event(
+            &mut rx,
+            Duration::from_millis(100),
+            EventKindType::SessionFilePathResponseEvent,
+        )
+        .await;
+        let payload =
+            payload_ref!(path_resp.kind, EventKind::SessionFilePathResponseEvent).unwrap();
+        assert_eq!(payload.id, req_id);
+        if req_id == 1 {
+            assert_eq!(
+                payload.data,
+                Ok(format!("{}/session/{id}.session", get_path(test_folder_name)).into())
+            );
+        } else {
+            assert_eq!(payload.data, Err(std::io::ErrorKind::NotFound));
+        }
+    }
+
+    stop_module(&event_bus, &mut storage).await;
+}
+
+#[tokio::test]
+pub async fn publish_save_failed_in_strict_mode() {
+    let event_bus = EventBus::default();
+    let test_folder_name = "publish_save_failed_in_strict_mode";
+    setup_empty_test_folder(test_folder_name);
+    // A file in place of the session folder makes every save fail.
+    std::fs::File::create(format!("{}/session", get_path(test_folder_name)))
+        .expect("Failed to create blocking session file");
+    let mut rx = event_bus.subscribe();
+    let mut storage = create_strict_storage_module(test_folder_name, &event_bus);
+
+    event_bus.publish(&Event {
+        kind: EventKind::SaveSessionRequestEvent(SaveSessionRequestPtr::new(Request {
+            id: 1,
+            sender_addr: 20,
+            data: Arc::new(RwLock::new(get_session())),
+        })),
+    });
+    let failed_event = wait_for_event(
+        &mut rx,
+        Duration::from_millis(100),
+        EventKindType::SaveFailedEvent,
+    )
+    .await;
+    let failure = payload_ref!(failed_event.kind, EventKind::SaveFailedEvent).unwrap();
+    assert_eq!(
+        failure.session_id,
+        SessionId::from("oschersleben_01_01_1970_13_00_00_000")
+    );
+
+    stop_module(&event_bus, &mut storage).await;
+}
+
+/// Stores an open session in the given test folder and returns its id.
+fn create_open_session(folder_name: &str) -> String {
+    let id = "oschersleben_01_01_1970_13_00_00_000".to_owned();
+    let file = format!("{}/session/{id}.session", get_path(folder_name));
+    let _ = create_dir(format!("{}/session", get_path(folder_name)));
+    let mut session = get_session();
+    session.finalized = false;
+    std::fs::write(&file, Session::to_json(&session).unwrap())
+        .unwrap_or_else(|err| panic!("Failed to write file {file}. Reason: {err}"));
+    create_session_info(&id, folder_name);
+    id
+}
+
+async fn load_session(event_bus: &EventBus, id: &SessionId) -> Arc<RwLock<Session>> {
+    let mut rx = event_bus.subscribe();
+    event_bus.publish(&Event {
+        kind: EventKind::LoadSessionRequestEvent(
+            Request {
+                id: 12,
+                sender_addr: 20,
+                data: id.clone(),
+            }
+            .into(),
+        ),
+    });
+    let load_resp = wait_for_event(
+        &mut rx,
+        Duration::from_millis(100),
+        EventKindType::LoadSessionResponseEvent,
+    )
+    .await;
+    let response = payload_ref!(load_resp.kind, EventKind::LoadSessionResponseEvent).unwrap();
+    response.data.clone().unwrap()
+}
+
+#[tokio::test]
+#[test_log::test]
+pub async fn finalize_open_session_on_startup() {
+    let event_bus = EventBus::default();
+    let test_folder_name = "finalize_open_session_on_startup";
+    setup_empty_test_folder(test_folder_name);
+    let id = create_open_session(test_folder_name);
+    let mut storage = create_storage_module(test_folder_name, &event_bus);
+
+    let session = load_session(&event_bus, &id.as_str().into()).await;
+    {
+        //scope is needed to clear the rwlock at the end.
+        let session = session.read().unwrap();
+        assert!(session.finalized);
+        assert_eq!(session.laps, get_session().laps);
+    }
+    let session_folder = format!("{}/session", get_path(test_folder_name));
+    assert!(std::fs::exists(format!("{session_folder}/{id}.session.gz")).unwrap());
+    assert!(!std::fs::exists(format!("{session_folder}/{id}.session")).unwrap());
+
+    stop_module(&event_bus, &mut storage).await;
+}
+
+#[tokio::test]
+#[test_log::test]
+pub async fn keep_recent_open_session_on_startup() {
+    let event_bus = EventBus::default();
+    let test_folder_name = "keep_recent_open_session_on_startup";
+    setup_empty_test_folder(test_folder_name);
+    let id = create_open_session(test_folder_name);
+    let mut storage = create_storage_module_with_open_session_timeout(
+        test_folder_name,
+        &event_bus,
+        Duration::from_secs(3600),
+    );
+
+    let session = load_session(&event_bus, &id.as_str().into()).await;
+    assert!(!session.read().unwrap().finalized);
+
+    stop_module(&event_bus, &mut storage).await;
+}
+
+#[tokio::test]
+pub async fn publish_heartbeat_on_startup() {
+    let event_bus = EventBus::default();
+    let test_folder_name = "publish_heartbeat_on_startup";
+    setup_empty_test_folder(test_folder_name);
+    let mut rx = event_bus.subscribe();
+    let mut storage = create_storage_module(test_folder_name, &event_bus);
+
+    let heartbeat_event = wait_for_event(
+        &mut rx,
+        Duration::from_millis(100),
+        EventKindType::ModuleHeartbeatEvent,
+    )
+    .await;
+    let heartbeat = payload_ref!(heartbeat_event.kind, EventKind::ModuleHeartbeatEvent).unwrap();
+    assert_eq!(heartbeat.name, "storage");
+
+    stop_module(&event_bus, &mut storage).await;
+}
+
+#[tokio::test]
+#[test_log::test]
+pub async fn classify_invalid_session_files() {
+    let event_bus = EventBus::default();
+    let test_folder_name = "classify_invalid_session_files";
+    setup_empty_test_folder(test_folder_name);
+    let session_folder = format!("{}/session", get_path(test_folder_name));
+    create_dir(&session_folder).expect("Failed to create session folder");
+    let invalid_utf8 = format!("{session_folder}/invalid_utf8.session");
+    std::fs::write(&invalid_utf8, b"{\"date\":\"01.01.1970\xff\xfe").unwrap();
+    let bad_json = format!("{session_folder}/bad_json.session");
+    std::fs::write(&bad_json, b"{\"date\":").unwrap();
+
+    assert!(matches!(
+        read_session_file(&invalid_utf8).await,
+        Err(SessionFileError::InvalidUtf8(19))
+    ));
+    assert!(matches!(
+        read_session_file(&bad_json).await,
+        Err(SessionFileError::InvalidJson(_))
+    ));
+    assert!(matches!(
+        read_session_file(format!("{session_folder}/missing.session")).await,
+        Err(SessionFileError::NotFound)
+    ));
+
+    let mut storage = create_storage_module(test_folder_name, &event_bus);
+    let mut rx = event_bus.subscribe();
+    event_bus.publish(&Event {
+        kind: EventKind::LoadSessionRequestEvent(Request::new(12, 20, "invalid_utf8".into())),
+    });
+    let load_resp = wait_for_event(
+        &mut rx,
+        Duration::from_millis(100),
+        EventKindType::LoadSessionResponseEvent,
+    )
+    .await;
+    let response = payload_ref!(load_resp.kind, EventKind::LoadSessionResponseEvent).unwrap();
+    assert_eq!(
+        response.data.as_ref().err(),
+        Some(&std::io::ErrorKind::InvalidData)
+    );
+
+    stop_module(&event_bus, &mut storage).await;
+}
+
+#[cfg(debug_assertions)]
+#[tokio::test]
+pub async fn save_session_as_pretty_json_in_debug_build() {
+    let event_bus = EventBus::default();
+    let test_folder_name = "save_session_as_pretty_json_in_debug_build";
+    setup_empty_test_folder(test_folder_name);
+    let mut storage = create_storage_module(test_folder_name, &event_bus);
+
+    let id = save_session(&event_bus, 1, "01.01.1970", "13:00:00.000").await;
+    let content = std::fs::read_to_string(format!(
+        "{}/session/{id}.session",
+        get_path(test_folder_name)
+    ))
+    .unwrap();
+    let session = Session::from_json(&content).unwrap();
+    assert_eq!(content, Session::to_json_pretty(&session).unwrap());
+    assert!(content.contains("\n  "));
+
+    stop_module(&event_bus, &mut storage).await;
+}
+
+#[test]
+fn select_compact_or_pretty_session_json() {
+    let session = get_session();
+
+    let compact = session_to_json(&session, false).unwrap();
+    assert_eq!(compact, Session::to_json(&session).unwrap());
+    assert!(!compact.contains('\n'));
+
+    let pretty = session_to_json(&session, true).unwrap();
+    assert_eq!(pretty, Session::to_json_pretty(&session).unwrap());
+    assert!(pretty.contains('\n'));
+    assert_eq!(Session::from_json(&pretty).unwrap(), session);
+}
+
+#[tokio::test]
+pub async fn coalesce_rapid_saves_of_same_session() {
+    let event_bus = EventBus::default();
+    let test_folder_name = "coalesce_rapid_saves_of_same_session";
+    setup_empty_test_folder(test_folder_name);
+    let mut storage = create_storage_module_with_save_coalescing(
+        test_folder_name,
+        &event_bus,
+        Duration::from_millis(200),
+    );
+
+    let updated_session = || {
+        let mut session = get_session();
+        session.laps.push(get_session().laps.remove(0));
+        session
+    };
+    let first_id = request_save(&event_bus, 1, get_session()).await;
+    let second_id = request_save(&event_bus, 2, updated_session()).await;
+    assert_eq!(first_id, second_id);
+
+    // Nothing is written within the coalescing window.
+    let session_path = format!("{}/session/{first_id}.session", get_path(test_folder_name));
+    assert!(!std::path::Path::new(&session_path).exists());
+
+    let mut written = false;
+    for _ in 0..50 {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        if std::path::Path::new(&session_path).exists() {
+            written = true;
+            break;
+        }
+    }
+    assert!(written, "Coalesced session was not written");
+    // Give the single write time to complete before reading it.
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let stored = read_session_file(&session_path).await.unwrap();
+    assert_eq!(stored, updated_session());
+
+    stop_module(&event_bus, &mut storage).await;
+}
+
+#[tokio::test]
+pub async fn verify_session_on_write() {
+    let event_bus = EventBus::default();
+    let test_folder_name = "verify_session_on_write";
+    setup_empty_test_folder(test_folder_name);
+    let mut storage = create_storage_module_with_verify_on_write(test_folder_name, &event_bus);
+
+    let id = request_save(&event_bus, 1, get_session()).await;
+    let session_path = format!("{}/session/{id}.session", get_path(test_folder_name));
+    assert_eq!(
+        read_session_file(&session_path).await.unwrap(),
+        get_session()
+    );
+
+    stop_module(&event_bus, &mut storage).await;
+}
+
+#[tokio::test]
+pub async fn reject_corrupted_session_on_verify() {
+    let test_folder_name = "reject_corrupted_session_on_verify";
+    setup_empty_test_folder(test_folder_name);
+    let path = format!("{}/corrupted.session", get_path(test_folder_name));
+    let json = Session::to_json(&get_session()).unwrap();
+
+    // Simulates a writer that drops the end of the file.
+    std::fs::write(&path, &json[..json.len() / 2]).unwrap();
+    let error = verify_session_file(&path, &json).await.unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+
+    // Simulates a writer that flips a digit, which is still valid JSON.
+    std::fs::write(&path, json.replacen("52", "53", 1)).unwrap();
+    let error = verify_session_file(&path, &json).await.unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+
+    std::fs::write(&path, &json).unwrap();
+    verify_session_file(&path, &json).await.unwrap();
+    // A file that matches the expected content but is no session is rejected as well.
+    std::fs::write(&path, "{}").unwrap();
+    let error = verify_session_file(&path, "{}").await.unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn compute_crc32_session_checksum() {
+    assert_eq!(session_checksum(b"123456789"), "cbf43926");
+    assert_eq!(session_checksum(b""), "00000000");
+}
+
+#[tokio::test]
+#[test_log::test]
+pub async fn detect_corrupted_session_by_checksum() {
+    let event_bus = EventBus::default();
+    let test_folder_name = "detect_corrupted_session_by_checksum";
+    setup_empty_test_folder(test_folder_name);
+    let mut storage = create_storage_module_with_verify_checksum(test_folder_name, &event_bus);
+
+    let id = request_save(&event_bus, 1, get_session()).await;
+    let session_path = format!("{}/session/{id}.session", get_path(test_folder_name));
+    let checksum_path = format!("{}/session/{id}.sum", get_path(test_folder_name));
+    let json = std::fs::read_to_string(&session_path).unwrap();
+    assert_eq!(
+        std::fs::read_to_string(&checksum_path).unwrap(),
+        session_checksum(json.as_bytes())
+    );
+    assert_eq!(
+        *load_session(&event_bus, &id).await.read().unwrap(),
+        get_session()
+    );
+
+    // Simulates a flipped digit, which is still a valid session.
+    std::fs::write(&session_path, json.replacen("52", "53", 1)).unwrap();
+    assert!(read_session_file(&session_path).await.is_ok());
+    assert!(matches!(
+        read_verified_session_file(&session_path, &checksum_path).await,
+        Err(SessionFileError::ChecksumMismatch { .. })
+    ));
+
+    let mut rx = event_bus.subscribe();
+    event_bus.publish(&Event {
+        kind: EventKind::LoadSessionRequestEvent(Request::new(13, 20, id.clone())),
+    });
+    let load_resp = wait_for_event(
+        &mut rx,
+        Duration::from_millis(100),
+        EventKindType::LoadSessionResponseEvent,
+    )
+    .await;
+    let response = payload_ref!(load_resp.kind, EventKind::LoadSessionResponseEvent).unwrap();
+    assert_eq!(
+        response.data.as_ref().err(),
+        Some(&std::io::ErrorKind::InvalidData)
+    );
+
+    stop_module(&event_bus, &mut storage).await;
+}
+
+async fn list_session_infos(event_bus: &EventBus, id: u64) -> Vec<SessionInfo> {
+    let mut rx = event_bus.subscribe();
+    event_bus.publish(&Event {
+        kind: EventKind::LoadStoredSessionIdsRequestEvent(Request::new(id, 20, ())),
+    });
+    let ids_event = wait_for_event(
+        &mut rx,
+        Duration::from_millis(100),
+        EventKindType::LoadStoredSessionIdsResponseEvent,
+    )
+    .await;
+    (*payload_ref!(ids_event.kind, EventKind::LoadStoredSessionIdsResponseEvent)
+        .unwrap()
+        .data)
+        .clone()
+}
+
+#[tokio::test]
+#[test_log::test]
+pub async fn list_externally_added_session_without_restart() {
+    let event_bus = EventBus::default();
+    let test_folder_name = "list_externally_added_session_without_restart";
+    setup_empty_test_folder(test_folder_name);
+    let mut storage = create_storage_module(test_folder_name, &event_bus);
+    assert!(list_session_infos(&event_bus, 1).await.is_empty());
+
+    // Copied from another device without the info file.
+    let session = get_session();
+    std::fs::write(
+        format!("{}/session/copied.session", get_path(test_folder_name)),
+        Session::to_json(&session).unwrap(),
+    )
+    .unwrap();
+    let infos = list_session_infos(&event_bus, 2).await;
+    assert_eq!(
+        infos,
+        vec![SessionInfo::new(
+            "copied".to_owned(),
+            NaiveDateTime::new(session.date, session.time),
+            session.track.name.clone(),
+            session.laps.len(),
+        )]
+    );
+
+    stop_module(&event_bus, &mut storage).await;
+}
+
+#[test]
+fn second_storage_on_same_folder_is_rejected() {
+    let folder = "second_storage_on_same_folder";
+    setup_empty_test_folder(folder);
+    let event_bus = EventBus::default();
+    let path = std::path::PathBuf::from(get_path(folder));
+
+    let storage = FilesSystemStorage::new(&path, event_bus.context()).unwrap();
+    assert!(path.join(LOCK_FILE).exists());
+    let error = FilesSystemStorage::new(&path, event_bus.context())
+        .err()
+        .expect("second storage on the same folder must fail");
+    assert_eq!(error.kind(), std::io::ErrorKind::WouldBlock);
+
+    drop(storage);
+    assert!(FilesSystemStorage::new(&path, event_bus.context()).is_ok());
+}
+
+#[tokio::test]
+#[test_log::test]
+pub async fn finalized_session_is_compressed_and_still_loads() {
+    let event_bus = EventBus::default();
+    let test_folder_name = "finalized_session_is_compressed_and_still_loads";
+    setup_empty_test_folder(test_folder_name);
+    let id = create_open_session(test_folder_name);
+    let mut storage = create_storage_module_with_open_session_timeout(
+        test_folder_name,
+        &event_bus,
+        Duration::from_secs(3600),
+    );
+
+    let mut rx = event_bus.subscribe();
+    event_bus.publish(&Event {
+        kind: EventKind::FinalizeSessionRequestEvent(
+            Request {
+                id: 13,
+                sender_addr: 20,
+                data: id.as_str().into(),
+            }
+            .into(),
+        ),
+    });
+    let event = wait_for_event(
+        &mut rx,
+        Duration::from_millis(100),
+        EventKindType::FinalizeSessionResponseEvent,
+    )
+    .await;
+    let response = payload_ref!(event.kind, EventKind::FinalizeSessionResponseEvent).unwrap();
+    assert_eq!(response.data, Ok(()));
+
+    let session_folder = format!("{}/session", get_path(test_folder_name));
+    assert!(!std::fs::exists(format!("{session_folder}/{id}.session")).unwrap());
+    let compressed = std::fs::read(format!("{session_folder}/{id}.session.gz")).unwrap();
+    assert_eq!(compressed[..2], [0x1f, 0x8b]);
+
+    let session = load_session(&event_bus, &id.as_str().into()).await;
+    {
+        //scope is needed to clear the rwlock at the end.
+        let session = session.read().unwrap();
+        assert!(session.finalized);
+        assert_eq!(session.laps, get_session().laps);
+    }
+    let verified = read_verified_session_file(
+        format!("{session_folder}/{id}.session"),
+        format!("{session_folder}/{id}.sum"),
+    )
+    .await
+    .unwrap();
+    assert!(verified.finalized);
+
+    stop_module(&event_bus, &mut storage).await;
+}
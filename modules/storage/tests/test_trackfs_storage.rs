@@ -2,7 +2,7 @@
 //
 // SPDX-License-Identifier: GPL-2.0-or-later
 
-use common::track::Track;
+use common::{id::TrackId, test_helper::track::get_track, track::Track};
 use module_core::{
     EmptyRequestPtr, Event, EventBus, EventKind, EventKindType, Request, payload_ref,
     test_helper::{stop_module, wait_for_event},
@@ -15,7 +15,9 @@ use std::{
     time::Duration,
 };
 mod helper;
-use helper::{create_storage_module, get_path, setup_empty_test_folder};
+use helper::{
+    create_storage_module, create_storage_module_with_max_tracks, get_path, setup_empty_test_folder,
+};
 
 fn init_none_empty_test(test_folder_name: &str) -> Vec<String> {
     let ids = vec!["Oschersleben".to_owned(), "Most".to_owned()];
@@ -88,7 +90,10 @@ pub async fn load_stored_track_ids() {
     assert_eq!(payload.receiver_addr, 22);
     let mut ids = payload.data.clone();
     ids.sort();
-    assert_eq!(exp_ids, ids);
+    assert_eq!(
+        exp_ids.into_iter().map(TrackId::from).collect::<Vec<_>>(),
+        ids
+    );
 
     stop_module(&eb, &mut storage).await;
 }
@@ -128,3 +133,128 @@ pub async fn read_stored_session_ids() {
 
     stop_module(&eb, &mut storage).await;
 }
+
+#[tokio::test]
+pub async fn load_stored_track_ids_from_new_storage() {
+    let eb = EventBus::default();
+    let test_folder_name = "load_stored_track_ids_from_new_storage";
+    setup_empty_test_folder(test_folder_name);
+    let mut storage = create_storage_module(test_folder_name, &eb);
+
+    let mut rx = eb.subscribe();
+    eb.publish(&Event {
+        kind: EventKind::LoadStoredTrackIdsRequest(EmptyRequestPtr::new(Request {
+            id: 10,
+            sender_addr: 22,
+            data: (),
+        })),
+    });
+    let load_stored_event = wait_for_event(
+        &mut rx,
+        Duration::from_millis(100),
+        EventKindType::LoadStoredTrackIdsResponseEvent,
+    )
+    .await;
+
+    let payload = payload_ref!(
+        load_stored_event.kind,
+        EventKind::LoadStoredTrackIdsResponseEvent
+    )
+    .unwrap();
+    assert!(payload.data.is_empty());
+    let mut track_folder = PathBuf::from_str(&get_path(test_folder_name)).unwrap();
+    track_folder.push("track");
+    assert!(track_folder.is_dir());
+
+    stop_module(&eb, &mut storage).await;
+}
+
+#[tokio::test]
+pub async fn save_and_load_track() {
+    let eb = EventBus::default();
+    let test_folder_name = "save_and_load_track";
+    init_none_empty_test(test_folder_name);
+    let mut storage = create_storage_module(test_folder_name, &eb);
+    let mut track = get_track();
+    track.sectors.pop();
+
+    let mut rx = eb.subscribe();
+    eb.publish(&Event {
+        kind: EventKind::SaveTrackRequestEvent(Request::new(
+            1,
+            22,
+            ("Oschersleben".into(), track.clone()),
+        )),
+    });
+    let save_event = wait_for_event(
+        &mut rx,
+        Duration::from_millis(100),
+        EventKindType::SaveTrackResponseEvent,
+    )
+    .await;
+    let payload = payload_ref!(save_event.kind, EventKind::SaveTrackResponseEvent).unwrap();
+    assert_eq!(payload.data, Ok(()));
+
+    for (id, expected) in [
+        ("Oschersleben", Ok(track)),
+        ("Unknown", Err(std::io::ErrorKind::NotFound)),
+    ] {
+        eb.publish(&Event {
+            kind: EventKind::LoadTrackRequestEvent(Request::new(2, 22, id.into())),
+        });
+        let load_event = wait_for_event(
+            &mut rx,
+            Duration::from_millis(100),
+            EventKindType::LoadTrackResponseEvent,
+        )
+        .await;
+        let payload = payload_ref!(load_event.kind, EventKind::LoadTrackResponseEvent).unwrap();
+        assert_eq!(payload.data, expected);
+    }
+
+    stop_module(&eb, &mut storage).await;
+}
+
+#[tokio::test]
+pub async fn evict_least_recently_used_track() {
+    let eb = EventBus::default();
+    let test_folder_name = "evict_least_recently_used_track";
+    setup_empty_test_folder(test_folder_name);
+    let mut storage = create_storage_module_with_max_tracks(test_folder_name, &eb, 2);
+    let mut rx = eb.subscribe();
+
+    for (id, request) in [("A", "save"), ("B", "save"), ("A", "load"), ("C", "save")] {
+        let (kind, expected) = match request {
+            "save" => (
+                EventKind::SaveTrackRequestEvent(Request::new(1, 22, (id.into(), get_track()))),
+                EventKindType::SaveTrackResponseEvent,
+            ),
+            _ => (
+                EventKind::LoadTrackRequestEvent(Request::new(2, 22, id.into())),
+                EventKindType::LoadTrackResponseEvent,
+            ),
+        };
+        eb.publish(&Event { kind });
+        let _ = wait_for_event(&mut rx, Duration::from_millis(500), expected).await;
+        // Ensure distinct last use timestamps.
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    }
+
+    eb.publish(&Event {
+        kind: EventKind::LoadStoredTrackIdsRequest(EmptyRequestPtr::new(Request {
+            id: 3,
+            sender_addr: 22,
+            data: (),
+        })),
+    });
+    let event = wait_for_event(
+        &mut rx,
+        Duration::from_millis(100),
+        EventKindType::LoadStoredTrackIdsResponseEvent,
+    )
+    .await;
+    let payload = payload_ref!(event.kind, EventKind::LoadStoredTrackIdsResponseEvent).unwrap();
+    assert_eq!(payload.data, vec![TrackId::from("A"), TrackId::from("C")]);
+
+    stop_module(&eb, &mut storage).await;
+}
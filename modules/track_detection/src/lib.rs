@@ -2,7 +2,9 @@
 //
 // SPDX-License-Identifier: GPL-2.0-or-later
 
-use algorithm::is_on_track;
+use algorithm::{
+    DetectionResult, DistanceModel, TieBreak, calculate_distance_with, detect_with_model,
+};
 use async_trait::async_trait;
 use common::{position::Position, track::Track};
 use module_core::{
@@ -12,6 +14,48 @@ use module_core::{
 use std::{collections::VecDeque, result::Result};
 use tracing::{error, info};
 
+/// Radius in meters around the start line of a track in which the track is detected.
+const DETECTION_RADIUS: u16 = 500;
+
+/// Configuration of a [`TrackDetection`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TrackDetectionConfig {
+    /// The formula the distances between the position and the start lines are measured with.
+    pub distance_model: DistanceModel,
+
+    /// Number of consecutive detections in which another track must be clearly closer
+    /// before the detection switches away from the previously detected track.
+    ///
+    /// Avoids that the detection flaps between tracks with close start lines. `1` switches
+    /// on the first detection of another track.
+    pub switch_after: usize,
+
+    /// Distance in meters by which the start line of another track must be closer than the
+    /// one of the previously detected track to count towards [`switch_after`](Self::switch_after).
+    pub switch_margin: f64,
+}
+
+impl Default for TrackDetectionConfig {
+    fn default() -> Self {
+        TrackDetectionConfig {
+            distance_model: DistanceModel::default(),
+            switch_after: 3,
+            switch_margin: 20.0,
+        }
+    }
+}
+
+/// Hysteresis state of the track detection.
+#[derive(Debug, Default)]
+struct DetectionHysteresis {
+    /// The track that is currently reported as detected.
+    selected: Option<Track>,
+
+    /// Name of the track that was clearly closer than the selected track and for how many
+    /// consecutive detections.
+    challenger: Option<(String, usize)>,
+}
+
 /// The `TrackDetection` module is responsible for detecting which tracks
 /// the system is currently located on, based on GNSS position updates and
 /// previously loaded track data.
@@ -23,26 +67,92 @@ pub struct TrackDetection {
     position: Option<Position>,
     pending_requests: VecDeque<EmptyRequestPtr>,
     tracks: Vec<Track>,
+    config: TrackDetectionConfig,
+    hysteresis: DetectionHysteresis,
 }
 
 impl TrackDetection {
     /// Creates a new `TrackDetection` instance with an empty state and
     /// initialized communication context.
     pub fn new(ctx: ModuleCtx) -> Self {
+        TrackDetection::new_with_config(ctx, TrackDetectionConfig::default())
+    }
+
+    /// Creates a new `TrackDetection` instance with a custom configuration.
+    pub fn new_with_config(ctx: ModuleCtx, config: TrackDetectionConfig) -> Self {
         TrackDetection {
             ctx,
             position: None,
             pending_requests: VecDeque::new(),
             tracks: vec![],
+            config,
+            hysteresis: DetectionHysteresis::default(),
         }
     }
 
+    /// Applies the hysteresis to a single detected track.
+    ///
+    /// The previously detected track is kept as long as it is still in detection range and
+    /// the detected track wasn't clearly closer for [`TrackDetectionConfig::switch_after`]
+    /// consecutive detections.
+    fn debounce(&mut self, detected: Track, position: &Position) -> Track {
+        let Some(selected) = self.hysteresis.selected.take() else {
+            self.hysteresis.selected = Some(detected.clone());
+            return detected;
+        };
+        let selected_in_range = matches!(
+            detect_with_model(
+                std::slice::from_ref(&selected),
+                position,
+                DETECTION_RADIUS,
+                TieBreak::ByName,
+                self.config.distance_model,
+            ),
+            DetectionResult::Single(_)
+        );
+        let clearly_closer =
+            calculate_distance_with(self.config.distance_model, &selected.startline, position)
+                - calculate_distance_with(
+                    self.config.distance_model,
+                    &detected.startline,
+                    position,
+                )
+                > self.config.switch_margin;
+
+        let track = if detected.name == selected.name || !selected_in_range {
+            self.hysteresis.challenger = None;
+            detected
+        } else if clearly_closer {
+            let count = match self.hysteresis.challenger.take() {
+                Some((name, count)) if name == detected.name => count + 1,
+                _ => 1,
+            };
+            if count >= self.config.switch_after {
+                info!(
+                    "Track detection switched from {} to {}",
+                    selected.name, detected.name
+                );
+                detected
+            } else {
+                self.hysteresis.challenger = Some((detected.name, count));
+                selected
+            }
+        } else {
+            self.hysteresis.challenger = None;
+            selected
+        };
+        self.hysteresis.selected = Some(track.clone());
+        track
+    }
+
     /// Processes any pending detection requests if both position and
     /// track data are available.
     ///
-    /// For each request, it determines which tracks are within a
-    /// configured proximity threshold of the current position and sends
-    /// a corresponding detection response event.
+    /// For each request, it determines the track with the closest start line
+    /// within a configured proximity threshold of the current position and sends
+    /// a corresponding detection response event. Equally close tracks are
+    /// resolved by their name. A single detected track is debounced, see
+    /// [`TrackDetectionConfig::switch_after`].
     fn handle_pending_requests(&mut self) {
         if self.position.is_none() {
             return;
@@ -50,11 +160,18 @@ impl TrackDetection {
         if self.pending_requests.is_empty() || self.tracks.is_empty() {
             return;
         }
-        let detected_tracks: Vec<Track> =
-            is_on_track(&self.tracks, self.position.as_ref().unwrap(), 500)
-                .into_iter()
-                .cloned()
-                .collect();
+        let position = self.position.unwrap();
+        let detected_tracks: Vec<Track> = match detect_with_model(
+            &self.tracks,
+            &position,
+            DETECTION_RADIUS,
+            TieBreak::ByName,
+            self.config.distance_model,
+        ) {
+            DetectionResult::Single(track) => vec![self.debounce(track, &position)],
+            DetectionResult::Ambiguous(tracks) => tracks,
+            DetectionResult::None => vec![],
+        };
         while !self.pending_requests.is_empty() {
             let request = self.pending_requests.pop_front().unwrap();
             let response =
@@ -95,7 +212,7 @@ impl Module for TrackDetection {
         let mut run = true;
         while run {
             tokio::select! {
-                event = self.ctx.receiver.recv() => {
+                event = self.ctx.recv() => {
                     match event {
                         Ok(event) => {
                             match event.kind {
@@ -104,6 +221,12 @@ impl Module for TrackDetection {
                                     self.position = Some(Position { latitude: position.latitude(), longitude: position.longitude() });
                                     self.handle_pending_requests();
                                 }
+                                EventKind::GnssPositionBatchEvent(positions) => {
+                                    if let Some(position) = positions.last() {
+                                        self.position = Some(Position { latitude: position.latitude(), longitude: position.longitude() });
+                                        self.handle_pending_requests();
+                                    }
+                                }
                                 EventKind::LoadAllStoredTracksResponseEvent(tracks) => {
                                     self.tracks = tracks.data.clone();
                                     self.handle_pending_requests();
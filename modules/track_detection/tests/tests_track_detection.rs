@@ -4,7 +4,11 @@
 
 use chrono::NaiveDate;
 use chrono::NaiveTime;
-use common::{position::GnssPosition, test_helper::track::get_track};
+use common::{
+    position::{GnssPosition, Position},
+    test_helper::track::get_track,
+    track::Track,
+};
 use module_core::ModuleCtx;
 use module_core::test_helper::register_response_event;
 use module_core::{
@@ -79,3 +83,86 @@ pub async fn handle_track_detection_request() {
 
     stop_module(&event_bus, &mut td).await
 }
+
+/// Requests a track detection at the given position and returns the names of the detected tracks.
+async fn detect_at(event_bus: &EventBus, latitude: f64, longitude: f64) -> Vec<String> {
+    let mut rx = event_bus.subscribe();
+    event_bus.publish(&Event {
+        kind: EventKind::GnssPositionEvent(GnssPositionPtr::new(GnssPosition::new(
+            latitude,
+            longitude,
+            20.0,
+            &NaiveTime::parse_from_str("00:00:00.000", "%H:%M:%S%.3f").unwrap(),
+            &NaiveDate::parse_from_str("01.01.1970", "%d.%m.%Y").unwrap(),
+        ))),
+    });
+    event_bus.publish(&Event {
+        kind: EventKind::DetectTrackRequestEvent(
+            Request {
+                id: 0,
+                sender_addr: 11,
+                data: (),
+            }
+            .into(),
+        ),
+    });
+    let event = wait_for_event(
+        &mut rx,
+        Duration::from_millis(100),
+        EventKindType::DetectTrackResponseEvent,
+    )
+    .await;
+    payload_ref!(event.kind, EventKind::DetectTrackResponseEvent)
+        .unwrap()
+        .data
+        .iter()
+        .map(|track| track.name.clone())
+        .collect()
+}
+
+#[tokio::test]
+pub async fn detected_track_is_debounced() {
+    let event_bus = EventBus::default();
+    let mut td = create_module(event_bus.context());
+    let track_a = Track {
+        name: "A".to_string(),
+        ..get_track()
+    };
+    // The start line of track B is about 100m north of the one of track A.
+    let track_b = Track {
+        name: "B".to_string(),
+        startline: Position::new(
+            &(track_a.startline.latitude + 0.0009),
+            &track_a.startline.longitude,
+        ),
+        ..get_track()
+    };
+    let near_a = (track_a.startline.latitude, track_a.startline.longitude);
+    let near_b = (track_b.startline.latitude, track_b.startline.longitude);
+
+    let _ = register_response_event(
+        EventKindType::LoadAllStoredTracksRequestEvent,
+        Event {
+            kind: EventKind::LoadAllStoredTracksResponseEvent(
+                Response {
+                    id: 0,
+                    receiver_addr: 20,
+                    data: vec![track_a, track_b],
+                }
+                .into(),
+            ),
+        },
+        event_bus.context(),
+    );
+
+    for (latitude, longitude) in [near_a, near_b, near_a, near_b, near_b, near_a] {
+        assert_eq!(detect_at(&event_bus, latitude, longitude).await, ["A"]);
+    }
+    // Track B must be clearly closer for three consecutive detections.
+    assert_eq!(detect_at(&event_bus, near_b.0, near_b.1).await, ["A"]);
+    assert_eq!(detect_at(&event_bus, near_b.0, near_b.1).await, ["A"]);
+    assert_eq!(detect_at(&event_bus, near_b.0, near_b.1).await, ["B"]);
+    assert_eq!(detect_at(&event_bus, near_a.0, near_a.1).await, ["B"]);
+
+    stop_module(&event_bus, &mut td).await
+}
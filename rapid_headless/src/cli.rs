@@ -0,0 +1,94 @@
+// SPDX-FileCopyrightText: 2025, 2026 All contributors
+//
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use clap::{CommandFactory, Parser, ValueEnum, error::ErrorKind};
+use std::ffi::OsString;
+
+/// Default address of the gpsd daemon.
+pub const DEFAULT_GPSD_ADDRESS: &str = "127.0.0.1:2947";
+
+/// The source of the GNSS positions.
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum GnssSource {
+    /// Positions of a GNSS receiver read from the gpsd daemon.
+    Gpsd,
+    /// Positions interpolated along the points of the source file, starting over at the end.
+    Fake,
+    /// Positions interpolated along the points of the source file, stopping at the end.
+    Replay,
+}
+
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+pub struct Cli {
+    /// Source of the GNSS positions
+    #[arg(long, value_enum)]
+    pub gnss: GnssSource,
+    /// Address of the gpsd daemon, only for the gpsd source (default: 127.0.0.1:2947)
+    #[arg(long)]
+    pub gpsd_address: Option<String>,
+    /// CSV file with the longitude and latitude of the points, only for the fake and replay
+    /// sources
+    #[arg(short = 'f', long, required_if_eq_any([("gnss", "fake"), ("gnss", "replay")]))]
+    pub gnss_source_file: Option<String>,
+    /// Path prefix of the REST and WebSocket routes, e.g. /laptimer
+    #[arg(long, default_value = "/")]
+    pub rest_base_path: String,
+    /// Number of decimals of the coordinates in live position messages
+    #[arg(long, default_value_t = 6)]
+    pub rest_position_precision: u32,
+    /// Enables the REST simulation routes to inject GNSS positions, only for testing
+    #[arg(long)]
+    pub rest_simulation: bool,
+    /// Origin allowed to access the REST routes from a browser, may be repeated, e.g.
+    /// https://dashboard.example or http://localhost:* (default: localhost on every port)
+    #[arg(long)]
+    pub rest_cors_origin: Vec<String>,
+    /// Seconds between the WebSocket Pings that keep idle live session connections open,
+    /// 0 disables the Pings
+    #[arg(long, default_value_t = 30)]
+    pub rest_ping_interval: u64,
+    /// Stops all modules after the given number of finished laps, e.g. for demo runs
+    #[arg(long)]
+    pub stop_after_laps: Option<usize>,
+}
+
+impl Cli {
+    /// Parses the command line arguments of the process, exits with the usage on errors.
+    pub fn parse_args() -> Self {
+        Cli::try_parse_args(std::env::args_os()).unwrap_or_else(|e| e.exit())
+    }
+
+    /// Parses the given command line arguments.
+    ///
+    /// Besides the checks of clap, options of a GNSS source other than the selected one
+    /// are rejected as [`ErrorKind::ArgumentConflict`].
+    pub fn try_parse_args<I, T>(args: I) -> Result<Self, clap::Error>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<OsString> + Clone,
+    {
+        let cli = Cli::try_parse_from(args)?;
+        let conflict = match cli.gnss {
+            GnssSource::Gpsd if cli.gnss_source_file.is_some() => {
+                Some(("--gnss-source-file", "gpsd"))
+            }
+            GnssSource::Fake if cli.gpsd_address.is_some() => Some(("--gpsd-address", "fake")),
+            GnssSource::Replay if cli.gpsd_address.is_some() => Some(("--gpsd-address", "replay")),
+            _ => None,
+        };
+        match conflict {
+            Some((arg, source)) => Err(Cli::command().error(
+                ErrorKind::ArgumentConflict,
+                format!("the argument '{arg}' cannot be used with '--gnss {source}'"),
+            )),
+            None => Ok(cli),
+        }
+    }
+
+    /// Returns the address of the gpsd daemon.
+    pub fn gpsd_address(&self) -> &str {
+        self.gpsd_address.as_deref().unwrap_or(DEFAULT_GPSD_ADDRESS)
+    }
+}
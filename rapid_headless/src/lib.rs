@@ -0,0 +1,10 @@
+// SPDX-FileCopyrightText: 2026 All contributors
+//
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+//! Headless laptimer application
+//!
+//! Runs all modules of the laptimer without a user interface, controlled via the REST API.
+
+/// Module for the command line interface.
+pub mod cli;
@@ -3,12 +3,14 @@
 // SPDX-License-Identifier: GPL-2.0-or-later
 
 use active_session::ActiveSession;
-use clap::{CommandFactory, Parser};
 use dirs::data_local_dir;
-use gnss::{constant_source::ConstantGnssModule, gpsd_source::GpsdModule};
+use gnss::{
+    acceleration::AccelerationModule, constant_source::ConstantGnssModule, gpsd_source::GpsdModule,
+};
 use laptimer::SimpleLaptimer;
-use module_core::{Event, EventBus, EventKind, Module};
-use rest::Rest;
+use module_core::{Event, EventBus, EventKind, Module, auto_stop::AutoStopModule, supervise};
+use rapid_headless::cli::{Cli, GnssSource};
+use rest::{CorsConfig, Rest, RestConfig};
 use std::str::FromStr;
 use std::time::Duration;
 use storage::FilesSystemStorage;
@@ -16,16 +18,8 @@ use tracing::{debug, error, info};
 use tracing_subscriber::EnvFilter;
 use track_detection::TrackDetection;
 
-#[derive(Parser, Debug)]
-#[command(author, version, about, long_about = None)]
-struct Cli {
-    #[arg(short, long)]
-    gps_fake: bool,
-    #[arg(short = 'f', long)]
-    gps_source_file: Option<String>,
-    #[arg(short = 'd', long)]
-    gpsd: bool,
-}
+/// Number of restarts of a panicking module before it is given up.
+const MAX_MODULE_RESTARTS: usize = 3;
 
 fn read_lap_points_from_file(file_path: &str) -> Result<Vec<common::position::Position>, ()> {
     let mut rdr = csv::Reader::from_path(file_path).unwrap();
@@ -44,8 +38,8 @@ fn read_lap_points_from_file(file_path: &str) -> Result<Vec<common::position::Po
     Ok(positions)
 }
 
-async fn get_gpsd_module(eb: &EventBus) -> Result<Box<dyn Module>, ()> {
-    match GpsdModule::new(eb.context(), "127.0.0.1:2947").await {
+async fn get_gpsd_module(eb: &EventBus, address: &str) -> Result<Box<dyn Module>, ()> {
+    match GpsdModule::new(eb.context(), address).await {
         Ok(gpsd) => Ok(Box::new(gpsd)),
         Err(e) => {
             error!("Failed to connect to gpsd!. Error: {}", e);
@@ -54,17 +48,20 @@ async fn get_gpsd_module(eb: &EventBus) -> Result<Box<dyn Module>, ()> {
     }
 }
 
-fn create_fake_gps_module(eb: &EventBus, cli: &Cli) -> Result<Box<dyn Module>, ()> {
-    if let Some(source_file) = &cli.gps_source_file {
-        let positions = read_lap_points_from_file(source_file).unwrap();
-        Ok(Box::new(
-            ConstantGnssModule::new(eb.context(), &positions, 40.0, Duration::from_secs(5))
-                .unwrap(),
-        ))
-    } else {
-        error!("Failed to create ConstantGnssModule. Error: gps_source_file not set");
-        Cli::command().print_help().unwrap();
-        Err(())
+/// Creates a GNSS source interpolating along the points of the source file, that starts
+/// over at the last point if `looping` is set.
+fn create_fake_gps_module(
+    eb: &EventBus,
+    source_file: &str,
+    looping: bool,
+) -> Result<Box<dyn Module>, ()> {
+    let positions = read_lap_points_from_file(source_file)?;
+    match ConstantGnssModule::new(eb.context(), &positions, 40.0, Duration::from_secs(5)) {
+        Ok(module) => Ok(Box::new(module.with_looping(looping))),
+        Err(e) => {
+            error!("Failed to create ConstantGnssModule. Error: {}", e);
+            Err(())
+        }
     }
 }
 
@@ -78,7 +75,7 @@ fn get_storage_dir() -> Result<std::path::PathBuf, ()> {
 
 #[tokio::main]
 async fn main() -> Result<(), ()> {
-    let cli = Cli::parse();
+    let cli = Cli::parse_args();
     tracing_subscriber::fmt()
         .with_env_filter(EnvFilter::from_default_env())
         .init();
@@ -86,11 +83,11 @@ async fn main() -> Result<(), ()> {
     let storage_dir = get_storage_dir()?;
     let eb = EventBus::default();
 
-    // setup ctrl-c handler
-    let ctx = eb.context();
+    // setup ctrl-c handler, it only publishes and must not subscribe the priority lane
+    let sender = eb.context().sender;
     match ctrlc::set_handler(move || {
         info!("Received Ctrl-C, sending quit event to the modules...");
-        let _ = ctx.sender.send(Event {
+        let _ = sender.send(Event {
             kind: EventKind::QuitEvent,
         });
         info!("Sending event to the modules...");
@@ -102,29 +99,63 @@ async fn main() -> Result<(), ()> {
         }
     }
 
-    let mut gpsd: Box<dyn Module> = if cli.gpsd {
-        get_gpsd_module(&eb).await?
-    } else if cli.gps_fake {
-        create_fake_gps_module(&eb, &cli)?
-    } else {
-        error!("No GPS source specified. Use --gpsd or --gps-fake");
-        Cli::command().print_help().unwrap();
-        return Err(());
+    // clap ensures that the fake and replay sources have a source file.
+    let source_file = cli.gnss_source_file.as_deref().unwrap_or_default();
+    let mut gpsd: Box<dyn Module> = match cli.gnss {
+        GnssSource::Gpsd => get_gpsd_module(&eb, cli.gpsd_address()).await?,
+        GnssSource::Fake => create_fake_gps_module(&eb, source_file, true)?,
+        GnssSource::Replay => create_fake_gps_module(&eb, source_file, false)?,
+    };
+    let mut storage = match FilesSystemStorage::new(&storage_dir, eb.context()) {
+        Ok(storage) => storage,
+        Err(e) => {
+            error!("Failed to open the storage: {}", e);
+            return Err(());
+        }
     };
-    let mut storage = FilesSystemStorage::new(&storage_dir, eb.context());
     let mut laptimer = SimpleLaptimer::new(eb.context());
     let mut track_detection = TrackDetection::new(eb.context());
     let mut active_session = ActiveSession::new(eb.context());
-    let mut rest = Rest::new(eb.context());
+    let mut acceleration = AccelerationModule::new(eb.context());
+    let mut rest = Rest::new_with_config(
+        eb.context(),
+        RestConfig {
+            base_path: cli.rest_base_path.clone(),
+            position_precision: cli.rest_position_precision,
+            simulation: cli.rest_simulation,
+            cors: if cli.rest_cors_origin.is_empty() {
+                CorsConfig::default()
+            } else {
+                CorsConfig {
+                    allowed_origins: cli.rest_cors_origin.clone(),
+                    ..Default::default()
+                }
+            },
+            live_ping_interval: (cli.rest_ping_interval > 0)
+                .then(|| Duration::from_secs(cli.rest_ping_interval)),
+            ..Default::default()
+        },
+    );
+
+    let mut auto_stop = cli
+        .stop_after_laps
+        .map(|laps| AutoStopModule::new(eb.context(), laps));
 
     info!("Starting modules...");
     tokio::join!(
-        storage.run(),
-        gpsd.run(),
-        track_detection.run(),
-        laptimer.run(),
-        active_session.run(),
-        rest.run()
+        supervise("storage", &mut storage, MAX_MODULE_RESTARTS),
+        supervise("gnss", gpsd.as_mut(), MAX_MODULE_RESTARTS),
+        supervise("track_detection", &mut track_detection, MAX_MODULE_RESTARTS),
+        supervise("laptimer", &mut laptimer, MAX_MODULE_RESTARTS),
+        supervise("active_session", &mut active_session, MAX_MODULE_RESTARTS),
+        supervise("acceleration", &mut acceleration, MAX_MODULE_RESTARTS),
+        supervise("rest", &mut rest, MAX_MODULE_RESTARTS),
+        async {
+            match auto_stop.as_mut() {
+                Some(auto_stop) => supervise("auto_stop", auto_stop, MAX_MODULE_RESTARTS).await,
+                None => Ok(()),
+            }
+        }
     )
     .0
 }
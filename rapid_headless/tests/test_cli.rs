@@ -0,0 +1,84 @@
+// SPDX-FileCopyrightText: 2026 All contributors
+//
+// SPDX-License-Identifier: GPL-2.0-or-later
+
+use clap::error::ErrorKind;
+use rapid_headless::cli::{Cli, DEFAULT_GPSD_ADDRESS, GnssSource};
+
+fn parse(args: &[&str]) -> Result<Cli, clap::Error> {
+    Cli::try_parse_args(std::iter::once("rapid_headless").chain(args.iter().copied()))
+}
+
+#[test]
+fn parse_gpsd_source() {
+    let cli = parse(&["--gnss", "gpsd"]).unwrap();
+    assert_eq!(cli.gnss, GnssSource::Gpsd);
+    assert_eq!(cli.gpsd_address(), DEFAULT_GPSD_ADDRESS);
+
+    let cli = parse(&["--gnss", "gpsd", "--gpsd-address", "192.168.1.2:2947"]).unwrap();
+    assert_eq!(cli.gpsd_address(), "192.168.1.2:2947");
+}
+
+#[test]
+fn parse_fake_and_replay_source() {
+    let cli = parse(&["--gnss", "fake", "--gnss-source-file", "track.csv"]).unwrap();
+    assert_eq!(cli.gnss, GnssSource::Fake);
+    assert_eq!(cli.gnss_source_file.as_deref(), Some("track.csv"));
+
+    let cli = parse(&["--gnss", "replay", "-f", "track.csv"]).unwrap();
+    assert_eq!(cli.gnss, GnssSource::Replay);
+    assert_eq!(cli.gnss_source_file.as_deref(), Some("track.csv"));
+}
+
+#[test]
+fn reject_missing_or_unknown_source() {
+    assert_eq!(
+        parse(&[]).unwrap_err().kind(),
+        ErrorKind::MissingRequiredArgument
+    );
+    assert_eq!(
+        parse(&["--gnss", "serial"]).unwrap_err().kind(),
+        ErrorKind::InvalidValue
+    );
+}
+
+#[test]
+fn reject_source_file_missing_for_fake_and_replay() {
+    for source in ["fake", "replay"] {
+        assert_eq!(
+            parse(&["--gnss", source]).unwrap_err().kind(),
+            ErrorKind::MissingRequiredArgument
+        );
+    }
+}
+
+#[test]
+fn reject_options_of_other_source() {
+    assert_eq!(
+        parse(&["--gnss", "gpsd", "--gnss-source-file", "track.csv"])
+            .unwrap_err()
+            .kind(),
+        ErrorKind::ArgumentConflict
+    );
+    for source in ["fake", "replay"] {
+        assert_eq!(
+            parse(&[
+                "--gnss",
+                source,
+                "-f",
+                "track.csv",
+                "--gpsd-address",
+                "127.0.0.1:1"
+            ])
+            .unwrap_err()
+            .kind(),
+            ErrorKind::ArgumentConflict
+        );
+    }
+    assert_eq!(
+        parse(&["--gnss", "gpsd", "--gnss", "fake"])
+            .unwrap_err()
+            .kind(),
+        ErrorKind::ArgumentConflict
+    );
+}